@@ -0,0 +1,22 @@
+-- =============================================================================
+-- MIGRATION 006: OAuth Authorization State (PKCE)
+-- =============================================================================
+-- Pending OAuth2 authorization-code flows
+-- One row per /authorize call, consumed (deleted) by the matching /callback
+-- =============================================================================
+
+CREATE TABLE oauth_states (
+    state_hash      VARCHAR(64) PRIMARY KEY,
+    -- SHA-256 (hex) of the `state` parameter sent to the provider
+
+    provider        VARCHAR(50) NOT NULL,
+    -- 'google', 'github', 'discord', 'twitter'
+
+    code_verifier   VARCHAR(128) NOT NULL,
+    -- PKCE verifier (RFC 7636), sent to the provider on code exchange
+
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at      TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_oauth_states_expires ON oauth_states(expires_at);
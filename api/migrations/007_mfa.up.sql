@@ -0,0 +1,44 @@
+-- =============================================================================
+-- MIGRATION 007: Multi-Factor Authentication (TOTP)
+-- =============================================================================
+-- Opt-in TOTP (RFC 6238) second factor plus single-use recovery codes
+-- A row in user_mfa with enabled_at = NULL is a pending (unconfirmed) enrollment
+-- =============================================================================
+
+CREATE TABLE user_mfa (
+    user_id             UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+
+    totp_secret         VARCHAR(64) NOT NULL,
+    -- Base32 shared secret (as shown to the authenticator app)
+
+    enabled_at          TIMESTAMPTZ,
+    -- Set once the user confirms enrollment with a valid code
+
+    last_used_step      BIGINT,
+    -- Last accepted TOTP time step (replay protection)
+
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_user_mfa_updated_at
+    BEFORE UPDATE ON user_mfa
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE mfa_recovery_codes (
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id             UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    code_hash           VARCHAR(64) NOT NULL,
+    -- SHA-256 (hex) of the normalized recovery code
+
+    used_at             TIMESTAMPTZ,
+    -- Single use: set when redeemed
+
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT unique_recovery_code UNIQUE(user_id, code_hash)
+);
+
+CREATE INDEX idx_recovery_codes_user ON mfa_recovery_codes(user_id) WHERE used_at IS NULL;
@@ -0,0 +1,26 @@
+-- =============================================================================
+-- MIGRATION 008: Single-Use Action Tokens
+-- =============================================================================
+-- Short-lived tokens delivered by email (verification links, etc.)
+-- Only a SHA-256 hash is stored; a token is consumed by setting used_at
+-- =============================================================================
+
+CREATE TABLE action_tokens (
+    token_hash          VARCHAR(64) PRIMARY KEY,
+    -- SHA-256 (hex) of the token sent to the user
+
+    user_id             UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    purpose             VARCHAR(32) NOT NULL,
+    -- 'email_verification'
+
+    email               VARCHAR(255) NOT NULL,
+    -- Address the token was sent to (must still match when redeemed)
+
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at          TIMESTAMPTZ NOT NULL,
+    used_at             TIMESTAMPTZ
+);
+
+CREATE INDEX idx_action_tokens_user_purpose ON action_tokens(user_id, purpose) WHERE used_at IS NULL;
+CREATE INDEX idx_action_tokens_expires ON action_tokens(expires_at);
@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS security_events;
+
+ALTER TABLE user_sessions
+    DROP COLUMN IF EXISTS access_expires_at,
+    DROP COLUMN IF EXISTS access_jti,
+    DROP COLUMN IF EXISTS refresh_jti;
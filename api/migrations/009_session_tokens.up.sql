@@ -0,0 +1,35 @@
+-- =============================================================================
+-- MIGRATION 009: Session Token Tracking & Security Events
+-- =============================================================================
+-- Each session remembers the jti of its latest refresh/access token so a
+-- rotated refresh token presented again can be detected without Redis
+-- =============================================================================
+
+ALTER TABLE user_sessions
+    ADD COLUMN refresh_jti          VARCHAR(64),
+    -- jti of the only refresh token currently valid for this session
+    ADD COLUMN access_jti           VARCHAR(64),
+    -- jti of the most recently issued access token
+    ADD COLUMN access_expires_at    TIMESTAMPTZ;
+    -- Expiry of that access token (blacklist TTL when the session is killed)
+
+-- =============================================================================
+-- Security Events (audit trail of suspicious activity)
+-- =============================================================================
+CREATE TABLE security_events (
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id             UUID REFERENCES users(id) ON DELETE CASCADE,
+
+    event_type          VARCHAR(50) NOT NULL,
+    -- 'token_reuse_detected'
+
+    session_id          VARCHAR(64),
+    -- JWT "sid" the event relates to, if any
+
+    details             JSONB NOT NULL DEFAULT '{}',
+
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_security_events_user ON security_events(user_id, created_at DESC);
+CREATE INDEX idx_security_events_type ON security_events(event_type, created_at DESC);
@@ -0,0 +1,21 @@
+-- =============================================================================
+-- MIGRATION 010: Login Attempts (Per-Account Throttling)
+-- =============================================================================
+-- Failed password logins per account, used when Redis is not configured
+-- Keyed by normalized email so unknown addresses are throttled the same way
+-- =============================================================================
+
+CREATE TABLE login_attempts (
+    account_key         VARCHAR(255) PRIMARY KEY,
+    -- Lower-cased email the attempts were made against
+
+    failed_count        INTEGER NOT NULL DEFAULT 0,
+    last_failed_at      TIMESTAMPTZ,
+
+    locked_until        TIMESTAMPTZ,
+    -- Temporary lockout; NULL or in the past = not locked
+
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_login_attempts_updated ON login_attempts(updated_at);
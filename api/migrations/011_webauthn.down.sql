@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS webauthn_ceremonies;
+DROP TABLE IF EXISTS webauthn_credentials;
+DELETE FROM auth_methods WHERE provider = 'passkey';
+DROP INDEX IF EXISTS unique_provider_per_user;
+ALTER TABLE auth_methods ADD CONSTRAINT unique_provider_per_user UNIQUE(user_id, provider);
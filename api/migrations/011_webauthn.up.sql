@@ -0,0 +1,63 @@
+-- =============================================================================
+-- MIGRATION 011: WebAuthn Passkeys
+-- =============================================================================
+-- Each passkey is an auth method (provider 'passkey', provider_id = base64url
+-- credential id) with its public key stored alongside.
+-- A user may hold several passkeys, so one-per-user only applies to the
+-- other providers.
+-- =============================================================================
+
+ALTER TABLE auth_methods DROP CONSTRAINT unique_provider_per_user;
+CREATE UNIQUE INDEX unique_provider_per_user
+    ON auth_methods(user_id, provider)
+    WHERE provider <> 'passkey';
+
+CREATE TABLE webauthn_credentials (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    auth_method_id  UUID NOT NULL UNIQUE REFERENCES auth_methods(id) ON DELETE CASCADE,
+    user_id         UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    credential_id   BYTEA NOT NULL UNIQUE,
+    -- Raw credential id chosen by the authenticator
+
+    public_key      BYTEA NOT NULL,
+    -- COSE_Key as returned at registration
+
+    algorithm       INTEGER NOT NULL,
+    -- COSE algorithm: -7 (ES256), -8 (EdDSA), -257 (RS256)
+
+    sign_count      BIGINT NOT NULL DEFAULT 0,
+    -- Last signature counter seen; 0 if the authenticator does not count
+
+    transports      TEXT[] NOT NULL DEFAULT '{}',
+    -- Hints for the browser: 'usb', 'nfc', 'ble', 'internal', 'hybrid'
+
+    name            VARCHAR(100) NOT NULL,
+    -- Label shown in the user's passkey list
+
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at    TIMESTAMPTZ
+);
+
+CREATE INDEX idx_webauthn_credentials_user ON webauthn_credentials(user_id);
+
+-- =============================================================================
+-- Pending ceremonies
+-- =============================================================================
+-- One row per begin call, consumed (deleted) by the matching finish call
+
+CREATE TABLE webauthn_ceremonies (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id     UUID REFERENCES users(id) ON DELETE CASCADE,
+    -- Registering user, or the user named at login (NULL for discoverable login)
+
+    purpose     VARCHAR(20) NOT NULL,
+    -- 'registration' | 'authentication'
+
+    challenge   BYTEA NOT NULL,
+
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at  TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_webauthn_ceremonies_expires ON webauthn_ceremonies(expires_at);
@@ -0,0 +1,2 @@
+DELETE FROM action_tokens WHERE purpose = 'magic_link';
+ALTER TABLE action_tokens DROP COLUMN IF EXISTS binding_hash;
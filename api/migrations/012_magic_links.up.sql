@@ -0,0 +1,9 @@
+-- =============================================================================
+-- MIGRATION 012: Device-Bound Action Tokens
+-- =============================================================================
+-- Magic sign-in links may only be redeemed by the browser that asked for them.
+-- That browser holds a random secret in a cookie; its SHA-256 is stored here.
+-- =============================================================================
+
+ALTER TABLE action_tokens ADD COLUMN binding_hash VARCHAR(64);
+-- NULL for tokens usable anywhere (email verification, password reset)
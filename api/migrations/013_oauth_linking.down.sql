@@ -0,0 +1,2 @@
+DELETE FROM oauth_states WHERE link_user_id IS NOT NULL;
+ALTER TABLE oauth_states DROP COLUMN IF EXISTS link_user_id;
@@ -0,0 +1,11 @@
+-- =============================================================================
+-- MIGRATION 013: OAuth Account Linking
+-- =============================================================================
+-- A signed-in user can start an OAuth flow to attach a provider to their own
+-- account. The pending authorization remembers who asked, so the callback
+-- links the identity instead of logging in.
+-- =============================================================================
+
+ALTER TABLE oauth_states
+    ADD COLUMN link_user_id UUID REFERENCES users(id) ON DELETE CASCADE;
+-- NULL for login flows
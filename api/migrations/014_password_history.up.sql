@@ -0,0 +1,24 @@
+-- =============================================================================
+-- MIGRATION 014: Password History
+-- =============================================================================
+-- Hashes of recently used passwords, so a password change cannot bring an
+-- old one back. The current password is always the newest row.
+-- =============================================================================
+
+CREATE TABLE password_history (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id         UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    password_hash   VARCHAR(255) NOT NULL,
+    -- Argon2 PHC string, same format as auth_methods.password_hash
+
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_password_history_user ON password_history(user_id, created_at DESC);
+
+-- Seed with the passwords already in use
+INSERT INTO password_history (user_id, password_hash, created_at)
+SELECT user_id, password_hash, updated_at
+FROM auth_methods
+WHERE provider = 'password' AND password_hash IS NOT NULL;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_user_sessions_fingerprint;
+DROP INDEX IF EXISTS idx_user_sessions_revoke_token;
+ALTER TABLE user_sessions DROP COLUMN IF EXISTS revoke_token_hash;
@@ -0,0 +1,14 @@
+-- =============================================================================
+-- MIGRATION 015: Session Revoke Links
+-- =============================================================================
+-- New-device sign-in alerts carry a link that revokes the new session without
+-- signing in. Only the SHA-256 of the link token is stored.
+-- =============================================================================
+
+ALTER TABLE user_sessions ADD COLUMN revoke_token_hash VARCHAR(64);
+
+CREATE UNIQUE INDEX idx_user_sessions_revoke_token ON user_sessions(revoke_token_hash)
+    WHERE revoke_token_hash IS NOT NULL;
+
+CREATE INDEX idx_user_sessions_fingerprint ON user_sessions(user_id, device_fingerprint)
+    WHERE device_fingerprint IS NOT NULL;
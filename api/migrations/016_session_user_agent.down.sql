@@ -0,0 +1,5 @@
+ALTER TABLE user_sessions
+    DROP COLUMN IF EXISTS os_version,
+    DROP COLUMN IF EXISTS os,
+    DROP COLUMN IF EXISTS browser_version,
+    DROP COLUMN IF EXISTS browser;
@@ -0,0 +1,12 @@
+-- =============================================================================
+-- MIGRATION 016: Parsed User Agents
+-- =============================================================================
+-- Sessions keep the browser and operating system parsed from the User-Agent.
+-- device_type now also holds 'smart_tv', 'bot', 'cli', 'app' and 'other'.
+-- =============================================================================
+
+ALTER TABLE user_sessions
+    ADD COLUMN browser         VARCHAR(64),
+    ADD COLUMN browser_version VARCHAR(32),
+    ADD COLUMN os              VARCHAR(64),
+    ADD COLUMN os_version      VARCHAR(32);
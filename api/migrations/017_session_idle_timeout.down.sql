@@ -0,0 +1,12 @@
+CREATE OR REPLACE FUNCTION touch_session()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.last_active_at = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trigger_touch_session
+    BEFORE UPDATE ON user_sessions
+    FOR EACH ROW
+    EXECUTE FUNCTION touch_session();
@@ -0,0 +1,10 @@
+-- =============================================================================
+-- MIGRATION 017: Session Idle Timeout
+-- =============================================================================
+-- last_active_at drives the idle timeout, so only real activity may move it:
+-- access-token use (touch) and refresh-token rotation set it explicitly.
+-- The trigger bumped it on every update, revocations included.
+-- =============================================================================
+
+DROP TRIGGER IF EXISTS trigger_touch_session ON user_sessions;
+DROP FUNCTION IF EXISTS touch_session();
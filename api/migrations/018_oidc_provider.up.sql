@@ -0,0 +1,100 @@
+-- =============================================================================
+-- MIGRATION 018: OpenID Connect Provider
+-- =============================================================================
+-- Internal apps sign users in through quax (authorization code + PKCE).
+-- Codes and refresh tokens hang off the user's quax session, so revoking
+-- or signing out of that session ends every app sign-in it produced.
+-- =============================================================================
+
+CREATE TABLE oidc_clients (
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    client_id           VARCHAR(64) NOT NULL UNIQUE,
+    client_secret_hash  VARCHAR(64),
+    -- SHA-256 (hex) of the secret; NULL = public client (PKCE only)
+
+    name                VARCHAR(255) NOT NULL,
+    redirect_uris       TEXT[] NOT NULL DEFAULT '{}',
+    -- Exact-match allow list
+
+    allowed_scopes      TEXT[] NOT NULL DEFAULT '{openid,profile,email}',
+
+    require_consent     BOOLEAN NOT NULL DEFAULT TRUE,
+    -- FALSE for first-party apps the user never has to approve
+
+    is_active           BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by          UUID REFERENCES users(id) ON DELETE SET NULL,
+
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_oidc_clients_updated_at
+    BEFORE UPDATE ON oidc_clients
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- =============================================================================
+-- Authorization codes: one per approved /authorize, consumed (deleted) by /token
+-- =============================================================================
+CREATE TABLE oidc_authorization_codes (
+    code_hash       VARCHAR(64) PRIMARY KEY,
+    -- SHA-256 (hex) of the code handed to the client
+
+    client_id       UUID NOT NULL REFERENCES oidc_clients(id) ON DELETE CASCADE,
+    user_id         UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_id      VARCHAR(64) NOT NULL REFERENCES user_sessions(session_id) ON DELETE CASCADE,
+
+    redirect_uri    TEXT NOT NULL,
+    scope           TEXT NOT NULL,
+    code_challenge  VARCHAR(128) NOT NULL,
+    -- PKCE S256 challenge (RFC 7636)
+    nonce           VARCHAR(255),
+    auth_time       BIGINT NOT NULL,
+
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at      TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_oidc_codes_expires ON oidc_authorization_codes(expires_at);
+
+-- =============================================================================
+-- Consent: scopes a user has approved for a client
+-- =============================================================================
+CREATE TABLE oidc_consents (
+    user_id         UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    client_id       UUID NOT NULL REFERENCES oidc_clients(id) ON DELETE CASCADE,
+    scopes          TEXT[] NOT NULL DEFAULT '{}',
+
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (user_id, client_id)
+);
+
+CREATE TRIGGER update_oidc_consents_updated_at
+    BEFORE UPDATE ON oidc_consents
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- =============================================================================
+-- Refresh tokens: rotated on every use, valid only while the session is live
+-- =============================================================================
+CREATE TABLE oidc_refresh_tokens (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    token_hash      VARCHAR(64) NOT NULL UNIQUE,
+
+    client_id       UUID NOT NULL REFERENCES oidc_clients(id) ON DELETE CASCADE,
+    user_id         UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_id      VARCHAR(64) NOT NULL REFERENCES user_sessions(session_id) ON DELETE CASCADE,
+
+    scope           TEXT NOT NULL,
+    auth_time       BIGINT NOT NULL,
+
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at      TIMESTAMPTZ NOT NULL,
+    revoked_at      TIMESTAMPTZ
+    -- Set on rotation; presenting a revoked token again is treated as theft
+);
+
+CREATE INDEX idx_oidc_refresh_tokens_session ON oidc_refresh_tokens(session_id, client_id);
@@ -0,0 +1,66 @@
+-- =============================================================================
+-- MIGRATION 019: Roles and Permissions (RBAC)
+-- =============================================================================
+-- users.role names a row in `roles`; what a role may do is the set of
+-- permission strings in `role_permissions`. `admin` implicitly holds every
+-- permission and has no rows here.
+-- =============================================================================
+
+CREATE TABLE permissions (
+    name            VARCHAR(100) PRIMARY KEY,
+    -- "<resource>:<action>", e.g. 'users:write'
+    description     TEXT NOT NULL
+);
+
+INSERT INTO permissions (name, description) VALUES
+    ('users:read',         'List users, their sessions and security events'),
+    ('users:write',        'Change user roles and lift lockouts'),
+    ('users:impersonate',  'Act as a user'),
+    ('stats:read',         'View dashboard statistics'),
+    ('logs:write',         'Change the log level at runtime'),
+    ('api_keys:read',      'View API keys'),
+    ('api_keys:write',     'Create, change, rotate and delete API keys'),
+    ('oidc_clients:read',  'View OIDC clients'),
+    ('oidc_clients:write', 'Register, change and delete OIDC clients'),
+    ('roles:read',         'View roles and permissions'),
+    ('roles:write',        'Create, change and delete custom roles');
+
+CREATE TABLE roles (
+    name            VARCHAR(20) PRIMARY KEY,
+    description     TEXT,
+
+    is_system       BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Built-in roles cannot be deleted; admin cannot be changed at all
+
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_roles_updated_at
+    BEFORE UPDATE ON roles
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+INSERT INTO roles (name, description, is_system) VALUES
+    ('admin',     'Full access',                          TRUE),
+    ('moderator', 'Reviews users and activity',           TRUE),
+    ('user',      'Regular account, no admin access',     TRUE);
+
+-- Any other role already assigned keeps working (with no permissions)
+INSERT INTO roles (name)
+SELECT DISTINCT role FROM users
+ON CONFLICT (name) DO NOTHING;
+
+CREATE TABLE role_permissions (
+    role            VARCHAR(20) NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    permission      VARCHAR(100) NOT NULL REFERENCES permissions(name) ON DELETE CASCADE,
+    PRIMARY KEY (role, permission)
+);
+
+INSERT INTO role_permissions (role, permission) VALUES
+    ('moderator', 'users:read'),
+    ('moderator', 'stats:read');
+
+-- A role in use cannot be deleted
+ALTER TABLE users
+    ADD CONSTRAINT fk_users_role FOREIGN KEY (role) REFERENCES roles(name);
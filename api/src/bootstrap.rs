@@ -6,20 +6,25 @@ use crate::{
         admin::api_key::{repository::ApiKeyRepositoryImpl, service::ApiKeyService},
         auth::{
             auth_method::{AuthMethodRepositoryImpl, AuthMethodService},
+            mfa::{MfaRepositoryImpl, MfaService},
+            password_policy::{PasswordHistoryRepositoryImpl, PasswordPolicy},
+            security_event::{SecurityEventRepositoryImpl, SecurityEventService},
             service::AuthService,
             session::{SessionRepositoryImpl, SessionService},
+            throttle::{LoginThrottle, PgLoginAttemptStore},
+            utils::{JwtKeys, PasswordHasher},
         },
         user::{
             UserProfileRepository, UserProfileRepositoryImpl, UserRepository, UserRepositoryImpl,
         },
     },
-    infrastructure::{config::Config, persistence::Database},
+    infrastructure::{config::Config, geoip::GeoIp, persistence::Database},
 };
 use std::sync::Arc;
 
 /// Bootstrap the application
 /// - Create admin user from env if no admin exists
-pub async fn bootstrap(db: &Database, config: &Config) -> eyre::Result<()> {
+pub async fn bootstrap(db: &Database, config: &Config, jwt_keys: Arc<JwtKeys>) -> eyre::Result<()> {
     tracing::info!("🚀 Running bootstrap checks...");
 
     // Check if bootstrap is enabled
@@ -64,8 +69,32 @@ pub async fn bootstrap(db: &Database, config: &Config) -> eyre::Result<()> {
     let session_repo = Arc::new(SessionRepositoryImpl::new());
 
     // Create services
-    let auth_method_service = AuthMethodService::new(db.clone(), auth_method_repo);
-    let session_service = SessionService::new(db.clone(), session_repo);
+    let password_hasher = PasswordHasher::from_config(&config.password_hash)
+        .map_err(|e| eyre::eyre!("Invalid password hashing settings: {e}"))?;
+    let password_policy = PasswordPolicy::new(
+        db.clone(),
+        Arc::new(PasswordHistoryRepositoryImpl::new()),
+        password_hasher.clone(),
+        Arc::new(config.clone()),
+    )?;
+    let auth_method_service = AuthMethodService::new(
+        db.clone(),
+        auth_method_repo,
+        password_hasher,
+        password_policy,
+    );
+    // The admin is created without a session, so nothing needs locating
+    let session_service = SessionService::new(
+        db.clone(),
+        session_repo,
+        Arc::new(config.clone()),
+        GeoIp::disabled(),
+    );
+    let mfa_service = MfaService::new(
+        db.clone(),
+        Arc::new(MfaRepositoryImpl::new()),
+        Arc::new(config.clone()),
+    );
 
     let auth_service = AuthService::new(
         db.clone(),
@@ -75,23 +104,35 @@ pub async fn bootstrap(db: &Database, config: &Config) -> eyre::Result<()> {
         Arc::new(config.clone()),
         None,
         session_service,
+        mfa_service,
+        SecurityEventService::new(
+            db.clone(),
+            Arc::new(SecurityEventRepositoryImpl::new()),
+            GeoIp::disabled(),
+        ),
+        // The admin is created without a session, so there is nothing to alert
+        None,
+        LoginThrottle::new(
+            Arc::new(PgLoginAttemptStore::new(db.clone())),
+            Arc::new(config.clone()),
+        ),
+        jwt_keys,
     );
 
-    // Create admin user using the new register signature
+    // Create admin account (no session, nobody is signing in here)
     let admin_id = match auth_service
-        .register(
+        .create_account(
             &admin_email,
             Some(&admin_username),
             &admin_password,
             Some(&admin_name),
-            None,
         )
         .await
     {
-        Ok((auth_response, _)) => {
+        Ok(user) => {
             // Promote to admin by updating role directly in DB
             sqlx::query("UPDATE users SET role = 'admin' WHERE id = $1")
-                .bind(auth_response.user.id)
+                .bind(user.id)
                 .execute(db.pool())
                 .await?;
 
@@ -106,7 +147,7 @@ pub async fn bootstrap(db: &Database, config: &Config) -> eyre::Result<()> {
             tracing::info!("╚════════════════════════════════════════════════════════════╝");
             tracing::info!("");
 
-            Some(auth_response.user.id)
+            Some(user.id)
         }
         Err(e) => {
             tracing::error!("❌ Failed to create bootstrap admin: {}", e);
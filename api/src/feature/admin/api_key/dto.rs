@@ -18,6 +18,7 @@ pub struct UpdateApiKey {
     pub name: Option<String>,
     pub scopes: Option<Vec<String>>,
     pub is_active: Option<bool>,
+    pub rate_limit_rpm: Option<i32>,
 }
 
 /// API Key response (without sensitive hash)
@@ -27,7 +28,9 @@ pub struct ApiKeyResponse {
     pub name: String,
     pub scopes: Vec<String>,
     pub created_by: Option<Uuid>,
+    pub rate_limit_rpm: Option<i32>,
     pub last_used_at: Option<DateTime<Utc>>,
+    pub use_count: i32,
     pub expires_at: Option<DateTime<Utc>>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
@@ -40,7 +43,9 @@ impl From<ApiKey> for ApiKeyResponse {
             name: key.name,
             scopes: key.scopes,
             created_by: key.created_by,
+            rate_limit_rpm: key.rate_limit_rpm,
             last_used_at: key.last_used_at,
+            use_count: key.use_count.unwrap_or(0),
             expires_at: key.expires_at,
             is_active: key.is_active,
             created_at: key.created_at,
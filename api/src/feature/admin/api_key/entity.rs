@@ -11,7 +11,10 @@ pub struct ApiKey {
     pub key_hash: String,
     pub scopes: Vec<String>,
     pub created_by: Option<Uuid>,
+    /// Requests per minute through `api_key_middleware`; NULL = unlimited
+    pub rate_limit_rpm: Option<i32>,
     pub last_used_at: Option<DateTime<Utc>>,
+    pub use_count: Option<i32>,
     pub expires_at: Option<DateTime<Utc>>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
@@ -7,7 +7,10 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
-    infrastructure::web::response::{ApiError, ApiResult, ApiSuccess, codes::generic},
+    infrastructure::web::response::{
+        ApiError, ApiResult, ApiSuccess,
+        codes::{generic, validation},
+    },
     state::AppState,
 };
 
@@ -100,6 +103,13 @@ pub async fn update_key(
     Path(id): Path<Uuid>,
     Json(req): Json<UpdateApiKey>,
 ) -> ApiResult<ApiKeyResponse> {
+    if req.rate_limit_rpm.is_some_and(|rpm| rpm < 1) {
+        return Err(ApiError::default()
+            .with_code(StatusCode::BAD_REQUEST)
+            .with_error_code(validation::INVALID_INPUT)
+            .with_message("rate_limit_rpm must be at least 1"));
+    }
+
     let service = make_service(&state);
 
     let key = service
@@ -60,7 +60,8 @@ pub trait ApiKeyRepository: Send + Sync {
 
     async fn delete(&self, pool: &PgPool, id: Uuid) -> Result<bool, ApiKeyError>;
 
-    async fn update_last_used(&self, pool: &PgPool, id: Uuid) -> Result<(), ApiKeyError>;
+    /// Stamp `last_used_at` and bump `use_count`
+    async fn record_use(&self, pool: &PgPool, id: Uuid) -> Result<(), ApiKeyError>;
 }
 
 #[derive(Debug, Clone)]
@@ -157,13 +158,15 @@ impl ApiKeyRepository for ApiKeyRepositoryImpl {
              SET name = COALESCE($1, name),
                  scopes = COALESCE($2, scopes),
                  is_active = COALESCE($3, is_active),
+                 rate_limit_rpm = COALESCE($4, rate_limit_rpm),
                  updated_at = NOW()
-             WHERE id = $4
+             WHERE id = $5
              RETURNING *",
         )
         .bind(&payload.name)
         .bind(&payload.scopes)
         .bind(payload.is_active)
+        .bind(payload.rate_limit_rpm)
         .bind(id)
         .fetch_optional(pool)
         .await?;
@@ -180,8 +183,10 @@ impl ApiKeyRepository for ApiKeyRepositoryImpl {
         Ok(result.rows_affected() > 0)
     }
 
-    async fn update_last_used(&self, pool: &PgPool, id: Uuid) -> Result<(), ApiKeyError> {
-        sqlx::query("UPDATE api_keys SET last_used_at = NOW() WHERE id = $1")
+    async fn record_use(&self, pool: &PgPool, id: Uuid) -> Result<(), ApiKeyError> {
+        sqlx::query(
+            "UPDATE api_keys SET last_used_at = NOW(), use_count = COALESCE(use_count, 0) + 1 WHERE id = $1",
+        )
             .bind(id)
             .execute(pool)
             .await?;
@@ -1,24 +1,53 @@
 use axum::{
     Router, middleware,
-    routing::{delete, get, patch, post},
+    routing::{get, patch, post},
 };
 
 use crate::{
-    infrastructure::web::middleware::{admin_middleware, auth_middleware},
+    feature::auth::rbac::permissions,
+    infrastructure::web::middleware::{
+        auth_middleware, forbid_impersonation, require_permission, require_recent_auth,
+    },
     state::AppState,
 };
 
 use super::handler;
 
 pub fn api_key_routes() -> Router<AppState> {
+    let permission = |p| middleware::from_fn_with_state(p, require_permission);
+
     Router::new()
-        .route("/", get(handler::list_keys))
-        .route("/", post(handler::create_key))
-        .route("/{id}", get(handler::get_key))
-        .route("/{id}", patch(handler::update_key))
-        .route("/{id}", delete(handler::delete_key))
-        .route("/{id}/revoke", post(handler::revoke_key))
-        .route("/{id}/refresh", post(handler::refresh_key))
-        .route_layer(middleware::from_fn(admin_middleware))
+        .route(
+            "/",
+            get(handler::list_keys).route_layer(permission(permissions::API_KEYS_READ)),
+        )
+        .route(
+            "/",
+            post(handler::create_key)
+                .route_layer(middleware::from_fn(require_recent_auth))
+                .route_layer(middleware::from_fn(forbid_impersonation))
+                .route_layer(permission(permissions::API_KEYS_WRITE)),
+        )
+        .route(
+            "/{id}",
+            get(handler::get_key).route_layer(permission(permissions::API_KEYS_READ)),
+        )
+        .route(
+            "/{id}",
+            patch(handler::update_key)
+                .delete(handler::delete_key)
+                .route_layer(permission(permissions::API_KEYS_WRITE)),
+        )
+        .route(
+            "/{id}/revoke",
+            post(handler::revoke_key).route_layer(permission(permissions::API_KEYS_WRITE)),
+        )
+        .route(
+            "/{id}/refresh",
+            post(handler::refresh_key)
+                .route_layer(middleware::from_fn(require_recent_auth))
+                .route_layer(middleware::from_fn(forbid_impersonation))
+                .route_layer(permission(permissions::API_KEYS_WRITE)),
+        )
         .route_layer(middleware::from_fn(auth_middleware))
 }
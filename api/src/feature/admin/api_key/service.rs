@@ -66,7 +66,8 @@ impl ApiKeyService {
         })
     }
 
-    /// Validate an API key
+    /// Validate an API key. Does not count as a use; call `record_use` once
+    /// the request is admitted.
     pub async fn validate_key(&self, plain_key: &str) -> Result<ApiKey, ApiKeyError> {
         // Hash the provided key
         let key_hash = format!("{:x}", md5::compute(plain_key));
@@ -78,21 +79,24 @@ impl ApiKeyService {
             .await?
             .ok_or(ApiKeyError::InvalidKey)?;
 
-        // Check if active
-        if !key.is_active {
-            return Err(ApiKeyError::Revoked);
-        }
+        ensure_usable(&key)?;
 
-        // Check expiration
-        if let Some(expires_at) = key.expires_at
-            && Utc::now() > expires_at
-        {
-            return Err(ApiKeyError::Expired);
-        }
+        Ok(key)
+    }
 
-        // Update last used
-        self.repo.update_last_used(self.db.pool(), key.id).await?;
+    /// Update last used and use count
+    pub async fn record_use(&self, id: Uuid) -> Result<(), ApiKeyError> {
+        self.repo.record_use(self.db.pool(), id).await
+    }
 
+    /// Key by ID, as long as it is still active and unexpired
+    pub async fn find_usable(&self, id: Uuid) -> Result<ApiKey, ApiKeyError> {
+        let key = self
+            .repo
+            .find_by_id(self.db.pool(), id)
+            .await?
+            .ok_or(ApiKeyError::NotFound)?;
+        ensure_usable(&key)?;
         Ok(key)
     }
 
@@ -133,6 +137,7 @@ impl ApiKeyService {
             name: None,
             scopes: None,
             is_active: Some(false),
+            rate_limit_rpm: None,
         };
         self.update_key(id, &payload).await
     }
@@ -168,3 +173,17 @@ impl ApiKeyService {
         .await
     }
 }
+
+fn ensure_usable(key: &ApiKey) -> Result<(), ApiKeyError> {
+    if !key.is_active {
+        return Err(ApiKeyError::Revoked);
+    }
+
+    if let Some(expires_at) = key.expires_at
+        && Utc::now() > expires_at
+    {
+        return Err(ApiKeyError::Expired);
+    }
+
+    Ok(())
+}
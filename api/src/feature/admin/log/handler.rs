@@ -10,7 +10,7 @@ use crate::{
 /// POST /api/v1/admin/log/level
 ///
 /// Dynamically change log level at runtime without restart.
-/// Protected: requires valid JWT with Admin role.
+/// Protected: requires the `logs:write` permission.
 pub async fn set_log_level(
     State(state): State<AppState>,
     Json(req): Json<SetLogLevelRequest>,
@@ -1,5 +1,7 @@
 pub mod api_key;
 pub mod log;
+pub mod oidc_client;
+pub mod role;
 pub mod routes;
 pub mod stats;
 pub mod user;
@@ -0,0 +1,156 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    feature::auth::{
+        AuthUser,
+        oidc::{CreateOidcClient, OidcClient, OidcClientWithSecret, OidcError, UpdateOidcClient},
+    },
+    infrastructure::web::response::{
+        ApiError, ApiResult, ApiSuccess,
+        codes::{generic, validation},
+    },
+    state::AppState,
+};
+
+/// Query params for listing clients
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+fn client_error(e: OidcError, action: &str) -> ApiError {
+    match e {
+        OidcError::InvalidRequest(message) => ApiError::default()
+            .with_code(StatusCode::BAD_REQUEST)
+            .with_error_code(validation::INVALID_INPUT)
+            .with_message(message),
+        e => ApiError::default()
+            .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+            .with_error_code(generic::INTERNAL)
+            .with_message(format!("Failed to {action} OIDC client: {e}")),
+    }
+}
+
+fn not_found() -> ApiError {
+    ApiError::default()
+        .with_code(StatusCode::NOT_FOUND)
+        .with_error_code(generic::NOT_FOUND)
+        .with_message("OIDC client not found")
+}
+
+/// GET /api/v1/admin/oidc-clients - List registered clients
+pub async fn list_clients(
+    State(state): State<AppState>,
+    Query(query): Query<ListQuery>,
+) -> ApiResult<Vec<OidcClient>> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let clients = state
+        .oidc_service
+        .list_clients(limit, offset)
+        .await
+        .map_err(|e| client_error(e, "list"))?;
+
+    Ok(ApiSuccess::default()
+        .with_data(clients)
+        .with_message("OIDC clients retrieved"))
+}
+
+/// POST /api/v1/admin/oidc-clients - Register a client
+pub async fn create_client(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<CreateOidcClient>,
+) -> ApiResult<OidcClientWithSecret> {
+    let client = state
+        .oidc_service
+        .register_client(&req, Some(auth_user.user_id))
+        .await
+        .map_err(|e| client_error(e, "create"))?;
+
+    let message = if client.client_secret.is_some() {
+        "OIDC client registered - save the secret, it won't be shown again!"
+    } else {
+        "OIDC client registered"
+    };
+
+    Ok(ApiSuccess::default()
+        .with_code(StatusCode::CREATED)
+        .with_data(client)
+        .with_message(message))
+}
+
+/// GET /api/v1/admin/oidc-clients/:id - Get a client
+pub async fn get_client(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<OidcClient> {
+    let client = state
+        .oidc_service
+        .get_client(id)
+        .await
+        .map_err(|e| client_error(e, "get"))?
+        .ok_or_else(not_found)?;
+
+    Ok(ApiSuccess::default()
+        .with_data(client)
+        .with_message("OIDC client retrieved"))
+}
+
+/// PATCH /api/v1/admin/oidc-clients/:id - Update a client
+pub async fn update_client(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateOidcClient>,
+) -> ApiResult<OidcClient> {
+    let client = state
+        .oidc_service
+        .update_client(id, &req)
+        .await
+        .map_err(|e| client_error(e, "update"))?
+        .ok_or_else(not_found)?;
+
+    Ok(ApiSuccess::default()
+        .with_data(client)
+        .with_message("OIDC client updated"))
+}
+
+/// DELETE /api/v1/admin/oidc-clients/:id - Delete a client and every grant it holds
+pub async fn delete_client(State(state): State<AppState>, Path(id): Path<Uuid>) -> ApiResult<()> {
+    let deleted = state
+        .oidc_service
+        .delete_client(id)
+        .await
+        .map_err(|e| client_error(e, "delete"))?;
+
+    if !deleted {
+        return Err(not_found());
+    }
+
+    Ok(ApiSuccess::default().with_message("OIDC client deleted"))
+}
+
+/// POST /api/v1/admin/oidc-clients/:id/rotate-secret - Issue a new client secret
+pub async fn rotate_secret(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<OidcClientWithSecret> {
+    let client = state
+        .oidc_service
+        .rotate_client_secret(id)
+        .await
+        .map_err(|e| client_error(e, "rotate the secret of"))?
+        .ok_or_else(not_found)?;
+
+    Ok(ApiSuccess::default()
+        .with_data(client)
+        .with_message("Client secret rotated - save it, it won't be shown again!"))
+}
@@ -0,0 +1,49 @@
+use axum::{
+    Router, middleware,
+    routing::{get, patch, post},
+};
+
+use crate::{
+    feature::auth::rbac::permissions,
+    infrastructure::web::middleware::{
+        auth_middleware, forbid_impersonation, require_permission, require_recent_auth,
+    },
+    state::AppState,
+};
+
+use super::handler;
+
+pub fn oidc_client_routes() -> Router<AppState> {
+    let permission = |p| middleware::from_fn_with_state(p, require_permission);
+
+    Router::new()
+        .route(
+            "/",
+            get(handler::list_clients).route_layer(permission(permissions::OIDC_CLIENTS_READ)),
+        )
+        .route(
+            "/",
+            post(handler::create_client)
+                .route_layer(middleware::from_fn(require_recent_auth))
+                .route_layer(middleware::from_fn(forbid_impersonation))
+                .route_layer(permission(permissions::OIDC_CLIENTS_WRITE)),
+        )
+        .route(
+            "/{id}",
+            get(handler::get_client).route_layer(permission(permissions::OIDC_CLIENTS_READ)),
+        )
+        .route(
+            "/{id}",
+            patch(handler::update_client)
+                .delete(handler::delete_client)
+                .route_layer(permission(permissions::OIDC_CLIENTS_WRITE)),
+        )
+        .route(
+            "/{id}/rotate-secret",
+            post(handler::rotate_secret)
+                .route_layer(middleware::from_fn(require_recent_auth))
+                .route_layer(middleware::from_fn(forbid_impersonation))
+                .route_layer(permission(permissions::OIDC_CLIENTS_WRITE)),
+        )
+        .route_layer(middleware::from_fn(auth_middleware))
+}
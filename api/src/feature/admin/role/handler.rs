@@ -0,0 +1,158 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+use crate::{
+    feature::auth::{
+        AuthUser,
+        rbac::{CreateRole, Permission, RbacError, RoleDefinition, UpdateRole},
+    },
+    infrastructure::web::response::{
+        ApiError, ApiResult, ApiSuccess,
+        codes::{generic, validation},
+    },
+    state::AppState,
+};
+
+fn role_error(e: RbacError, action: &str) -> ApiError {
+    let (code, error_code) = match e {
+        RbacError::InvalidName | RbacError::UnknownPermission(_) => {
+            (StatusCode::BAD_REQUEST, validation::INVALID_INPUT)
+        }
+        RbacError::NotFound => (StatusCode::NOT_FOUND, generic::NOT_FOUND),
+        RbacError::AlreadyExists | RbacError::InUse => (StatusCode::CONFLICT, generic::CONFLICT),
+        RbacError::SystemRole | RbacError::Escalation => {
+            (StatusCode::FORBIDDEN, generic::FORBIDDEN)
+        }
+        RbacError::Database(_) => {
+            return ApiError::default()
+                .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+                .with_error_code(generic::INTERNAL)
+                .with_message(format!("Failed to {action} role: {e}"));
+        }
+    };
+
+    ApiError::default()
+        .with_code(code)
+        .with_error_code(error_code)
+        .with_message(e.to_string())
+}
+
+/// GET /api/v1/admin/roles - List roles with their permissions
+pub async fn list_roles(State(state): State<AppState>) -> ApiResult<Vec<RoleDefinition>> {
+    let roles = state
+        .rbac_service
+        .list_roles()
+        .await
+        .map_err(|e| role_error(e, "list"))?;
+
+    Ok(ApiSuccess::default()
+        .with_data(roles)
+        .with_message("Roles retrieved"))
+}
+
+/// GET /api/v1/admin/roles/permissions - Permissions that can be granted
+pub async fn list_permissions(State(state): State<AppState>) -> ApiResult<Vec<Permission>> {
+    let permissions = state
+        .rbac_service
+        .list_permissions()
+        .await
+        .map_err(|e| role_error(e, "list permissions of"))?;
+
+    Ok(ApiSuccess::default()
+        .with_data(permissions)
+        .with_message("Permissions retrieved"))
+}
+
+/// POST /api/v1/admin/roles - Create a custom role
+pub async fn create_role(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<CreateRole>,
+) -> ApiResult<RoleDefinition> {
+    let role = state
+        .rbac_service
+        .create_role(&auth_user.roles, &req)
+        .await
+        .map_err(|e| role_error(e, "create"))?;
+
+    tracing::info!(
+        target: "security",
+        admin_id = %auth_user.user_id,
+        role = %role.name,
+        permissions = ?role.permissions,
+        "Role created"
+    );
+
+    Ok(ApiSuccess::default()
+        .with_code(StatusCode::CREATED)
+        .with_data(role)
+        .with_message("Role created"))
+}
+
+/// GET /api/v1/admin/roles/:name - Get a role
+pub async fn get_role(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> ApiResult<RoleDefinition> {
+    let role = state
+        .rbac_service
+        .get_role(&name)
+        .await
+        .map_err(|e| role_error(e, "get"))?
+        .ok_or_else(|| role_error(RbacError::NotFound, "get"))?;
+
+    Ok(ApiSuccess::default()
+        .with_data(role)
+        .with_message("Role retrieved"))
+}
+
+/// PATCH /api/v1/admin/roles/:name - Change a role's description or permissions
+pub async fn update_role(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(name): Path<String>,
+    Json(req): Json<UpdateRole>,
+) -> ApiResult<RoleDefinition> {
+    let role = state
+        .rbac_service
+        .update_role(&auth_user.roles, &name, &req)
+        .await
+        .map_err(|e| role_error(e, "update"))?;
+
+    tracing::info!(
+        target: "security",
+        admin_id = %auth_user.user_id,
+        role = %role.name,
+        permissions = ?role.permissions,
+        "Role updated"
+    );
+
+    Ok(ApiSuccess::default()
+        .with_data(role)
+        .with_message("Role updated"))
+}
+
+/// DELETE /api/v1/admin/roles/:name - Delete a custom role no user holds
+pub async fn delete_role(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(name): Path<String>,
+) -> ApiResult<()> {
+    state
+        .rbac_service
+        .delete_role(&auth_user.roles, &name)
+        .await
+        .map_err(|e| role_error(e, "delete"))?;
+
+    tracing::info!(
+        target: "security",
+        admin_id = %auth_user.user_id,
+        role = %name,
+        "Role deleted"
+    );
+
+    Ok(ApiSuccess::default().with_message("Role deleted"))
+}
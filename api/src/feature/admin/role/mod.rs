@@ -0,0 +1,4 @@
+mod handler;
+pub mod routes;
+
+pub use routes::role_routes;
@@ -0,0 +1,48 @@
+use axum::{
+    Router, middleware,
+    routing::{get, patch, post},
+};
+
+use crate::{
+    feature::auth::rbac::permissions,
+    infrastructure::web::middleware::{
+        auth_middleware, forbid_impersonation, require_permission, require_recent_auth,
+    },
+    state::AppState,
+};
+
+use super::handler;
+
+pub fn role_routes() -> Router<AppState> {
+    let permission = |p| middleware::from_fn_with_state(p, require_permission);
+
+    Router::new()
+        .route(
+            "/",
+            get(handler::list_roles).route_layer(permission(permissions::ROLES_READ)),
+        )
+        .route(
+            "/permissions",
+            get(handler::list_permissions).route_layer(permission(permissions::ROLES_READ)),
+        )
+        .route(
+            "/",
+            post(handler::create_role)
+                .route_layer(middleware::from_fn(require_recent_auth))
+                .route_layer(middleware::from_fn(forbid_impersonation))
+                .route_layer(permission(permissions::ROLES_WRITE)),
+        )
+        .route(
+            "/{name}",
+            get(handler::get_role).route_layer(permission(permissions::ROLES_READ)),
+        )
+        .route(
+            "/{name}",
+            patch(handler::update_role)
+                .delete(handler::delete_role)
+                .route_layer(middleware::from_fn(require_recent_auth))
+                .route_layer(middleware::from_fn(forbid_impersonation))
+                .route_layer(permission(permissions::ROLES_WRITE)),
+        )
+        .route_layer(middleware::from_fn(auth_middleware))
+}
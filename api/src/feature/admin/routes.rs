@@ -4,18 +4,57 @@ use axum::{
 };
 
 use crate::{
-    infrastructure::web::middleware::{admin_middleware, auth_middleware},
+    feature::auth::rbac::permissions,
+    infrastructure::web::middleware::{
+        auth_middleware, forbid_impersonation, require_permission, require_recent_auth,
+    },
     state::AppState,
 };
 
 use super::{log, stats, user};
 
 pub fn admin_routes() -> Router<AppState> {
+    let permission = |p| middleware::from_fn_with_state(p, require_permission);
+
     Router::new()
-        .route("/log/level", post(log::handler::set_log_level))
-        .route("/users", get(user::handler::list_users))
-        .route("/users/{id}/role", post(user::handler::update_user_role))
-        .route("/stats", get(stats::handler::get_dashboard_stats))
-        .route_layer(middleware::from_fn(admin_middleware))
+        .route(
+            "/log/level",
+            post(log::handler::set_log_level).route_layer(permission(permissions::LOGS_WRITE)),
+        )
+        .route(
+            "/users",
+            get(user::handler::list_users).route_layer(permission(permissions::USERS_READ)),
+        )
+        .route(
+            "/users/{id}/role",
+            post(user::handler::update_user_role)
+                .route_layer(middleware::from_fn(require_recent_auth))
+                .route_layer(permission(permissions::USERS_WRITE)),
+        )
+        .route(
+            "/users/{id}/unlock",
+            post(user::handler::unlock_user).route_layer(permission(permissions::USERS_WRITE)),
+        )
+        .route(
+            "/users/{id}/impersonate",
+            post(user::handler::impersonate_user)
+                .route_layer(middleware::from_fn(require_recent_auth))
+                .route_layer(middleware::from_fn(forbid_impersonation))
+                .route_layer(permission(permissions::USERS_IMPERSONATE)),
+        )
+        .route(
+            "/users/{id}/sessions",
+            get(user::handler::list_user_sessions).route_layer(permission(permissions::USERS_READ)),
+        )
+        .route(
+            "/users/{id}/security-events",
+            get(user::handler::list_user_security_events)
+                .route_layer(permission(permissions::USERS_READ)),
+        )
+        .route(
+            "/stats",
+            get(stats::handler::get_dashboard_stats)
+                .route_layer(permission(permissions::STATS_READ)),
+        )
         .route_layer(middleware::from_fn(auth_middleware))
 }
@@ -8,7 +8,7 @@ use crate::{
 
 /// GET /api/v1/admin/stats
 ///
-/// Get dashboard statistics (admin only).
+/// Get dashboard statistics (`stats:read`).
 pub async fn get_dashboard_stats(
     State(state): State<AppState>,
 ) -> ApiResult<DashboardStatsResponse> {
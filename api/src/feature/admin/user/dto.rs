@@ -14,8 +14,37 @@ pub struct AdminUserResponse {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Access token for acting as a user, from `POST /admin/users/:id/impersonate`
+#[derive(Debug, Serialize)]
+pub struct ImpersonationResponse {
+    pub access_token: String,
+    pub expires_in: i64,
+    pub user: AdminUserResponse,
+}
+
 /// Update user role request
 #[derive(Debug, Deserialize)]
 pub struct UpdateUserRoleRequest {
     pub role: String,
 }
+
+/// Session of a user, for admin
+#[derive(Debug, Serialize)]
+pub struct AdminSessionResponse {
+    pub id: Uuid,
+    pub device: Option<String>,
+    pub device_type: Option<String>,
+    pub browser: Option<String>,
+    pub browser_version: Option<String>,
+    pub os: Option<String>,
+    pub os_version: Option<String>,
+    pub ip: String,
+    /// From the GeoIP database, when one is configured
+    pub location: Option<String>,
+    pub country_code: Option<String>,
+    pub new_device: bool,
+    /// Admin who started the session to act as the user
+    pub impersonated_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub last_active_at: DateTime<Utc>,
+}
@@ -1,18 +1,27 @@
-use axum::{Extension, Json, extract::Path, extract::State, http::StatusCode};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
 use uuid::Uuid;
 
 use crate::{
     feature::{
-        admin::user::dto::{AdminUserResponse, UpdateUserRoleRequest},
-        auth::AuthUser,
+        admin::user::dto::{
+            AdminSessionResponse, AdminUserResponse, ImpersonationResponse, UpdateUserRoleRequest,
+        },
+        auth::{AuthUser, Role, security_event::SecurityEvent, session::DeviceInfo},
     },
     infrastructure::web::response::{ApiError, ApiResult, ApiSuccess, codes::generic},
     state::AppState,
 };
 
+/// Security events returned per request
+const SECURITY_EVENT_LIMIT: i64 = 100;
+
 /// GET /api/v1/admin/users
 ///
-/// List all users (admin only)
+/// List all users (`users:read`)
 pub async fn list_users(State(state): State<AppState>) -> ApiResult<Vec<AdminUserResponse>> {
     let users = state
         .admin_user_repo
@@ -43,8 +52,10 @@ pub async fn list_users(State(state): State<AppState>) -> ApiResult<Vec<AdminUse
 
 /// POST /api/v1/admin/users/:id/role
 ///
-/// Update a user's role (admin only).
-/// Cannot change your own role (prevents self-demotion).
+/// Assign a role (`users:write`). The caller must hold every permission of
+/// both the user's current role and the new one, and only admins manage
+/// admins. Cannot change your own role (prevents self-demotion). Roles ride
+/// in the tokens, so a change signs the user out everywhere.
 pub async fn update_user_role(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
@@ -53,11 +64,17 @@ pub async fn update_user_role(
 ) -> ApiResult<AdminUserResponse> {
     // Validate role
     let role = req.role.to_lowercase();
-    if role != "admin" && role != "user" {
+    let exists = state
+        .rbac_service
+        .get_role(&role)
+        .await
+        .map_err(|e| ApiError::default().log_only(e))?
+        .is_some();
+    if !exists {
         return Err(ApiError::default()
             .with_code(StatusCode::BAD_REQUEST)
             .with_error_code(generic::INVALID_INPUT)
-            .with_message("Role must be either 'admin' or 'user'"));
+            .with_message(format!("Unknown role '{}'", role)));
     }
 
     // Prevent changing own role
@@ -68,6 +85,31 @@ pub async fn update_user_role(
             .with_message("Cannot change your own role"));
     }
 
+    // No granting (or taking away) more than the caller holds
+    let current = state
+        .user_repo
+        .find_by_id(state.db.pool(), user_id)
+        .await
+        .map_err(|e| ApiError::default().log_only(e))?
+        .map(|u| u.role());
+    let new_role = Role::from(role.as_str());
+    for role in current.iter().chain([&new_role]) {
+        let covered = state
+            .rbac_service
+            .covers(&auth_user.roles, role)
+            .await
+            .map_err(|e| ApiError::default().log_only(e))?;
+        if !covered {
+            return Err(ApiError::default()
+                .with_code(StatusCode::FORBIDDEN)
+                .with_error_code(generic::FORBIDDEN)
+                .with_message(format!(
+                    "Managing the '{}' role needs permissions you do not hold",
+                    role
+                )));
+        }
+    }
+
     // Update the user's role
     let user = state
         .admin_user_repo
@@ -81,6 +123,16 @@ pub async fn update_user_role(
                 .with_message("User not found")
         })?;
 
+    // Old tokens still carry the old role
+    if current != Some(new_role) {
+        state
+            .auth_service
+            .session_service()
+            .revoke_all_sessions(user_id, "role_change")
+            .await
+            .map_err(|e| ApiError::default().log_only(e))?;
+    }
+
     let name = user.username.clone().unwrap_or_else(|| user.email.clone());
     Ok(ApiSuccess::default()
         .with_data(AdminUserResponse {
@@ -94,3 +146,183 @@ pub async fn update_user_role(
         })
         .with_message(format!("User role updated to '{}'", role)))
 }
+
+/// POST /api/v1/admin/users/:id/unlock
+///
+/// Lift a failed-login lockout and clear the account's attempt counters, for
+/// users whose role grants nothing the caller lacks.
+pub async fn unlock_user(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(user_id): Path<Uuid>,
+) -> ApiResult<()> {
+    let user = state
+        .user_repo
+        .find_by_id(state.db.pool(), user_id)
+        .await
+        .map_err(|e| ApiError::default().log_only(e))?
+        .ok_or_else(|| {
+            ApiError::default()
+                .with_code(StatusCode::NOT_FOUND)
+                .with_error_code(generic::NOT_FOUND)
+                .with_message("User not found")
+        })?;
+
+    let covered = state
+        .rbac_service
+        .covers(&auth_user.roles, &user.role())
+        .await
+        .map_err(|e| ApiError::default().log_only(e))?;
+    if !covered {
+        return Err(ApiError::default()
+            .with_code(StatusCode::FORBIDDEN)
+            .with_error_code(generic::FORBIDDEN)
+            .with_message("Cannot unlock a user with permissions you do not hold"));
+    }
+
+    state
+        .auth_service
+        .login_throttle()
+        .unlock(&user.email)
+        .await
+        .map_err(|e| ApiError::default().log_only(e))?;
+
+    Ok(ApiSuccess::default().with_message("Account unlocked"))
+}
+
+/// POST /api/v1/admin/users/:id/impersonate
+///
+/// Short-lived access token for acting as an active, non-admin user
+/// (`users:impersonate`) whose role grants nothing the caller lacks. The
+/// token cannot be refreshed and carries the admin in its `act` claim.
+/// Needs a recent authentication and is refused to impersonation tokens, so
+/// impersonations cannot be chained.
+pub async fn impersonate_user(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(user_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> ApiResult<ImpersonationResponse> {
+    if user_id == auth_user.user_id {
+        return Err(ApiError::default()
+            .with_code(StatusCode::FORBIDDEN)
+            .with_error_code(generic::FORBIDDEN)
+            .with_message("Cannot impersonate yourself"));
+    }
+
+    let user = state
+        .user_repo
+        .find_by_id(state.db.pool(), user_id)
+        .await
+        .map_err(|e| ApiError::default().log_only(e))?
+        .filter(|u| u.is_active)
+        .ok_or_else(|| {
+            ApiError::default()
+                .with_code(StatusCode::NOT_FOUND)
+                .with_error_code(generic::NOT_FOUND)
+                .with_message("User not found")
+        })?;
+
+    if user.role() == Role::Admin {
+        return Err(ApiError::default()
+            .with_code(StatusCode::FORBIDDEN)
+            .with_error_code(generic::FORBIDDEN)
+            .with_message("Cannot impersonate an administrator"));
+    }
+
+    // ...nor anyone whose role can do more than the caller
+    let covered = state
+        .rbac_service
+        .covers(&auth_user.roles, &user.role())
+        .await
+        .map_err(|e| ApiError::default().log_only(e))?;
+    if !covered {
+        return Err(ApiError::default()
+            .with_code(StatusCode::FORBIDDEN)
+            .with_error_code(generic::FORBIDDEN)
+            .with_message("Cannot impersonate a user with permissions you do not hold"));
+    }
+
+    let device_info = DeviceInfo::from_headers(&headers);
+    let token = state
+        .auth_service
+        .impersonate(&auth_user, &user, &device_info)
+        .await
+        .map_err(|e| ApiError::default().log_only(e))?;
+
+    let name = user.username.clone().unwrap_or_else(|| user.email.clone());
+    Ok(ApiSuccess::default()
+        .with_data(ImpersonationResponse {
+            access_token: token.access_token,
+            expires_in: token.expires_in,
+            user: AdminUserResponse {
+                id: user.id,
+                email: user.email,
+                username: user.username,
+                name,
+                role: user.role,
+                created_at: user.created_at,
+                updated_at: user.updated_at,
+            },
+        })
+        .with_message("Impersonation started"))
+}
+
+/// GET /api/v1/admin/users/:id/sessions
+///
+/// A user's active sessions with their GeoIP location.
+pub async fn list_user_sessions(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> ApiResult<Vec<AdminSessionResponse>> {
+    let sessions = state
+        .auth_service
+        .session_service()
+        .list_sessions(user_id)
+        .await
+        .map_err(|e| ApiError::default().log_only(e))?;
+
+    let sessions = sessions
+        .into_iter()
+        .map(|s| AdminSessionResponse {
+            id: s.id,
+            device: s.device_name,
+            device_type: s.device_type,
+            browser: s.browser,
+            browser_version: s.browser_version,
+            os: s.os,
+            os_version: s.os_version,
+            ip: s.ip_address,
+            location: s.location,
+            country_code: s.ip_country_code,
+            new_device: s.metadata.as_ref().is_some_and(|m| m.new_device),
+            impersonated_by: s.metadata.and_then(|m| m.0.impersonated_by),
+            created_at: s.created_at,
+            last_active_at: s.last_active_at,
+        })
+        .collect();
+
+    Ok(ApiSuccess::default()
+        .with_data(sessions)
+        .with_message("Sessions retrieved"))
+}
+
+/// GET /api/v1/admin/users/:id/security-events
+///
+/// A user's most recent security events, newest first. Events carrying an IP
+/// address include its GeoIP location.
+pub async fn list_user_security_events(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> ApiResult<Vec<SecurityEvent>> {
+    let events = state
+        .auth_service
+        .security_events()
+        .list_for_user(user_id, SECURITY_EVENT_LIMIT)
+        .await
+        .map_err(|e| ApiError::default().log_only(e))?;
+
+    Ok(ApiSuccess::default()
+        .with_data(events)
+        .with_message("Security events retrieved"))
+}
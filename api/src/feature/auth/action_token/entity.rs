@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// What a single-use token may be redeemed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionTokenPurpose {
+    EmailVerification,
+    PasswordReset,
+    MagicLink,
+}
+
+impl ActionTokenPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionTokenPurpose::EmailVerification => "email_verification",
+            ActionTokenPurpose::PasswordReset => "password_reset",
+            ActionTokenPurpose::MagicLink => "magic_link",
+        }
+    }
+}
+
+/// A token delivered out-of-band (usually by email). Only the SHA-256 hash of
+/// the token is stored; `used_at` is set when it is redeemed.
+#[derive(Debug, Clone, FromRow)]
+pub struct ActionToken {
+    pub token_hash: String,
+    pub user_id: Uuid,
+    pub purpose: String,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    /// SHA-256 of a secret held by the requesting browser, if the token is
+    /// only valid there
+    pub binding_hash: Option<String>,
+}
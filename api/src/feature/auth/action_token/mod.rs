@@ -0,0 +1,7 @@
+pub mod entity;
+pub mod repository;
+pub mod service;
+
+pub use entity::{ActionToken, ActionTokenPurpose};
+pub use repository::{ActionTokenRepository, ActionTokenRepositoryImpl};
+pub use service::ActionTokenService;
@@ -0,0 +1,186 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::entity::{ActionToken, ActionTokenPurpose};
+
+#[async_trait]
+pub trait ActionTokenRepository: Send + Sync {
+    /// Store a new token hash
+    #[allow(clippy::too_many_arguments)]
+    async fn create(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+        user_id: Uuid,
+        purpose: ActionTokenPurpose,
+        email: &str,
+        expires_at: DateTime<Utc>,
+        binding_hash: Option<&str>,
+    ) -> Result<ActionToken, sqlx::Error>;
+
+    /// Atomically mark an unused, unexpired token as used and return it.
+    /// Returns `None` if the token is unknown, expired, already used or bound
+    /// to a different binding.
+    async fn consume(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+        purpose: ActionTokenPurpose,
+        binding_hash: Option<&str>,
+    ) -> Result<Option<ActionToken>, sqlx::Error>;
+
+    /// Look up an unused, unexpired token without consuming it
+    async fn find_valid(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+        purpose: ActionTokenPurpose,
+        binding_hash: Option<&str>,
+    ) -> Result<Option<ActionToken>, sqlx::Error>;
+
+    /// Mark every outstanding token of one purpose for a user as used
+    async fn invalidate_for_user(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        purpose: ActionTokenPurpose,
+    ) -> Result<u64, sqlx::Error>;
+
+    /// When the most recent token of one purpose was issued for a user
+    async fn last_issued_at(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        purpose: ActionTokenPurpose,
+    ) -> Result<Option<DateTime<Utc>>, sqlx::Error>;
+
+    /// Delete expired tokens
+    async fn cleanup_expired(&self, pool: &PgPool) -> Result<u64, sqlx::Error>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ActionTokenRepositoryImpl;
+
+impl ActionTokenRepositoryImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl ActionTokenRepository for ActionTokenRepositoryImpl {
+    async fn create(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+        user_id: Uuid,
+        purpose: ActionTokenPurpose,
+        email: &str,
+        expires_at: DateTime<Utc>,
+        binding_hash: Option<&str>,
+    ) -> Result<ActionToken, sqlx::Error> {
+        sqlx::query_as::<_, ActionToken>(
+            r#"
+            INSERT INTO action_tokens (token_hash, user_id, purpose, email, expires_at, binding_hash)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(token_hash)
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .bind(email)
+        .bind(expires_at)
+        .bind(binding_hash)
+        .fetch_one(pool)
+        .await
+    }
+
+    async fn consume(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+        purpose: ActionTokenPurpose,
+        binding_hash: Option<&str>,
+    ) -> Result<Option<ActionToken>, sqlx::Error> {
+        sqlx::query_as::<_, ActionToken>(
+            r#"
+            UPDATE action_tokens
+            SET used_at = NOW()
+            WHERE token_hash = $1 AND purpose = $2 AND used_at IS NULL AND expires_at > NOW()
+              AND binding_hash IS NOT DISTINCT FROM $3
+            RETURNING *
+            "#,
+        )
+        .bind(token_hash)
+        .bind(purpose.as_str())
+        .bind(binding_hash)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn find_valid(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+        purpose: ActionTokenPurpose,
+        binding_hash: Option<&str>,
+    ) -> Result<Option<ActionToken>, sqlx::Error> {
+        sqlx::query_as::<_, ActionToken>(
+            r#"
+            SELECT * FROM action_tokens
+            WHERE token_hash = $1 AND purpose = $2 AND used_at IS NULL AND expires_at > NOW()
+              AND binding_hash IS NOT DISTINCT FROM $3
+            "#,
+        )
+        .bind(token_hash)
+        .bind(purpose.as_str())
+        .bind(binding_hash)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn invalidate_for_user(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        purpose: ActionTokenPurpose,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE action_tokens
+            SET used_at = NOW()
+            WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn last_issued_at(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        purpose: ActionTokenPurpose,
+    ) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT MAX(created_at) FROM action_tokens WHERE user_id = $1 AND purpose = $2",
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .fetch_one(pool)
+        .await
+    }
+
+    async fn cleanup_expired(&self, pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM action_tokens WHERE expires_at < NOW()")
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
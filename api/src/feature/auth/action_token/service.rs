@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    feature::auth::utils::{random_token, sha256_hex},
+    infrastructure::persistence::Database,
+};
+
+use super::{ActionToken, ActionTokenPurpose, ActionTokenRepository};
+
+/// Random bytes per token (256 bits)
+const TOKEN_BYTES: usize = 32;
+
+/// Issues and redeems single-use tokens sent to users by email
+#[derive(Clone)]
+pub struct ActionTokenService {
+    db: Database,
+    repo: Arc<dyn ActionTokenRepository>,
+}
+
+impl ActionTokenService {
+    pub fn new(db: Database, repo: Arc<dyn ActionTokenRepository>) -> Self {
+        Self { db, repo }
+    }
+
+    /// Issue a fresh token, invalidating any outstanding token of the same
+    /// purpose for the user. Returns the raw token — it is not stored.
+    ///
+    /// With a `binding`, the token can only be redeemed by presenting the same
+    /// value again (e.g. a cookie set on the requesting browser).
+    pub async fn issue(
+        &self,
+        user_id: Uuid,
+        purpose: ActionTokenPurpose,
+        email: &str,
+        ttl_secs: i64,
+        binding: Option<&str>,
+    ) -> Result<String, sqlx::Error> {
+        self.repo
+            .invalidate_for_user(self.db.pool(), user_id, purpose)
+            .await?;
+
+        let token = random_token(TOKEN_BYTES);
+        self.repo
+            .create(
+                self.db.pool(),
+                &sha256_hex(&token),
+                user_id,
+                purpose,
+                email,
+                Utc::now() + Duration::seconds(ttl_secs),
+                binding.map(sha256_hex).as_deref(),
+            )
+            .await?;
+
+        Ok(token)
+    }
+
+    /// Redeem a raw token. Returns `None` if it is unknown, expired, already
+    /// used, was issued for another purpose or `binding` does not match.
+    pub async fn redeem(
+        &self,
+        token: &str,
+        purpose: ActionTokenPurpose,
+        binding: Option<&str>,
+    ) -> Result<Option<ActionToken>, sqlx::Error> {
+        self.repo
+            .consume(
+                self.db.pool(),
+                &sha256_hex(token),
+                purpose,
+                binding.map(sha256_hex).as_deref(),
+            )
+            .await
+    }
+
+    /// Like [`redeem`](Self::redeem), but leaves the token usable. For checks
+    /// that must pass before the token is spent.
+    pub async fn peek(
+        &self,
+        token: &str,
+        purpose: ActionTokenPurpose,
+        binding: Option<&str>,
+    ) -> Result<Option<ActionToken>, sqlx::Error> {
+        self.repo
+            .find_valid(
+                self.db.pool(),
+                &sha256_hex(token),
+                purpose,
+                binding.map(sha256_hex).as_deref(),
+            )
+            .await
+    }
+
+    /// When the latest token of this purpose was issued to the user
+    pub async fn last_issued_at(
+        &self,
+        user_id: Uuid,
+        purpose: ActionTokenPurpose,
+    ) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        self.repo
+            .last_issued_at(self.db.pool(), user_id, purpose)
+            .await
+    }
+
+    /// Invalidate all outstanding tokens of this purpose for the user
+    pub async fn invalidate(
+        &self,
+        user_id: Uuid,
+        purpose: ActionTokenPurpose,
+    ) -> Result<u64, sqlx::Error> {
+        self.repo
+            .invalidate_for_user(self.db.pool(), user_id, purpose)
+            .await
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::entity::AuthMethod;
+
+/// A login method as shown to its owner
+#[derive(Debug, Serialize)]
+pub struct AuthMethodResponse {
+    pub id: Uuid,
+    pub provider: String,
+    pub is_primary: bool,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuthMethod> for AuthMethodResponse {
+    fn from(method: AuthMethod) -> Self {
+        Self {
+            id: method.id,
+            provider: method.provider,
+            is_primary: method.is_primary,
+            last_used_at: method.last_used_at,
+            created_at: method.created_at,
+        }
+    }
+}
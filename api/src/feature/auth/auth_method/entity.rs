@@ -11,6 +11,7 @@ pub enum AuthProvider {
     Github,
     Discord,
     Twitter,
+    Passkey,
 }
 
 impl AuthProvider {
@@ -21,6 +22,7 @@ impl AuthProvider {
             AuthProvider::Github => "github",
             AuthProvider::Discord => "discord",
             AuthProvider::Twitter => "twitter",
+            AuthProvider::Passkey => "passkey",
         }
     }
 }
@@ -35,6 +37,7 @@ impl TryFrom<&str> for AuthProvider {
             "github" => Ok(AuthProvider::Github),
             "discord" => Ok(AuthProvider::Discord),
             "twitter" => Ok(AuthProvider::Twitter),
+            "passkey" => Ok(AuthProvider::Passkey),
             _ => Err(()),
         }
     }
@@ -66,24 +69,12 @@ impl AuthMethod {
         self.provider == "password"
     }
 
-    pub fn is_oauth(&self) -> bool {
-        self.provider != "password"
+    pub fn is_passkey(&self) -> bool {
+        self.provider == "passkey"
     }
 
-    pub fn verify_password(&self, password: &str) -> Result<bool, argon2::password_hash::Error> {
-        use argon2::{
-            Argon2,
-            password_hash::{PasswordHash, PasswordVerifier},
-        };
-
-        let Some(hash) = &self.password_hash else {
-            return Ok(false);
-        };
-
-        let parsed = PasswordHash::new(hash)?;
-        Ok(Argon2::default()
-            .verify_password(password.as_bytes(), &parsed)
-            .is_ok())
+    pub fn is_oauth(&self) -> bool {
+        !self.is_password() && !self.is_passkey()
     }
 }
 
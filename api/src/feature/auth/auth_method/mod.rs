@@ -1,7 +1,9 @@
+pub mod dto;
 pub mod entity;
 pub mod repository;
 pub mod service;
 
+pub use dto::AuthMethodResponse;
 pub use entity::{AuthMethod, AuthProvider};
 pub use repository::{AuthMethodRepository, AuthMethodRepositoryError, AuthMethodRepositoryImpl};
 pub use service::AuthMethodService;
@@ -13,6 +13,9 @@ pub enum AuthMethodRepositoryError {
     #[error("Auth method already exists for this provider")]
     AlreadyExists,
 
+    #[error("Cannot remove the last login method")]
+    LastMethod,
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 }
@@ -83,6 +86,16 @@ pub trait AuthMethodRepository: Send + Sync {
     /// Delete auth method
     async fn delete(&self, pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error>;
 
+    /// Delete one of the user's auth methods unless it is their last one.
+    /// A removed primary method hands the flag to the most recently used
+    /// remaining method.
+    async fn delete_for_user(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        id: Uuid,
+    ) -> Result<(), AuthMethodRepositoryError>;
+
     /// Check if user has any auth methods
     async fn has_any(&self, pool: &PgPool, user_id: Uuid) -> Result<bool, sqlx::Error>;
 }
@@ -216,11 +229,12 @@ impl AuthMethodRepository for AuthMethodRepositoryImpl {
         pool: &PgPool,
         user_id: Uuid,
     ) -> Result<Vec<AuthMethod>, sqlx::Error> {
-        let methods =
-            sqlx::query_as::<_, AuthMethod>("SELECT * FROM auth_methods WHERE user_id = $1")
-                .bind(user_id)
-                .fetch_all(pool)
-                .await?;
+        let methods = sqlx::query_as::<_, AuthMethod>(
+            "SELECT * FROM auth_methods WHERE user_id = $1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
         Ok(methods)
     }
 
@@ -290,6 +304,53 @@ impl AuthMethodRepository for AuthMethodRepositoryImpl {
         Ok(result.rows_affected() > 0)
     }
 
+    async fn delete_for_user(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        id: Uuid,
+    ) -> Result<(), AuthMethodRepositoryError> {
+        let mut tx = pool.begin().await?;
+
+        // Lock the user's methods so concurrent unlinks cannot remove them all
+        let methods: Vec<(Uuid, bool)> =
+            sqlx::query_as("SELECT id, is_primary FROM auth_methods WHERE user_id = $1 FOR UPDATE")
+                .bind(user_id)
+                .fetch_all(&mut *tx)
+                .await?;
+
+        let Some(&(_, was_primary)) = methods.iter().find(|(method_id, _)| *method_id == id) else {
+            return Err(AuthMethodRepositoryError::NotFound);
+        };
+        if methods.len() <= 1 {
+            return Err(AuthMethodRepositoryError::LastMethod);
+        }
+
+        sqlx::query("DELETE FROM auth_methods WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        if was_primary {
+            sqlx::query(
+                r#"
+                UPDATE auth_methods SET is_primary = true
+                WHERE id = (
+                    SELECT id FROM auth_methods WHERE user_id = $1
+                    ORDER BY last_used_at DESC NULLS LAST, created_at
+                    LIMIT 1
+                )
+                "#,
+            )
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
     async fn has_any(&self, pool: &PgPool, user_id: Uuid) -> Result<bool, sqlx::Error> {
         let exists: bool =
             sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM auth_methods WHERE user_id = $1)")
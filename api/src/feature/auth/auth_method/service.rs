@@ -5,6 +5,7 @@ use crate::{
         entity::{AuthMethod, AuthProvider, CreateOAuthAuth, CreatePasswordAuth},
         repository::{AuthMethodRepository, AuthMethodRepositoryError},
     },
+    feature::auth::{password_policy::PasswordPolicy, utils::PasswordHasher},
     infrastructure::persistence::Database,
 };
 
@@ -12,11 +13,36 @@ use crate::{
 pub struct AuthMethodService {
     db: Database,
     repo: Arc<dyn AuthMethodRepository>,
+    hasher: PasswordHasher,
+    password_policy: PasswordPolicy,
 }
 
 impl AuthMethodService {
-    pub fn new(db: Database, repo: Arc<dyn AuthMethodRepository>) -> Self {
-        Self { db, repo }
+    pub fn new(
+        db: Database,
+        repo: Arc<dyn AuthMethodRepository>,
+        hasher: PasswordHasher,
+        password_policy: PasswordPolicy,
+    ) -> Self {
+        Self {
+            db,
+            repo,
+            hasher,
+            password_policy,
+        }
+    }
+
+    /// Policy new passwords must be checked against before they are stored
+    pub fn password_policy(&self) -> &PasswordPolicy {
+        &self.password_policy
+    }
+
+    /// Keep the new hash for the reuse check. The password itself is already
+    /// stored at this point, so a failure only costs history.
+    async fn remember_password(&self, user_id: uuid::Uuid, password_hash: &str) {
+        if let Err(e) = self.password_policy.remember(user_id, password_hash).await {
+            tracing::warn!("Failed to record password history: {e}");
+        }
     }
 
     pub async fn create_password_auth(
@@ -25,19 +51,25 @@ impl AuthMethodService {
         password: &str,
         is_primary: bool,
     ) -> Result<AuthMethod, AuthMethodRepositoryError> {
-        let password_hash = hash_password(password)
+        let password_hash = self
+            .hasher
+            .hash(password)
             .map_err(|_| AuthMethodRepositoryError::Database(sqlx::Error::RowNotFound))?;
 
-        self.repo
+        let method = self
+            .repo
             .create_password(
                 self.db.pool(),
                 CreatePasswordAuth {
                     user_id,
-                    password_hash,
+                    password_hash: password_hash.clone(),
                     is_primary,
                 },
             )
-            .await
+            .await?;
+
+        self.remember_password(user_id, &password_hash).await;
+        Ok(method)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -104,32 +136,105 @@ impl AuthMethodService {
 
     pub async fn update_password(
         &self,
-        auth_method_id: uuid::Uuid,
+        auth_method: &AuthMethod,
         new_password: &str,
     ) -> Result<bool, sqlx::Error> {
-        let password_hash = hash_password(new_password).map_err(|_| sqlx::Error::RowNotFound)?;
-        self.repo
-            .update_password(self.db.pool(), auth_method_id, &password_hash)
+        let password_hash = self
+            .hasher
+            .hash(new_password)
+            .map_err(|_| sqlx::Error::RowNotFound)?;
+        let updated = self
+            .repo
+            .update_password(self.db.pool(), auth_method.id, &password_hash)
+            .await?;
+
+        if updated {
+            self.remember_password(auth_method.user_id, &password_hash)
+                .await;
+        }
+        Ok(updated)
+    }
+
+    /// Check a password against a password login method
+    pub fn verify_password(
+        &self,
+        auth_method: &AuthMethod,
+        password: &str,
+    ) -> Result<bool, argon2::password_hash::Error> {
+        match &auth_method.password_hash {
+            Some(hash) => self.hasher.verify(password, hash),
+            None => Ok(false),
+        }
+    }
+
+    /// Re-hash a just verified password if its stored hash predates the
+    /// current Argon2 parameters or pepper. Failures are logged; the old hash
+    /// keeps working.
+    pub async fn upgrade_password_hash(&self, auth_method: &AuthMethod, password: &str) {
+        let Some(hash) = &auth_method.password_hash else {
+            return;
+        };
+        if !self.hasher.needs_rehash(hash) {
+            return;
+        }
+
+        let new_hash = match self.hasher.hash(password) {
+            Ok(new_hash) => new_hash,
+            Err(e) => {
+                tracing::warn!("Failed to upgrade password hash: {e}");
+                return;
+            }
+        };
+
+        match self
+            .repo
+            .update_password(self.db.pool(), auth_method.id, &new_hash)
             .await
+        {
+            Ok(_) => tracing::debug!(auth_method_id = %auth_method.id, "Upgraded password hash"),
+            Err(e) => tracing::warn!("Failed to upgrade password hash: {e}"),
+        }
     }
 
     pub async fn delete(&self, auth_method_id: uuid::Uuid) -> Result<bool, sqlx::Error> {
         self.repo.delete(self.db.pool(), auth_method_id).await
     }
 
-    pub async fn touch(&self, auth_method_id: uuid::Uuid) -> Result<(), sqlx::Error> {
-        self.repo.touch(self.db.pool(), auth_method_id).await
+    /// Remove one of the user's login methods, refusing to remove the last one
+    pub async fn unlink(
+        &self,
+        user_id: uuid::Uuid,
+        auth_method_id: uuid::Uuid,
+    ) -> Result<(), AuthMethodRepositoryError> {
+        self.repo
+            .delete_for_user(self.db.pool(), user_id, auth_method_id)
+            .await
     }
-}
 
-fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
-    use argon2::{
-        Argon2, PasswordHasher,
-        password_hash::{SaltString, rand_core::OsRng},
-    };
+    /// Make one of the user's login methods the primary one
+    pub async fn set_primary(
+        &self,
+        user_id: uuid::Uuid,
+        auth_method_id: uuid::Uuid,
+    ) -> Result<AuthMethod, AuthMethodRepositoryError> {
+        let method = self
+            .repo
+            .find_by_id(self.db.pool(), auth_method_id)
+            .await?
+            .filter(|m| m.user_id == user_id)
+            .ok_or(AuthMethodRepositoryError::NotFound)?;
+
+        if !self.repo.set_primary(self.db.pool(), method.id).await? {
+            return Err(AuthMethodRepositoryError::NotFound);
+        }
+
+        Ok(AuthMethod {
+            is_primary: true,
+            ..method
+        })
+    }
 
-    let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
-    let password_hash = argon2.hash_password(password.as_bytes(), &salt)?;
-    Ok(password_hash.to_string())
+    pub async fn touch(&self, auth_method_id: uuid::Uuid) -> Result<(), sqlx::Error> {
+        self.repo.touch(self.db.pool(), auth_method_id).await
+    }
 }
@@ -0,0 +1,91 @@
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+use crate::{
+    feature::auth::{
+        auth_method::{AuthMethodRepositoryError, AuthMethodResponse},
+        types::AuthUser,
+    },
+    infrastructure::web::response::{
+        ApiError, ApiResult, ApiSuccess,
+        codes::{auth as auth_codes, generic},
+    },
+    state::AppState,
+};
+
+fn auth_method_error(e: AuthMethodRepositoryError) -> ApiError {
+    match e {
+        AuthMethodRepositoryError::NotFound => ApiError::default()
+            .with_code(StatusCode::NOT_FOUND)
+            .with_error_code(generic::NOT_FOUND)
+            .with_message("Login method not found"),
+        AuthMethodRepositoryError::LastMethod => ApiError::default()
+            .with_code(StatusCode::CONFLICT)
+            .with_error_code(auth_codes::LAST_LOGIN_METHOD)
+            .with_message("Add another sign-in method before removing this one"),
+        e => ApiError::default()
+            .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+            .with_error_code(auth_codes::INTERNAL_ERROR)
+            .with_message("Failed to update login methods")
+            .log_only(e),
+    }
+}
+
+/// GET /api/v1/auth/methods
+///
+/// Password, passkeys and linked OAuth providers of the current user.
+pub async fn list_auth_methods(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> ApiResult<Vec<AuthMethodResponse>> {
+    let methods = state
+        .auth_service
+        .auth_method_service()
+        .list_by_user(auth_user.user_id)
+        .await
+        .map_err(|e| auth_method_error(e.into()))?;
+
+    Ok(ApiSuccess::default()
+        .with_data(methods.into_iter().map(Into::into).collect())
+        .with_message("Login methods retrieved"))
+}
+
+/// DELETE /api/v1/auth/methods/{id}
+///
+/// Unlinks a login method. The last remaining one cannot be removed.
+pub async fn delete_auth_method(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<()> {
+    state
+        .auth_service
+        .auth_method_service()
+        .unlink(auth_user.user_id, id)
+        .await
+        .map_err(auth_method_error)?;
+
+    Ok(ApiSuccess::default().with_message("Login method removed"))
+}
+
+/// POST /api/v1/auth/methods/{id}/primary
+pub async fn set_primary_auth_method(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<AuthMethodResponse> {
+    let method = state
+        .auth_service
+        .auth_method_service()
+        .set_primary(auth_user.user_id, id)
+        .await
+        .map_err(auth_method_error)?;
+
+    Ok(ApiSuccess::default()
+        .with_data(method.into())
+        .with_message("Primary login method updated"))
+}
@@ -4,26 +4,35 @@ use validator::Validate;
 
 use crate::{
     feature::auth::{
+        handlers::password::weak_password_error,
         repository::AuthError,
+        service::{LoginOutcome, ReauthProof},
         session::DeviceInfo,
         types::{
-            AuthResponse, AuthUser, LoginCredentials, RegisterRequest, TokenResponse, UserResponse,
+            AuthUser, LoginCredentials, LoginResponse, ReauthenticateRequest, RegisterRequest,
+            RegisterResponse, TokenResponse, UserResponse,
         },
         utils::REFRESH_TOKEN_COOKIE,
     },
-    infrastructure::web::response::{
-        ApiError, ApiResult, ApiSuccess,
-        codes::{auth as auth_codes, validation as val_codes},
+    infrastructure::{
+        config::EmailVerificationRequirement,
+        web::response::{
+            ApiError, ApiResult, ApiSuccess,
+            codes::{auth as auth_codes, validation as val_codes},
+        },
     },
     state::AppState,
 };
 
 /// POST /api/v1/auth/register
+///
+/// Sends a verification email. When `EMAIL_VERIFICATION_REQUIRED=login`, no
+/// session is started until the address is verified.
 pub async fn register(
     State(state): State<AppState>,
     headers: axum::http::HeaderMap,
     Json(req): Json<RegisterRequest>,
-) -> ApiResult<AuthResponse> {
+) -> ApiResult<RegisterResponse> {
     if let Err(e) = req.validate() {
         return Err(ApiError::default()
             .with_code(StatusCode::BAD_REQUEST)
@@ -31,19 +40,60 @@ pub async fn register(
             .with_message(format!("Validation error: {}", e)));
     }
 
-    // Extract device info from headers
-    let user_agent = headers
-        .get("user-agent")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("Unknown");
+    let map_err = |e: AuthError| match e {
+        AuthError::EmailExists => ApiError::default()
+            .with_code(StatusCode::CONFLICT)
+            .with_error_code(auth_codes::EMAIL_EXISTS)
+            .with_message("Email already registered"),
+        AuthError::UsernameExists => ApiError::default()
+            .with_code(StatusCode::CONFLICT)
+            .with_error_code(auth_codes::EMAIL_EXISTS)
+            .with_message("Username already taken"),
+        AuthError::WeakPassword(violations) => weak_password_error(violations),
+        _ => ApiError::default()
+            .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+            .with_error_code(auth_codes::INTERNAL_ERROR)
+            .with_message("Registration failed"),
+    };
 
-    let ip_address = headers
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .or_else(|| headers.get("x-real-ip").and_then(|v| v.to_str().ok()))
-        .unwrap_or("0.0.0.0");
+    let verification = &state.email_verification_service;
+
+    if verification.requirement() == EmailVerificationRequirement::Login {
+        let user = state
+            .auth_service
+            .create_account(
+                &req.email,
+                req.username.as_deref(),
+                &req.password,
+                req.name.as_deref(),
+            )
+            .await
+            .map_err(map_err)?;
 
-    let device_info = DeviceInfo::from_user_agent(user_agent, ip_address);
+        if let Err(e) = verification.send_verification(user.id, &user.email).await {
+            tracing::error!("Failed to send verification email: {}", e);
+        }
+
+        let role = user.role().to_string();
+        return Ok(ApiSuccess::default()
+            .with_code(StatusCode::CREATED)
+            .with_data(RegisterResponse::VerificationRequired {
+                user: UserResponse {
+                    id: user.id,
+                    email: user.email,
+                    email_verified: user.email_verified,
+                    username: user.username,
+                    name: req.name.unwrap_or_default(),
+                    avatar_url: None,
+                    role,
+                },
+                verification_required: true,
+            })
+            .with_message("Registration successful, check your inbox to verify your email"));
+    }
+
+    // Extract device info from headers
+    let device_info = DeviceInfo::from_headers(&headers);
 
     let (response, refresh_cookie) = state
         .auth_service
@@ -55,24 +105,18 @@ pub async fn register(
             Some(&device_info),
         )
         .await
-        .map_err(|e: AuthError| match e {
-            AuthError::EmailExists => ApiError::default()
-                .with_code(StatusCode::CONFLICT)
-                .with_error_code(auth_codes::EMAIL_EXISTS)
-                .with_message("Email already registered"),
-            AuthError::UsernameExists => ApiError::default()
-                .with_code(StatusCode::CONFLICT)
-                .with_error_code(auth_codes::EMAIL_EXISTS)
-                .with_message("Username already taken"),
-            _ => ApiError::default()
-                .with_code(StatusCode::INTERNAL_SERVER_ERROR)
-                .with_error_code(auth_codes::INTERNAL_ERROR)
-                .with_message("Registration failed"),
-        })?;
+        .map_err(map_err)?;
+
+    if let Err(e) = verification
+        .send_verification(response.user.id, &response.user.email)
+        .await
+    {
+        tracing::error!("Failed to send verification email: {}", e);
+    }
 
     Ok(ApiSuccess::default()
         .with_code(StatusCode::CREATED)
-        .with_data(response)
+        .with_data(RegisterResponse::Authenticated(response))
         .with_cookie(refresh_cookie)
         .with_message("Registration successful"))
 }
@@ -83,27 +127,16 @@ pub async fn login(
     jar: CookieJar,
     headers: axum::http::HeaderMap,
     Json(creds): Json<LoginCredentials>,
-) -> ApiResult<AuthResponse> {
+) -> ApiResult<LoginResponse> {
     // Check existing refresh token to avoid concurrent login issues
     if let Some(_cookie) = jar.get(REFRESH_TOKEN_COOKIE) {
         let _ = state.auth_service.logout(None, None).await;
     }
 
     // Extract device info from headers
-    let user_agent = headers
-        .get("user-agent")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("Unknown");
-
-    let ip_address = headers
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .or_else(|| headers.get("x-real-ip").and_then(|v| v.to_str().ok()))
-        .unwrap_or("0.0.0.0");
+    let device_info = DeviceInfo::from_headers(&headers);
 
-    let device_info = DeviceInfo::from_user_agent(user_agent, ip_address);
-
-    let (response, refresh_cookie) = state
+    let outcome = state
         .auth_service
         .login(&creds.email, &creds.password, Some(&device_info))
         .await
@@ -112,16 +145,37 @@ pub async fn login(
                 .with_code(StatusCode::UNAUTHORIZED)
                 .with_error_code(auth_codes::INVALID_CREDENTIALS)
                 .with_message("Invalid email or password"),
+            AuthError::EmailNotVerified => ApiError::default()
+                .with_code(StatusCode::FORBIDDEN)
+                .with_error_code(auth_codes::EMAIL_NOT_VERIFIED)
+                .with_message("Please verify your email address before signing in"),
+            AuthError::AccountLocked { retry_after_secs } => ApiError::default()
+                .with_code(StatusCode::LOCKED)
+                .with_error_code(auth_codes::ACCOUNT_LOCKED)
+                .with_message(format!(
+                    "Account temporarily locked. Try again in {retry_after_secs} seconds"
+                )),
+            AuthError::TooManyAttempts { retry_after_secs } => ApiError::default()
+                .with_code(StatusCode::TOO_MANY_REQUESTS)
+                .with_error_code(auth_codes::LOGIN_THROTTLED)
+                .with_message(format!(
+                    "Too many failed attempts. Try again in {retry_after_secs} seconds"
+                )),
             _ => ApiError::default()
                 .with_code(StatusCode::INTERNAL_SERVER_ERROR)
                 .with_error_code(auth_codes::INTERNAL_ERROR)
                 .with_message("Login failed"),
         })?;
 
-    Ok(ApiSuccess::default()
-        .with_data(response)
-        .with_cookie(refresh_cookie)
-        .with_message("Login successful"))
+    match outcome {
+        LoginOutcome::Authenticated(response, refresh_cookie) => Ok(ApiSuccess::default()
+            .with_data(LoginResponse::Authenticated(response))
+            .with_cookie(refresh_cookie)
+            .with_message("Login successful")),
+        LoginOutcome::MfaRequired(challenge) => Ok(ApiSuccess::default()
+            .with_data(LoginResponse::MfaRequired(challenge))
+            .with_message("MFA verification required")),
+    }
 }
 
 /// POST /api/v1/auth/refresh
@@ -149,6 +203,10 @@ pub async fn refresh(State(state): State<AppState>, jar: CookieJar) -> ApiResult
                 .with_code(StatusCode::UNAUTHORIZED)
                 .with_error_code(auth_codes::TOKEN_EXPIRED)
                 .with_message("Invalid or expired refresh token"),
+            AuthError::TokenReuseDetected => ApiError::default()
+                .with_code(StatusCode::UNAUTHORIZED)
+                .with_error_code(auth_codes::TOKEN_INVALID)
+                .with_message("Refresh token was already used, session revoked"),
             _ => ApiError::default()
                 .with_code(StatusCode::UNAUTHORIZED)
                 .with_error_code(auth_codes::TOKEN_INVALID)
@@ -161,6 +219,61 @@ pub async fn refresh(State(state): State<AppState>, jar: CookieJar) -> ApiResult
         .with_message("Token refreshed"))
 }
 
+/// POST /api/v1/auth/reauthenticate
+///
+/// Step-up: confirm the password or an MFA code to unlock routes that need a
+/// recent authentication (403 `AUTH_019`). Returns fresh tokens for the same
+/// session; the access token must be replaced with the new one.
+pub async fn reauthenticate(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<ReauthenticateRequest>,
+) -> ApiResult<TokenResponse> {
+    let proof = match (req.password.as_deref(), req.code.as_deref()) {
+        (Some(password), None) if !password.is_empty() => ReauthProof::Password(password),
+        (None, Some(code)) if !code.is_empty() => ReauthProof::MfaCode(code),
+        _ => {
+            return Err(ApiError::default()
+                .with_code(StatusCode::BAD_REQUEST)
+                .with_error_code(val_codes::INVALID_INPUT)
+                .with_message("Provide either a password or an MFA code"));
+        }
+    };
+
+    let (tokens, refresh_cookie) = state
+        .auth_service
+        .reauthenticate(&auth_user, proof)
+        .await
+        .map_err(|e| match e {
+            AuthError::InvalidCredentials => ApiError::default()
+                .with_code(StatusCode::UNAUTHORIZED)
+                .with_error_code(auth_codes::INVALID_CREDENTIALS)
+                .with_message("Re-authentication failed"),
+            AuthError::AccountLocked { retry_after_secs } => ApiError::default()
+                .with_code(StatusCode::LOCKED)
+                .with_error_code(auth_codes::ACCOUNT_LOCKED)
+                .with_message(format!(
+                    "Account temporarily locked. Try again in {retry_after_secs} seconds"
+                )),
+            AuthError::TooManyAttempts { retry_after_secs } => ApiError::default()
+                .with_code(StatusCode::TOO_MANY_REQUESTS)
+                .with_error_code(auth_codes::LOGIN_THROTTLED)
+                .with_message(format!(
+                    "Too many failed attempts. Try again in {retry_after_secs} seconds"
+                )),
+            e => ApiError::default()
+                .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+                .with_error_code(auth_codes::INTERNAL_ERROR)
+                .with_message("Re-authentication failed")
+                .log_only(e),
+        })?;
+
+    Ok(ApiSuccess::default()
+        .with_data(tokens)
+        .with_cookie(refresh_cookie)
+        .with_message("Re-authenticated"))
+}
+
 /// POST /api/v1/auth/logout
 pub async fn logout(
     State(state): State<AppState>,
@@ -182,6 +295,11 @@ pub async fn logout(
         .revoke_by_session_id(&auth_user.session_id, "user_logout")
         .await;
 
+    // Ending an impersonation must leave the admin's own refresh cookie alone
+    if auth_user.is_impersonated() {
+        return Ok(ApiSuccess::default().with_message("Impersonation ended"));
+    }
+
     let clear_cookie = state
         .auth_service
         .logout(refresh_token.as_deref(), access_token)
@@ -217,6 +335,7 @@ pub async fn me(
     let response = UserResponse {
         id: user_with_profile.id,
         email: user_with_profile.email,
+        email_verified: user_with_profile.email_verified,
         username: user_with_profile.username,
         name: user_with_profile.full_name.unwrap_or_default(),
         avatar_url: user_with_profile.avatar_url,
@@ -0,0 +1,20 @@
+use axum::{
+    Json,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+
+use crate::state::AppState;
+
+/// GET /.well-known/jwks.json
+///
+/// Public keys that verify access tokens, as a bare RFC 7517 key set (not
+/// wrapped in the usual API envelope) so standard JWT libraries can consume it.
+pub async fn jwks(State(state): State<AppState>) -> Response {
+    (
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(state.jwt_keys.jwks().clone()),
+    )
+        .into_response()
+}
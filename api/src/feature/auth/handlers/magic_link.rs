@@ -0,0 +1,130 @@
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar};
+use validator::Validate;
+
+use crate::{
+    feature::auth::{
+        magic_link::{MagicLinkError, MagicLinkRequest, MagicLinkVerifyRequest},
+        repository::AuthError,
+        service::LoginOutcome,
+        session::DeviceInfo,
+        types::LoginResponse,
+        utils::random_token,
+    },
+    infrastructure::web::response::{
+        ApiError, ApiResult, ApiSuccess,
+        codes::{auth as auth_codes, validation as val_codes},
+    },
+    state::AppState,
+};
+
+/// Cookie binding a sign-in link to the browser that requested it
+const MAGIC_LINK_COOKIE: &str = "magic_link_device";
+const MAGIC_LINK_COOKIE_PATH: &str = "/api/v1/auth/magic-link";
+
+fn device_cookie(state: &AppState, value: String, max_age_secs: i64) -> Cookie<'static> {
+    let cookie_config = &state.config.cookie;
+
+    Cookie::build((MAGIC_LINK_COOKIE, value))
+        .http_only(true)
+        .secure(cookie_config.secure)
+        .same_site(cookie_config.same_site)
+        .path(MAGIC_LINK_COOKIE_PATH)
+        .max_age(time::Duration::seconds(max_age_secs))
+        .build()
+}
+
+fn validation_error(e: validator::ValidationErrors) -> ApiError {
+    ApiError::default()
+        .with_code(StatusCode::BAD_REQUEST)
+        .with_error_code(val_codes::INVALID_INPUT)
+        .with_message(format!("Validation error: {}", e))
+}
+
+fn invalid_link() -> ApiError {
+    ApiError::default()
+        .with_code(StatusCode::BAD_REQUEST)
+        .with_error_code(auth_codes::TOKEN_INVALID)
+        .with_message("Invalid or expired sign-in link")
+}
+
+/// POST /api/v1/auth/magic-link
+///
+/// Emails a single-use sign-in link. Always answers the same way, whether or
+/// not the address belongs to an account, and always sets the device cookie
+/// the link must be redeemed with.
+pub async fn magic_link_request(
+    State(state): State<AppState>,
+    Json(req): Json<MagicLinkRequest>,
+) -> ApiResult<()> {
+    req.validate().map_err(validation_error)?;
+
+    let binding = random_token(32);
+    if let Err(e) = state
+        .magic_link_service
+        .request_link(&req.email, &binding)
+        .await
+    {
+        tracing::error!("Failed to send magic link email: {}", e);
+    }
+
+    let cookie = device_cookie(&state, binding, state.config.magic_link.token_ttl_secs);
+
+    Ok(ApiSuccess::default()
+        .with_cookie(cookie)
+        .with_message("If an account exists for this email, a sign-in link was sent"))
+}
+
+/// POST /api/v1/auth/magic-link/verify
+///
+/// Redeems a sign-in link from the browser that requested it. Same response
+/// shape as `/login`: a session, or an MFA challenge for accounts with TOTP.
+pub async fn magic_link_verify(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    jar: CookieJar,
+    Json(req): Json<MagicLinkVerifyRequest>,
+) -> ApiResult<LoginResponse> {
+    req.validate().map_err(validation_error)?;
+
+    let binding = jar
+        .get(MAGIC_LINK_COOKIE)
+        .map(|c| c.value().to_string())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(invalid_link)?;
+    let device_info = DeviceInfo::from_headers(&headers);
+
+    let outcome = state
+        .magic_link_service
+        .sign_in(&req.token, &binding, Some(&device_info))
+        .await
+        .map_err(|e| match e {
+            MagicLinkError::InvalidToken => invalid_link(),
+            MagicLinkError::Auth(AuthError::InvalidCredentials | AuthError::UserNotFound) => {
+                invalid_link()
+            }
+            e => ApiError::default()
+                .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+                .with_error_code(auth_codes::INTERNAL_ERROR)
+                .with_message("Failed to sign in")
+                .log_only(e),
+        })?;
+
+    let cleared = device_cookie(&state, String::new(), 0);
+
+    match outcome {
+        LoginOutcome::Authenticated(response, refresh_cookie) => Ok(ApiSuccess::default()
+            .with_data(LoginResponse::Authenticated(response))
+            .with_cookie(refresh_cookie)
+            .with_cookie(cleared)
+            .with_message("Login successful")),
+        LoginOutcome::MfaRequired(challenge) => Ok(ApiSuccess::default()
+            .with_data(LoginResponse::MfaRequired(challenge))
+            .with_cookie(cleared)
+            .with_message("MFA verification required")),
+    }
+}
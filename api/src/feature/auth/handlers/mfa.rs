@@ -0,0 +1,219 @@
+use axum::{Extension, Json, extract::State, http::StatusCode};
+use validator::Validate;
+
+use crate::{
+    feature::auth::{
+        mfa::{
+            MfaCodeRequest, MfaEnrollResponse, MfaError, MfaStatusResponse, MfaVerifyRequest,
+            RecoveryCodesResponse,
+        },
+        repository::AuthError,
+        session::DeviceInfo,
+        types::{AuthResponse, AuthUser},
+    },
+    infrastructure::web::response::{
+        ApiError, ApiResult, ApiSuccess,
+        codes::{auth as auth_codes, validation as val_codes},
+    },
+    state::AppState,
+};
+
+fn validation_error(e: validator::ValidationErrors) -> ApiError {
+    ApiError::default()
+        .with_code(StatusCode::BAD_REQUEST)
+        .with_error_code(val_codes::INVALID_INPUT)
+        .with_message(format!("Validation error: {}", e))
+}
+
+fn mfa_error(e: MfaError) -> ApiError {
+    match e {
+        MfaError::InvalidCode => ApiError::default()
+            .with_code(StatusCode::UNAUTHORIZED)
+            .with_error_code(auth_codes::MFA_INVALID_CODE)
+            .with_message("Invalid verification code"),
+        MfaError::NotEnabled => ApiError::default()
+            .with_code(StatusCode::BAD_REQUEST)
+            .with_error_code(auth_codes::MFA_NOT_ENABLED)
+            .with_message("MFA is not enabled"),
+        MfaError::NoPendingEnrollment => ApiError::default()
+            .with_code(StatusCode::BAD_REQUEST)
+            .with_error_code(auth_codes::MFA_NOT_ENABLED)
+            .with_message("Start MFA enrollment first"),
+        MfaError::AlreadyEnabled => ApiError::default()
+            .with_code(StatusCode::CONFLICT)
+            .with_error_code(auth_codes::MFA_ALREADY_ENABLED)
+            .with_message("MFA is already enabled"),
+        MfaError::Database(e) => ApiError::default()
+            .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+            .with_error_code(auth_codes::INTERNAL_ERROR)
+            .with_message("MFA operation failed")
+            .log_only(e),
+    }
+}
+
+/// Errors of a code check that runs under the login throttle
+fn throttled_mfa_error(e: AuthError) -> ApiError {
+    match e {
+        AuthError::Mfa(e) => mfa_error(e),
+        AuthError::AccountLocked { retry_after_secs } => ApiError::default()
+            .with_code(StatusCode::LOCKED)
+            .with_error_code(auth_codes::ACCOUNT_LOCKED)
+            .with_message(format!(
+                "Account temporarily locked. Try again in {retry_after_secs} seconds"
+            )),
+        AuthError::TooManyAttempts { retry_after_secs } => ApiError::default()
+            .with_code(StatusCode::TOO_MANY_REQUESTS)
+            .with_error_code(auth_codes::LOGIN_THROTTLED)
+            .with_message(format!(
+                "Too many failed attempts. Try again in {retry_after_secs} seconds"
+            )),
+        e => ApiError::default()
+            .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+            .with_error_code(auth_codes::INTERNAL_ERROR)
+            .with_message("MFA operation failed")
+            .log_only(e),
+    }
+}
+
+/// GET /api/v1/auth/mfa
+pub async fn mfa_status(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> ApiResult<MfaStatusResponse> {
+    let status = state
+        .auth_service
+        .mfa_service()
+        .status(auth_user.user_id)
+        .await
+        .map_err(mfa_error)?;
+
+    Ok(ApiSuccess::default()
+        .with_data(status)
+        .with_message("MFA status retrieved"))
+}
+
+/// POST /api/v1/auth/mfa/enroll
+///
+/// Starts enrollment and returns the secret + provisioning URI. MFA is not
+/// active until the enrollment is confirmed.
+pub async fn mfa_enroll(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> ApiResult<MfaEnrollResponse> {
+    let user = state
+        .user_repo
+        .find_by_id(state.db.pool(), auth_user.user_id)
+        .await
+        .map_err(|e| ApiError::default().log_only(e))?
+        .ok_or_else(|| {
+            ApiError::default()
+                .with_code(StatusCode::NOT_FOUND)
+                .with_error_code(auth_codes::USER_NOT_FOUND)
+                .with_message("User not found")
+        })?;
+
+    let enrollment = state
+        .auth_service
+        .mfa_service()
+        .begin_enrollment(user.id, &user.email)
+        .await
+        .map_err(mfa_error)?;
+
+    Ok(ApiSuccess::default()
+        .with_data(enrollment)
+        .with_message("Scan the QR code, then confirm with a code"))
+}
+
+/// POST /api/v1/auth/mfa/confirm
+///
+/// Activates MFA and returns the recovery codes (shown only once).
+pub async fn mfa_confirm(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<MfaCodeRequest>,
+) -> ApiResult<RecoveryCodesResponse> {
+    req.validate().map_err(validation_error)?;
+
+    let recovery_codes = state
+        .auth_service
+        .mfa_service()
+        .confirm_enrollment(auth_user.user_id, &req.code)
+        .await
+        .map_err(mfa_error)?;
+
+    Ok(ApiSuccess::default()
+        .with_data(RecoveryCodesResponse { recovery_codes })
+        .with_message("MFA enabled"))
+}
+
+/// POST /api/v1/auth/mfa/disable
+///
+/// Needs a recent authentication; wrong codes count as failed logins.
+pub async fn mfa_disable(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<MfaCodeRequest>,
+) -> ApiResult<()> {
+    req.validate().map_err(validation_error)?;
+
+    state
+        .auth_service
+        .disable_mfa(auth_user.user_id, &req.code)
+        .await
+        .map_err(throttled_mfa_error)?;
+
+    Ok(ApiSuccess::default().with_message("MFA disabled"))
+}
+
+/// POST /api/v1/auth/mfa/recovery-codes
+///
+/// Replaces all recovery codes; the old ones stop working immediately. Needs
+/// a recent authentication; wrong codes count as failed logins.
+pub async fn mfa_regenerate_recovery_codes(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<MfaCodeRequest>,
+) -> ApiResult<RecoveryCodesResponse> {
+    req.validate().map_err(validation_error)?;
+
+    let recovery_codes = state
+        .auth_service
+        .regenerate_recovery_codes(auth_user.user_id, &req.code)
+        .await
+        .map_err(throttled_mfa_error)?;
+
+    Ok(ApiSuccess::default()
+        .with_data(RecoveryCodesResponse { recovery_codes })
+        .with_message("Recovery codes regenerated"))
+}
+
+/// POST /api/v1/auth/mfa/verify
+///
+/// Second login step: exchanges the challenge token from `/login` plus a TOTP
+/// or recovery code for a session.
+pub async fn mfa_verify(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(req): Json<MfaVerifyRequest>,
+) -> ApiResult<AuthResponse> {
+    req.validate().map_err(validation_error)?;
+
+    let device_info = DeviceInfo::from_headers(&headers);
+
+    let (response, refresh_cookie) = state
+        .auth_service
+        .complete_mfa_login(&req.mfa_token, &req.code, Some(&device_info))
+        .await
+        .map_err(|e| match e {
+            AuthError::InvalidCredentials => ApiError::default()
+                .with_code(StatusCode::UNAUTHORIZED)
+                .with_error_code(auth_codes::TOKEN_INVALID)
+                .with_message("Invalid or expired MFA token"),
+            e => throttled_mfa_error(e),
+        })?;
+
+    Ok(ApiSuccess::default()
+        .with_data(response)
+        .with_cookie(refresh_cookie)
+        .with_message("Login successful"))
+}
@@ -1,7 +1,30 @@
+pub mod auth_method;
 pub mod core;
+pub mod jwks;
+pub mod magic_link;
+pub mod mfa;
+pub mod oauth;
+pub mod oidc;
 pub mod password;
 pub mod session;
+pub mod token;
+pub mod verification;
+pub mod webauthn;
 
-pub use core::{login, logout, me, refresh, register};
-pub use password::change_password;
-pub use session::{list_sessions, logout_all_sessions, revoke_session};
+pub use auth_method::{delete_auth_method, list_auth_methods, set_primary_auth_method};
+pub use core::{login, logout, me, reauthenticate, refresh, register};
+pub use jwks::jwks;
+pub use magic_link::{magic_link_request, magic_link_verify};
+pub use mfa::{
+    mfa_confirm, mfa_disable, mfa_enroll, mfa_regenerate_recovery_codes, mfa_status, mfa_verify,
+};
+pub use oauth::{oauth_authorize, oauth_callback, oauth_link};
+pub use oidc::{oidc_authorize, oidc_token, openid_configuration, userinfo};
+pub use password::{change_password, forgot_password, reset_password, set_password};
+pub use session::{list_sessions, logout_all_sessions, revoke_session, revoke_session_by_link};
+pub use token::{introspect, revoke};
+pub use verification::{resend_verification, verify_email};
+pub use webauthn::{
+    delete_passkey, list_passkeys, passkey_login_begin, passkey_login_finish,
+    passkey_register_begin, passkey_register_finish,
+};
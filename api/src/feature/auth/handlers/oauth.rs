@@ -0,0 +1,180 @@
+use axum::{
+    Extension,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Redirect,
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+
+use crate::{
+    feature::auth::{
+        auth_method::AuthProvider,
+        oauth::{CallbackOutcome, OAuthCallbackQuery, OAuthError, OAuthLinkResponse},
+        service::LoginOutcome,
+        session::DeviceInfo,
+        types::AuthUser,
+    },
+    infrastructure::web::response::{ApiError, ApiResult, ApiSuccess, codes::generic},
+    state::AppState,
+};
+
+/// Short-lived cookie binding the `state` parameter to the browser that started the flow
+const OAUTH_STATE_COOKIE: &str = "oauth_state";
+const OAUTH_COOKIE_PATH: &str = "/api/v1/auth/oauth";
+
+/// Build the state cookie. Always `SameSite=Lax`: the provider redirects back with
+/// a cross-site top-level navigation, which `Strict` cookies would not survive.
+fn state_cookie(state: &AppState, value: String, max_age_secs: i64) -> Cookie<'static> {
+    Cookie::build((OAUTH_STATE_COOKIE, value))
+        .http_only(true)
+        .secure(state.config.cookie.secure)
+        .same_site(SameSite::Lax)
+        .path(OAUTH_COOKIE_PATH)
+        .max_age(time::Duration::seconds(max_age_secs))
+        .build()
+}
+
+fn parse_provider(provider: &str) -> Option<AuthProvider> {
+    AuthProvider::try_from(provider)
+        .ok()
+        .filter(|p| !matches!(p, AuthProvider::Password | AuthProvider::Passkey))
+}
+
+fn provider_not_found(provider: &str) -> ApiError {
+    ApiError::default()
+        .with_code(StatusCode::NOT_FOUND)
+        .with_error_code(generic::NOT_FOUND)
+        .with_message(format!("OAuth provider '{provider}' is not available"))
+}
+
+/// GET /api/v1/auth/oauth/{provider}/authorize
+///
+/// Redirects the browser to the provider's consent screen.
+pub async fn oauth_authorize(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    jar: CookieJar,
+) -> Result<(CookieJar, Redirect), ApiError> {
+    let not_found = || provider_not_found(&provider);
+
+    let auth_provider = parse_provider(&provider).ok_or_else(not_found)?;
+
+    let request = state
+        .oauth_service
+        .authorize(auth_provider)
+        .await
+        .map_err(|e| match e {
+            OAuthError::ProviderNotConfigured => not_found(),
+            e => ApiError::default().log_only(e),
+        })?;
+
+    let cookie = state_cookie(&state, request.state, state.config.oauth.state_ttl_secs);
+
+    Ok((jar.add(cookie), Redirect::to(&request.url)))
+}
+
+/// POST /api/v1/auth/oauth/{provider}/link
+///
+/// Starts a flow that attaches the provider to the signed-in account. Returns
+/// the consent URL for the frontend to navigate to; the callback then
+/// redirects with `?linked=<provider>` or `?error=<reason>`.
+pub async fn oauth_link(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(provider): Path<String>,
+) -> ApiResult<OAuthLinkResponse> {
+    let not_found = || provider_not_found(&provider);
+
+    let auth_provider = parse_provider(&provider).ok_or_else(not_found)?;
+
+    let request = state
+        .oauth_service
+        .authorize_link(auth_provider, auth_user.user_id)
+        .await
+        .map_err(|e| match e {
+            OAuthError::ProviderNotConfigured => not_found(),
+            e => ApiError::default().log_only(e),
+        })?;
+
+    let cookie = state_cookie(&state, request.state, state.config.oauth.state_ttl_secs);
+
+    Ok(ApiSuccess::default()
+        .with_data(OAuthLinkResponse {
+            authorization_url: request.url,
+        })
+        .with_cookie(cookie)
+        .with_message("Continue at the provider to link your account"))
+}
+
+/// GET /api/v1/auth/oauth/{provider}/callback
+///
+/// Completes the flow and redirects to the frontend. On success the refresh
+/// cookie is set (the frontend then calls `/auth/refresh` for an access token);
+/// accounts with MFA get `?mfa_token=<challenge>` for `/auth/mfa/verify`;
+/// link flows get `?linked=<provider>`; on failure the redirect carries
+/// `?error=<reason>`.
+pub async fn oauth_callback(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuthCallbackQuery>,
+    jar: CookieJar,
+    headers: HeaderMap,
+) -> (CookieJar, Redirect) {
+    let expected_state = jar.get(OAUTH_STATE_COOKIE).map(|c| c.value().to_string());
+    let jar = jar.add(state_cookie(&state, String::new(), 0));
+
+    let result = async {
+        let auth_provider = parse_provider(&provider).ok_or(OAuthError::ProviderNotConfigured)?;
+
+        if let Some(error) = query.error {
+            return Err(OAuthError::AccessDenied(error));
+        }
+
+        let (Some(code), Some(returned_state)) = (query.code, query.state) else {
+            return Err(OAuthError::InvalidState);
+        };
+
+        // The state must come back to the same browser that started the flow
+        let same_browser = expected_state.is_some_and(|expected| {
+            constant_time_eq::constant_time_eq(expected.as_bytes(), returned_state.as_bytes())
+        });
+        if !same_browser {
+            return Err(OAuthError::InvalidState);
+        }
+
+        let device_info = DeviceInfo::from_headers(&headers);
+        state
+            .oauth_service
+            .callback(auth_provider, &code, &returned_state, Some(&device_info))
+            .await
+    }
+    .await;
+
+    let redirect_base = &state.config.oauth.success_redirect;
+    let separator = if redirect_base.contains('?') {
+        '&'
+    } else {
+        '?'
+    };
+    match result {
+        Ok(CallbackOutcome::Login(LoginOutcome::Authenticated(_, refresh_cookie))) => {
+            (jar.add(refresh_cookie), Redirect::to(redirect_base))
+        }
+        Ok(CallbackOutcome::Login(LoginOutcome::MfaRequired(challenge))) => {
+            let location = format!(
+                "{redirect_base}{separator}mfa_token={}",
+                challenge.mfa_token
+            );
+            (jar, Redirect::to(&location))
+        }
+        Ok(CallbackOutcome::Linked(linked)) => {
+            let location = format!("{redirect_base}{separator}linked={}", linked.as_str());
+            (jar, Redirect::to(&location))
+        }
+        Err(e) => {
+            tracing::warn!(provider = %provider, "OAuth callback failed: {e}");
+            let location = format!("{redirect_base}{separator}error={}", e.reason());
+            (jar, Redirect::to(&location))
+        }
+    }
+}
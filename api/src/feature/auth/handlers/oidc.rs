@@ -0,0 +1,153 @@
+use axum::{
+    Extension, Form, Json,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+
+use crate::{
+    feature::auth::{
+        oidc::dto::OAuthErrorResponse,
+        oidc::{AuthorizeRequest, AuthorizeResponse, OidcError, TokenGrantRequest},
+        types::AuthUser,
+    },
+    infrastructure::web::response::{
+        ApiError, ApiResult, ApiSuccess,
+        codes::{auth as auth_codes, generic},
+    },
+    state::AppState,
+};
+
+/// `client_id` and `client_secret` from an `Authorization: Basic` header
+fn basic_credentials(headers: &HeaderMap) -> Option<(String, String)> {
+    let encoded = headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(STANDARD.decode(encoded).ok()?).ok()?;
+    let (id, secret) = decoded.split_once(':')?;
+    Some((id.to_string(), secret.to_string()))
+}
+
+/// Bare RFC 6749 error body (not wrapped in the usual API envelope)
+fn oauth_error(e: OidcError) -> Response {
+    let status = match e {
+        OidcError::InvalidClient | OidcError::InvalidToken => StatusCode::UNAUTHORIZED,
+        OidcError::NoSigningKey
+        | OidcError::Jwt(_)
+        | OidcError::Session(_)
+        | OidcError::Database(_) => {
+            tracing::error!("OIDC request failed: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        _ => StatusCode::BAD_REQUEST,
+    };
+    let challenge = match e {
+        OidcError::InvalidToken => Some(r#"Bearer error="invalid_token""#),
+        OidcError::InvalidClient => Some(r#"Basic realm="quax""#),
+        _ => None,
+    };
+    let body = OAuthErrorResponse {
+        error: e.error_code(),
+        error_description: e.to_string(),
+    };
+
+    let mut response = (status, [(header::CACHE_CONTROL, "no-store")], Json(body)).into_response();
+    if let Some(challenge) = challenge {
+        response.headers_mut().insert(
+            header::WWW_AUTHENTICATE,
+            header::HeaderValue::from_static(challenge),
+        );
+    }
+    response
+}
+
+/// GET /.well-known/openid-configuration
+///
+/// OIDC discovery document, bare JSON like the JWKS
+pub async fn openid_configuration(State(state): State<AppState>) -> Response {
+    match state.oidc_service.discovery() {
+        Ok(document) => (
+            [(header::CACHE_CONTROL, "public, max-age=300")],
+            Json(document),
+        )
+            .into_response(),
+        Err(e) => oauth_error(e),
+    }
+}
+
+/// POST /api/v1/oauth/authorize (auth required)
+///
+/// Called by the frontend page the discovery document advertises as the
+/// authorization endpoint, once the user is signed in to quax. Answers with
+/// the consent screen to show, or the client URL to send the browser to.
+pub async fn oidc_authorize(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<AuthorizeRequest>,
+) -> ApiResult<AuthorizeResponse> {
+    let response = state
+        .oidc_service
+        .authorize(&auth_user, &req)
+        .await
+        .map_err(|e| match e {
+            OidcError::InvalidClient | OidcError::InvalidRedirectUri => ApiError::default()
+                .with_code(StatusCode::BAD_REQUEST)
+                .with_error_code(auth_codes::OIDC_CLIENT_INVALID)
+                .with_message(e.to_string()),
+            _ => {
+                tracing::error!("OIDC authorization failed: {:?}", e);
+                ApiError::default()
+                    .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+                    .with_error_code(generic::INTERNAL)
+                    .with_message("Authorization failed")
+            }
+        })?;
+
+    let message = match response {
+        AuthorizeResponse::ConsentRequired { .. } => "Consent required",
+        AuthorizeResponse::Redirect { .. } => "Authorization complete",
+    };
+
+    Ok(ApiSuccess::default()
+        .with_data(response)
+        .with_message(message))
+}
+
+/// POST /api/v1/oauth/token
+///
+/// Redeems an authorization code (PKCE) or rotates a refresh token. Clients
+/// authenticate with HTTP Basic or form credentials; public clients send
+/// only `client_id`.
+pub async fn oidc_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Form(req): Form<TokenGrantRequest>,
+) -> Response {
+    match state
+        .oidc_service
+        .token(&req, basic_credentials(&headers))
+        .await
+    {
+        Ok(tokens) => ([(header::CACHE_CONTROL, "no-store")], Json(tokens)).into_response(),
+        Err(e) => oauth_error(e),
+    }
+}
+
+/// GET|POST /api/v1/oauth/userinfo (client access token)
+pub async fn userinfo(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let Some(token) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    else {
+        return oauth_error(OidcError::InvalidToken);
+    };
+
+    match state.oidc_service.userinfo(token).await {
+        Ok(info) => ([(header::CACHE_CONTROL, "no-store")], Json(info)).into_response(),
+        Err(e) => oauth_error(e),
+    }
+}
@@ -3,8 +3,10 @@ use validator::Validate;
 
 use crate::{
     feature::auth::{
-        auth_method::AuthProvider,
-        types::{AuthUser, ChangePasswordRequest},
+        auth_method::{AuthMethodRepositoryError, AuthProvider},
+        password_policy::{PasswordPolicyError, PersonalInfo, PolicyViolation},
+        password_reset::{ForgotPasswordRequest, PasswordResetError, ResetPasswordRequest},
+        types::{AuthUser, ChangePasswordRequest, PasswordUpdateResponse, SetPasswordRequest},
     },
     infrastructure::web::response::{
         ApiError, ApiResult, ApiSuccess,
@@ -13,12 +15,99 @@ use crate::{
     state::AppState,
 };
 
+/// 400 listing every password policy rule the candidate failed
+pub(crate) fn weak_password_error(violations: Vec<PolicyViolation>) -> ApiError {
+    ApiError::default()
+        .with_code(StatusCode::BAD_REQUEST)
+        .with_error_code(val_codes::PASSWORD_POLICY)
+        .with_message("Password does not meet the policy")
+        .with_errors(violations)
+}
+
+fn password_already_set() -> ApiError {
+    ApiError::default()
+        .with_code(StatusCode::CONFLICT)
+        .with_error_code(auth_codes::PASSWORD_ALREADY_SET)
+        .with_message("A password is already set, use change-password instead")
+}
+
+/// Check a new password for the signed-in user against the policy
+async fn check_password_policy(
+    state: &AppState,
+    auth_user: &AuthUser,
+    password: &str,
+) -> Result<(), ApiError> {
+    let internal = |e: &dyn std::fmt::Display| {
+        ApiError::default()
+            .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+            .with_error_code(auth_codes::INTERNAL_ERROR)
+            .with_message("Failed to check password")
+            .log_only(e)
+    };
+
+    let user = state
+        .user_repo
+        .find_by_id(state.db.pool(), auth_user.user_id)
+        .await
+        .map_err(|e| internal(&e))?
+        .ok_or_else(|| {
+            ApiError::default()
+                .with_code(StatusCode::NOT_FOUND)
+                .with_error_code(auth_codes::USER_NOT_FOUND)
+                .with_message("User not found")
+        })?;
+
+    state
+        .auth_service
+        .auth_method_service()
+        .password_policy()
+        .validate(
+            password,
+            PersonalInfo {
+                email: &user.email,
+                username: user.username.as_deref(),
+            },
+            Some(user.id),
+        )
+        .await
+        .map_err(|e| match e {
+            PasswordPolicyError::Rejected(violations) => weak_password_error(violations),
+            e => internal(&e),
+        })
+}
+
+/// Sign out the user's other sessions when asked to; returns how many were revoked
+async fn revoke_other_sessions(
+    state: &AppState,
+    auth_user: &AuthUser,
+    requested: bool,
+) -> Result<u64, ApiError> {
+    if !requested {
+        return Ok(0);
+    }
+
+    state
+        .auth_service
+        .session_service()
+        .revoke_all_except(auth_user.user_id, &auth_user.session_id)
+        .await
+        .map_err(|e| {
+            ApiError::default()
+                .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+                .with_error_code(auth_codes::INTERNAL_ERROR)
+                .with_message("Password updated, but other sessions could not be revoked")
+                .log_only(e)
+        })
+}
+
 /// POST /api/v1/auth/change-password
+///
+/// With `revoke_other_sessions` every other device is signed out.
 pub async fn change_password(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
     Json(req): Json<ChangePasswordRequest>,
-) -> ApiResult<()> {
+) -> ApiResult<PasswordUpdateResponse> {
     // Validate request
     if let Err(e) = req.validate() {
         return Err(ApiError::default()
@@ -27,14 +116,6 @@ pub async fn change_password(
             .with_message(format!("Validation error: {}", e)));
     }
 
-    // Validate new password length
-    if req.new_password.len() < 8 {
-        return Err(ApiError::default()
-            .with_code(StatusCode::BAD_REQUEST)
-            .with_error_code(val_codes::INVALID_INPUT)
-            .with_message("New password must be at least 8 characters"));
-    }
-
     // Find password auth method
     let auth_method = state
         .auth_service
@@ -55,8 +136,10 @@ pub async fn change_password(
         })?;
 
     // Verify current password
-    if !auth_method
-        .verify_password(&req.current_password)
+    if !state
+        .auth_service
+        .auth_method_service()
+        .verify_password(&auth_method, &req.current_password)
         .map_err(|_| {
             ApiError::default()
                 .with_code(StatusCode::INTERNAL_SERVER_ERROR)
@@ -70,11 +153,13 @@ pub async fn change_password(
             .with_message("Current password is incorrect"));
     }
 
+    check_password_policy(&state, &auth_user, &req.new_password).await?;
+
     // Update password in database
     state
         .auth_service
         .auth_method_service()
-        .update_password(auth_method.id, &req.new_password)
+        .update_password(&auth_method, &req.new_password)
         .await
         .map_err(|_| {
             ApiError::default()
@@ -83,5 +168,123 @@ pub async fn change_password(
                 .with_message("Failed to update password")
         })?;
 
-    Ok(ApiSuccess::default().with_message("Password changed successfully"))
+    let revoked_sessions =
+        revoke_other_sessions(&state, &auth_user, req.revoke_other_sessions).await?;
+
+    Ok(ApiSuccess::default()
+        .with_data(PasswordUpdateResponse { revoked_sessions })
+        .with_message("Password changed successfully"))
+}
+
+/// POST /api/v1/auth/set-password
+///
+/// Adds a password to an account that has none (e.g. created through OAuth).
+/// There is no current password to check, so the route requires a recent
+/// sign-in or re-authentication. With `revoke_other_sessions` every other
+/// device is signed out.
+pub async fn set_password(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<SetPasswordRequest>,
+) -> ApiResult<PasswordUpdateResponse> {
+    if let Err(e) = req.validate() {
+        return Err(ApiError::default()
+            .with_code(StatusCode::BAD_REQUEST)
+            .with_error_code(val_codes::INVALID_INPUT)
+            .with_message(format!("Validation error: {}", e)));
+    }
+
+    let auth_methods = state.auth_service.auth_method_service();
+    let has_password = auth_methods
+        .find_by_user_and_provider(auth_user.user_id, AuthProvider::Password)
+        .await
+        .map_err(|e| {
+            ApiError::default()
+                .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+                .with_error_code(auth_codes::INTERNAL_ERROR)
+                .with_message("Failed to fetch auth method")
+                .log_only(e)
+        })?
+        .is_some();
+    if has_password {
+        return Err(password_already_set());
+    }
+
+    check_password_policy(&state, &auth_user, &req.new_password).await?;
+
+    auth_methods
+        .create_password_auth(auth_user.user_id, &req.new_password, false)
+        .await
+        .map_err(|e| match e {
+            AuthMethodRepositoryError::AlreadyExists => password_already_set(),
+            e => ApiError::default()
+                .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+                .with_error_code(auth_codes::INTERNAL_ERROR)
+                .with_message("Failed to set password")
+                .log_only(e),
+        })?;
+
+    let revoked_sessions =
+        revoke_other_sessions(&state, &auth_user, req.revoke_other_sessions).await?;
+
+    Ok(ApiSuccess::default()
+        .with_code(StatusCode::CREATED)
+        .with_data(PasswordUpdateResponse { revoked_sessions })
+        .with_message("Password set successfully"))
+}
+
+/// POST /api/v1/auth/forgot-password
+///
+/// Always answers the same way, whether or not the address belongs to an account.
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    Json(req): Json<ForgotPasswordRequest>,
+) -> ApiResult<()> {
+    if let Err(e) = req.validate() {
+        return Err(ApiError::default()
+            .with_code(StatusCode::BAD_REQUEST)
+            .with_error_code(val_codes::INVALID_INPUT)
+            .with_message(format!("Validation error: {}", e)));
+    }
+
+    if let Err(e) = state.password_reset_service.request_reset(&req.email).await {
+        tracing::error!("Failed to send password reset email: {}", e);
+    }
+
+    Ok(ApiSuccess::default()
+        .with_message("If an account exists for this email, a reset link was sent"))
+}
+
+/// POST /api/v1/auth/reset-password
+///
+/// Sets a new password from an emailed token and revokes every session.
+pub async fn reset_password(
+    State(state): State<AppState>,
+    Json(req): Json<ResetPasswordRequest>,
+) -> ApiResult<()> {
+    if let Err(e) = req.validate() {
+        return Err(ApiError::default()
+            .with_code(StatusCode::BAD_REQUEST)
+            .with_error_code(val_codes::INVALID_INPUT)
+            .with_message(format!("Validation error: {}", e)));
+    }
+
+    state
+        .password_reset_service
+        .reset_password(&req.token, &req.new_password)
+        .await
+        .map_err(|e| match e {
+            PasswordResetError::InvalidToken => ApiError::default()
+                .with_code(StatusCode::BAD_REQUEST)
+                .with_error_code(auth_codes::TOKEN_INVALID)
+                .with_message("Invalid or expired reset link"),
+            PasswordResetError::WeakPassword(violations) => weak_password_error(violations),
+            e => ApiError::default()
+                .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+                .with_error_code(auth_codes::INTERNAL_ERROR)
+                .with_message("Failed to reset password")
+                .log_only(e),
+        })?;
+
+    Ok(ApiSuccess::default().with_message("Password has been reset, please sign in again"))
 }
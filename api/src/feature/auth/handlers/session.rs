@@ -1,14 +1,19 @@
 use axum::{
-    Extension,
+    Extension, Json,
     extract::{Path, State},
     http::StatusCode,
 };
 use uuid::Uuid;
+use validator::Validate;
 
 use crate::{
-    feature::auth::types::AuthUser,
+    feature::auth::{
+        session::SessionResponse,
+        types::{AuthUser, RevokeSessionLinkRequest},
+    },
     infrastructure::web::response::{
         ApiError, ApiResult, ApiSuccess, codes::auth as auth_codes, codes::generic,
+        codes::validation as val_codes,
     },
     state::AppState,
 };
@@ -17,7 +22,7 @@ use crate::{
 pub async fn list_sessions(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
-) -> ApiResult<Vec<serde_json::Value>> {
+) -> ApiResult<Vec<SessionResponse>> {
     tracing::info!(
         "Listing sessions for user: {}, JWT session_id: {}",
         auth_user.user_id,
@@ -41,18 +46,7 @@ pub async fn list_sessions(
 
     let session_responses: Vec<_> = sessions
         .into_iter()
-        .map(|session| {
-            serde_json::json!({
-                "id": session.id,
-                "device": session.device_name.unwrap_or_else(|| "Unknown Device".to_string()),
-                "device_type": session.device_type,
-                "location": session.location.unwrap_or_else(|| "Unknown Location".to_string()),
-                "ip": session.ip_address,
-                "created_at": session.created_at.to_rfc3339(),
-                "last_active_at": session.last_active_at.to_rfc3339(),
-                "is_current": session.session_id == auth_user.session_id
-            })
-        })
+        .map(|session| SessionResponse::from_session(session, &auth_user.session_id))
         .collect();
 
     Ok(ApiSuccess::default()
@@ -132,3 +126,41 @@ pub async fn revoke_session(
 
     Ok(ApiSuccess::default().with_message("Session revoked"))
 }
+
+/// POST /api/v1/auth/sessions/revoke
+///
+/// Redeems the link from a new sign-in alert: signs that session out without
+/// requiring the user to sign in first.
+pub async fn revoke_session_by_link(
+    State(state): State<AppState>,
+    Json(req): Json<RevokeSessionLinkRequest>,
+) -> ApiResult<()> {
+    if let Err(e) = req.validate() {
+        return Err(ApiError::default()
+            .with_code(StatusCode::BAD_REQUEST)
+            .with_error_code(val_codes::INVALID_INPUT)
+            .with_message(format!("Validation error: {}", e)));
+    }
+
+    let revoked = state
+        .auth_service
+        .session_service()
+        .revoke_by_token(&req.token)
+        .await
+        .map_err(|e| {
+            ApiError::default()
+                .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+                .with_error_code(auth_codes::INTERNAL_ERROR)
+                .with_message("Failed to revoke session")
+                .log_only(e)
+        })?;
+
+    if revoked.is_none() {
+        return Err(ApiError::default()
+            .with_code(StatusCode::BAD_REQUEST)
+            .with_error_code(auth_codes::TOKEN_INVALID)
+            .with_message("Invalid link or the session has already ended"));
+    }
+
+    Ok(ApiSuccess::default().with_message("Session revoked"))
+}
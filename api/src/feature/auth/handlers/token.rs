@@ -0,0 +1,37 @@
+use axum::{
+    Form, Json,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+
+use crate::{feature::auth::types::TokenRequest, state::AppState};
+
+/// POST /api/v1/oauth/introspect (API key or service token required)
+///
+/// RFC 7662 introspection for downstream services: unlike a local signature
+/// check, it sees blacklisted tokens and revoked sessions. Bare JSON, not
+/// wrapped in the usual API envelope.
+pub async fn introspect(State(state): State<AppState>, Form(req): Form<TokenRequest>) -> Response {
+    let introspection = state
+        .auth_service
+        .introspect(&req.token, req.token_type_hint.as_deref())
+        .await;
+
+    ([(header::CACHE_CONTROL, "no-store")], Json(introspection)).into_response()
+}
+
+/// POST /api/v1/oauth/revoke
+///
+/// RFC 7009 revocation of an access or refresh token, including refresh
+/// tokens held by OIDC clients; holding the token is enough. Always 200, also
+/// for tokens that are unknown or already invalid.
+pub async fn revoke(State(state): State<AppState>, Form(req): Form<TokenRequest>) -> Response {
+    state
+        .auth_service
+        .revoke_token(&req.token, req.token_type_hint.as_deref())
+        .await;
+    state.oidc_service.revoke_refresh_token(&req.token).await;
+
+    [(header::CACHE_CONTROL, "no-store")].into_response()
+}
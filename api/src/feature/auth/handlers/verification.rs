@@ -0,0 +1,64 @@
+use axum::{Json, extract::State, http::StatusCode};
+use validator::Validate;
+
+use crate::{
+    feature::auth::verification::{
+        ResendVerificationRequest, VerificationError, VerifyEmailRequest,
+    },
+    infrastructure::web::response::{
+        ApiError, ApiResult, ApiSuccess,
+        codes::{auth as auth_codes, validation as val_codes},
+    },
+    state::AppState,
+};
+
+fn validation_error(e: validator::ValidationErrors) -> ApiError {
+    ApiError::default()
+        .with_code(StatusCode::BAD_REQUEST)
+        .with_error_code(val_codes::INVALID_INPUT)
+        .with_message(format!("Validation error: {}", e))
+}
+
+/// POST /api/v1/auth/verify-email
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Json(req): Json<VerifyEmailRequest>,
+) -> ApiResult<()> {
+    req.validate().map_err(validation_error)?;
+
+    state
+        .email_verification_service
+        .verify(&req.token)
+        .await
+        .map_err(|e| match e {
+            VerificationError::InvalidToken => ApiError::default()
+                .with_code(StatusCode::BAD_REQUEST)
+                .with_error_code(auth_codes::TOKEN_INVALID)
+                .with_message("Invalid or expired verification link"),
+            e => ApiError::default()
+                .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+                .with_error_code(auth_codes::INTERNAL_ERROR)
+                .with_message("Email verification failed")
+                .log_only(e),
+        })?;
+
+    Ok(ApiSuccess::default().with_message("Email verified"))
+}
+
+/// POST /api/v1/auth/resend-verification
+///
+/// Always answers the same way, whether or not the address belongs to an
+/// unverified account.
+pub async fn resend_verification(
+    State(state): State<AppState>,
+    Json(req): Json<ResendVerificationRequest>,
+) -> ApiResult<()> {
+    req.validate().map_err(validation_error)?;
+
+    if let Err(e) = state.email_verification_service.resend(&req.email).await {
+        tracing::error!("Failed to resend verification email: {}", e);
+    }
+
+    Ok(ApiSuccess::default()
+        .with_message("If the account exists and is unverified, a verification email was sent"))
+}
@@ -0,0 +1,203 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    feature::auth::{
+        repository::AuthError,
+        service::LoginOutcome,
+        session::DeviceInfo,
+        types::{AuthUser, LoginResponse},
+        webauthn::{
+            PasskeyLoginBeginRequest, PasskeyLoginFinishRequest, PasskeyLoginOptions,
+            PasskeyRegisterFinishRequest, PasskeyRegistrationOptions, PasskeyResponse,
+            WebAuthnError,
+        },
+    },
+    infrastructure::web::response::{
+        ApiError, ApiResult, ApiSuccess,
+        codes::{auth as auth_codes, generic, validation as val_codes},
+    },
+    state::AppState,
+};
+
+fn validation_error(e: validator::ValidationErrors) -> ApiError {
+    ApiError::default()
+        .with_code(StatusCode::BAD_REQUEST)
+        .with_error_code(val_codes::INVALID_INPUT)
+        .with_message(format!("Validation error: {}", e))
+}
+
+fn webauthn_error(e: WebAuthnError) -> ApiError {
+    match e {
+        WebAuthnError::InvalidCeremony => ApiError::default()
+            .with_code(StatusCode::BAD_REQUEST)
+            .with_error_code(auth_codes::PASSKEY_REJECTED)
+            .with_message("Passkey request expired, please try again"),
+        WebAuthnError::Rejected(reason) => ApiError::default()
+            .with_code(StatusCode::BAD_REQUEST)
+            .with_error_code(auth_codes::PASSKEY_REJECTED)
+            .with_message(format!("Passkey rejected: {reason}")),
+        WebAuthnError::UnknownCredential | WebAuthnError::CounterRegression => ApiError::default()
+            .with_code(StatusCode::UNAUTHORIZED)
+            .with_error_code(auth_codes::PASSKEY_REJECTED)
+            .with_message("Passkey not recognised"),
+        WebAuthnError::CredentialExists => ApiError::default()
+            .with_code(StatusCode::CONFLICT)
+            .with_error_code(auth_codes::PASSKEY_EXISTS)
+            .with_message("This passkey is already registered"),
+        WebAuthnError::NotFound => ApiError::default()
+            .with_code(StatusCode::NOT_FOUND)
+            .with_error_code(generic::NOT_FOUND)
+            .with_message("Passkey not found"),
+        WebAuthnError::LastLoginMethod => ApiError::default()
+            .with_code(StatusCode::CONFLICT)
+            .with_error_code(auth_codes::LAST_LOGIN_METHOD)
+            .with_message("Add another sign-in method before removing this one"),
+        WebAuthnError::Auth(AuthError::InvalidCredentials | AuthError::UserNotFound) => {
+            ApiError::default()
+                .with_code(StatusCode::UNAUTHORIZED)
+                .with_error_code(auth_codes::INVALID_CREDENTIALS)
+                .with_message("Passkey not recognised")
+        }
+        WebAuthnError::Auth(AuthError::EmailNotVerified) => ApiError::default()
+            .with_code(StatusCode::FORBIDDEN)
+            .with_error_code(auth_codes::EMAIL_NOT_VERIFIED)
+            .with_message("Please verify your email address before signing in"),
+        WebAuthnError::Auth(e) => ApiError::default()
+            .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+            .with_error_code(auth_codes::INTERNAL_ERROR)
+            .with_message("Passkey operation failed")
+            .log_only(e),
+        WebAuthnError::Database(e) => ApiError::default()
+            .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+            .with_error_code(auth_codes::INTERNAL_ERROR)
+            .with_message("Passkey operation failed")
+            .log_only(e),
+    }
+}
+
+/// POST /api/v1/auth/webauthn/register/begin
+///
+/// Returns options for `navigator.credentials.create()`.
+pub async fn passkey_register_begin(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> ApiResult<PasskeyRegistrationOptions> {
+    let options = state
+        .webauthn_service
+        .begin_registration(auth_user.user_id)
+        .await
+        .map_err(webauthn_error)?;
+
+    Ok(ApiSuccess::default()
+        .with_data(options)
+        .with_message("Create a passkey with these options"))
+}
+
+/// POST /api/v1/auth/webauthn/register/finish
+pub async fn passkey_register_finish(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<PasskeyRegisterFinishRequest>,
+) -> ApiResult<PasskeyResponse> {
+    req.validate().map_err(validation_error)?;
+
+    let passkey = state
+        .webauthn_service
+        .finish_registration(auth_user.user_id, req)
+        .await
+        .map_err(webauthn_error)?;
+
+    Ok(ApiSuccess::default()
+        .with_code(StatusCode::CREATED)
+        .with_data(passkey)
+        .with_message("Passkey registered"))
+}
+
+/// GET /api/v1/auth/webauthn/credentials
+pub async fn list_passkeys(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> ApiResult<Vec<PasskeyResponse>> {
+    let passkeys = state
+        .webauthn_service
+        .list_passkeys(auth_user.user_id)
+        .await
+        .map_err(webauthn_error)?;
+
+    Ok(ApiSuccess::default()
+        .with_data(passkeys)
+        .with_message("Passkeys retrieved"))
+}
+
+/// DELETE /api/v1/auth/webauthn/credentials/{id}
+pub async fn delete_passkey(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<()> {
+    state
+        .webauthn_service
+        .delete_passkey(auth_user.user_id, id)
+        .await
+        .map_err(webauthn_error)?;
+
+    Ok(ApiSuccess::default().with_message("Passkey removed"))
+}
+
+/// POST /api/v1/auth/webauthn/login/begin
+///
+/// Returns options for `navigator.credentials.get()`. Without an email the
+/// browser offers any discoverable passkey for this site.
+pub async fn passkey_login_begin(
+    State(state): State<AppState>,
+    Json(req): Json<PasskeyLoginBeginRequest>,
+) -> ApiResult<PasskeyLoginOptions> {
+    req.validate().map_err(validation_error)?;
+
+    let options = state
+        .webauthn_service
+        .begin_login(req.email.as_deref())
+        .await
+        .map_err(webauthn_error)?;
+
+    Ok(ApiSuccess::default()
+        .with_data(options)
+        .with_message("Sign in with a passkey using these options"))
+}
+
+/// POST /api/v1/auth/webauthn/login/finish
+///
+/// Same response shape as `/login`: a session, or an MFA challenge when the
+/// authenticator did not verify the user and the account has TOTP enabled.
+pub async fn passkey_login_finish(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(req): Json<PasskeyLoginFinishRequest>,
+) -> ApiResult<LoginResponse> {
+    let device_info = DeviceInfo::from_headers(&headers);
+
+    let outcome = state
+        .webauthn_service
+        .finish_login(req, Some(&device_info))
+        .await
+        .map_err(|e| {
+            tracing::debug!("Passkey login rejected: {e}");
+            webauthn_error(e)
+        })?;
+
+    match outcome {
+        LoginOutcome::Authenticated(response, refresh_cookie) => Ok(ApiSuccess::default()
+            .with_data(LoginResponse::Authenticated(response))
+            .with_cookie(refresh_cookie)
+            .with_message("Login successful")),
+        LoginOutcome::MfaRequired(challenge) => Ok(ApiSuccess::default()
+            .with_data(LoginResponse::MfaRequired(challenge))
+            .with_message("MFA verification required")),
+    }
+}
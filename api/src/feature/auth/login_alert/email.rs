@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::infrastructure::mail::{Email, Mailer};
+
+use super::notifier::{LoginAlertError, LoginAlertNotifier, NewSignInAlert};
+
+/// Escape text taken from request headers before it goes into the HTML body
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Emails the account owner through the configured mailer
+#[derive(Clone)]
+pub struct EmailLoginAlertNotifier {
+    mailer: Arc<dyn Mailer>,
+}
+
+impl EmailLoginAlertNotifier {
+    pub fn new(mailer: Arc<dyn Mailer>) -> Self {
+        Self { mailer }
+    }
+}
+
+#[async_trait]
+impl LoginAlertNotifier for EmailLoginAlertNotifier {
+    async fn notify(&self, alert: &NewSignInAlert) -> Result<(), LoginAlertError> {
+        let NewSignInAlert {
+            device_name,
+            ip_address,
+            revoke_url,
+            ..
+        } = alert;
+        let when = alert.signed_in_at.format("%Y-%m-%d %H:%M UTC");
+        let location = alert.location.as_deref().unwrap_or("Unknown");
+
+        self.mailer
+            .send(&Email {
+                to: alert.email.clone(),
+                subject: "New sign-in to your account".to_string(),
+                text_body: format!(
+                    "Your account was just signed in to from a new device:\n\n\
+                     {device_name}\nIP address: {ip_address}\nLocation: {location}\n\
+                     Time: {when}\n\n\
+                     If this was you, there is nothing to do. If not, sign this \
+                     device out right away and change your password:\n\n{revoke_url}"
+                ),
+                html_body: Some(format!(
+                    "<p>Your account was just signed in to from a new device:</p>\
+                     <p>{}<br>IP address: {}<br>Location: {}<br>Time: {when}</p>\
+                     <p>If this was you, there is nothing to do. If not, \
+                     <a href=\"{revoke_url}\">sign this device out</a> right away \
+                     and change your password.</p>",
+                    escape_html(device_name),
+                    escape_html(ip_address),
+                    escape_html(location),
+                )),
+            })
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+
+use super::notifier::{LoginAlertError, LoginAlertNotifier, NewSignInAlert};
+
+/// Writes alerts to the `security` tracing target instead of notifying anyone.
+///
+/// For development: the revoke link is logged in full.
+#[derive(Debug, Clone, Default)]
+pub struct LogLoginAlertNotifier;
+
+#[async_trait]
+impl LoginAlertNotifier for LogLoginAlertNotifier {
+    async fn notify(&self, alert: &NewSignInAlert) -> Result<(), LoginAlertError> {
+        tracing::info!(
+            target: "security",
+            user_id = %alert.user_id,
+            device = %alert.device_name,
+            ip = %alert.ip_address,
+            location = ?alert.location,
+            revoke_url = %alert.revoke_url,
+            "New sign-in from an unrecognized device"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+pub mod email;
+pub mod log;
+pub mod notifier;
+
+use std::sync::Arc;
+
+pub use email::EmailLoginAlertNotifier;
+pub use log::LogLoginAlertNotifier;
+pub use notifier::{LoginAlertError, LoginAlertNotifier, NewSignInAlert};
+
+use crate::infrastructure::{
+    config::{LoginAlertChannel, LoginAlertConfig},
+    mail::Mailer,
+};
+
+/// Build the configured alert backend, `None` when alerts are off
+pub fn build_login_alert_notifier(
+    config: &LoginAlertConfig,
+    mailer: Arc<dyn Mailer>,
+) -> Option<Arc<dyn LoginAlertNotifier>> {
+    match config.channel {
+        LoginAlertChannel::Off => None,
+        LoginAlertChannel::Log => Some(Arc::new(LogLoginAlertNotifier)),
+        LoginAlertChannel::Email => Some(Arc::new(EmailLoginAlertNotifier::new(mailer))),
+    }
+}
@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::infrastructure::mail::MailError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoginAlertError {
+    #[error("Mail error: {0}")]
+    Mail(#[from] MailError),
+}
+
+/// A sign-in from a device the account has not used before
+#[derive(Debug, Clone)]
+pub struct NewSignInAlert {
+    pub user_id: Uuid,
+    pub email: String,
+    pub device_name: String,
+    pub ip_address: String,
+    /// GeoIP location of the address, when known
+    pub location: Option<String>,
+    pub signed_in_at: DateTime<Utc>,
+    /// Frontend link that revokes the new session without signing in
+    pub revoke_url: String,
+}
+
+/// Abstraction over where "new sign-in" alerts are delivered
+#[async_trait]
+pub trait LoginAlertNotifier: Send + Sync {
+    /// Tell the account owner about `alert`
+    async fn notify(&self, alert: &NewSignInAlert) -> Result<(), LoginAlertError>;
+}
@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use validator::Validate;
+
+/// Request body for POST /auth/magic-link
+#[derive(Debug, Deserialize, Validate)]
+pub struct MagicLinkRequest {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+}
+
+/// Request body for POST /auth/magic-link/verify
+#[derive(Debug, Deserialize, Validate)]
+pub struct MagicLinkVerifyRequest {
+    #[validate(length(min = 1, max = 128, message = "Token is required"))]
+    pub token: String,
+}
@@ -0,0 +1,5 @@
+pub mod dto;
+pub mod service;
+
+pub use dto::{MagicLinkRequest, MagicLinkVerifyRequest};
+pub use service::{MagicLinkError, MagicLinkService};
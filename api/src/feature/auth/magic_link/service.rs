@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+
+use crate::{
+    feature::{
+        auth::{
+            action_token::{ActionTokenPurpose, ActionTokenService},
+            repository::AuthError,
+            service::{AuthService, LoginOutcome},
+            session::DeviceInfo,
+        },
+        user::repository::UserRepository,
+    },
+    infrastructure::{
+        config::Config,
+        mail::{Email, Mailer},
+        persistence::Database,
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MagicLinkError {
+    #[error("Invalid or expired sign-in link")]
+    InvalidToken,
+
+    #[error("Login failed: {0}")]
+    Auth(#[from] AuthError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Emails single-use sign-in links and redeems them for a session
+#[derive(Clone)]
+pub struct MagicLinkService {
+    db: Database,
+    user_repo: Arc<dyn UserRepository>,
+    tokens: ActionTokenService,
+    mailer: Arc<dyn Mailer>,
+    config: Arc<Config>,
+    auth_service: Arc<AuthService>,
+}
+
+impl MagicLinkService {
+    pub fn new(
+        db: Database,
+        user_repo: Arc<dyn UserRepository>,
+        tokens: ActionTokenService,
+        mailer: Arc<dyn Mailer>,
+        config: Arc<Config>,
+        auth_service: Arc<AuthService>,
+    ) -> Self {
+        Self {
+            db,
+            user_repo,
+            tokens,
+            mailer,
+            config,
+            auth_service,
+        }
+    }
+
+    /// Email a sign-in link if `email` belongs to an active account. The link
+    /// only works together with `binding`, which the caller keeps on the
+    /// requesting device. Returns `Ok(())` for unknown accounts and inside the
+    /// cooldown window too, so callers cannot tell whether the account exists;
+    /// the mail goes out in the background so both paths take the same time.
+    pub async fn request_link(&self, email: &str, binding: &str) -> Result<(), MagicLinkError> {
+        let Some(user) = self
+            .user_repo
+            .find_by_email(self.db.pool(), email)
+            .await?
+            .filter(|u| u.is_active)
+        else {
+            return Ok(());
+        };
+
+        let cooldown = Duration::seconds(self.config.magic_link.resend_cooldown_secs);
+        if let Some(last) = self
+            .tokens
+            .last_issued_at(user.id, ActionTokenPurpose::MagicLink)
+            .await?
+            && last + cooldown > Utc::now()
+        {
+            return Ok(());
+        }
+
+        let ttl_secs = self.config.magic_link.token_ttl_secs;
+        let token = self
+            .tokens
+            .issue(
+                user.id,
+                ActionTokenPurpose::MagicLink,
+                &user.email,
+                ttl_secs,
+                Some(binding),
+            )
+            .await?;
+
+        let link = format!(
+            "{}/magic-link?token={}",
+            self.config.mail.frontend_url.trim_end_matches('/'),
+            token
+        );
+        let minutes = ttl_secs / 60;
+
+        let email = Email {
+            to: user.email.clone(),
+            subject: "Your sign-in link".to_string(),
+            text_body: format!(
+                "Use this link to sign in:\n\n{link}\n\n\
+                 It works once, in the browser where you requested it, and \
+                 expires in {minutes} minute(s). If this wasn't you, you can \
+                 ignore this message."
+            ),
+            html_body: Some(format!(
+                "<p><a href=\"{link}\">Sign in</a></p>\
+                 <p>The link works once, in the browser where you requested it, \
+                 and expires in {minutes} minute(s). If this wasn't you, you can \
+                 ignore this message.</p>"
+            )),
+        };
+        let mailer = Arc::clone(&self.mailer);
+        tokio::spawn(async move {
+            if let Err(e) = mailer.send(&email).await {
+                tracing::error!("Failed to send magic link email: {e}");
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Redeem a sign-in link presented together with its device binding
+    pub async fn sign_in(
+        &self,
+        token: &str,
+        binding: &str,
+        device_info: Option<&DeviceInfo>,
+    ) -> Result<LoginOutcome, MagicLinkError> {
+        let record = self
+            .tokens
+            .redeem(token, ActionTokenPurpose::MagicLink, Some(binding))
+            .await?
+            .ok_or(MagicLinkError::InvalidToken)?;
+
+        // The link is only valid for the address it was sent to
+        let user = self
+            .user_repo
+            .find_by_id(self.db.pool(), record.user_id)
+            .await?
+            .filter(|u| u.email.eq_ignore_ascii_case(&record.email))
+            .ok_or(MagicLinkError::InvalidToken)?;
+
+        let outcome = self
+            .auth_service
+            .magic_link_login(user.id, device_info)
+            .await?;
+
+        Ok(outcome)
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Response for POST /auth/mfa/enroll
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaEnrollResponse {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    /// `otpauth://` URI to render as a QR code
+    pub provisioning_uri: String,
+}
+
+/// Request body carrying a TOTP code or a recovery code
+#[derive(Debug, Deserialize, Validate)]
+pub struct MfaCodeRequest {
+    #[validate(length(min = 1, max = 64, message = "Code is required"))]
+    pub code: String,
+}
+
+/// Request body for POST /auth/mfa/verify (second login step)
+#[derive(Debug, Deserialize, Validate)]
+pub struct MfaVerifyRequest {
+    #[validate(length(min = 1, message = "MFA token is required"))]
+    pub mfa_token: String,
+
+    #[validate(length(min = 1, max = 64, message = "Code is required"))]
+    pub code: String,
+}
+
+/// Returned by login instead of tokens when the account has MFA enabled
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    pub mfa_token: String,
+    pub expires_in: i64, // in seconds
+}
+
+/// Freshly generated recovery codes — shown to the user exactly once
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// Response for GET /auth/mfa
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaStatusResponse {
+    pub enabled: bool,
+    pub recovery_codes_remaining: i64,
+}
@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// TOTP enrollment for a user. `enabled_at` is NULL until the user confirms
+/// the enrollment with a valid code.
+#[derive(Debug, Clone, FromRow)]
+pub struct UserMfa {
+    pub user_id: Uuid,
+    pub totp_secret: String,
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserMfa {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+}
@@ -0,0 +1,13 @@
+pub mod dto;
+pub mod entity;
+pub mod repository;
+pub mod service;
+pub mod totp;
+
+pub use dto::{
+    MfaChallengeResponse, MfaCodeRequest, MfaEnrollResponse, MfaStatusResponse, MfaVerifyRequest,
+    RecoveryCodesResponse,
+};
+pub use entity::UserMfa;
+pub use repository::{MfaRepository, MfaRepositoryImpl};
+pub use service::{MfaError, MfaService};
@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::entity::UserMfa;
+
+#[async_trait]
+pub trait MfaRepository: Send + Sync {
+    /// Find the MFA enrollment (pending or enabled) for a user
+    async fn find(&self, pool: &PgPool, user_id: Uuid) -> Result<Option<UserMfa>, sqlx::Error>;
+
+    /// Create or replace a pending enrollment. Returns `None` if MFA is already
+    /// enabled — an active secret is never overwritten.
+    async fn upsert_pending(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        totp_secret: &str,
+    ) -> Result<Option<UserMfa>, sqlx::Error>;
+
+    /// Mark a pending enrollment as enabled
+    async fn enable(&self, pool: &PgPool, user_id: Uuid, step: i64) -> Result<bool, sqlx::Error>;
+
+    /// Record an accepted TOTP step. Returns `false` if the step (or a later
+    /// one) was already used, so concurrent replays of one code cannot both win.
+    async fn record_step(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        step: i64,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Remove the enrollment and all recovery codes
+    async fn delete(&self, pool: &PgPool, user_id: Uuid) -> Result<(), sqlx::Error>;
+
+    /// Replace all recovery codes for a user with a fresh set of hashes
+    async fn replace_recovery_codes(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        code_hashes: &[String],
+    ) -> Result<(), sqlx::Error>;
+
+    /// Mark an unused recovery code as used. Returns `false` if no match.
+    async fn consume_recovery_code(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Count unused recovery codes
+    async fn count_recovery_codes(&self, pool: &PgPool, user_id: Uuid) -> Result<i64, sqlx::Error>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MfaRepositoryImpl;
+
+impl MfaRepositoryImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl MfaRepository for MfaRepositoryImpl {
+    async fn find(&self, pool: &PgPool, user_id: Uuid) -> Result<Option<UserMfa>, sqlx::Error> {
+        sqlx::query_as::<_, UserMfa>("SELECT * FROM user_mfa WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    async fn upsert_pending(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        totp_secret: &str,
+    ) -> Result<Option<UserMfa>, sqlx::Error> {
+        sqlx::query_as::<_, UserMfa>(
+            r#"
+            INSERT INTO user_mfa (user_id, totp_secret)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+                SET totp_secret = EXCLUDED.totp_secret, last_used_step = NULL
+                WHERE user_mfa.enabled_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(totp_secret)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn enable(&self, pool: &PgPool, user_id: Uuid, step: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_mfa
+            SET enabled_at = NOW(), last_used_step = $2
+            WHERE user_id = $1 AND enabled_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn record_step(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        step: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_mfa
+            SET last_used_step = $2
+            WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, pool: &PgPool, user_id: Uuid) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM mfa_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM user_mfa WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        code_hashes: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM mfa_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO mfa_recovery_codes (user_id, code_hash)
+            SELECT $1, UNNEST($2::VARCHAR[])
+            "#,
+        )
+        .bind(user_id)
+        .bind(code_hashes)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE mfa_recovery_codes
+            SET used_at = NOW()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn count_recovery_codes(&self, pool: &PgPool, user_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM mfa_recovery_codes WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+    }
+}
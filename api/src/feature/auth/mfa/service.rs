@@ -0,0 +1,233 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use data_encoding::BASE32_NOPAD;
+use rand::RngCore;
+use uuid::Uuid;
+
+use crate::{
+    feature::auth::utils::sha256_hex,
+    infrastructure::{config::Config, persistence::Database},
+};
+
+use super::{
+    dto::{MfaEnrollResponse, MfaStatusResponse},
+    repository::MfaRepository,
+    totp,
+};
+
+/// Number of recovery codes issued per enrollment / regeneration
+const RECOVERY_CODE_COUNT: usize = 10;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MfaError {
+    #[error("MFA is not enabled")]
+    NotEnabled,
+
+    #[error("MFA is already enabled")]
+    AlreadyEnabled,
+
+    #[error("No pending MFA enrollment")]
+    NoPendingEnrollment,
+
+    #[error("Invalid MFA code")]
+    InvalidCode,
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// TOTP enrollment, verification and recovery codes
+#[derive(Clone)]
+pub struct MfaService {
+    db: Database,
+    repo: Arc<dyn MfaRepository>,
+    config: Arc<Config>,
+}
+
+impl MfaService {
+    pub fn new(db: Database, repo: Arc<dyn MfaRepository>, config: Arc<Config>) -> Self {
+        Self { db, repo, config }
+    }
+
+    /// Whether the user has a confirmed TOTP enrollment
+    pub async fn is_enabled(&self, user_id: Uuid) -> Result<bool, MfaError> {
+        Ok(self
+            .repo
+            .find(self.db.pool(), user_id)
+            .await?
+            .is_some_and(|mfa| mfa.is_enabled()))
+    }
+
+    pub async fn status(&self, user_id: Uuid) -> Result<MfaStatusResponse, MfaError> {
+        let enabled = self.is_enabled(user_id).await?;
+        let recovery_codes_remaining = if enabled {
+            self.repo
+                .count_recovery_codes(self.db.pool(), user_id)
+                .await?
+        } else {
+            0
+        };
+
+        Ok(MfaStatusResponse {
+            enabled,
+            recovery_codes_remaining,
+        })
+    }
+
+    /// Start (or restart) enrollment with a fresh secret
+    pub async fn begin_enrollment(
+        &self,
+        user_id: Uuid,
+        account: &str,
+    ) -> Result<MfaEnrollResponse, MfaError> {
+        let secret = totp::generate_secret();
+
+        self.repo
+            .upsert_pending(self.db.pool(), user_id, &secret)
+            .await?
+            .ok_or(MfaError::AlreadyEnabled)?;
+
+        Ok(MfaEnrollResponse {
+            provisioning_uri: totp::provisioning_uri(&secret, account, &self.config.mfa.issuer),
+            secret,
+        })
+    }
+
+    /// Confirm enrollment with a code from the authenticator app.
+    /// Returns the plaintext recovery codes (only ever shown here).
+    pub async fn confirm_enrollment(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<Vec<String>, MfaError> {
+        let mfa = self
+            .repo
+            .find(self.db.pool(), user_id)
+            .await?
+            .ok_or(MfaError::NoPendingEnrollment)?;
+
+        if mfa.is_enabled() {
+            return Err(MfaError::AlreadyEnabled);
+        }
+
+        let step = totp::verify(&mfa.totp_secret, code, current_step(), None)
+            .ok_or(MfaError::InvalidCode)?;
+
+        if !self
+            .repo
+            .enable(self.db.pool(), user_id, step as i64)
+            .await?
+        {
+            return Err(MfaError::AlreadyEnabled);
+        }
+
+        self.issue_recovery_codes(user_id).await
+    }
+
+    /// Verify a second factor: a TOTP code, or an unused recovery code
+    /// (which is consumed).
+    pub async fn verify(&self, user_id: Uuid, code: &str) -> Result<(), MfaError> {
+        let mfa = self
+            .repo
+            .find(self.db.pool(), user_id)
+            .await?
+            .filter(|mfa| mfa.is_enabled())
+            .ok_or(MfaError::NotEnabled)?;
+
+        let last_used = mfa.last_used_step.map(|s| s as u64);
+        if let Some(step) = totp::verify(&mfa.totp_secret, code, current_step(), last_used) {
+            return if self
+                .repo
+                .record_step(self.db.pool(), user_id, step as i64)
+                .await?
+            {
+                Ok(())
+            } else {
+                Err(MfaError::InvalidCode)
+            };
+        }
+
+        let hash = sha256_hex(&normalize_recovery_code(code));
+        if self
+            .repo
+            .consume_recovery_code(self.db.pool(), user_id, &hash)
+            .await?
+        {
+            tracing::info!("Recovery code used for user {user_id}");
+            return Ok(());
+        }
+
+        Err(MfaError::InvalidCode)
+    }
+
+    /// Turn MFA off (requires a valid second factor)
+    pub async fn disable(&self, user_id: Uuid, code: &str) -> Result<(), MfaError> {
+        self.verify(user_id, code).await?;
+        self.repo.delete(self.db.pool(), user_id).await?;
+        Ok(())
+    }
+
+    /// Replace all recovery codes (requires a valid second factor)
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<Vec<String>, MfaError> {
+        self.verify(user_id, code).await?;
+        self.issue_recovery_codes(user_id).await
+    }
+
+    async fn issue_recovery_codes(&self, user_id: Uuid) -> Result<Vec<String>, MfaError> {
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| generate_recovery_code())
+            .collect();
+        let hashes: Vec<String> = codes
+            .iter()
+            .map(|c| sha256_hex(&normalize_recovery_code(c)))
+            .collect();
+
+        self.repo
+            .replace_recovery_codes(self.db.pool(), user_id, &hashes)
+            .await?;
+
+        Ok(codes)
+    }
+}
+
+fn current_step() -> u64 {
+    totp::step_at(Utc::now().timestamp() as u64)
+}
+
+/// Recovery code like `k7m2q-9xw4a`: 50 random bits, lowercase base32
+fn generate_recovery_code() -> String {
+    let mut bytes = [0u8; 7];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let encoded = BASE32_NOPAD.encode(&bytes).to_lowercase();
+    format!("{}-{}", &encoded[..5], &encoded[5..10])
+}
+
+/// Users may type recovery codes with or without the dash, in any case
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery_code_format_and_normalization() {
+        let code = generate_recovery_code();
+        assert_eq!(code.len(), 11);
+        assert_eq!(&code[5..6], "-");
+        assert_eq!(
+            normalize_recovery_code(&code.to_uppercase()),
+            normalize_recovery_code(&code)
+        );
+        assert_eq!(normalize_recovery_code(" ab12c-D3e4F "), "ab12cd3e4f");
+    }
+}
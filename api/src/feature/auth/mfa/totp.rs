@@ -0,0 +1,129 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+
+/// RFC 6238 defaults — what every authenticator app supports
+pub const DIGITS: u32 = 6;
+pub const PERIOD_SECS: u64 = 30;
+
+/// Accept codes from one step before/after the current one (clock drift)
+const SKEW_STEPS: u64 = 1;
+
+/// Generate a new shared secret: 160 random bits, base32 encoded (RFC 4226 §4)
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    BASE32_NOPAD.encode(&bytes)
+}
+
+/// `otpauth://` URI for QR codes (Google Authenticator key URI format)
+pub fn provisioning_uri(secret: &str, account: &str, issuer: &str) -> String {
+    let mut url = reqwest::Url::parse("otpauth://totp/").expect("static URL");
+    url.set_path(&format!("{issuer}:{account}"));
+    url.query_pairs_mut()
+        .append_pair("secret", secret)
+        .append_pair("issuer", issuer)
+        .append_pair("algorithm", "SHA1")
+        .append_pair("digits", &DIGITS.to_string())
+        .append_pair("period", &PERIOD_SECS.to_string());
+    url.to_string()
+}
+
+/// Time step for a unix timestamp
+pub fn step_at(unix_secs: u64) -> u64 {
+    unix_secs / PERIOD_SECS
+}
+
+/// HOTP value for a raw key and counter (RFC 4226 §5.3)
+fn hotp(key: &[u8], counter: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[19] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    )
+}
+
+/// Code for a base32 secret at a given step; `None` if the secret is malformed
+pub fn code_at(secret: &str, step: u64) -> Option<String> {
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    Some(hotp(&key, step))
+}
+
+/// Verify a code against the steps around `now_step`.
+///
+/// Returns the matched step so the caller can persist it; steps at or before
+/// `last_used_step` are rejected to prevent replaying an observed code.
+pub fn verify(secret: &str, code: &str, now_step: u64, last_used_step: Option<u64>) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+
+    (now_step.saturating_sub(SKEW_STEPS)..=now_step + SKEW_STEPS)
+        .filter(|step| last_used_step.is_none_or(|last| *step > last))
+        .find(|step| {
+            constant_time_eq::constant_time_eq(hotp(&key, *step).as_bytes(), code.as_bytes())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 Appendix B secret ("12345678901234567890") in base32
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn test_rfc6238_sha1_vectors() {
+        // The RFC lists 8-digit values; the 6-digit codes are their last 6 digits
+        for (time, expected) in [
+            (59u64, "287082"),
+            (1111111109, "081804"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ] {
+            assert_eq!(code_at(RFC_SECRET, step_at(time)).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_verify_allows_skew_and_rejects_replay() {
+        let step = step_at(1234567890);
+        let previous = code_at(RFC_SECRET, step - 1).unwrap();
+
+        assert_eq!(verify(RFC_SECRET, &previous, step, None), Some(step - 1));
+        assert_eq!(verify(RFC_SECRET, &previous, step, Some(step - 1)), None);
+        assert_eq!(verify(RFC_SECRET, &previous, step + 5, None), None);
+        assert_eq!(verify(RFC_SECRET, "12345", step, None), None);
+    }
+
+    #[test]
+    fn test_provisioning_uri() {
+        let uri = provisioning_uri("ABC", "jane@example.com", "Quax");
+        assert!(uri.starts_with("otpauth://totp/Quax:jane@example.com?"));
+        assert!(uri.contains("secret=ABC"));
+        assert!(uri.contains("issuer=Quax"));
+    }
+
+    #[test]
+    fn test_generated_secret_roundtrips() {
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 32);
+        assert!(code_at(&secret, 1).is_some());
+    }
+}
@@ -1,20 +1,39 @@
+pub mod action_token;
 pub mod auth_method;
 pub mod handlers;
+pub mod login_alert;
+pub mod magic_link;
+pub mod mfa;
+pub mod oauth;
+pub mod oidc;
+pub mod password_policy;
+pub mod password_reset;
+pub mod rbac;
 mod repository;
 mod routes;
+pub mod security_event;
 pub mod service;
 pub mod session;
+pub mod throttle;
 pub mod types;
 pub mod utils;
+pub mod verification;
+pub mod webauthn;
 
 pub use handlers::{
-    change_password, list_sessions, login, logout, logout_all_sessions, me, refresh, register,
-    revoke_session,
+    change_password, delete_auth_method, delete_passkey, forgot_password, introspect, jwks,
+    list_auth_methods, list_passkeys, list_sessions, login, logout, logout_all_sessions,
+    magic_link_request, magic_link_verify, me, mfa_confirm, mfa_disable, mfa_enroll,
+    mfa_regenerate_recovery_codes, mfa_status, mfa_verify, oauth_authorize, oauth_callback,
+    oauth_link, oidc_authorize, oidc_token, openid_configuration, passkey_login_begin,
+    passkey_login_finish, passkey_register_begin, passkey_register_finish, reauthenticate, refresh,
+    register, resend_verification, reset_password, revoke, revoke_session, revoke_session_by_link,
+    set_password, set_primary_auth_method, userinfo, verify_email,
 };
 pub use repository::AuthError;
-pub use routes::{auth_routes, auth_sensitive_routes};
+pub use routes::{auth_routes, auth_sensitive_routes, oauth_routes, well_known_routes};
 pub use types::{
-    AuthResponse, AuthUser, Claims, LoginCredentials, RegisterRequest, Role, TokenType,
-    UserResponse,
+    AuthResponse, AuthUser, Claims, LoginCredentials, LoginResponse, RegisterRequest,
+    RegisterResponse, Role, ServicePrincipal, TokenType, UserResponse,
 };
 pub use utils::REFRESH_TOKEN_COOKIE;
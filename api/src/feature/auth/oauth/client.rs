@@ -0,0 +1,225 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::{
+    feature::auth::auth_method::AuthProvider, infrastructure::config::OAuthProviderConfig,
+};
+
+use super::entity::{OAuthTokenResponse, OAuthUserInfo};
+
+#[derive(Debug, thiserror::Error)]
+pub enum OAuthClientError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Provider returned {status}: {body}")]
+    Provider { status: u16, body: String },
+
+    #[error("Unexpected userinfo payload: {0}")]
+    InvalidUserInfo(&'static str),
+}
+
+/// Thin HTTP client for the provider's token and userinfo endpoints
+#[derive(Clone)]
+pub struct OAuthClient {
+    http: reqwest::Client,
+}
+
+impl Default for OAuthClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OAuthClient {
+    pub fn new() -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .user_agent(concat!("quax/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("Failed to build HTTP client");
+        Self { http }
+    }
+
+    /// Exchange an authorization code for tokens (RFC 6749 §4.1.3 + PKCE)
+    pub async fn exchange_code(
+        &self,
+        provider: AuthProvider,
+        config: &OAuthProviderConfig,
+        code: &str,
+        redirect_uri: &str,
+        code_verifier: &str,
+    ) -> Result<OAuthTokenResponse, OAuthClientError> {
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", code_verifier),
+            ("client_id", config.client_id.as_str()),
+        ];
+
+        let mut request = self
+            .http
+            .post(&config.token_url)
+            .header(reqwest::header::ACCEPT, "application/json");
+
+        // X (Twitter) only accepts confidential client credentials via HTTP Basic
+        if provider == AuthProvider::Twitter {
+            request = request.basic_auth(&config.client_id, Some(&config.client_secret));
+        } else {
+            form.push(("client_secret", config.client_secret.as_str()));
+        }
+
+        let response = request.form(&form).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(OAuthClientError::Provider {
+                status: status.as_u16(),
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+
+        Ok(response.json::<OAuthTokenResponse>().await?)
+    }
+
+    /// Fetch the provider's user profile and normalize it
+    pub async fn fetch_userinfo(
+        &self,
+        provider: AuthProvider,
+        config: &OAuthProviderConfig,
+        access_token: &str,
+    ) -> Result<OAuthUserInfo, OAuthClientError> {
+        let payload = self.get_json(&config.userinfo_url, access_token).await?;
+        let mut info = parse_userinfo(provider, &payload)?;
+
+        // GitHub omits private emails from /user; look them up explicitly
+        if provider == AuthProvider::Github && info.email.is_none() {
+            let emails_url = format!("{}/emails", config.userinfo_url.trim_end_matches('/'));
+            let emails = self.get_json(&emails_url, access_token).await?;
+            if let Some(primary) = emails.as_array().and_then(|list| {
+                list.iter()
+                    .find(|e| e["primary"].as_bool().unwrap_or(false))
+            }) {
+                info.email = primary["email"].as_str().map(|s| s.to_string());
+                info.email_verified = primary["verified"].as_bool().unwrap_or(false);
+            }
+        }
+
+        Ok(info)
+    }
+
+    async fn get_json(&self, url: &str, access_token: &str) -> Result<Value, OAuthClientError> {
+        let response = self
+            .http
+            .get(url)
+            .bearer_auth(access_token)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(OAuthClientError::Provider {
+                status: status.as_u16(),
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+
+        Ok(response.json::<Value>().await?)
+    }
+}
+
+/// Map a provider-specific userinfo payload onto `OAuthUserInfo`
+pub fn parse_userinfo(
+    provider: AuthProvider,
+    payload: &Value,
+) -> Result<OAuthUserInfo, OAuthClientError> {
+    let str_field = |v: &Value| v.as_str().map(|s| s.to_string());
+
+    let info = match provider {
+        AuthProvider::Google => OAuthUserInfo {
+            provider_id: str_field(&payload["sub"])
+                .ok_or(OAuthClientError::InvalidUserInfo("missing sub"))?,
+            email: str_field(&payload["email"]),
+            email_verified: payload["email_verified"].as_bool().unwrap_or(false),
+            name: str_field(&payload["name"]),
+        },
+        AuthProvider::Github => OAuthUserInfo {
+            // GitHub IDs are numeric
+            provider_id: payload["id"]
+                .as_i64()
+                .map(|id| id.to_string())
+                .ok_or(OAuthClientError::InvalidUserInfo("missing id"))?,
+            // Public profile emails must be verified on GitHub
+            email_verified: payload["email"].is_string(),
+            email: str_field(&payload["email"]),
+            name: str_field(&payload["name"]).or_else(|| str_field(&payload["login"])),
+        },
+        AuthProvider::Discord => OAuthUserInfo {
+            provider_id: str_field(&payload["id"])
+                .ok_or(OAuthClientError::InvalidUserInfo("missing id"))?,
+            email: str_field(&payload["email"]),
+            email_verified: payload["verified"].as_bool().unwrap_or(false),
+            name: str_field(&payload["global_name"]).or_else(|| str_field(&payload["username"])),
+        },
+        AuthProvider::Twitter => {
+            let data = &payload["data"];
+            OAuthUserInfo {
+                provider_id: str_field(&data["id"])
+                    .ok_or(OAuthClientError::InvalidUserInfo("missing data.id"))?,
+                // Only confirmed emails are ever returned
+                email_verified: data["confirmed_email"].is_string(),
+                email: str_field(&data["confirmed_email"]),
+                name: str_field(&data["name"]).or_else(|| str_field(&data["username"])),
+            }
+        }
+        AuthProvider::Password | AuthProvider::Passkey => {
+            return Err(OAuthClientError::InvalidUserInfo("not an OAuth provider"));
+        }
+    };
+
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_google_userinfo() {
+        let info = parse_userinfo(
+            AuthProvider::Google,
+            &json!({ "sub": "123", "email": "a@example.com", "email_verified": true, "name": "A" }),
+        )
+        .unwrap();
+        assert_eq!(info.provider_id, "123");
+        assert_eq!(info.email.as_deref(), Some("a@example.com"));
+        assert!(info.email_verified);
+    }
+
+    #[test]
+    fn test_parse_github_userinfo_without_email() {
+        let info = parse_userinfo(
+            AuthProvider::Github,
+            &json!({ "id": 42, "login": "octocat", "name": null, "email": null }),
+        )
+        .unwrap();
+        assert_eq!(info.provider_id, "42");
+        assert_eq!(info.name.as_deref(), Some("octocat"));
+        assert!(info.email.is_none());
+        assert!(!info.email_verified);
+    }
+
+    #[test]
+    fn test_parse_twitter_userinfo() {
+        let info = parse_userinfo(
+            AuthProvider::Twitter,
+            &json!({ "data": { "id": "99", "name": "Bird", "username": "bird" } }),
+        )
+        .unwrap();
+        assert_eq!(info.provider_id, "99");
+        assert!(info.email.is_none());
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Pending authorization created by `/authorize`, consumed by `/callback`
+#[derive(Debug, Clone, FromRow)]
+pub struct OAuthState {
+    pub state_hash: String,
+    pub provider: String,
+    pub code_verifier: String,
+    /// Set when a signed-in user is linking this provider to their account
+    pub link_user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Token endpoint response (RFC 6749 §5.1)
+#[derive(Debug, Clone, Deserialize)]
+pub struct OAuthTokenResponse {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_in: Option<i64>,
+}
+
+/// Provider identity normalized across providers
+#[derive(Debug, Clone)]
+pub struct OAuthUserInfo {
+    /// Stable user ID at the provider (stored as `auth_methods.provider_id`)
+    pub provider_id: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
+
+/// Query parameters the provider appends to the callback URL
+#[derive(Debug, Deserialize)]
+pub struct OAuthCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Response to starting a link flow; the frontend navigates to the URL
+#[derive(Debug, Serialize)]
+pub struct OAuthLinkResponse {
+    pub authorization_url: String,
+}
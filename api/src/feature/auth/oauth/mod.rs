@@ -0,0 +1,9 @@
+pub mod client;
+pub mod entity;
+pub mod pkce;
+pub mod repository;
+pub mod service;
+
+pub use entity::{OAuthCallbackQuery, OAuthLinkResponse, OAuthState, OAuthUserInfo};
+pub use repository::{OAuthStateRepository, OAuthStateRepositoryImpl};
+pub use service::{AuthorizationRequest, CallbackOutcome, OAuthError, OAuthService};
@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::entity::OAuthState;
+
+#[async_trait]
+pub trait OAuthStateRepository: Send + Sync {
+    /// Store a pending authorization
+    async fn create(
+        &self,
+        pool: &PgPool,
+        state_hash: &str,
+        provider: &str,
+        code_verifier: &str,
+        link_user_id: Option<Uuid>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Atomically fetch and delete a pending authorization (single use).
+    /// Expired rows are never returned.
+    async fn consume(
+        &self,
+        pool: &PgPool,
+        state_hash: &str,
+    ) -> Result<Option<OAuthState>, sqlx::Error>;
+
+    /// Remove expired pending authorizations
+    async fn cleanup_expired(&self, pool: &PgPool) -> Result<u64, sqlx::Error>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OAuthStateRepositoryImpl;
+
+impl OAuthStateRepositoryImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl OAuthStateRepository for OAuthStateRepositoryImpl {
+    async fn create(
+        &self,
+        pool: &PgPool,
+        state_hash: &str,
+        provider: &str,
+        code_verifier: &str,
+        link_user_id: Option<Uuid>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO oauth_states (state_hash, provider, code_verifier, link_user_id, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(state_hash)
+        .bind(provider)
+        .bind(code_verifier)
+        .bind(link_user_id)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn consume(
+        &self,
+        pool: &PgPool,
+        state_hash: &str,
+    ) -> Result<Option<OAuthState>, sqlx::Error> {
+        let state = sqlx::query_as::<_, OAuthState>(
+            r#"
+            DELETE FROM oauth_states
+            WHERE state_hash = $1 AND expires_at > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(state_hash)
+        .fetch_optional(pool)
+        .await?;
+        Ok(state)
+    }
+
+    async fn cleanup_expired(&self, pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM oauth_states WHERE expires_at <= NOW()")
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,311 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    feature::auth::{
+        auth_method::{AuthMethodRepositoryError, AuthProvider},
+        repository::AuthError,
+        service::{AuthService, LoginOutcome},
+        session::DeviceInfo,
+        utils::{random_token, sha256_hex},
+    },
+    infrastructure::{
+        config::{Config, OAuthProviderConfig},
+        persistence::Database,
+    },
+};
+
+use super::{
+    client::{OAuthClient, OAuthClientError},
+    entity::{OAuthTokenResponse, OAuthUserInfo},
+    pkce,
+    repository::OAuthStateRepository,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum OAuthError {
+    #[error("OAuth provider not configured")]
+    ProviderNotConfigured,
+
+    #[error("Invalid or expired OAuth state")]
+    InvalidState,
+
+    #[error("Provider denied access: {0}")]
+    AccessDenied(String),
+
+    #[error("Provider request failed: {0}")]
+    Provider(#[from] OAuthClientError),
+
+    #[error("Provider did not return an email address")]
+    EmailUnavailable,
+
+    #[error("Provider email address is not verified")]
+    EmailNotVerified,
+
+    #[error("This provider account is already linked")]
+    AlreadyLinked,
+
+    #[error("Linking failed: {0}")]
+    AuthMethod(#[from] AuthMethodRepositoryError),
+
+    #[error("Login failed: {0}")]
+    Auth(#[from] AuthError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl OAuthError {
+    /// Short machine-readable reason, passed to the frontend as `?error=`
+    pub fn reason(&self) -> &'static str {
+        match self {
+            OAuthError::ProviderNotConfigured => "provider_not_configured",
+            OAuthError::InvalidState => "invalid_state",
+            OAuthError::AccessDenied(_) => "access_denied",
+            OAuthError::Provider(_) => "provider_error",
+            OAuthError::EmailUnavailable => "email_unavailable",
+            OAuthError::EmailNotVerified => "email_not_verified",
+            OAuthError::AlreadyLinked => "already_linked",
+            OAuthError::AuthMethod(_) => "link_failed",
+            OAuthError::Auth(AuthError::UnverifiedAccountExists) => "account_unverified",
+            OAuthError::Auth(_) | OAuthError::Database(_) => "login_failed",
+        }
+    }
+}
+
+/// Provider authorization URL plus the raw `state` bound to the browser
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+    pub url: String,
+    pub state: String,
+}
+
+/// Result of a completed authorization
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum CallbackOutcome {
+    /// Login flow: a session or an MFA challenge
+    Login(LoginOutcome),
+    /// Link flow: the provider was attached to the signed-in user's account
+    Linked(AuthProvider),
+}
+
+/// OAuth2 authorization-code flow with PKCE, handing off to `AuthService::oauth_login`
+#[derive(Clone)]
+pub struct OAuthService {
+    db: Database,
+    repo: Arc<dyn OAuthStateRepository>,
+    client: OAuthClient,
+    config: Arc<Config>,
+    auth_service: Arc<AuthService>,
+}
+
+impl OAuthService {
+    pub fn new(
+        db: Database,
+        repo: Arc<dyn OAuthStateRepository>,
+        config: Arc<Config>,
+        auth_service: Arc<AuthService>,
+    ) -> Self {
+        Self {
+            db,
+            repo,
+            client: OAuthClient::new(),
+            config,
+            auth_service,
+        }
+    }
+
+    fn provider_config(&self, provider: AuthProvider) -> Result<&OAuthProviderConfig, OAuthError> {
+        self.config
+            .oauth
+            .provider(provider.as_str())
+            .ok_or(OAuthError::ProviderNotConfigured)
+    }
+
+    /// Callback URL registered with the provider
+    pub fn redirect_uri(&self, provider: AuthProvider) -> String {
+        format!(
+            "{}/api/v1/auth/oauth/{}/callback",
+            self.config.oauth.callback_base_url.trim_end_matches('/'),
+            provider.as_str()
+        )
+    }
+
+    /// Start a login authorization
+    pub async fn authorize(
+        &self,
+        provider: AuthProvider,
+    ) -> Result<AuthorizationRequest, OAuthError> {
+        self.start(provider, None).await
+    }
+
+    /// Start an authorization that links the provider to `user_id` on callback
+    pub async fn authorize_link(
+        &self,
+        provider: AuthProvider,
+        user_id: Uuid,
+    ) -> Result<AuthorizationRequest, OAuthError> {
+        self.start(provider, Some(user_id)).await
+    }
+
+    /// Persist state + PKCE verifier, build the provider URL
+    async fn start(
+        &self,
+        provider: AuthProvider,
+        link_user_id: Option<Uuid>,
+    ) -> Result<AuthorizationRequest, OAuthError> {
+        let provider_config = self.provider_config(provider)?;
+
+        // Opportunistic cleanup of abandoned flows
+        let _ = self.repo.cleanup_expired(self.db.pool()).await;
+
+        let state = random_token(32);
+        let verifier = pkce::generate_verifier();
+        let expires_at = Utc::now() + Duration::seconds(self.config.oauth.state_ttl_secs);
+
+        self.repo
+            .create(
+                self.db.pool(),
+                &sha256_hex(&state),
+                provider.as_str(),
+                &verifier,
+                link_user_id,
+                expires_at,
+            )
+            .await?;
+
+        let mut url = reqwest::Url::parse(&provider_config.authorize_url)
+            .map_err(|_| OAuthError::ProviderNotConfigured)?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &provider_config.client_id)
+            .append_pair("redirect_uri", &self.redirect_uri(provider))
+            .append_pair("scope", &provider_config.scopes.join(" "))
+            .append_pair("state", &state)
+            .append_pair("code_challenge", &pkce::challenge_for(&verifier))
+            .append_pair("code_challenge_method", "S256");
+
+        Ok(AuthorizationRequest {
+            url: url.to_string(),
+            state,
+        })
+    }
+
+    /// Complete an authorization: verify state, exchange the code and fetch the
+    /// provider identity. Link flows attach it to the user who started them;
+    /// login flows log the user in (creating or linking the account by email),
+    /// with a challenge instead of a session for accounts with MFA enabled.
+    pub async fn callback(
+        &self,
+        provider: AuthProvider,
+        code: &str,
+        state: &str,
+        device_info: Option<&DeviceInfo>,
+    ) -> Result<CallbackOutcome, OAuthError> {
+        let provider_config = self.provider_config(provider)?;
+
+        let pending = self
+            .repo
+            .consume(self.db.pool(), &sha256_hex(state))
+            .await?
+            .ok_or(OAuthError::InvalidState)?;
+
+        if pending.provider != provider.as_str() {
+            return Err(OAuthError::InvalidState);
+        }
+
+        let tokens = self
+            .client
+            .exchange_code(
+                provider,
+                provider_config,
+                code,
+                &self.redirect_uri(provider),
+                &pending.code_verifier,
+            )
+            .await?;
+
+        let info = self
+            .client
+            .fetch_userinfo(provider, provider_config, &tokens.access_token)
+            .await?;
+
+        let expires_at = tokens
+            .expires_in
+            .map(|secs| Utc::now() + Duration::seconds(secs));
+
+        if let Some(user_id) = pending.link_user_id {
+            self.link(user_id, provider, &info, &tokens, expires_at)
+                .await?;
+            return Ok(CallbackOutcome::Linked(provider));
+        }
+
+        let email = info.email.as_deref().ok_or(OAuthError::EmailUnavailable)?;
+
+        // oauth_login links by email — never trust an unverified address
+        if !info.email_verified {
+            return Err(OAuthError::EmailNotVerified);
+        }
+
+        let result = self
+            .auth_service
+            .oauth_login(
+                provider,
+                &info.provider_id,
+                email,
+                info.name.as_deref(),
+                Some(&tokens.access_token),
+                tokens.refresh_token.as_deref(),
+                expires_at,
+                device_info,
+            )
+            .await?;
+
+        Ok(CallbackOutcome::Login(result))
+    }
+
+    /// Attach a provider identity to an existing account. The user proved who
+    /// they are by signing in, so the provider email does not have to match.
+    async fn link(
+        &self,
+        user_id: Uuid,
+        provider: AuthProvider,
+        info: &OAuthUserInfo,
+        tokens: &OAuthTokenResponse,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(), OAuthError> {
+        let methods = self.auth_service.auth_method_service();
+
+        if let Some(existing) = methods
+            .find_by_provider_id(provider, &info.provider_id)
+            .await?
+        {
+            return if existing.user_id == user_id {
+                Ok(())
+            } else {
+                Err(OAuthError::AlreadyLinked)
+            };
+        }
+
+        methods
+            .create_oauth_auth(
+                user_id,
+                provider,
+                &info.provider_id,
+                Some(&tokens.access_token),
+                tokens.refresh_token.as_deref(),
+                expires_at,
+                false,
+            )
+            .await
+            .map_err(|e| match e {
+                AuthMethodRepositoryError::AlreadyExists => OAuthError::AlreadyLinked,
+                e => OAuthError::AuthMethod(e),
+            })?;
+
+        Ok(())
+    }
+}
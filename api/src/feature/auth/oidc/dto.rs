@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+use super::entity::OidcClient;
+
+/// POST /oauth/authorize body: the client's authorization request, relayed
+/// by the frontend page the client sent the browser to
+#[derive(Debug, Deserialize)]
+pub struct AuthorizeRequest {
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: String,
+    #[serde(default)]
+    pub scope: String,
+    pub state: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub nonce: Option<String>,
+    /// `none` fails instead of asking for consent, `consent` always asks
+    pub prompt: Option<String>,
+    /// The user's answer on the consent screen; omitted on the first call
+    pub consent: Option<bool>,
+}
+
+/// Client as shown on the consent screen
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsentClient {
+    pub client_id: String,
+    pub name: String,
+}
+
+/// Next step of an authorization request
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AuthorizeResponse {
+    /// Show the consent screen, then call again with `consent`
+    ConsentRequired {
+        client: ConsentClient,
+        scopes: Vec<String>,
+    },
+    /// Send the browser to the client's redirect URI, carrying a code or an error
+    Redirect { redirect_to: String },
+}
+
+/// Token endpoint form (RFC 6749 §4.1.3, §6). Client credentials may come
+/// from the form or from HTTP Basic auth.
+#[derive(Debug, Default, Deserialize)]
+pub struct TokenGrantRequest {
+    #[serde(default)]
+    pub grant_type: String,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub refresh_token: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    /// Client credentials grant: subset of the API key's scopes
+    pub scope: Option<String>,
+}
+
+/// Token endpoint response (RFC 6749 §5.1, OIDC Core §3.1.3.3)
+#[derive(Debug, Clone, Serialize)]
+pub struct OidcTokenResponse {
+    pub access_token: String,
+    pub token_type: &'static str,
+    pub expires_in: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    pub scope: String,
+}
+
+/// Error body of the token and userinfo endpoints (RFC 6749 §5.2)
+#[derive(Debug, Clone, Serialize)]
+pub struct OAuthErrorResponse {
+    pub error: &'static str,
+    pub error_description: String,
+}
+
+/// Standard claims released by scope (OIDC Core §5.4)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProfileClaims {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+}
+
+/// ID token claims (OIDC Core §2)
+#[derive(Debug, Clone, Serialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: String,
+    pub exp: i64,
+    pub iat: i64,
+    pub auth_time: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// quax session, shared by every app signed in through it
+    pub sid: String,
+    #[serde(flatten)]
+    pub profile: ProfileClaims,
+}
+
+/// UserInfo response (OIDC Core §5.3.2)
+#[derive(Debug, Clone, Serialize)]
+pub struct UserInfo {
+    pub sub: String,
+    #[serde(flatten)]
+    pub profile: ProfileClaims,
+}
+
+/// `/.well-known/openid-configuration` (OIDC Discovery §3)
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveryDocument {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+    pub jwks_uri: String,
+    pub revocation_endpoint: String,
+    pub scopes_supported: &'static [&'static str],
+    pub response_types_supported: &'static [&'static str],
+    pub grant_types_supported: &'static [&'static str],
+    pub subject_types_supported: &'static [&'static str],
+    pub id_token_signing_alg_values_supported: Vec<String>,
+    pub token_endpoint_auth_methods_supported: &'static [&'static str],
+    pub code_challenge_methods_supported: &'static [&'static str],
+    pub claims_supported: &'static [&'static str],
+}
+
+/// DTO for registering a client
+#[derive(Debug, Deserialize)]
+pub struct CreateOidcClient {
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    /// Defaults to every supported scope
+    pub allowed_scopes: Option<Vec<String>>,
+    /// `false` registers a public client (SPA, mobile) that relies on PKCE
+    /// alone; defaults to `true`
+    pub confidential: Option<bool>,
+    /// Defaults to `true`
+    pub require_consent: Option<bool>,
+}
+
+/// DTO for updating a client
+#[derive(Debug, Deserialize)]
+pub struct UpdateOidcClient {
+    pub name: Option<String>,
+    pub redirect_uris: Option<Vec<String>>,
+    pub allowed_scopes: Option<Vec<String>>,
+    pub require_consent: Option<bool>,
+    pub is_active: Option<bool>,
+}
+
+/// Client with its plain secret (only returned on creation and rotation)
+#[derive(Debug, Serialize)]
+pub struct OidcClientWithSecret {
+    #[serde(flatten)]
+    pub client: OidcClient,
+    pub client_secret: Option<String>, // Plain text secret - only shown once!
+}
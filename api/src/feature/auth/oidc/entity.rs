@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Scopes this provider understands
+pub const SUPPORTED_SCOPES: &[&str] = &["openid", "profile", "email"];
+
+/// Internal app registered to sign users in through quax
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct OidcClient {
+    pub id: Uuid,
+    pub client_id: String,
+    #[serde(skip)]
+    pub client_secret_hash: Option<String>,
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub allowed_scopes: Vec<String>,
+    pub require_consent: bool,
+    pub is_active: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OidcClient {
+    /// Has a secret to authenticate with at the token endpoint
+    pub fn is_confidential(&self) -> bool {
+        self.client_secret_hash.is_some()
+    }
+
+    /// Exact match against the registered redirect URIs
+    pub fn allows_redirect(&self, redirect_uri: &str) -> bool {
+        self.redirect_uris.iter().any(|uri| uri == redirect_uri)
+    }
+}
+
+/// Code issued by an approved `/authorize`, consumed by `/token`
+#[derive(Debug, Clone, FromRow)]
+pub struct AuthorizationCode {
+    pub code_hash: String,
+    pub client_id: Uuid,
+    pub user_id: Uuid,
+    /// quax session the user approved the request from
+    pub session_id: String,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub code_challenge: String,
+    pub nonce: Option<String>,
+    pub auth_time: i64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Refresh token held by a client, rotated on every use
+#[derive(Debug, Clone, FromRow)]
+pub struct OidcRefreshToken {
+    pub id: Uuid,
+    pub token_hash: String,
+    pub client_id: Uuid,
+    pub user_id: Uuid,
+    pub session_id: String,
+    pub scope: String,
+    pub auth_time: i64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
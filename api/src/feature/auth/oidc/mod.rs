@@ -0,0 +1,12 @@
+pub mod dto;
+pub mod entity;
+pub mod repository;
+pub mod service;
+
+pub use dto::{
+    AuthorizeRequest, AuthorizeResponse, CreateOidcClient, OidcClientWithSecret, TokenGrantRequest,
+    UpdateOidcClient,
+};
+pub use entity::OidcClient;
+pub use repository::{OidcRepository, OidcRepositoryImpl};
+pub use service::{OidcError, OidcService};
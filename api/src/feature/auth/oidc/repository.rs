@@ -0,0 +1,417 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::{
+    dto::UpdateOidcClient,
+    entity::{AuthorizationCode, OidcClient, OidcRefreshToken},
+};
+
+#[async_trait]
+pub trait OidcRepository: Send + Sync {
+    #[allow(clippy::too_many_arguments)]
+    async fn create_client(
+        &self,
+        pool: &PgPool,
+        client_id: &str,
+        client_secret_hash: Option<&str>,
+        name: &str,
+        redirect_uris: &[String],
+        allowed_scopes: &[String],
+        require_consent: bool,
+        created_by: Option<Uuid>,
+    ) -> Result<OidcClient, sqlx::Error>;
+
+    async fn find_client_by_id(
+        &self,
+        pool: &PgPool,
+        id: Uuid,
+    ) -> Result<Option<OidcClient>, sqlx::Error>;
+
+    /// Look up a client by its public `client_id`
+    async fn find_client(
+        &self,
+        pool: &PgPool,
+        client_id: &str,
+    ) -> Result<Option<OidcClient>, sqlx::Error>;
+
+    async fn list_clients(
+        &self,
+        pool: &PgPool,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OidcClient>, sqlx::Error>;
+
+    async fn update_client(
+        &self,
+        pool: &PgPool,
+        id: Uuid,
+        payload: &UpdateOidcClient,
+    ) -> Result<Option<OidcClient>, sqlx::Error>;
+
+    async fn set_client_secret(
+        &self,
+        pool: &PgPool,
+        id: Uuid,
+        client_secret_hash: &str,
+    ) -> Result<Option<OidcClient>, sqlx::Error>;
+
+    async fn delete_client(&self, pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error>;
+
+    /// Scopes the user has approved for a client
+    async fn find_consent(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        client_id: Uuid,
+    ) -> Result<Option<Vec<String>>, sqlx::Error>;
+
+    /// Add `scopes` to what the user has approved for a client
+    async fn grant_consent(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        client_id: Uuid,
+        scopes: &[String],
+    ) -> Result<(), sqlx::Error>;
+
+    async fn create_code(&self, pool: &PgPool, code: &AuthorizationCode)
+    -> Result<(), sqlx::Error>;
+
+    /// Atomically fetch and delete a code (single use). Expired codes are
+    /// never returned.
+    async fn consume_code(
+        &self,
+        pool: &PgPool,
+        code_hash: &str,
+    ) -> Result<Option<AuthorizationCode>, sqlx::Error>;
+
+    /// Remove expired codes
+    async fn cleanup_expired_codes(&self, pool: &PgPool) -> Result<u64, sqlx::Error>;
+
+    #[allow(clippy::too_many_arguments)]
+    async fn create_refresh_token(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+        client_id: Uuid,
+        user_id: Uuid,
+        session_id: &str,
+        scope: &str,
+        auth_time: i64,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error>;
+
+    async fn find_refresh_token(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+    ) -> Result<Option<OidcRefreshToken>, sqlx::Error>;
+
+    /// Retire a refresh token on use. Returns `false` if it was already
+    /// retired (a concurrent or replayed use).
+    async fn retire_refresh_token(&self, pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error>;
+
+    /// Revoke every refresh token a client holds for a session
+    async fn revoke_refresh_tokens(
+        &self,
+        pool: &PgPool,
+        session_id: &str,
+        client_id: Uuid,
+    ) -> Result<u64, sqlx::Error>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OidcRepositoryImpl;
+
+impl OidcRepositoryImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl OidcRepository for OidcRepositoryImpl {
+    async fn create_client(
+        &self,
+        pool: &PgPool,
+        client_id: &str,
+        client_secret_hash: Option<&str>,
+        name: &str,
+        redirect_uris: &[String],
+        allowed_scopes: &[String],
+        require_consent: bool,
+        created_by: Option<Uuid>,
+    ) -> Result<OidcClient, sqlx::Error> {
+        sqlx::query_as::<_, OidcClient>(
+            r#"
+            INSERT INTO oidc_clients
+                (client_id, client_secret_hash, name, redirect_uris, allowed_scopes,
+                 require_consent, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(client_id)
+        .bind(client_secret_hash)
+        .bind(name)
+        .bind(redirect_uris)
+        .bind(allowed_scopes)
+        .bind(require_consent)
+        .bind(created_by)
+        .fetch_one(pool)
+        .await
+    }
+
+    async fn find_client_by_id(
+        &self,
+        pool: &PgPool,
+        id: Uuid,
+    ) -> Result<Option<OidcClient>, sqlx::Error> {
+        sqlx::query_as::<_, OidcClient>("SELECT * FROM oidc_clients WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    async fn find_client(
+        &self,
+        pool: &PgPool,
+        client_id: &str,
+    ) -> Result<Option<OidcClient>, sqlx::Error> {
+        sqlx::query_as::<_, OidcClient>("SELECT * FROM oidc_clients WHERE client_id = $1")
+            .bind(client_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    async fn list_clients(
+        &self,
+        pool: &PgPool,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OidcClient>, sqlx::Error> {
+        sqlx::query_as::<_, OidcClient>(
+            "SELECT * FROM oidc_clients ORDER BY created_at DESC LIMIT $1 OFFSET $2",
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+    }
+
+    async fn update_client(
+        &self,
+        pool: &PgPool,
+        id: Uuid,
+        payload: &UpdateOidcClient,
+    ) -> Result<Option<OidcClient>, sqlx::Error> {
+        sqlx::query_as::<_, OidcClient>(
+            r#"
+            UPDATE oidc_clients
+            SET name = COALESCE($1, name),
+                redirect_uris = COALESCE($2, redirect_uris),
+                allowed_scopes = COALESCE($3, allowed_scopes),
+                require_consent = COALESCE($4, require_consent),
+                is_active = COALESCE($5, is_active)
+            WHERE id = $6
+            RETURNING *
+            "#,
+        )
+        .bind(&payload.name)
+        .bind(&payload.redirect_uris)
+        .bind(&payload.allowed_scopes)
+        .bind(payload.require_consent)
+        .bind(payload.is_active)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn set_client_secret(
+        &self,
+        pool: &PgPool,
+        id: Uuid,
+        client_secret_hash: &str,
+    ) -> Result<Option<OidcClient>, sqlx::Error> {
+        sqlx::query_as::<_, OidcClient>(
+            "UPDATE oidc_clients SET client_secret_hash = $1 WHERE id = $2 RETURNING *",
+        )
+        .bind(client_secret_hash)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn delete_client(&self, pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM oidc_clients WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_consent(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        client_id: Uuid,
+    ) -> Result<Option<Vec<String>>, sqlx::Error> {
+        sqlx::query_scalar("SELECT scopes FROM oidc_consents WHERE user_id = $1 AND client_id = $2")
+            .bind(user_id)
+            .bind(client_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    async fn grant_consent(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        client_id: Uuid,
+        scopes: &[String],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO oidc_consents (user_id, client_id, scopes)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, client_id) DO UPDATE
+            SET scopes = ARRAY(
+                SELECT DISTINCT unnest(oidc_consents.scopes || EXCLUDED.scopes)
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(client_id)
+        .bind(scopes)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn create_code(
+        &self,
+        pool: &PgPool,
+        code: &AuthorizationCode,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO oidc_authorization_codes
+                (code_hash, client_id, user_id, session_id, redirect_uri, scope,
+                 code_challenge, nonce, auth_time, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#,
+        )
+        .bind(&code.code_hash)
+        .bind(code.client_id)
+        .bind(code.user_id)
+        .bind(&code.session_id)
+        .bind(&code.redirect_uri)
+        .bind(&code.scope)
+        .bind(&code.code_challenge)
+        .bind(&code.nonce)
+        .bind(code.auth_time)
+        .bind(code.created_at)
+        .bind(code.expires_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn consume_code(
+        &self,
+        pool: &PgPool,
+        code_hash: &str,
+    ) -> Result<Option<AuthorizationCode>, sqlx::Error> {
+        sqlx::query_as::<_, AuthorizationCode>(
+            r#"
+            DELETE FROM oidc_authorization_codes
+            WHERE code_hash = $1 AND expires_at > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(code_hash)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn cleanup_expired_codes(&self, pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM oidc_authorization_codes WHERE expires_at <= NOW()")
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn create_refresh_token(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+        client_id: Uuid,
+        user_id: Uuid,
+        session_id: &str,
+        scope: &str,
+        auth_time: i64,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO oidc_refresh_tokens
+                (token_hash, client_id, user_id, session_id, scope, auth_time, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(token_hash)
+        .bind(client_id)
+        .bind(user_id)
+        .bind(session_id)
+        .bind(scope)
+        .bind(auth_time)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_refresh_token(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+    ) -> Result<Option<OidcRefreshToken>, sqlx::Error> {
+        sqlx::query_as::<_, OidcRefreshToken>(
+            "SELECT * FROM oidc_refresh_tokens WHERE token_hash = $1",
+        )
+        .bind(token_hash)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn retire_refresh_token(&self, pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE oidc_refresh_tokens SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn revoke_refresh_tokens(
+        &self,
+        pool: &PgPool,
+        session_id: &str,
+        client_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oidc_refresh_tokens SET revoked_at = NOW()
+            WHERE session_id = $1 AND client_id = $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(session_id)
+        .bind(client_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
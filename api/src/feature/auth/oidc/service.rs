@@ -0,0 +1,942 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use jsonwebtoken::encode;
+use uuid::Uuid;
+
+use crate::{
+    feature::{
+        admin::api_key::{repository::ApiKeyError, service::ApiKeyService},
+        auth::{
+            oauth::pkce,
+            service::AuthService,
+            session::{SessionRepositoryError, UserSession},
+            types::AuthUser,
+            utils::{
+                JwtError, JwtKeys, create_client_access_token, create_service_access_token,
+                random_token, sha256_hex, validate_access_token,
+            },
+        },
+        user::{UserWithProfile, repository::UserRepository},
+    },
+    infrastructure::{
+        config::Config,
+        persistence::{Database, SessionBlacklist},
+    },
+};
+
+use super::{
+    dto::{
+        AuthorizeRequest, AuthorizeResponse, ConsentClient, CreateOidcClient, DiscoveryDocument,
+        IdTokenClaims, OidcClientWithSecret, OidcTokenResponse, ProfileClaims, TokenGrantRequest,
+        UpdateOidcClient, UserInfo,
+    },
+    entity::{AuthorizationCode, OidcClient, SUPPORTED_SCOPES},
+    repository::OidcRepository,
+};
+
+/// Random bytes per public client ID
+const CLIENT_ID_BYTES: usize = 18;
+/// Random bytes per client secret, authorization code and refresh token (256 bits)
+const SECRET_BYTES: usize = 32;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OidcError {
+    #[error("Unknown or inactive client")]
+    InvalidClient,
+
+    #[error("redirect_uri is not registered for this client")]
+    InvalidRedirectUri,
+
+    #[error("{0}")]
+    InvalidRequest(&'static str),
+
+    #[error("Requested scope is not allowed for this client")]
+    InvalidScope,
+
+    #[error("Invalid, expired or revoked grant")]
+    InvalidGrant,
+
+    #[error("Unsupported grant type")]
+    UnsupportedGrantType,
+
+    #[error("Only the authorization code flow is supported")]
+    UnsupportedResponseType,
+
+    #[error("The user denied the request")]
+    AccessDenied,
+
+    #[error("User consent is required")]
+    ConsentRequired,
+
+    #[error("Invalid or expired access token")]
+    InvalidToken,
+
+    #[error("ID tokens need an asymmetric signing key (JWT_KEYS_DIR)")]
+    NoSigningKey,
+
+    #[error("Token creation failed: {0}")]
+    Jwt(#[from] JwtError),
+
+    #[error("Session error: {0}")]
+    Session(#[from] SessionRepositoryError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl OidcError {
+    /// OAuth 2.0 `error` code (RFC 6749 §4.1.2.1 and §5.2, OIDC Core §3.1.2.6)
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            OidcError::InvalidClient => "invalid_client",
+            OidcError::InvalidRedirectUri | OidcError::InvalidRequest(_) => "invalid_request",
+            OidcError::InvalidScope => "invalid_scope",
+            OidcError::InvalidGrant => "invalid_grant",
+            OidcError::UnsupportedGrantType => "unsupported_grant_type",
+            OidcError::UnsupportedResponseType => "unsupported_response_type",
+            OidcError::AccessDenied => "access_denied",
+            OidcError::ConsentRequired => "consent_required",
+            OidcError::InvalidToken => "invalid_token",
+            OidcError::NoSigningKey
+            | OidcError::Jwt(_)
+            | OidcError::Session(_)
+            | OidcError::Database(_) => "server_error",
+        }
+    }
+
+    /// Authorization errors reported to the client through its redirect URI.
+    /// The rest mean the client or redirect URI cannot be trusted, or the
+    /// server failed.
+    fn redirects(&self) -> bool {
+        matches!(
+            self,
+            OidcError::InvalidRequest(_)
+                | OidcError::InvalidScope
+                | OidcError::UnsupportedResponseType
+                | OidcError::AccessDenied
+                | OidcError::ConsentRequired
+        )
+    }
+}
+
+/// OpenID Connect provider: internal apps sign users in with the
+/// authorization code flow (PKCE required). Every grant hangs off the quax
+/// session the user approved it from, so one quax sign-in covers every app
+/// and ending that session signs the user out of all of them.
+///
+/// The token endpoint also serves machine clients: API keys are exchanged
+/// for short-lived access tokens with the client credentials grant.
+#[derive(Clone)]
+pub struct OidcService {
+    db: Database,
+    repo: Arc<dyn OidcRepository>,
+    user_repo: Arc<dyn UserRepository>,
+    api_keys: ApiKeyService,
+    auth_service: Arc<AuthService>,
+    session_blacklist: Option<Arc<dyn SessionBlacklist>>,
+    jwt_keys: Arc<JwtKeys>,
+    config: Arc<Config>,
+}
+
+impl OidcService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        db: Database,
+        repo: Arc<dyn OidcRepository>,
+        user_repo: Arc<dyn UserRepository>,
+        api_keys: ApiKeyService,
+        auth_service: Arc<AuthService>,
+        session_blacklist: Option<Arc<dyn SessionBlacklist>>,
+        jwt_keys: Arc<JwtKeys>,
+        config: Arc<Config>,
+    ) -> Self {
+        Self {
+            db,
+            repo,
+            user_repo,
+            api_keys,
+            auth_service,
+            session_blacklist,
+            jwt_keys,
+            config,
+        }
+    }
+
+    // ─── Client registration ────────────────────────────────────────────────
+
+    /// Register a client. Confidential clients get a secret, returned only here.
+    pub async fn register_client(
+        &self,
+        req: &CreateOidcClient,
+        created_by: Option<Uuid>,
+    ) -> Result<OidcClientWithSecret, OidcError> {
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(OidcError::InvalidRequest("name is required"));
+        }
+        validate_redirect_uris(&req.redirect_uris)?;
+        let scopes = match &req.allowed_scopes {
+            Some(scopes) => {
+                validate_scopes(scopes)?;
+                scopes.clone()
+            }
+            None => SUPPORTED_SCOPES.iter().map(|s| s.to_string()).collect(),
+        };
+
+        let secret = req
+            .confidential
+            .unwrap_or(true)
+            .then(|| random_token(SECRET_BYTES));
+        let secret_hash = secret.as_deref().map(sha256_hex);
+
+        let client = self
+            .repo
+            .create_client(
+                self.db.pool(),
+                &random_token(CLIENT_ID_BYTES),
+                secret_hash.as_deref(),
+                name,
+                &req.redirect_uris,
+                &scopes,
+                req.require_consent.unwrap_or(true),
+                created_by,
+            )
+            .await?;
+
+        Ok(OidcClientWithSecret {
+            client,
+            client_secret: secret,
+        })
+    }
+
+    pub async fn list_clients(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OidcClient>, OidcError> {
+        Ok(self
+            .repo
+            .list_clients(self.db.pool(), limit, offset)
+            .await?)
+    }
+
+    pub async fn get_client(&self, id: Uuid) -> Result<Option<OidcClient>, OidcError> {
+        Ok(self.repo.find_client_by_id(self.db.pool(), id).await?)
+    }
+
+    pub async fn update_client(
+        &self,
+        id: Uuid,
+        req: &UpdateOidcClient,
+    ) -> Result<Option<OidcClient>, OidcError> {
+        if let Some(uris) = &req.redirect_uris {
+            validate_redirect_uris(uris)?;
+        }
+        if let Some(scopes) = &req.allowed_scopes {
+            validate_scopes(scopes)?;
+        }
+        Ok(self.repo.update_client(self.db.pool(), id, req).await?)
+    }
+
+    pub async fn delete_client(&self, id: Uuid) -> Result<bool, OidcError> {
+        Ok(self.repo.delete_client(self.db.pool(), id).await?)
+    }
+
+    /// Replace a client's secret; the old one stops working immediately.
+    /// A public client becomes confidential.
+    pub async fn rotate_client_secret(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<OidcClientWithSecret>, OidcError> {
+        let secret = random_token(SECRET_BYTES);
+        let client = self
+            .repo
+            .set_client_secret(self.db.pool(), id, &sha256_hex(&secret))
+            .await?;
+
+        Ok(client.map(|client| OidcClientWithSecret {
+            client,
+            client_secret: Some(secret),
+        }))
+    }
+
+    // ─── Protocol ───────────────────────────────────────────────────────────
+
+    /// Discovery document for `/.well-known/openid-configuration`. Without
+    /// an asymmetric key the provider is off: relying parties could only
+    /// check HS256 ID tokens with the secret that also signs quax tokens.
+    pub fn discovery(&self) -> Result<DiscoveryDocument, OidcError> {
+        self.require_signing_key()?;
+        let issuer = &self.config.oidc.issuer;
+        let (header, _) = self.jwt_keys.access_signer();
+
+        Ok(DiscoveryDocument {
+            issuer: issuer.clone(),
+            authorization_endpoint: self.config.oidc.authorize_url.clone(),
+            token_endpoint: format!("{issuer}/api/v1/oauth/token"),
+            userinfo_endpoint: format!("{issuer}/api/v1/oauth/userinfo"),
+            jwks_uri: format!("{issuer}/.well-known/jwks.json"),
+            revocation_endpoint: format!("{issuer}/api/v1/oauth/revoke"),
+            scopes_supported: SUPPORTED_SCOPES,
+            response_types_supported: &["code"],
+            grant_types_supported: &["authorization_code", "refresh_token", "client_credentials"],
+            subject_types_supported: &["public"],
+            id_token_signing_alg_values_supported: vec![format!("{:?}", header.alg)],
+            token_endpoint_auth_methods_supported: &[
+                "client_secret_basic",
+                "client_secret_post",
+                "none",
+            ],
+            code_challenge_methods_supported: &["S256"],
+            claims_supported: &[
+                "sub",
+                "iss",
+                "aud",
+                "exp",
+                "iat",
+                "auth_time",
+                "nonce",
+                "sid",
+                "name",
+                "preferred_username",
+                "picture",
+                "email",
+                "email_verified",
+            ],
+        })
+    }
+
+    fn require_signing_key(&self) -> Result<(), OidcError> {
+        if self.jwt_keys.has_public_keys() {
+            Ok(())
+        } else {
+            Err(OidcError::NoSigningKey)
+        }
+    }
+
+    /// Handle an authorization request for the signed-in `user`: ask for
+    /// consent, or approve it with a code for the client's redirect URI.
+    /// Protocol errors are sent to the client the same way; an unknown client
+    /// or redirect URI is an error for the frontend to show instead.
+    pub async fn authorize(
+        &self,
+        user: &AuthUser,
+        req: &AuthorizeRequest,
+    ) -> Result<AuthorizeResponse, OidcError> {
+        self.require_signing_key()?;
+        let client = self
+            .repo
+            .find_client(self.db.pool(), &req.client_id)
+            .await?
+            .filter(|c| c.is_active)
+            .ok_or(OidcError::InvalidClient)?;
+        if !client.allows_redirect(&req.redirect_uri) {
+            return Err(OidcError::InvalidRedirectUri);
+        }
+
+        match self.grant_code(user, &client, req).await {
+            Err(e) if e.redirects() => {
+                let description = e.to_string();
+                let redirect_to = self.redirect_url(
+                    &req.redirect_uri,
+                    &[
+                        ("error", e.error_code()),
+                        ("error_description", &description),
+                    ],
+                    req.state.as_deref(),
+                )?;
+                Ok(AuthorizeResponse::Redirect { redirect_to })
+            }
+            result => result,
+        }
+    }
+
+    async fn grant_code(
+        &self,
+        user: &AuthUser,
+        client: &OidcClient,
+        req: &AuthorizeRequest,
+    ) -> Result<AuthorizeResponse, OidcError> {
+        if req.response_type != "code" {
+            return Err(OidcError::UnsupportedResponseType);
+        }
+        let challenge = req
+            .code_challenge
+            .as_deref()
+            .filter(|c| !c.is_empty())
+            .ok_or(OidcError::InvalidRequest("code_challenge is required"))?;
+        if req.code_challenge_method.as_deref() != Some("S256") {
+            return Err(OidcError::InvalidRequest(
+                "code_challenge_method must be S256",
+            ));
+        }
+        let scopes = requested_scopes(client, &req.scope)?;
+
+        if req.consent == Some(false) {
+            return Err(OidcError::AccessDenied);
+        }
+        if client.require_consent && req.consent != Some(true) {
+            let granted = self
+                .repo
+                .find_consent(self.db.pool(), user.user_id, client.id)
+                .await?
+                .unwrap_or_default();
+            let covered = req.prompt.as_deref() != Some("consent")
+                && scopes.iter().all(|s| granted.contains(s));
+
+            if !covered {
+                if req.prompt.as_deref() == Some("none") {
+                    return Err(OidcError::ConsentRequired);
+                }
+                return Ok(AuthorizeResponse::ConsentRequired {
+                    client: ConsentClient {
+                        client_id: client.client_id.clone(),
+                        name: client.name.clone(),
+                    },
+                    scopes,
+                });
+            }
+        }
+        if req.consent == Some(true) {
+            self.repo
+                .grant_consent(self.db.pool(), user.user_id, client.id, &scopes)
+                .await?;
+        }
+
+        // Opportunistic cleanup of unredeemed codes
+        let _ = self.repo.cleanup_expired_codes(self.db.pool()).await;
+
+        let code = random_token(SECRET_BYTES);
+        let now = Utc::now();
+        self.repo
+            .create_code(
+                self.db.pool(),
+                &AuthorizationCode {
+                    code_hash: sha256_hex(&code),
+                    client_id: client.id,
+                    user_id: user.user_id,
+                    session_id: user.session_id.clone(),
+                    redirect_uri: req.redirect_uri.clone(),
+                    scope: scopes.join(" "),
+                    code_challenge: challenge.to_string(),
+                    nonce: req.nonce.clone(),
+                    auth_time: user.auth_time,
+                    created_at: now,
+                    expires_at: now + Duration::seconds(self.config.oidc.code_ttl_secs),
+                },
+            )
+            .await?;
+
+        let redirect_to =
+            self.redirect_url(&req.redirect_uri, &[("code", &code)], req.state.as_deref())?;
+        Ok(AuthorizeResponse::Redirect { redirect_to })
+    }
+
+    /// `redirect_uri` with the response parameters, `state` and `iss` (RFC 9207)
+    fn redirect_url(
+        &self,
+        redirect_uri: &str,
+        params: &[(&str, &str)],
+        state: Option<&str>,
+    ) -> Result<String, OidcError> {
+        let mut url =
+            reqwest::Url::parse(redirect_uri).map_err(|_| OidcError::InvalidRedirectUri)?;
+        {
+            let mut query = url.query_pairs_mut();
+            query.extend_pairs(params);
+            if let Some(state) = state {
+                query.append_pair("state", state);
+            }
+            query.append_pair("iss", &self.config.oidc.issuer);
+        }
+        Ok(url.to_string())
+    }
+
+    /// Token endpoint: authenticate the client, then redeem a code, rotate
+    /// a refresh token or exchange an API key. `basic` holds HTTP Basic
+    /// credentials, if sent. Only the API key exchange works without an
+    /// asymmetric signing key.
+    pub async fn token(
+        &self,
+        req: &TokenGrantRequest,
+        basic: Option<(String, String)>,
+    ) -> Result<OidcTokenResponse, OidcError> {
+        // API keys are not OIDC clients
+        if req.grant_type == "client_credentials" {
+            return self.client_credentials(req, basic).await;
+        }
+
+        self.require_signing_key()?;
+        let client = self.authenticate_client(req, basic).await?;
+
+        match req.grant_type.as_str() {
+            "authorization_code" => self.exchange_code(&client, req).await,
+            "refresh_token" => self.refresh(&client, req).await,
+            "" => Err(OidcError::InvalidRequest("grant_type is required")),
+            _ => Err(OidcError::UnsupportedGrantType),
+        }
+    }
+
+    /// Confidential clients must present their secret; public clients only
+    /// name themselves and rely on PKCE
+    async fn authenticate_client(
+        &self,
+        req: &TokenGrantRequest,
+        basic: Option<(String, String)>,
+    ) -> Result<OidcClient, OidcError> {
+        let (client_id, secret) = match basic {
+            Some((id, secret)) => (id, Some(secret)),
+            None => (
+                req.client_id.clone().ok_or(OidcError::InvalidClient)?,
+                req.client_secret.clone(),
+            ),
+        };
+
+        let client = self
+            .repo
+            .find_client(self.db.pool(), &client_id)
+            .await?
+            .filter(|c| c.is_active)
+            .ok_or(OidcError::InvalidClient)?;
+
+        if let Some(expected) = &client.client_secret_hash {
+            let presented = secret.as_deref().map(sha256_hex).unwrap_or_default();
+            if !constant_time_eq::constant_time_eq(expected.as_bytes(), presented.as_bytes()) {
+                return Err(OidcError::InvalidClient);
+            }
+        }
+
+        Ok(client)
+    }
+
+    async fn exchange_code(
+        &self,
+        client: &OidcClient,
+        req: &TokenGrantRequest,
+    ) -> Result<OidcTokenResponse, OidcError> {
+        let code = req
+            .code
+            .as_deref()
+            .ok_or(OidcError::InvalidRequest("code is required"))?;
+        let verifier = req
+            .code_verifier
+            .as_deref()
+            .ok_or(OidcError::InvalidRequest("code_verifier is required"))?;
+
+        let grant = self
+            .repo
+            .consume_code(self.db.pool(), &sha256_hex(code))
+            .await?
+            .ok_or(OidcError::InvalidGrant)?;
+
+        if grant.client_id != client.id
+            || req.redirect_uri.as_deref() != Some(grant.redirect_uri.as_str())
+            || pkce::challenge_for(verifier) != grant.code_challenge
+        {
+            return Err(OidcError::InvalidGrant);
+        }
+
+        let session = self
+            .live_session(&grant.session_id)
+            .await?
+            .ok_or(OidcError::InvalidGrant)?;
+
+        self.issue(client, &session, &grant.scope, grant.auth_time, grant.nonce)
+            .await
+    }
+
+    /// Rotate a refresh token. A token that was already used means the chain
+    /// leaked: everything the client holds for that session is revoked.
+    async fn refresh(
+        &self,
+        client: &OidcClient,
+        req: &TokenGrantRequest,
+    ) -> Result<OidcTokenResponse, OidcError> {
+        let presented = req
+            .refresh_token
+            .as_deref()
+            .ok_or(OidcError::InvalidRequest("refresh_token is required"))?;
+
+        let token = self
+            .repo
+            .find_refresh_token(self.db.pool(), &sha256_hex(presented))
+            .await?
+            .filter(|t| t.client_id == client.id)
+            .ok_or(OidcError::InvalidGrant)?;
+
+        if token.revoked_at.is_some()
+            || !self
+                .repo
+                .retire_refresh_token(self.db.pool(), token.id)
+                .await?
+        {
+            tracing::warn!(
+                target: "security",
+                client_id = %client.client_id,
+                user_id = %token.user_id,
+                "OIDC refresh token reused, revoking the client's tokens for the session"
+            );
+            self.repo
+                .revoke_refresh_tokens(self.db.pool(), &token.session_id, client.id)
+                .await?;
+            return Err(OidcError::InvalidGrant);
+        }
+
+        if token.expires_at < Utc::now() {
+            return Err(OidcError::InvalidGrant);
+        }
+
+        let session = self
+            .live_session(&token.session_id)
+            .await?
+            .ok_or(OidcError::InvalidGrant)?;
+
+        self.issue(client, &session, &token.scope, token.auth_time, None)
+            .await
+    }
+
+    /// Client credentials grant (RFC 6749 §4.4): the API key is the client
+    /// secret, optionally named by its ID as `client_id`. The token carries
+    /// the requested scopes (default: all of the key's) for a service
+    /// principal; no refresh token, the client asks again.
+    async fn client_credentials(
+        &self,
+        req: &TokenGrantRequest,
+        basic: Option<(String, String)>,
+    ) -> Result<OidcTokenResponse, OidcError> {
+        let (client_id, secret) = match basic {
+            Some((id, secret)) => (Some(id), Some(secret)),
+            None => (req.client_id.clone(), req.client_secret.clone()),
+        };
+        let secret = secret.ok_or(OidcError::InvalidClient)?;
+
+        let key = self
+            .api_keys
+            .validate_key(&secret)
+            .await
+            .map_err(|e| match e {
+                ApiKeyError::Database(e) => OidcError::Database(e),
+                _ => OidcError::InvalidClient,
+            })?;
+        if client_id.is_some_and(|id| id != key.id.to_string()) {
+            return Err(OidcError::InvalidClient);
+        }
+        self.api_keys
+            .record_use(key.id)
+            .await
+            .map_err(|e| match e {
+                ApiKeyError::Database(e) => OidcError::Database(e),
+                _ => OidcError::InvalidClient,
+            })?;
+
+        let scope = match req.scope.as_deref() {
+            Some(requested) => {
+                let scopes = dedup_scopes(requested);
+                if !scopes.iter().all(|s| key.scopes.contains(s)) {
+                    return Err(OidcError::InvalidScope);
+                }
+                scopes.join(" ")
+            }
+            None => key.scopes.join(" "),
+        };
+
+        let (access_token, claims) = create_service_access_token(
+            &self.jwt_keys,
+            key.id,
+            &scope,
+            self.config.oidc.service_token_ttl_secs,
+        )?;
+
+        tracing::info!(
+            target: "security",
+            api_key_id = %key.id,
+            scope = %scope,
+            "Service token issued"
+        );
+
+        Ok(OidcTokenResponse {
+            access_token,
+            token_type: "Bearer",
+            expires_in: claims.exp - claims.iat,
+            id_token: None,
+            refresh_token: None,
+            scope,
+        })
+    }
+
+    /// Access, ID and refresh token for a grant on a live session
+    async fn issue(
+        &self,
+        client: &OidcClient,
+        session: &UserSession,
+        scope: &str,
+        auth_time: i64,
+        nonce: Option<String>,
+    ) -> Result<OidcTokenResponse, OidcError> {
+        let user = self
+            .user_repo
+            .find_with_profile(self.db.pool(), session.user_id)
+            .await?
+            .filter(|u| u.is_active)
+            .ok_or(OidcError::InvalidGrant)?;
+
+        let (access_token, claims) = create_client_access_token(
+            &self.jwt_keys,
+            user.id,
+            &[user.role()],
+            &client.client_id,
+            scope,
+            &session.session_id,
+            session.created_at.timestamp(),
+            auth_time,
+        )?;
+
+        let id_token = self.id_token(client, &user, session, scope, auth_time, nonce)?;
+
+        let refresh_token = random_token(SECRET_BYTES);
+        self.repo
+            .create_refresh_token(
+                self.db.pool(),
+                &sha256_hex(&refresh_token),
+                client.id,
+                user.id,
+                &session.session_id,
+                scope,
+                auth_time,
+                Utc::now() + Duration::seconds(self.config.oidc.refresh_token_ttl_secs),
+            )
+            .await?;
+
+        Ok(OidcTokenResponse {
+            access_token,
+            token_type: "Bearer",
+            expires_in: claims.exp - claims.iat,
+            id_token: Some(id_token),
+            refresh_token: Some(refresh_token),
+            scope: scope.to_string(),
+        })
+    }
+
+    /// ID token, signed with the active key from `JWT_KEYS_DIR` so clients
+    /// verify it through the JWKS
+    fn id_token(
+        &self,
+        client: &OidcClient,
+        user: &UserWithProfile,
+        session: &UserSession,
+        scope: &str,
+        auth_time: i64,
+        nonce: Option<String>,
+    ) -> Result<String, OidcError> {
+        let now = Utc::now();
+        let claims = IdTokenClaims {
+            iss: self.config.oidc.issuer.clone(),
+            sub: user.id.to_string(),
+            aud: client.client_id.clone(),
+            exp: (now + Duration::seconds(self.jwt_keys.access_expiry_secs)).timestamp(),
+            iat: now.timestamp(),
+            auth_time,
+            nonce,
+            sid: session.session_id.clone(),
+            profile: profile_claims(user, scope),
+        };
+
+        let (header, key) = self.jwt_keys.access_signer();
+        Ok(encode(&header, &claims, key).map_err(|_| JwtError::CreationFailed)?)
+    }
+
+    /// UserInfo for a client access token, released by its scope. Checked
+    /// against the session, so a signed-out user's tokens stop working here.
+    pub async fn userinfo(&self, access_token: &str) -> Result<UserInfo, OidcError> {
+        let claims = validate_access_token(&self.jwt_keys, access_token)
+            .map_err(|_| OidcError::InvalidToken)?;
+        let scope = claims
+            .scope
+            .as_deref()
+            .filter(|_| claims.client_id.is_some())
+            .ok_or(OidcError::InvalidToken)?;
+
+        if let Some(ref blacklist) = self.session_blacklist
+            && blacklist.is_blacklisted(&claims.jti).await.unwrap_or(true)
+        {
+            return Err(OidcError::InvalidToken);
+        }
+
+        self.live_session(&claims.sid)
+            .await?
+            .ok_or(OidcError::InvalidToken)?;
+
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| OidcError::InvalidToken)?;
+        let user = self
+            .user_repo
+            .find_with_profile(self.db.pool(), user_id)
+            .await?
+            .filter(|u| u.is_active)
+            .ok_or(OidcError::InvalidToken)?;
+
+        Ok(UserInfo {
+            sub: claims.sub,
+            profile: profile_claims(&user, scope),
+        })
+    }
+
+    /// RFC 7009 revocation of a client refresh token, together with every
+    /// other token the client holds for that session. Unknown tokens are ignored.
+    pub async fn revoke_refresh_token(&self, token: &str) {
+        let result = match self
+            .repo
+            .find_refresh_token(self.db.pool(), &sha256_hex(token))
+            .await
+        {
+            Ok(Some(token)) => self
+                .repo
+                .revoke_refresh_tokens(self.db.pool(), &token.session_id, token.client_id)
+                .await
+                .map(|_| ()),
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            tracing::error!("Failed to revoke OIDC refresh token: {:?}", e);
+        }
+    }
+
+    async fn live_session(&self, session_id: &str) -> Result<Option<UserSession>, OidcError> {
+        Ok(self
+            .auth_service
+            .session_service()
+            .get_session(session_id)
+            .await?
+            .filter(UserSession::is_live))
+    }
+}
+
+/// Requested scopes, deduplicated. `openid` is required and everything must
+/// be allowed for the client.
+fn requested_scopes(client: &OidcClient, scope: &str) -> Result<Vec<String>, OidcError> {
+    let scopes = dedup_scopes(scope);
+    if !scopes.iter().all(|s| client.allowed_scopes.contains(s)) {
+        return Err(OidcError::InvalidScope);
+    }
+
+    if !scopes.iter().any(|s| s == "openid") {
+        return Err(OidcError::InvalidScope);
+    }
+    Ok(scopes)
+}
+
+/// Space-separated scope list, in order, without duplicates
+fn dedup_scopes(scope: &str) -> Vec<String> {
+    let mut scopes: Vec<String> = Vec::new();
+    for s in scope.split_whitespace() {
+        if !scopes.iter().any(|seen| seen == s) {
+            scopes.push(s.to_string());
+        }
+    }
+    scopes
+}
+
+/// Claims about the user that `scope` releases
+fn profile_claims(user: &UserWithProfile, scope: &str) -> ProfileClaims {
+    let mut claims = ProfileClaims::default();
+
+    for s in scope.split_whitespace() {
+        match s {
+            "profile" => {
+                claims.name = user.full_name.clone().or(user.display_name.clone());
+                claims.preferred_username = user.username.clone();
+                claims.picture = user.avatar_url.clone();
+            }
+            "email" => {
+                claims.email = Some(user.email.clone());
+                claims.email_verified = Some(user.email_verified);
+            }
+            _ => {}
+        }
+    }
+
+    claims
+}
+
+fn validate_redirect_uris(uris: &[String]) -> Result<(), OidcError> {
+    if uris.is_empty() {
+        return Err(OidcError::InvalidRequest(
+            "At least one redirect URI is required",
+        ));
+    }
+    for uri in uris {
+        let url = reqwest::Url::parse(uri)
+            .map_err(|_| OidcError::InvalidRequest("Redirect URIs must be absolute URLs"))?;
+        if url.fragment().is_some() {
+            return Err(OidcError::InvalidRequest(
+                "Redirect URIs must not contain a fragment",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_scopes(scopes: &[String]) -> Result<(), OidcError> {
+    if !scopes.iter().any(|s| s == "openid") {
+        return Err(OidcError::InvalidRequest(
+            "Allowed scopes must include openid",
+        ));
+    }
+    if let Some(unknown) = scopes
+        .iter()
+        .find(|s| !SUPPORTED_SCOPES.contains(&s.as_str()))
+    {
+        tracing::debug!("Rejected unsupported OIDC scope '{unknown}'");
+        return Err(OidcError::InvalidRequest("Unsupported scope"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(scopes: &[&str]) -> OidcClient {
+        OidcClient {
+            id: Uuid::new_v4(),
+            client_id: "app".into(),
+            client_secret_hash: None,
+            name: "App".into(),
+            redirect_uris: vec!["https://app.example.com/callback".into()],
+            allowed_scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            require_consent: true,
+            is_active: true,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_requested_scopes_need_openid_and_client_permission() {
+        let client = client(&["openid", "email"]);
+
+        assert_eq!(
+            requested_scopes(&client, "openid email openid").unwrap(),
+            ["openid", "email"]
+        );
+        assert!(matches!(
+            requested_scopes(&client, "email"),
+            Err(OidcError::InvalidScope)
+        ));
+        assert!(matches!(
+            requested_scopes(&client, "openid profile"),
+            Err(OidcError::InvalidScope)
+        ));
+    }
+
+    #[test]
+    fn test_redirect_uris_must_be_absolute_without_fragment() {
+        assert!(validate_redirect_uris(&["https://app.example.com/cb".into()]).is_ok());
+        assert!(validate_redirect_uris(&["/cb".into()]).is_err());
+        assert!(validate_redirect_uris(&["https://app.example.com/cb#x".into()]).is_err());
+        assert!(validate_redirect_uris(&[]).is_err());
+    }
+}
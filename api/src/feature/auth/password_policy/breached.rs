@@ -0,0 +1,188 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use data_encoding::HEXUPPER;
+use sha1::{Digest, Sha1};
+
+/// Length of the hash prefix that names a range file
+const PREFIX_LEN: usize = 5;
+
+/// Offline list of breached passwords, in the "Have I Been Pwned" formats
+///
+/// - a file with one uppercase SHA-1 hash per line, optionally followed by
+///   `:count`, sorted by hash (HIBP's "ordered by hash" download); it is
+///   binary-searched on disk, so full dumps of tens of GB work
+/// - a directory of range files named after the first five hex characters
+///   of the hash, each holding the remaining 35 characters per line (again
+///   with an optional `:count`); only the matching file is read per check
+#[derive(Debug, Clone)]
+pub enum BreachedPasswords {
+    SortedFile(PathBuf),
+    Ranges(PathBuf),
+}
+
+impl BreachedPasswords {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            return Ok(Self::Ranges(path.to_path_buf()));
+        }
+
+        // Fail at startup rather than on the first password change
+        File::open(path)?;
+        Ok(Self::SortedFile(path.to_path_buf()))
+    }
+
+    /// Whether `password` appears in the list
+    pub async fn contains(&self, password: &str) -> io::Result<bool> {
+        let digest: [u8; 20] = Sha1::digest(password.as_bytes()).into();
+        let hex = HEXUPPER.encode(&digest);
+
+        match self {
+            Self::SortedFile(path) => {
+                let path = path.clone();
+                tokio::task::spawn_blocking(move || search_sorted(&path, &hex))
+                    .await
+                    .map_err(io::Error::other)?
+            }
+            Self::Ranges(dir) => {
+                let (prefix, _) = hex.split_at(PREFIX_LEN);
+                let file = dir.join(prefix);
+
+                let contents =
+                    match tokio::task::spawn_blocking(move || std::fs::read_to_string(file))
+                        .await
+                        .map_err(io::Error::other)?
+                    {
+                        Ok(contents) => contents,
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+                        Err(e) => return Err(e),
+                    };
+
+                Ok(contents
+                    .lines()
+                    .any(|line| parse_line(line, prefix) == Some(digest)))
+            }
+        }
+    }
+}
+
+/// Binary search a file sorted by hash for `hex`. `lo` always sits at the
+/// start of a line and every line before it sorts below `hex`; `hi` is the
+/// start of a line sorting above it (or the end of the file).
+fn search_sorted(path: &Path, hex: &str) -> io::Result<bool> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let (mut lo, mut hi) = (0, reader.get_ref().metadata()?.len());
+    let mut line = Vec::new();
+
+    while lo < hi {
+        // First line starting after the midpoint, or `lo` once the range
+        // holds a single line
+        let mid = lo + (hi - lo) / 2;
+        let mut start = lo;
+        if mid > lo {
+            reader.seek(SeekFrom::Start(mid - 1))?;
+            line.clear();
+            let skipped = reader.read_until(b'\n', &mut line)? as u64;
+            if mid - 1 + skipped < hi {
+                start = mid - 1 + skipped;
+            }
+        }
+
+        reader.seek(SeekFrom::Start(start))?;
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)? as u64;
+        if read == 0 {
+            break;
+        }
+
+        match line_hash(&line).as_str().cmp(hex) {
+            std::cmp::Ordering::Equal => return Ok(true),
+            std::cmp::Ordering::Less => lo = start + read,
+            std::cmp::Ordering::Greater => hi = start,
+        }
+    }
+
+    Ok(false)
+}
+
+/// Uppercase hash of a `HASH[:count]` line
+fn line_hash(line: &[u8]) -> String {
+    let line = String::from_utf8_lossy(line);
+    line.split(':')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_uppercase()
+}
+
+/// Parse `HASH[:count]`, where the line holds the hash minus `prefix`
+fn parse_line(line: &str, prefix: &str) -> Option<[u8; 20]> {
+    let suffix = line.split(':').next()?.trim();
+    let hex = format!("{prefix}{}", suffix.to_ascii_uppercase());
+    HEXUPPER.decode(hex.as_bytes()).ok()?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SHA-1 of "password123"
+    const HASH: &str = "CBFDAC6008F9CAB4083784CBD1874F76618D2A97";
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("quax-breached-{name}-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_sorted_hash_file() {
+        let passwords: Vec<String> = (0..500).map(|i| format!("password-{i}")).collect();
+        let mut lines: Vec<String> = passwords
+            .iter()
+            .chain([&"password123".to_string(), &"password".to_string()])
+            .enumerate()
+            .map(|(i, p)| format!("{}:{}", HEXUPPER.encode(&Sha1::digest(p)), i * 7919))
+            .collect();
+        lines.sort();
+
+        let path = temp_path("file");
+        std::fs::write(&path, lines.join("\r\n")).unwrap();
+
+        let list = BreachedPasswords::load(&path).unwrap();
+        assert!(list.contains("password123").await.unwrap());
+        assert!(list.contains("password").await.unwrap());
+        for password in &passwords {
+            assert!(list.contains(password).await.unwrap(), "{password}");
+        }
+        assert!(!list.contains("correct horse battery staple").await.unwrap());
+        assert!(!list.contains("password-500").await.unwrap());
+
+        std::fs::remove_file(path).unwrap();
+        assert!(BreachedPasswords::load(temp_path("missing")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_range_directory() {
+        let dir = temp_path("ranges");
+        std::fs::create_dir(&dir).unwrap();
+        let (prefix, suffix) = HASH.split_at(PREFIX_LEN);
+        std::fs::write(
+            dir.join(prefix),
+            format!(
+                "0018A45C4D1DEF81644B54AB7F969B88D65:1\n{}:42\n",
+                suffix.to_lowercase()
+            ),
+        )
+        .unwrap();
+
+        let list = BreachedPasswords::load(&dir).unwrap();
+        assert!(list.contains("password123").await.unwrap());
+        // Missing range file = not breached
+        assert!(!list.contains("correct horse battery staple").await.unwrap());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,9 @@
+pub mod breached;
+pub mod repository;
+pub mod rules;
+pub mod service;
+
+pub use breached::BreachedPasswords;
+pub use repository::{PasswordHistoryRepository, PasswordHistoryRepositoryImpl};
+pub use rules::{PasswordRule, PersonalInfo, PolicyViolation};
+pub use service::{PasswordPolicy, PasswordPolicyError};
@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait PasswordHistoryRepository: Send + Sync {
+    /// Most recent password hashes of a user, newest first
+    async fn recent(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<String>, sqlx::Error>;
+
+    /// Record a new password hash, keeping only the newest `keep` entries
+    async fn record(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        password_hash: &str,
+        keep: i64,
+    ) -> Result<(), sqlx::Error>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PasswordHistoryRepositoryImpl;
+
+impl PasswordHistoryRepositoryImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl PasswordHistoryRepository for PasswordHistoryRepositoryImpl {
+    async fn recent(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT password_hash FROM password_history
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    async fn record(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        password_hash: &str,
+        keep: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO password_history (user_id, password_hash) VALUES ($1, $2)")
+            .bind(user_id)
+            .bind(password_hash)
+            .execute(pool)
+            .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM password_history
+            WHERE user_id = $1 AND id NOT IN (
+                SELECT id FROM password_history
+                WHERE user_id = $1
+                ORDER BY created_at DESC
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(keep.max(1))
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use serde::Serialize;
+
+use crate::infrastructure::config::PasswordPolicyConfig;
+
+/// Email local parts and usernames shorter than this are too generic to ban
+const MIN_PERSONAL_TOKEN_LEN: usize = 3;
+
+/// Individual policy rules, reported back to clients by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordRule {
+    MinLength,
+    MaxLength,
+    Lowercase,
+    Uppercase,
+    Digit,
+    Symbol,
+    PersonalInfo,
+    Breached,
+    Reused,
+}
+
+/// One failed rule with a human-readable explanation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PolicyViolation {
+    pub rule: PasswordRule,
+    pub message: String,
+}
+
+impl PolicyViolation {
+    pub fn new(rule: PasswordRule, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            message: message.into(),
+        }
+    }
+}
+
+/// Identity of the account the password is for
+#[derive(Debug, Clone, Copy)]
+pub struct PersonalInfo<'a> {
+    pub email: &'a str,
+    pub username: Option<&'a str>,
+}
+
+/// Check the rules that need nothing but the candidate and the account's
+/// identity (length, character classes, personal info)
+pub fn check(
+    config: &PasswordPolicyConfig,
+    password: &str,
+    personal: PersonalInfo<'_>,
+) -> Vec<PolicyViolation> {
+    let mut violations = Vec::new();
+
+    let length = password.chars().count();
+    if length < config.min_length {
+        violations.push(PolicyViolation::new(
+            PasswordRule::MinLength,
+            format!("Must be at least {} characters", config.min_length),
+        ));
+    }
+    if length > config.max_length {
+        violations.push(PolicyViolation::new(
+            PasswordRule::MaxLength,
+            format!("Must be at most {} characters", config.max_length),
+        ));
+    }
+
+    let classes = [
+        (
+            config.require_lowercase,
+            password.chars().any(char::is_lowercase),
+            PasswordRule::Lowercase,
+            "Must contain a lowercase letter",
+        ),
+        (
+            config.require_uppercase,
+            password.chars().any(char::is_uppercase),
+            PasswordRule::Uppercase,
+            "Must contain an uppercase letter",
+        ),
+        (
+            config.require_digit,
+            password.chars().any(|c| c.is_ascii_digit()),
+            PasswordRule::Digit,
+            "Must contain a digit",
+        ),
+        (
+            config.require_symbol,
+            password.chars().any(|c| !c.is_alphanumeric()),
+            PasswordRule::Symbol,
+            "Must contain a symbol",
+        ),
+    ];
+    for (required, present, rule, message) in classes {
+        if required && !present {
+            violations.push(PolicyViolation::new(rule, message));
+        }
+    }
+
+    if config.forbid_personal_info && contains_personal_info(password, personal) {
+        violations.push(PolicyViolation::new(
+            PasswordRule::PersonalInfo,
+            "Must not contain your email address or username",
+        ));
+    }
+
+    violations
+}
+
+fn contains_personal_info(password: &str, personal: PersonalInfo<'_>) -> bool {
+    let password = password.to_lowercase();
+    let local_part = personal.email.split('@').next();
+
+    [local_part, personal.username]
+        .into_iter()
+        .flatten()
+        .map(str::to_lowercase)
+        .filter(|token| token.chars().count() >= MIN_PERSONAL_TOKEN_LEN)
+        .any(|token| password.contains(&token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> PasswordPolicyConfig {
+        PasswordPolicyConfig {
+            min_length: 10,
+            max_length: 64,
+            require_lowercase: true,
+            require_uppercase: true,
+            require_digit: true,
+            require_symbol: true,
+            forbid_personal_info: true,
+            history_size: 0,
+            breached_list: None,
+        }
+    }
+
+    const ALICE: PersonalInfo<'static> = PersonalInfo {
+        email: "alice.smith@example.com",
+        username: Some("wonderland"),
+    };
+
+    fn rules(password: &str) -> Vec<PasswordRule> {
+        check(&config(), password, ALICE)
+            .into_iter()
+            .map(|v| v.rule)
+            .collect()
+    }
+
+    #[test]
+    fn test_accepts_compliant_password() {
+        assert!(rules("Tr0ub4dor&3-horse").is_empty());
+    }
+
+    #[test]
+    fn test_reports_every_failed_rule() {
+        assert_eq!(
+            rules("abc"),
+            vec![
+                PasswordRule::MinLength,
+                PasswordRule::Uppercase,
+                PasswordRule::Digit,
+                PasswordRule::Symbol,
+            ]
+        );
+        assert_eq!(rules(&"Aa1!".repeat(20)), vec![PasswordRule::MaxLength]);
+    }
+
+    #[test]
+    fn test_length_counts_characters_not_bytes() {
+        assert!(!rules("Ünïcödé-Pässw0rd").contains(&PasswordRule::MinLength));
+        assert!(rules("Äb1!Äb1!").contains(&PasswordRule::MinLength));
+    }
+
+    #[test]
+    fn test_rejects_email_and_username() {
+        assert_eq!(rules("My-ALICE.SMITH-1"), vec![PasswordRule::PersonalInfo]);
+        assert_eq!(rules("Wonderland#2024"), vec![PasswordRule::PersonalInfo]);
+
+        let mut lenient = config();
+        lenient.forbid_personal_info = false;
+        assert!(check(&lenient, "Wonderland#2024", ALICE).is_empty());
+    }
+
+    #[test]
+    fn test_ignores_very_short_personal_tokens() {
+        let short = PersonalInfo {
+            email: "al@example.com",
+            username: None,
+        };
+        assert!(check(&config(), "Royal-Palace#77", short).is_empty());
+    }
+}
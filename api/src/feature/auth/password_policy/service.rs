@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    feature::auth::utils::PasswordHasher,
+    infrastructure::{config::Config, persistence::Database},
+};
+
+use super::{
+    breached::BreachedPasswords,
+    repository::PasswordHistoryRepository,
+    rules::{self, PasswordRule, PersonalInfo, PolicyViolation},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PasswordPolicyError {
+    #[error("Password does not meet the policy")]
+    Rejected(Vec<PolicyViolation>),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Validates new passwords against the configured policy and remembers
+/// accepted ones for the reuse check
+#[derive(Clone)]
+pub struct PasswordPolicy {
+    db: Database,
+    history: Arc<dyn PasswordHistoryRepository>,
+    hasher: PasswordHasher,
+    breached: Option<Arc<BreachedPasswords>>,
+    config: Arc<Config>,
+}
+
+impl PasswordPolicy {
+    /// Loads the breached-password list, if one is configured
+    pub fn new(
+        db: Database,
+        history: Arc<dyn PasswordHistoryRepository>,
+        hasher: PasswordHasher,
+        config: Arc<Config>,
+    ) -> std::io::Result<Self> {
+        let breached = config
+            .password_policy
+            .breached_list
+            .as_deref()
+            .map(BreachedPasswords::load)
+            .transpose()?
+            .map(Arc::new);
+
+        Ok(Self {
+            db,
+            history,
+            hasher,
+            breached,
+            config,
+        })
+    }
+
+    /// Check a candidate password. `user_id` is `None` for accounts that do
+    /// not exist yet, which skips the reuse check. All failed rules are
+    /// reported together.
+    pub async fn validate(
+        &self,
+        password: &str,
+        personal: PersonalInfo<'_>,
+        user_id: Option<Uuid>,
+    ) -> Result<(), PasswordPolicyError> {
+        let policy = &self.config.password_policy;
+        let mut violations = rules::check(policy, password, personal);
+
+        // Hashing a huge input is the expensive part; stop at the length rules
+        if violations.iter().any(|v| v.rule == PasswordRule::MaxLength) {
+            return Err(PasswordPolicyError::Rejected(violations));
+        }
+
+        if let Some(breached) = &self.breached {
+            match breached.contains(password).await {
+                Ok(true) => violations.push(PolicyViolation::new(
+                    PasswordRule::Breached,
+                    "This password has appeared in a data breach, choose another one",
+                )),
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Breached password lookup failed: {e}"),
+            }
+        }
+
+        if let Some(user_id) = user_id
+            && policy.history_size > 0
+            && self.is_reused(user_id, password).await?
+        {
+            violations.push(PolicyViolation::new(
+                PasswordRule::Reused,
+                format!(
+                    "Must differ from your last {} password(s)",
+                    policy.history_size
+                ),
+            ));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PasswordPolicyError::Rejected(violations))
+        }
+    }
+
+    async fn is_reused(&self, user_id: Uuid, password: &str) -> Result<bool, sqlx::Error> {
+        let hashes = self
+            .history
+            .recent(
+                self.db.pool(),
+                user_id,
+                self.config.password_policy.history_size,
+            )
+            .await?;
+
+        Ok(hashes
+            .iter()
+            .any(|hash| self.hasher.verify(password, hash).unwrap_or(false)))
+    }
+
+    /// Remember a newly stored password hash for the reuse check
+    pub async fn remember(&self, user_id: Uuid, password_hash: &str) -> Result<(), sqlx::Error> {
+        let keep = self.config.password_policy.history_size;
+        if keep <= 0 {
+            return Ok(());
+        }
+
+        self.history
+            .record(self.db.pool(), user_id, password_hash, keep)
+            .await
+    }
+}
@@ -0,0 +1,20 @@
+use serde::Deserialize;
+use validator::Validate;
+
+/// Request body for POST /auth/forgot-password
+#[derive(Debug, Deserialize, Validate)]
+pub struct ForgotPasswordRequest {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+}
+
+/// Request body for POST /auth/reset-password
+#[derive(Debug, Deserialize, Validate)]
+pub struct ResetPasswordRequest {
+    #[validate(length(min = 1, max = 128, message = "Token is required"))]
+    pub token: String,
+
+    /// Checked against the password policy by the service
+    #[validate(length(min = 1, message = "New password is required"))]
+    pub new_password: String,
+}
@@ -0,0 +1,226 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    feature::{
+        auth::{
+            action_token::{ActionTokenPurpose, ActionTokenService},
+            auth_method::{AuthMethodService, AuthProvider},
+            password_policy::{PasswordPolicyError, PersonalInfo, PolicyViolation},
+            session::{SessionRepositoryError, SessionService},
+        },
+        user::repository::UserRepository,
+    },
+    infrastructure::{
+        config::Config,
+        mail::{Email, Mailer},
+        persistence::Database,
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PasswordResetError {
+    #[error("Invalid or expired reset token")]
+    InvalidToken,
+
+    #[error("Password does not meet the policy")]
+    WeakPassword(Vec<PolicyViolation>),
+
+    #[error("Session error: {0}")]
+    Session(#[from] SessionRepositoryError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Emails password reset links and applies resets
+#[derive(Clone)]
+pub struct PasswordResetService {
+    db: Database,
+    user_repo: Arc<dyn UserRepository>,
+    tokens: ActionTokenService,
+    auth_method_service: AuthMethodService,
+    session_service: SessionService,
+    mailer: Arc<dyn Mailer>,
+    config: Arc<Config>,
+}
+
+impl PasswordResetService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        db: Database,
+        user_repo: Arc<dyn UserRepository>,
+        tokens: ActionTokenService,
+        auth_method_service: AuthMethodService,
+        session_service: SessionService,
+        mailer: Arc<dyn Mailer>,
+        config: Arc<Config>,
+    ) -> Self {
+        Self {
+            db,
+            user_repo,
+            tokens,
+            auth_method_service,
+            session_service,
+            mailer,
+            config,
+        }
+    }
+
+    /// Email a reset link if `email` belongs to an active account with a
+    /// password. Returns `Ok(())` in every other case too, so callers cannot
+    /// tell whether the account exists; requests inside the cooldown window
+    /// are silently dropped. The mail goes out in the background so both
+    /// paths take the same time.
+    pub async fn request_reset(&self, email: &str) -> Result<(), PasswordResetError> {
+        let Some(user) = self.user_repo.find_by_email(self.db.pool(), email).await? else {
+            return Ok(());
+        };
+
+        if !user.is_active
+            || self
+                .auth_method_service
+                .find_by_user_and_provider(user.id, AuthProvider::Password)
+                .await?
+                .is_none()
+        {
+            return Ok(());
+        }
+
+        let cooldown = Duration::seconds(self.config.password_reset.resend_cooldown_secs);
+        if let Some(last) = self
+            .tokens
+            .last_issued_at(user.id, ActionTokenPurpose::PasswordReset)
+            .await?
+            && last + cooldown > Utc::now()
+        {
+            return Ok(());
+        }
+
+        let ttl_secs = self.config.password_reset.token_ttl_secs;
+        let token = self
+            .tokens
+            .issue(
+                user.id,
+                ActionTokenPurpose::PasswordReset,
+                &user.email,
+                ttl_secs,
+                None,
+            )
+            .await?;
+
+        let link = format!(
+            "{}/reset-password?token={}",
+            self.config.mail.frontend_url.trim_end_matches('/'),
+            token
+        );
+        let minutes = ttl_secs / 60;
+
+        let email = Email {
+            to: user.email.clone(),
+            subject: "Reset your password".to_string(),
+            text_body: format!(
+                "Someone asked to reset the password for your account. \
+                 Choose a new password here:\n\n{link}\n\n\
+                 The link expires in {minutes} minute(s). If this wasn't you, \
+                 you can ignore this message — your password has not changed."
+            ),
+            html_body: Some(format!(
+                "<p>Someone asked to reset the password for your account.</p>\
+                 <p><a href=\"{link}\">Choose a new password</a></p>\
+                 <p>The link expires in {minutes} minute(s). If this wasn't you, \
+                 you can ignore this message — your password has not changed.</p>"
+            )),
+        };
+        let mailer = Arc::clone(&self.mailer);
+        tokio::spawn(async move {
+            if let Err(e) = mailer.send(&email).await {
+                tracing::error!("Failed to send password reset email: {e}");
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Redeem a reset token: set the new password and sign the user out
+    /// everywhere. Returns the user id. A password rejected by the policy
+    /// leaves the token usable for another attempt.
+    pub async fn reset_password(
+        &self,
+        token: &str,
+        new_password: &str,
+    ) -> Result<Uuid, PasswordResetError> {
+        let pending = self
+            .tokens
+            .peek(token, ActionTokenPurpose::PasswordReset, None)
+            .await?
+            .ok_or(PasswordResetError::InvalidToken)?;
+
+        let owner = self
+            .user_repo
+            .find_by_id(self.db.pool(), pending.user_id)
+            .await?
+            .ok_or(PasswordResetError::InvalidToken)?;
+
+        self.auth_method_service
+            .password_policy()
+            .validate(
+                new_password,
+                PersonalInfo {
+                    email: &owner.email,
+                    username: owner.username.as_deref(),
+                },
+                Some(owner.id),
+            )
+            .await
+            .map_err(|e| match e {
+                PasswordPolicyError::Rejected(violations) => {
+                    PasswordResetError::WeakPassword(violations)
+                }
+                PasswordPolicyError::Database(e) => PasswordResetError::Database(e),
+            })?;
+
+        let record = self
+            .tokens
+            .redeem(token, ActionTokenPurpose::PasswordReset, None)
+            .await?
+            .ok_or(PasswordResetError::InvalidToken)?;
+
+        let user = self
+            .user_repo
+            .find_by_id(self.db.pool(), record.user_id)
+            .await?
+            .filter(|u| u.is_active && u.email.eq_ignore_ascii_case(&record.email))
+            .ok_or(PasswordResetError::InvalidToken)?;
+
+        let auth_method = self
+            .auth_method_service
+            .find_by_user_and_provider(user.id, AuthProvider::Password)
+            .await?
+            .ok_or(PasswordResetError::InvalidToken)?;
+
+        self.auth_method_service
+            .update_password(&auth_method, new_password)
+            .await?;
+
+        self.session_service
+            .revoke_all_sessions(user.id, "password_change")
+            .await?;
+
+        // Any other outstanding link is now stale
+        self.tokens
+            .invalidate(user.id, ActionTokenPurpose::PasswordReset)
+            .await?;
+
+        // Receiving the link proves ownership of the address
+        if !user.email_verified {
+            self.user_repo
+                .set_email_verified(self.db.pool(), user.id, true)
+                .await?;
+        }
+
+        Ok(user.id)
+    }
+}
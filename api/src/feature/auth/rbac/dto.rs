@@ -0,0 +1,17 @@
+use serde::Deserialize;
+
+/// `POST /admin/roles`
+#[derive(Debug, Deserialize)]
+pub struct CreateRole {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+/// `PATCH /admin/roles/:name`; `permissions` replaces the whole set
+#[derive(Debug, Deserialize)]
+pub struct UpdateRole {
+    pub description: Option<String>,
+    pub permissions: Option<Vec<String>>,
+}
@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+/// Entry of the permission catalog
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Permission {
+    pub name: String,
+    pub description: String,
+}
+
+/// Role with the permissions granted to it
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct RoleDefinition {
+    pub name: String,
+    pub description: Option<String>,
+    pub is_system: bool,
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,10 @@
+pub mod dto;
+pub mod entity;
+pub mod permissions;
+pub mod repository;
+pub mod service;
+
+pub use dto::{CreateRole, UpdateRole};
+pub use entity::{Permission, RoleDefinition};
+pub use repository::{RbacRepository, RbacRepositoryImpl};
+pub use service::{RbacError, RbacService};
@@ -0,0 +1,14 @@
+//! Permission strings checked by `require_permission`, one per row of the
+//! `permissions` table
+
+pub const USERS_READ: &str = "users:read";
+pub const USERS_WRITE: &str = "users:write";
+pub const USERS_IMPERSONATE: &str = "users:impersonate";
+pub const STATS_READ: &str = "stats:read";
+pub const LOGS_WRITE: &str = "logs:write";
+pub const API_KEYS_READ: &str = "api_keys:read";
+pub const API_KEYS_WRITE: &str = "api_keys:write";
+pub const OIDC_CLIENTS_READ: &str = "oidc_clients:read";
+pub const OIDC_CLIENTS_WRITE: &str = "oidc_clients:write";
+pub const ROLES_READ: &str = "roles:read";
+pub const ROLES_WRITE: &str = "roles:write";
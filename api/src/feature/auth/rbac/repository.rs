@@ -0,0 +1,187 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::entity::{Permission, RoleDefinition};
+
+/// Roles with their permissions aggregated, filtered by the caller's WHERE
+const SELECT_ROLES: &str = r#"
+    SELECT r.name, r.description, r.is_system,
+           COALESCE(
+               array_agg(rp.permission ORDER BY rp.permission)
+                   FILTER (WHERE rp.permission IS NOT NULL),
+               '{}'
+           ) AS permissions,
+           r.created_at, r.updated_at
+    FROM roles r
+    LEFT JOIN role_permissions rp ON rp.role = r.name
+"#;
+
+#[async_trait]
+pub trait RbacRepository: Send + Sync {
+    async fn list_permissions(&self, pool: &PgPool) -> Result<Vec<Permission>, sqlx::Error>;
+
+    async fn list_roles(&self, pool: &PgPool) -> Result<Vec<RoleDefinition>, sqlx::Error>;
+
+    async fn find_role(
+        &self,
+        pool: &PgPool,
+        name: &str,
+    ) -> Result<Option<RoleDefinition>, sqlx::Error>;
+
+    /// Permissions granted to a role (empty for unknown roles)
+    async fn role_permissions(&self, pool: &PgPool, name: &str)
+    -> Result<Vec<String>, sqlx::Error>;
+
+    async fn create_role(
+        &self,
+        pool: &PgPool,
+        name: &str,
+        description: Option<&str>,
+        permissions: &[String],
+    ) -> Result<RoleDefinition, sqlx::Error>;
+
+    /// Change the description and/or replace the permission set
+    async fn update_role(
+        &self,
+        pool: &PgPool,
+        name: &str,
+        description: Option<&str>,
+        permissions: Option<&[String]>,
+    ) -> Result<Option<RoleDefinition>, sqlx::Error>;
+
+    /// Fails with a foreign key violation while users still hold the role
+    async fn delete_role(&self, pool: &PgPool, name: &str) -> Result<bool, sqlx::Error>;
+}
+
+#[derive(Debug, Clone)]
+pub struct RbacRepositoryImpl;
+
+impl RbacRepositoryImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for RbacRepositoryImpl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl RbacRepository for RbacRepositoryImpl {
+    async fn list_permissions(&self, pool: &PgPool) -> Result<Vec<Permission>, sqlx::Error> {
+        sqlx::query_as::<_, Permission>("SELECT name, description FROM permissions ORDER BY name")
+            .fetch_all(pool)
+            .await
+    }
+
+    async fn list_roles(&self, pool: &PgPool) -> Result<Vec<RoleDefinition>, sqlx::Error> {
+        sqlx::query_as::<_, RoleDefinition>(&format!(
+            "{SELECT_ROLES} GROUP BY r.name ORDER BY r.name"
+        ))
+        .fetch_all(pool)
+        .await
+    }
+
+    async fn find_role(
+        &self,
+        pool: &PgPool,
+        name: &str,
+    ) -> Result<Option<RoleDefinition>, sqlx::Error> {
+        sqlx::query_as::<_, RoleDefinition>(&format!(
+            "{SELECT_ROLES} WHERE r.name = $1 GROUP BY r.name"
+        ))
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn role_permissions(
+        &self,
+        pool: &PgPool,
+        name: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>("SELECT permission FROM role_permissions WHERE role = $1")
+            .bind(name)
+            .fetch_all(pool)
+            .await
+    }
+
+    async fn create_role(
+        &self,
+        pool: &PgPool,
+        name: &str,
+        description: Option<&str>,
+        permissions: &[String],
+    ) -> Result<RoleDefinition, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("INSERT INTO roles (name, description) VALUES ($1, $2)")
+            .bind(name)
+            .bind(description)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO role_permissions (role, permission) SELECT $1, UNNEST($2::VARCHAR[])",
+        )
+        .bind(name)
+        .bind(permissions)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        self.find_role(pool, name)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn update_role(
+        &self,
+        pool: &PgPool,
+        name: &str,
+        description: Option<&str>,
+        permissions: Option<&[String]>,
+    ) -> Result<Option<RoleDefinition>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let updated = sqlx::query(
+            "UPDATE roles SET description = COALESCE($1, description), updated_at = NOW() WHERE name = $2",
+        )
+        .bind(description)
+        .bind(name)
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        if let Some(permissions) = permissions {
+            sqlx::query("DELETE FROM role_permissions WHERE role = $1")
+                .bind(name)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                "INSERT INTO role_permissions (role, permission) SELECT $1, UNNEST($2::VARCHAR[])",
+            )
+            .bind(name)
+            .bind(permissions)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        self.find_role(pool, name).await
+    }
+
+    async fn delete_role(&self, pool: &PgPool, name: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM roles WHERE name = $1")
+            .bind(name)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
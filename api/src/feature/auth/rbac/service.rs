@@ -0,0 +1,343 @@
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+
+use crate::{feature::auth::types::Role, infrastructure::persistence::Database};
+
+use super::{
+    dto::{CreateRole, UpdateRole},
+    entity::{Permission, RoleDefinition},
+    repository::RbacRepository,
+};
+
+/// How long a role's permission set is trusted before it is read again.
+/// Changes made through this instance apply immediately.
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Permission set per role name, with when it was read
+type PermissionCache = DashMap<String, (Arc<HashSet<String>>, Instant)>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RbacError {
+    #[error("Role names are 2-20 lowercase letters, digits, '-' or '_', starting with a letter")]
+    InvalidName,
+
+    #[error("Unknown permission '{0}'")]
+    UnknownPermission(String),
+
+    #[error("Role not found")]
+    NotFound,
+
+    #[error("Role already exists")]
+    AlreadyExists,
+
+    #[error("Built-in roles cannot be deleted, and admin cannot be changed")]
+    SystemRole,
+
+    #[error("Role is still assigned to users")]
+    InUse,
+
+    #[error("Cannot grant or manage permissions you do not hold")]
+    Escalation,
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Role-based access control: resolves the permissions behind the roles in
+/// an access token, and manages custom roles. `admin` holds every
+/// permission; other roles hold what `role_permissions` grants them.
+///
+/// Nobody can hand out more than they have: creating or editing a role,
+/// and assigning one to a user, needs every permission involved.
+#[derive(Clone)]
+pub struct RbacService {
+    db: Database,
+    repo: Arc<dyn RbacRepository>,
+    cache: Arc<PermissionCache>,
+}
+
+impl RbacService {
+    pub fn new(db: Database, repo: Arc<dyn RbacRepository>) -> Self {
+        Self {
+            db,
+            repo,
+            cache: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Whether any of `roles` grants `permission`
+    pub async fn has_permission(
+        &self,
+        roles: &[Role],
+        permission: &str,
+    ) -> Result<bool, RbacError> {
+        if roles.contains(&Role::Admin) {
+            return Ok(true);
+        }
+        for role in roles {
+            if self.role_permissions(role).await?.contains(permission) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Union of the permissions granted by `roles`
+    pub async fn permissions_of(&self, roles: &[Role]) -> Result<HashSet<String>, RbacError> {
+        let mut permissions = HashSet::new();
+        for role in roles {
+            permissions.extend(self.role_permissions(role).await?.iter().cloned());
+        }
+        Ok(permissions)
+    }
+
+    /// Cached permission set of one role
+    async fn role_permissions(&self, role: &Role) -> Result<Arc<HashSet<String>>, RbacError> {
+        if let Some(entry) = self.cache.get(role.as_str())
+            && entry.1.elapsed() < CACHE_TTL
+        {
+            return Ok(entry.0.clone());
+        }
+
+        let permissions: HashSet<String> = match role {
+            Role::Admin => self
+                .repo
+                .list_permissions(self.db.pool())
+                .await?
+                .into_iter()
+                .map(|p| p.name)
+                .collect(),
+            role => self
+                .repo
+                .role_permissions(self.db.pool(), role.as_str())
+                .await?
+                .into_iter()
+                .collect(),
+        };
+        let permissions = Arc::new(permissions);
+        self.cache.insert(
+            role.as_str().to_string(),
+            (permissions.clone(), Instant::now()),
+        );
+        Ok(permissions)
+    }
+
+    /// Whether `actor` holds at least the permissions of `role`, so they may
+    /// assign it, take it away, or act on its holders. Only admins cover admin.
+    pub async fn covers(&self, actor: &[Role], role: &Role) -> Result<bool, RbacError> {
+        if actor.contains(&Role::Admin) {
+            return Ok(true);
+        }
+        if *role == Role::Admin {
+            return Ok(false);
+        }
+
+        let held = self.permissions_of(actor).await?;
+        Ok(self.role_permissions(role).await?.is_subset(&held))
+    }
+
+    // ─── Role management ────────────────────────────────────────────────────
+
+    pub async fn list_permissions(&self) -> Result<Vec<Permission>, RbacError> {
+        Ok(self.repo.list_permissions(self.db.pool()).await?)
+    }
+
+    pub async fn list_roles(&self) -> Result<Vec<RoleDefinition>, RbacError> {
+        let mut roles = self.repo.list_roles(self.db.pool()).await?;
+        for role in &mut roles {
+            self.fill_admin(role).await?;
+        }
+        Ok(roles)
+    }
+
+    pub async fn get_role(&self, name: &str) -> Result<Option<RoleDefinition>, RbacError> {
+        let Some(mut role) = self.repo.find_role(self.db.pool(), name).await? else {
+            return Ok(None);
+        };
+        self.fill_admin(&mut role).await?;
+        Ok(Some(role))
+    }
+
+    /// Admin has no rows in `role_permissions`; show what it implicitly holds
+    async fn fill_admin(&self, role: &mut RoleDefinition) -> Result<(), RbacError> {
+        if Role::from(role.name.as_str()) == Role::Admin {
+            let mut all: Vec<String> = self
+                .role_permissions(&Role::Admin)
+                .await?
+                .iter()
+                .cloned()
+                .collect();
+            all.sort();
+            role.permissions = all;
+        }
+        Ok(())
+    }
+
+    pub async fn create_role(
+        &self,
+        actor: &[Role],
+        req: &CreateRole,
+    ) -> Result<RoleDefinition, RbacError> {
+        validate_role_name(&req.name)?;
+        let permissions = self.grantable(actor, &req.permissions).await?;
+
+        if self
+            .repo
+            .find_role(self.db.pool(), &req.name)
+            .await?
+            .is_some()
+        {
+            return Err(RbacError::AlreadyExists);
+        }
+
+        let role = self
+            .repo
+            .create_role(
+                self.db.pool(),
+                &req.name,
+                req.description.as_deref(),
+                &permissions,
+            )
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => RbacError::AlreadyExists,
+                e => RbacError::Database(e),
+            })?;
+        self.cache.remove(&req.name);
+        Ok(role)
+    }
+
+    pub async fn update_role(
+        &self,
+        actor: &[Role],
+        name: &str,
+        req: &UpdateRole,
+    ) -> Result<RoleDefinition, RbacError> {
+        let role = Role::from(name);
+        if role == Role::Admin {
+            return Err(RbacError::SystemRole);
+        }
+        if self.repo.find_role(self.db.pool(), name).await?.is_none() {
+            return Err(RbacError::NotFound);
+        }
+        if !self.covers(actor, &role).await? {
+            return Err(RbacError::Escalation);
+        }
+        let permissions = match &req.permissions {
+            Some(permissions) => Some(self.grantable(actor, permissions).await?),
+            None => None,
+        };
+
+        let updated = self
+            .repo
+            .update_role(
+                self.db.pool(),
+                name,
+                req.description.as_deref(),
+                permissions.as_deref(),
+            )
+            .await?
+            .ok_or(RbacError::NotFound)?;
+        self.cache.remove(name);
+        Ok(updated)
+    }
+
+    pub async fn delete_role(&self, actor: &[Role], name: &str) -> Result<(), RbacError> {
+        let role = self
+            .repo
+            .find_role(self.db.pool(), name)
+            .await?
+            .ok_or(RbacError::NotFound)?;
+        if role.is_system {
+            return Err(RbacError::SystemRole);
+        }
+        if !self.covers(actor, &Role::from(name)).await? {
+            return Err(RbacError::Escalation);
+        }
+
+        self.repo
+            .delete_role(self.db.pool(), name)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_foreign_key_violation() => RbacError::InUse,
+                e => RbacError::Database(e),
+            })?;
+        self.cache.remove(name);
+        Ok(())
+    }
+
+    /// `requested`, deduplicated, once every entry is a known permission the
+    /// actor holds
+    async fn grantable(
+        &self,
+        actor: &[Role],
+        requested: &[String],
+    ) -> Result<Vec<String>, RbacError> {
+        let known = self.role_permissions(&Role::Admin).await?;
+        let held = self.permissions_of(actor).await?;
+        let is_admin = actor.contains(&Role::Admin);
+
+        let mut permissions: Vec<String> = Vec::new();
+        for permission in requested {
+            if !known.contains(permission) {
+                return Err(RbacError::UnknownPermission(permission.clone()));
+            }
+            if !is_admin && !held.contains(permission) {
+                return Err(RbacError::Escalation);
+            }
+            if !permissions.contains(permission) {
+                permissions.push(permission.clone());
+            }
+        }
+        Ok(permissions)
+    }
+}
+
+fn validate_role_name(name: &str) -> Result<(), RbacError> {
+    let valid = (2..=20).contains(&name.len())
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(RbacError::InvalidName)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_names() {
+        assert!(validate_role_name("support").is_ok());
+        assert!(validate_role_name("billing_ops-2").is_ok());
+        assert!(validate_role_name("s").is_err());
+        assert!(validate_role_name("Support").is_err());
+        assert!(validate_role_name("2nd-line").is_err());
+        assert!(validate_role_name("a role").is_err());
+        assert!(validate_role_name("a-very-long-role-name").is_err());
+    }
+
+    #[test]
+    fn test_role_strings_round_trip() {
+        for name in ["admin", "moderator", "user", "support"] {
+            let role = Role::from(name);
+            assert_eq!(role.to_string(), name);
+            assert_eq!(serde_json::to_value(&role).unwrap(), name);
+        }
+        assert_eq!(Role::from("support"), Role::Custom("support".into()));
+        assert_eq!(
+            serde_json::from_str::<Role>("\"moderator\"").unwrap(),
+            Role::Moderator
+        );
+    }
+}
@@ -13,16 +13,50 @@ pub enum AuthError {
     #[error("Password hashing failed")]
     HashError,
 
+    #[error("Password does not meet the policy")]
+    WeakPassword(Vec<crate::feature::auth::password_policy::PolicyViolation>),
+
     #[error("Session expired - please login again")]
     SessionExpired,
 
+    #[error("Refresh token reuse detected - session revoked")]
+    TokenReuseDetected,
+
+    #[error("Could not start a session")]
+    SessionNotCreated,
+
     #[error("User not found")]
     UserNotFound,
 
+    #[error("Email address is not verified")]
+    EmailNotVerified,
+
+    #[error("An unverified account already uses this email address")]
+    UnverifiedAccountExists,
+
+    #[error("Account temporarily locked - try again in {retry_after_secs} seconds")]
+    AccountLocked { retry_after_secs: u64 },
+
+    #[error("Too many failed login attempts - try again in {retry_after_secs} seconds")]
+    TooManyAttempts { retry_after_secs: u64 },
+
+    #[error("MFA error: {0}")]
+    Mfa(#[from] crate::feature::auth::mfa::MfaError),
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 }
 
+impl From<crate::feature::auth::password_policy::PasswordPolicyError> for AuthError {
+    fn from(err: crate::feature::auth::password_policy::PasswordPolicyError) -> Self {
+        use crate::feature::auth::password_policy::PasswordPolicyError;
+        match err {
+            PasswordPolicyError::Rejected(violations) => AuthError::WeakPassword(violations),
+            PasswordPolicyError::Database(e) => AuthError::Database(e),
+        }
+    }
+}
+
 impl From<crate::feature::user::repository::UserRepositoryError> for AuthError {
     fn from(err: crate::feature::user::repository::UserRepositoryError) -> Self {
         use crate::feature::user::repository::UserRepositoryError;
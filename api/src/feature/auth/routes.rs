@@ -4,29 +4,136 @@ use axum::{
 };
 
 use crate::{
-    feature::auth::handlers, infrastructure::web::middleware::auth_middleware, state::AppState,
+    feature::auth::handlers,
+    infrastructure::web::middleware::{
+        auth_middleware, forbid_impersonation, machine_auth_middleware, require_recent_auth,
+        require_scope,
+    },
+    state::AppState,
 };
 
-/// Routes that need brute-force rate limiting (login, register)
+/// Routes that need brute-force rate limiting (login, register, MFA verify,
+/// passkey and magic-link login, verification and password reset emails,
+/// re-authentication)
 pub fn auth_sensitive_routes() -> Router<AppState> {
     Router::new()
         .route("/register", post(handlers::register))
         .route("/login", post(handlers::login))
+        .route("/mfa/verify", post(handlers::mfa_verify))
+        .route("/webauthn/login/begin", post(handlers::passkey_login_begin))
+        .route(
+            "/webauthn/login/finish",
+            post(handlers::passkey_login_finish),
+        )
+        .route("/magic-link", post(handlers::magic_link_request))
+        .route("/magic-link/verify", post(handlers::magic_link_verify))
+        .route("/resend-verification", post(handlers::resend_verification))
+        .route("/forgot-password", post(handlers::forgot_password))
+        .route("/reset-password", post(handlers::reset_password))
+        .route(
+            "/reauthenticate",
+            post(handlers::reauthenticate)
+                .route_layer(middleware::from_fn(forbid_impersonation))
+                .route_layer(middleware::from_fn(auth_middleware)),
+        )
+}
+
+/// Discovery documents served at the site root
+pub fn well_known_routes() -> Router<AppState> {
+    Router::new()
+        .route("/jwks.json", get(handlers::jwks))
+        .route("/openid-configuration", get(handlers::openid_configuration))
+}
+
+/// Token endpoints for other services (RFC 7662 introspection, RFC 7009
+/// revocation) and the OpenID Connect provider for internal apps
+pub fn oauth_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/introspect",
+            post(handlers::introspect)
+                .route_layer(middleware::from_fn_with_state(
+                    "tokens:introspect",
+                    require_scope,
+                ))
+                .route_layer(middleware::from_fn(machine_auth_middleware)),
+        )
+        .route("/revoke", post(handlers::revoke))
+        .route(
+            "/authorize",
+            post(handlers::oidc_authorize)
+                .route_layer(middleware::from_fn(forbid_impersonation))
+                .route_layer(middleware::from_fn(auth_middleware)),
+        )
+        .route("/token", post(handlers::oidc_token))
+        .route(
+            "/userinfo",
+            get(handlers::userinfo).post(handlers::userinfo),
+        )
 }
 
 /// Remaining auth routes — refresh + protected (global rate limit only)
 pub fn auth_routes() -> Router<AppState> {
-    let public = Router::new().route("/refresh", post(handlers::refresh));
+    let public = Router::new()
+        .route("/refresh", post(handlers::refresh))
+        .route("/verify-email", post(handlers::verify_email))
+        .route("/sessions/revoke", post(handlers::revoke_session_by_link))
+        .route(
+            "/oauth/{provider}/authorize",
+            get(handlers::oauth_authorize),
+        )
+        .route("/oauth/{provider}/callback", get(handlers::oauth_callback));
+
+    // Credential and MFA changes only the account owner may make
+    let owner_only = Router::new()
+        .route("/change-password", post(handlers::change_password))
+        .route(
+            "/set-password",
+            post(handlers::set_password).route_layer(middleware::from_fn(require_recent_auth)),
+        )
+        .route("/methods/{id}", delete(handlers::delete_auth_method))
+        .route(
+            "/methods/{id}/primary",
+            post(handlers::set_primary_auth_method),
+        )
+        .route("/oauth/{provider}/link", post(handlers::oauth_link))
+        .route("/mfa/enroll", post(handlers::mfa_enroll))
+        .route("/mfa/confirm", post(handlers::mfa_confirm))
+        .route(
+            "/mfa/disable",
+            post(handlers::mfa_disable).route_layer(middleware::from_fn(require_recent_auth)),
+        )
+        .route(
+            "/mfa/recovery-codes",
+            post(handlers::mfa_regenerate_recovery_codes)
+                .route_layer(middleware::from_fn(require_recent_auth)),
+        )
+        .route(
+            "/webauthn/register/begin",
+            post(handlers::passkey_register_begin),
+        )
+        .route(
+            "/webauthn/register/finish",
+            post(handlers::passkey_register_finish),
+        )
+        .route(
+            "/webauthn/credentials/{id}",
+            delete(handlers::delete_passkey),
+        )
+        .route_layer(middleware::from_fn(forbid_impersonation));
 
     let protected = Router::new()
         .route("/logout", post(handlers::logout))
         .route("/me", get(handlers::me))
-        .route("/change-password", post(handlers::change_password))
+        .route("/methods", get(handlers::list_auth_methods))
         .route(
             "/sessions",
             get(handlers::list_sessions).delete(handlers::logout_all_sessions),
         )
         .route("/sessions/{id}", delete(handlers::revoke_session))
+        .route("/mfa", get(handlers::mfa_status))
+        .route("/webauthn/credentials", get(handlers::list_passkeys))
+        .merge(owner_only)
         .layer(middleware::from_fn(auth_middleware));
 
     public.merge(protected)
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Kinds of security-relevant activity recorded in `security_events`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityEventType {
+    /// A rotated refresh token was presented again; the session was revoked
+    TokenReuseDetected,
+    /// A session was started from a device the account had not used before
+    NewDeviceSignIn,
+    /// An admin started a session acting as the user
+    ImpersonationStarted,
+}
+
+impl SecurityEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityEventType::TokenReuseDetected => "token_reuse_detected",
+            SecurityEventType::NewDeviceSignIn => "new_device_sign_in",
+            SecurityEventType::ImpersonationStarted => "impersonation_started",
+        }
+    }
+}
+
+/// Recorded security event
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct SecurityEvent {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub event_type: String,
+    pub session_id: Option<String>,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,7 @@
+pub mod entity;
+pub mod repository;
+pub mod service;
+
+pub use entity::{SecurityEvent, SecurityEventType};
+pub use repository::{SecurityEventRepository, SecurityEventRepositoryImpl};
+pub use service::SecurityEventService;
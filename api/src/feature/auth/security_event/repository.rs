@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::entity::{SecurityEvent, SecurityEventType};
+
+#[async_trait]
+pub trait SecurityEventRepository: Send + Sync {
+    /// Append an event
+    async fn create(
+        &self,
+        pool: &PgPool,
+        user_id: Option<Uuid>,
+        event_type: SecurityEventType,
+        session_id: Option<&str>,
+        details: &serde_json::Value,
+    ) -> Result<SecurityEvent, sqlx::Error>;
+
+    /// Most recent events for a user, newest first
+    async fn list_by_user(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<SecurityEvent>, sqlx::Error>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SecurityEventRepositoryImpl;
+
+impl SecurityEventRepositoryImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl SecurityEventRepository for SecurityEventRepositoryImpl {
+    async fn create(
+        &self,
+        pool: &PgPool,
+        user_id: Option<Uuid>,
+        event_type: SecurityEventType,
+        session_id: Option<&str>,
+        details: &serde_json::Value,
+    ) -> Result<SecurityEvent, sqlx::Error> {
+        sqlx::query_as::<_, SecurityEvent>(
+            r#"
+            INSERT INTO security_events (user_id, event_type, session_id, details)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(event_type.as_str())
+        .bind(session_id)
+        .bind(details)
+        .fetch_one(pool)
+        .await
+    }
+
+    async fn list_by_user(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<SecurityEvent>, sqlx::Error> {
+        sqlx::query_as::<_, SecurityEvent>(
+            r#"
+            SELECT * FROM security_events
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+}
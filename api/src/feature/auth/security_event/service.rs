@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::infrastructure::{geoip::GeoIp, persistence::Database};
+
+use super::{SecurityEvent, SecurityEventRepository, SecurityEventType};
+
+/// Records security events to the `security_events` table and the `security`
+/// tracing target
+#[derive(Clone)]
+pub struct SecurityEventService {
+    db: Database,
+    repo: Arc<dyn SecurityEventRepository>,
+    geoip: GeoIp,
+}
+
+impl SecurityEventService {
+    pub fn new(db: Database, repo: Arc<dyn SecurityEventRepository>, geoip: GeoIp) -> Self {
+        Self { db, repo, geoip }
+    }
+
+    /// Emit an event. An `ip_address` in `details` is located with GeoIP
+    /// (`location`, `country_code`) unless the caller already did. Persistence
+    /// failures are logged, never propagated — auditing must not break the
+    /// request that triggered it.
+    pub async fn emit(
+        &self,
+        user_id: Option<Uuid>,
+        event_type: SecurityEventType,
+        session_id: Option<&str>,
+        mut details: serde_json::Value,
+    ) {
+        if let Some(fields) = details.as_object_mut()
+            && !fields.contains_key("country_code")
+            && let Some(location) = fields
+                .get("ip_address")
+                .and_then(|ip| ip.as_str())
+                .and_then(|ip| self.geoip.lookup(ip))
+        {
+            fields.insert("location".into(), location.label().into());
+            fields.insert("country_code".into(), location.country_code.into());
+        }
+
+        tracing::warn!(
+            target: "security",
+            event = event_type.as_str(),
+            user_id = ?user_id,
+            session_id = ?session_id,
+            details = %details,
+            "Security event"
+        );
+
+        if let Err(e) = self
+            .repo
+            .create(self.db.pool(), user_id, event_type, session_id, &details)
+            .await
+        {
+            tracing::error!("Failed to record security event: {}", e);
+        }
+    }
+
+    /// Most recent events for a user, newest first
+    pub async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<SecurityEvent>, sqlx::Error> {
+        self.repo.list_by_user(self.db.pool(), user_id, limit).await
+    }
+}
@@ -6,22 +6,49 @@ use crate::{
     feature::{
         auth::{
             auth_method::{AuthMethodService, AuthProvider},
+            login_alert::{LoginAlertNotifier, NewSignInAlert},
+            mfa::{MfaChallengeResponse, MfaError, MfaService},
+            password_policy::PersonalInfo,
             repository::AuthError,
-            session::{DeviceInfo, SessionService},
-            types::{AuthResponse, TokenResponse, UserResponse},
+            security_event::{SecurityEventService, SecurityEventType},
+            session::{DeviceInfo, SessionMetadata, SessionService, SessionTokenIds, UserSession},
+            throttle::{LoginThrottle, ThrottleStatus},
+            types::{
+                AuthResponse, AuthUser, Claims, Role, TokenIntrospection, TokenResponse, TokenType,
+                UserResponse,
+            },
             utils::{
-                create_cleared_cookie, create_refresh_cookie, create_token_pair, extract_user_id,
+                JwtKeys, TokenPair, create_cleared_cookie, create_impersonation_token,
+                create_mfa_challenge_token, create_refresh_cookie, create_token_pair,
+                extract_user_id, validate_access_token, validate_mfa_challenge_token,
                 validate_refresh_token,
             },
         },
-        user::{UserProfileRepository, repository::UserRepository},
+        user::{User, UserProfileRepository, repository::UserRepository},
     },
     infrastructure::{
-        config::Config,
+        config::{Config, EmailVerificationRequirement},
         persistence::{Database, redis_trait::SessionBlacklist},
     },
 };
 
+/// Result of a first-factor login: either a full session, or a challenge
+/// that must be completed with a second factor (`complete_mfa_login`)
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum LoginOutcome {
+    Authenticated(AuthResponse, Cookie<'static>),
+    MfaRequired(MfaChallengeResponse),
+}
+
+/// What the user presents to re-authenticate (step-up) in a running session
+#[derive(Debug, Clone, Copy)]
+pub enum ReauthProof<'a> {
+    Password(&'a str),
+    /// TOTP or recovery code
+    MfaCode(&'a str),
+}
+
 /// Auth service with JWT, session management, and OAuth support
 #[derive(Clone)]
 pub struct AuthService {
@@ -32,9 +59,15 @@ pub struct AuthService {
     config: Arc<Config>,
     session_blacklist: Option<Arc<dyn SessionBlacklist>>,
     session_service: SessionService,
+    mfa_service: MfaService,
+    security_events: SecurityEventService,
+    login_alerts: Option<Arc<dyn LoginAlertNotifier>>,
+    login_throttle: LoginThrottle,
+    jwt_keys: Arc<JwtKeys>,
 }
 
 impl AuthService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         db: Database,
         user_repo: Arc<dyn UserRepository>,
@@ -43,6 +76,11 @@ impl AuthService {
         config: Arc<Config>,
         session_blacklist: Option<Arc<dyn SessionBlacklist>>,
         session_service: SessionService,
+        mfa_service: MfaService,
+        security_events: SecurityEventService,
+        login_alerts: Option<Arc<dyn LoginAlertNotifier>>,
+        login_throttle: LoginThrottle,
+        jwt_keys: Arc<JwtKeys>,
     ) -> Self {
         Self {
             db,
@@ -52,18 +90,29 @@ impl AuthService {
             config,
             session_blacklist,
             session_service,
+            mfa_service,
+            security_events,
+            login_alerts,
+            login_throttle,
+            jwt_keys,
         }
     }
 
-    /// Register new user with password
-    pub async fn register(
+    /// Create a password account (user, profile and auth method) without
+    /// starting a session
+    pub async fn create_account(
         &self,
         email: &str,
         username: Option<&str>,
         password: &str,
         full_name: Option<&str>,
-        device_info: Option<&DeviceInfo>,
-    ) -> Result<(AuthResponse, Cookie<'static>), AuthError> {
+    ) -> Result<User, AuthError> {
+        // Reject the password before anything is written
+        self.auth_method_service
+            .password_policy()
+            .validate(password, PersonalInfo { email, username }, None)
+            .await?;
+
         // 1. Create user (identity only)
         let user = self
             .user_repo
@@ -101,27 +150,26 @@ impl AuthService {
                 .await;
         }
 
-        // 5. Generate tokens
-        let roles = vec![user.role()];
-        let tokens =
-            create_token_pair(user.id, &user.email, &roles).map_err(|_| AuthError::HashError)?;
-
-        // 6. Create session record
-        if let Some(info) = device_info {
-            let refresh_expiry: i64 = std::env::var("JWT_REFRESH_EXPIRY_SECS")
-                .ok()
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(604800); // default 7 days
+        Ok(user)
+    }
 
-            let expires_at =
-                chrono::DateTime::from_timestamp(tokens.session_iat + refresh_expiry, 0)
-                    .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::days(7));
+    /// Register new user with password
+    pub async fn register(
+        &self,
+        email: &str,
+        username: Option<&str>,
+        password: &str,
+        full_name: Option<&str>,
+        device_info: Option<&DeviceInfo>,
+    ) -> Result<(AuthResponse, Cookie<'static>), AuthError> {
+        let user = self
+            .create_account(email, username, password, full_name)
+            .await?;
 
-            let _ = self
-                .session_service
-                .create_session(user.id, &tokens.session_id, info, expires_at)
-                .await;
-        }
+        // Generate tokens and create session record
+        let tokens = self
+            .start_session(&user, device_info, SessionMetadata::default())
+            .await?;
 
         let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
 
@@ -132,6 +180,7 @@ impl AuthService {
             user: UserResponse {
                 id: user.id,
                 email: user.email,
+                email_verified: user.email_verified,
                 username,
                 name: full_name.map(|s| s.to_string()).unwrap_or_default(),
                 avatar_url: None,
@@ -146,13 +195,50 @@ impl AuthService {
         Ok((response, refresh_cookie))
     }
 
-    /// Login user with password
+    /// Login user with password, subject to per-account throttling
     pub async fn login(
         &self,
         email: &str,
         password: &str,
         device_info: Option<&DeviceInfo>,
-    ) -> Result<(AuthResponse, Cookie<'static>), AuthError> {
+    ) -> Result<LoginOutcome, AuthError> {
+        match self.login_throttle.check(email).await {
+            ThrottleStatus::Allowed => {}
+            ThrottleStatus::Delayed { retry_after_secs } => {
+                return Err(AuthError::TooManyAttempts { retry_after_secs });
+            }
+            ThrottleStatus::Locked { retry_after_secs } => {
+                return Err(AuthError::AccountLocked { retry_after_secs });
+            }
+        }
+
+        let result = self.password_login(email, password, device_info).await;
+
+        match &result {
+            Err(AuthError::InvalidCredentials) => {
+                if let ThrottleStatus::Locked { retry_after_secs } =
+                    self.login_throttle.record_failure(email).await
+                {
+                    return Err(AuthError::AccountLocked { retry_after_secs });
+                }
+            }
+            // The password was right, whatever happened next. A pending second
+            // factor is not a success yet: its failures count against the account.
+            Ok(LoginOutcome::Authenticated(..)) | Err(AuthError::EmailNotVerified) => {
+                self.login_throttle.record_success(email).await;
+            }
+            Ok(LoginOutcome::MfaRequired(_)) | Err(_) => {}
+        }
+
+        result
+    }
+
+    async fn password_login(
+        &self,
+        email: &str,
+        password: &str,
+        device_info: Option<&DeviceInfo>,
+    ) -> Result<LoginOutcome, AuthError> {
         // 1. Find user by email
         let user = self
             .user_repo
@@ -173,84 +259,119 @@ impl AuthService {
             .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?
             .ok_or(AuthError::InvalidCredentials)?;
 
-        // 3. Verify password
-        if !auth_method
-            .verify_password(password)
+        // 3. Verify password, upgrading hashes made with older parameters
+        if !self
+            .auth_method_service
+            .verify_password(&auth_method, password)
             .map_err(|_| AuthError::HashError)?
         {
             return Err(AuthError::InvalidCredentials);
         }
+        self.auth_method_service
+            .upgrade_password_hash(&auth_method, password)
+            .await;
+
+        // 4. Unverified addresses may not sign in when the policy requires it
+        if self.config.email_verification.required == EmailVerificationRequirement::Login
+            && !user.email_verified
+        {
+            return Err(AuthError::EmailNotVerified);
+        }
 
-        // 4. Update last used (fire and forget)
+        // 5. Update last used (fire and forget)
         let _ = self.auth_method_service.touch(auth_method.id).await;
 
-        // 5. Get profile for response
-        let profile = self
-            .profile_repo
-            .find_by_user_id(self.db.pool(), user.id)
-            .await
-            .ok()
-            .flatten();
+        // 6. Second factor required? Hand back a challenge instead of tokens
+        if self.mfa_service.is_enabled(user.id).await? {
+            return Ok(LoginOutcome::MfaRequired(self.mfa_challenge(&user)?));
+        }
 
-        // 6. Generate tokens
-        let roles = vec![user.role()];
-        let tokens =
-            create_token_pair(user.id, &user.email, &roles).map_err(|_| AuthError::HashError)?;
+        // 7. Generate tokens and create session record
+        let tokens = self
+            .start_session(&user, device_info, SessionMetadata::default())
+            .await?;
 
-        // 7. Create session record
-        if let Some(info) = device_info {
-            tracing::info!(
-                "Creating session for user: {}, device: {:?}",
-                user.id,
-                info.device_type
-            );
-            let refresh_expiry: i64 = std::env::var("JWT_REFRESH_EXPIRY_SECS")
-                .ok()
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(604800); // default 7 days
-
-            let expires_at =
-                chrono::DateTime::from_timestamp(tokens.session_iat + refresh_expiry, 0)
-                    .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::days(7));
-
-            match self
-                .session_service
-                .create_session(user.id, &tokens.session_id, info, expires_at)
-                .await
-            {
-                Ok(session) => tracing::info!("Session created successfully: {:?}", session.id),
-                Err(e) => tracing::error!("Failed to create session: {:?}", e),
-            }
-        } else {
-            tracing::warn!("No device info provided, skipping session creation");
+        let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
+        let response = self.auth_response(user, tokens).await;
+
+        Ok(LoginOutcome::Authenticated(response, refresh_cookie))
+    }
+
+    /// Complete a login that returned `LoginOutcome::MfaRequired`, using a TOTP
+    /// code or a recovery code. The resulting session is flagged `mfa_used`.
+    /// Wrong codes are recorded by the login throttle like wrong passwords, so
+    /// retrying a challenge is delayed and eventually locks the account.
+    pub async fn complete_mfa_login(
+        &self,
+        mfa_token: &str,
+        code: &str,
+        device_info: Option<&DeviceInfo>,
+    ) -> Result<(AuthResponse, Cookie<'static>), AuthError> {
+        let claims = validate_mfa_challenge_token(&self.jwt_keys, mfa_token)
+            .map_err(|_| AuthError::InvalidCredentials)?;
+        let user_id = extract_user_id(&claims).map_err(|_| AuthError::InvalidCredentials)?;
+
+        let user = self
+            .user_repo
+            .find_by_id(self.db.pool(), user_id)
+            .await
+            .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?
+            .ok_or(AuthError::InvalidCredentials)?;
+
+        if !user.is_active {
+            return Err(AuthError::InvalidCredentials);
         }
 
-        let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
+        self.throttled_mfa(&user.email, self.mfa_service.verify(user.id, code))
+            .await?;
 
-        let username = user.username.clone();
-        let role = user.role().to_string();
+        let tokens = self
+            .start_session(
+                &user,
+                device_info,
+                SessionMetadata {
+                    mfa_used: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
 
-        let response = AuthResponse {
-            user: UserResponse {
-                id: user.id,
-                email: user.email,
-                username,
-                name: profile
-                    .as_ref()
-                    .and_then(|p| p.full_name.clone())
-                    .unwrap_or_default(),
-                avatar_url: profile.as_ref().and_then(|p| p.avatar_url.clone()),
-                role,
-            },
-            token: TokenResponse {
-                access_token: tokens.access_token,
-                expires_in: tokens.expires_in,
-            },
-        };
+        let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
+        let response = self.auth_response(user, tokens).await;
 
         Ok((response, refresh_cookie))
     }
 
+    /// Turn MFA off. The code is checked under the login throttle, so a
+    /// hijacked session cannot guess its way to switching MFA off.
+    pub async fn disable_mfa(&self, user_id: uuid::Uuid, code: &str) -> Result<(), AuthError> {
+        let user = self.mfa_user(user_id).await?;
+        self.throttled_mfa(&user.email, self.mfa_service.disable(user.id, code))
+            .await
+    }
+
+    /// Replace the recovery codes; the code is checked under the login throttle
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: uuid::Uuid,
+        code: &str,
+    ) -> Result<Vec<String>, AuthError> {
+        let user = self.mfa_user(user_id).await?;
+        self.throttled_mfa(
+            &user.email,
+            self.mfa_service.regenerate_recovery_codes(user.id, code),
+        )
+        .await
+    }
+
+    async fn mfa_user(&self, user_id: uuid::Uuid) -> Result<User, AuthError> {
+        self.user_repo
+            .find_by_id(self.db.pool(), user_id)
+            .await?
+            .filter(|u| u.is_active)
+            .ok_or(AuthError::InvalidCredentials)
+    }
+
     /// OAuth login/register
     #[allow(clippy::too_many_arguments)]
     pub async fn oauth_login(
@@ -262,8 +383,8 @@ impl AuthService {
         access_token: Option<&str>,
         refresh_token: Option<&str>,
         expires_at: Option<chrono::DateTime<chrono::Utc>>,
-        _device_info: Option<&DeviceInfo>,
-    ) -> Result<(AuthResponse, Cookie<'static>), AuthError> {
+        device_info: Option<&DeviceInfo>,
+    ) -> Result<LoginOutcome, AuthError> {
         // 1. Check if OAuth account exists
         if let Some(auth_method) = self
             .auth_method_service
@@ -279,42 +400,25 @@ impl AuthService {
                 .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?
                 .ok_or(AuthError::InvalidCredentials)?;
 
-            // Generate tokens
-            let roles = vec![user.role()];
-            let tokens = create_token_pair(user.id, &user.email, &roles)
-                .map_err(|_| AuthError::HashError)?;
+            if !user.is_active {
+                return Err(AuthError::InvalidCredentials);
+            }
 
-            let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
+            let _ = self.auth_method_service.touch(auth_method.id).await;
 
-            let profile = self
-                .profile_repo
-                .find_by_user_id(self.db.pool(), user.id)
-                .await
-                .ok()
-                .flatten();
-
-            let username = user.username.clone();
-            let role = user.role().to_string();
-
-            let response = AuthResponse {
-                user: UserResponse {
-                    id: user.id,
-                    email: user.email,
-                    username,
-                    name: profile
-                        .as_ref()
-                        .and_then(|p| p.full_name.clone())
-                        .unwrap_or_default(),
-                    avatar_url: profile.as_ref().and_then(|p| p.avatar_url.clone()),
-                    role,
-                },
-                token: TokenResponse {
-                    access_token: tokens.access_token,
-                    expires_in: tokens.expires_in,
-                },
-            };
+            if self.mfa_service.is_enabled(user.id).await? {
+                return Ok(LoginOutcome::MfaRequired(self.mfa_challenge(&user)?));
+            }
 
-            return Ok((response, refresh_cookie));
+            // Generate tokens and create session record
+            let tokens = self
+                .start_session(&user, device_info, SessionMetadata::default())
+                .await?;
+
+            let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
+            let response = self.auth_response(user, tokens).await;
+
+            return Ok(LoginOutcome::Authenticated(response, refresh_cookie));
         }
 
         // 2. Check if email exists - link to existing account
@@ -324,6 +428,17 @@ impl AuthService {
             .await
             .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?
         {
+            if !user.is_active {
+                return Err(AuthError::InvalidCredentials);
+            }
+
+            // Nobody proved ownership of an unverified account: it may have been
+            // registered by someone else ahead of the real owner, who must not be
+            // signed into it
+            if !user.email_verified {
+                return Err(AuthError::UnverifiedAccountExists);
+            }
+
             // Link OAuth to existing user
             let _ = self
                 .auth_method_service
@@ -339,42 +454,19 @@ impl AuthService {
                 .await
                 .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?;
 
-            // Generate tokens
-            let roles = vec![user.role()];
-            let tokens = create_token_pair(user.id, &user.email, &roles)
-                .map_err(|_| AuthError::HashError)?;
+            if self.mfa_service.is_enabled(user.id).await? {
+                return Ok(LoginOutcome::MfaRequired(self.mfa_challenge(&user)?));
+            }
 
-            let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
+            // Generate tokens and create session record
+            let tokens = self
+                .start_session(&user, device_info, SessionMetadata::default())
+                .await?;
 
-            let profile = self
-                .profile_repo
-                .find_by_user_id(self.db.pool(), user.id)
-                .await
-                .ok()
-                .flatten();
-
-            let username = user.username.clone();
-            let role = user.role().to_string();
-
-            let response = AuthResponse {
-                user: UserResponse {
-                    id: user.id,
-                    email: user.email,
-                    username,
-                    name: profile
-                        .as_ref()
-                        .and_then(|p| p.full_name.clone())
-                        .unwrap_or_default(),
-                    avatar_url: profile.as_ref().and_then(|p| p.avatar_url.clone()),
-                    role,
-                },
-                token: TokenResponse {
-                    access_token: tokens.access_token,
-                    expires_in: tokens.expires_in,
-                },
-            };
+            let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
+            let response = self.auth_response(user, tokens).await;
 
-            return Ok((response, refresh_cookie));
+            return Ok(LoginOutcome::Authenticated(response, refresh_cookie));
         }
 
         // 3. Create new user with OAuth
@@ -413,10 +505,12 @@ impl AuthService {
             .await
             .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?;
 
-        // 6. Generate tokens
-        let roles = vec![user.role()];
-        let tokens =
-            create_token_pair(user.id, &user.email, &roles).map_err(|_| AuthError::HashError)?;
+        let user = self.mark_email_verified(user).await?;
+
+        // 6. Generate tokens and create session record
+        let tokens = self
+            .start_session(&user, device_info, SessionMetadata::default())
+            .await?;
 
         let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
 
@@ -427,6 +521,7 @@ impl AuthService {
             user: UserResponse {
                 id: user.id,
                 email: user.email,
+                email_verified: user.email_verified,
                 username,
                 name: name.map(|s| s.to_string()).unwrap_or_default(),
                 avatar_url: None,
@@ -438,7 +533,254 @@ impl AuthService {
             },
         };
 
-        Ok((response, refresh_cookie))
+        Ok(LoginOutcome::Authenticated(response, refresh_cookie))
+    }
+
+    /// Log in the owner of a redeemed magic link. The link proves control of
+    /// the address, so it also marks the email as verified.
+    pub async fn magic_link_login(
+        &self,
+        user_id: uuid::Uuid,
+        device_info: Option<&DeviceInfo>,
+    ) -> Result<LoginOutcome, AuthError> {
+        let user = self
+            .user_repo
+            .find_by_id(self.db.pool(), user_id)
+            .await
+            .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?
+            .filter(|u| u.is_active)
+            .ok_or(AuthError::InvalidCredentials)?;
+
+        let user = self.mark_email_verified(user).await?;
+
+        if self.mfa_service.is_enabled(user.id).await? {
+            return Ok(LoginOutcome::MfaRequired(self.mfa_challenge(&user)?));
+        }
+
+        let tokens = self
+            .start_session(&user, device_info, SessionMetadata::default())
+            .await?;
+
+        let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
+        let response = self.auth_response(user, tokens).await;
+
+        Ok(LoginOutcome::Authenticated(response, refresh_cookie))
+    }
+
+    /// Log in the owner of a verified passkey assertion. A user-verifying
+    /// passkey already combines possession with a PIN or biometric, so only
+    /// presence-only assertions fall through to the TOTP challenge.
+    pub async fn passkey_login(
+        &self,
+        user_id: uuid::Uuid,
+        user_verified: bool,
+        device_info: Option<&DeviceInfo>,
+    ) -> Result<LoginOutcome, AuthError> {
+        let user = self
+            .user_repo
+            .find_by_id(self.db.pool(), user_id)
+            .await
+            .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?
+            .ok_or(AuthError::InvalidCredentials)?;
+
+        if !user.is_active {
+            return Err(AuthError::InvalidCredentials);
+        }
+
+        if self.config.email_verification.required == EmailVerificationRequirement::Login
+            && !user.email_verified
+        {
+            return Err(AuthError::EmailNotVerified);
+        }
+
+        if !user_verified && self.mfa_service.is_enabled(user.id).await? {
+            return Ok(LoginOutcome::MfaRequired(self.mfa_challenge(&user)?));
+        }
+
+        let tokens = self
+            .start_session(
+                &user,
+                device_info,
+                SessionMetadata {
+                    mfa_used: user_verified,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
+        let response = self.auth_response(user, tokens).await;
+
+        Ok(LoginOutcome::Authenticated(response, refresh_cookie))
+    }
+
+    /// Flag the user's address as verified (e.g. after an OAuth provider vouched for it)
+    async fn mark_email_verified(&self, mut user: User) -> Result<User, AuthError> {
+        if !user.email_verified {
+            self.user_repo
+                .set_email_verified(self.db.pool(), user.id, true)
+                .await?;
+            user.email_verified = true;
+        }
+        Ok(user)
+    }
+
+    /// Build the login response for an existing user (profile fields included)
+    async fn auth_response(&self, user: User, tokens: TokenPair) -> AuthResponse {
+        let profile = self
+            .profile_repo
+            .find_by_user_id(self.db.pool(), user.id)
+            .await
+            .ok()
+            .flatten();
+
+        let username = user.username.clone();
+        let role = user.role().to_string();
+
+        AuthResponse {
+            user: UserResponse {
+                id: user.id,
+                email: user.email,
+                email_verified: user.email_verified,
+                username,
+                name: profile
+                    .as_ref()
+                    .and_then(|p| p.full_name.clone())
+                    .unwrap_or_default(),
+                avatar_url: profile.as_ref().and_then(|p| p.avatar_url.clone()),
+                role,
+            },
+            token: TokenResponse {
+                access_token: tokens.access_token,
+                expires_in: tokens.expires_in,
+            },
+        }
+    }
+
+    /// Short-lived challenge proving the first factor succeeded
+    fn mfa_challenge(&self, user: &User) -> Result<MfaChallengeResponse, AuthError> {
+        let ttl = self.config.mfa.challenge_ttl_secs;
+        let mfa_token = create_mfa_challenge_token(&self.jwt_keys, user.id, ttl)
+            .map_err(|_| AuthError::HashError)?;
+
+        Ok(MfaChallengeResponse {
+            mfa_required: true,
+            mfa_token,
+            expires_in: ttl,
+        })
+    }
+
+    /// Issue a new token pair and record the session for an authenticated user.
+    /// Fails without device info or when the session row cannot be written, since
+    /// access tokens without a live session are rejected.
+    /// Sign-ins from a new device are flagged and trigger a login alert.
+    async fn start_session(
+        &self,
+        user: &User,
+        device_info: Option<&DeviceInfo>,
+        mut metadata: SessionMetadata,
+    ) -> Result<TokenPair, AuthError> {
+        let roles = vec![user.role()];
+        let tokens = create_token_pair(&self.jwt_keys, user.id, &user.email, &roles)
+            .map_err(|_| AuthError::HashError)?;
+
+        let Some(info) = device_info else {
+            tracing::error!("No device info provided, cannot create session");
+            return Err(AuthError::SessionNotCreated);
+        };
+
+        tracing::info!(
+            "Creating session for user: {}, device: {:?}",
+            user.id,
+            info.device_type
+        );
+        let lifetime = self
+            .config
+            .session
+            .absolute_lifetime_for(user.role() == Role::Admin);
+
+        let expires_at = chrono::DateTime::from_timestamp(tokens.session_iat + lifetime, 0)
+            .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::seconds(lifetime));
+
+        metadata.new_device = self
+            .session_service
+            .is_new_device(user.id, &info.fingerprint)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to check device history: {:?}", e);
+                false
+            });
+
+        let session = self
+            .session_service
+            .create_session(
+                user.id,
+                &tokens.session_id,
+                info,
+                expires_at,
+                &metadata,
+                &session_token_ids(&tokens),
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to create session: {:?}", e);
+                AuthError::SessionNotCreated
+            })?;
+
+        tracing::info!("Session created successfully: {:?}", session.id);
+        if metadata.new_device {
+            self.new_device_signed_in(user, &session).await;
+        }
+
+        Ok(tokens)
+    }
+
+    /// Record a sign-in from a new device and alert the account owner with a
+    /// link that revokes the session. Failures are logged, never propagated.
+    async fn new_device_signed_in(&self, user: &User, session: &UserSession) {
+        self.security_events
+            .emit(
+                Some(user.id),
+                SecurityEventType::NewDeviceSignIn,
+                Some(&session.session_id),
+                serde_json::json!({
+                    "ip_address": session.ip_address,
+                    "device_name": session.device_name,
+                }),
+            )
+            .await;
+
+        let Some(notifier) = &self.login_alerts else {
+            return;
+        };
+
+        let token = match self.session_service.issue_revoke_token(session.id).await {
+            Ok(token) => token,
+            Err(e) => {
+                tracing::error!("Failed to issue session revoke token: {:?}", e);
+                return;
+            }
+        };
+
+        let alert = NewSignInAlert {
+            user_id: user.id,
+            email: user.email.clone(),
+            device_name: session
+                .device_name
+                .clone()
+                .unwrap_or_else(|| "Unknown device".to_string()),
+            ip_address: session.ip_address.clone(),
+            location: session.location.clone(),
+            signed_in_at: session.created_at,
+            revoke_url: format!(
+                "{}/revoke-session?token={}",
+                self.config.mail.frontend_url.trim_end_matches('/'),
+                token
+            ),
+        };
+        if let Err(e) = notifier.notify(&alert).await {
+            tracing::error!("Failed to send new sign-in alert: {}", e);
+        }
     }
 
     /// Refresh access token with rotation
@@ -446,26 +788,11 @@ impl AuthService {
         &self,
         refresh_token: &str,
     ) -> Result<(TokenResponse, Cookie<'static>), AuthError> {
-        let claims = validate_refresh_token(refresh_token).map_err(|e| match e {
-            crate::feature::auth::utils::JwtError::SessionExpired => AuthError::SessionExpired,
-            _ => AuthError::InvalidCredentials,
-        })?;
+        let claims = validate_refresh_token(&self.jwt_keys, refresh_token)
+            .map_err(|_| AuthError::InvalidCredentials)?;
 
         let user_id = extract_user_id(&claims).map_err(|_| AuthError::InvalidCredentials)?;
 
-        // Check blacklist
-        if let Some(ref blacklist) = self.session_blacklist {
-            let is_blacklisted = blacklist
-                .is_blacklisted(&claims.jti)
-                .await
-                .map_err(|_| AuthError::InvalidCredentials)?;
-
-            if is_blacklisted {
-                tracing::warn!("Token reuse detected for session: {}", claims.sid);
-                return Err(AuthError::InvalidCredentials);
-            }
-        }
-
         // Check if session is still active in database
         let session = self
             .session_service
@@ -473,18 +800,32 @@ impl AuthService {
             .await
             .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?;
 
-        if let Some(s) = session {
-            if !s.is_active {
-                tracing::warn!("Session {} has been revoked", claims.sid);
-                return Err(AuthError::InvalidCredentials);
-            }
-        } else {
+        let Some(session) = session else {
             tracing::warn!("Session {} not found in database", claims.sid);
             return Err(AuthError::InvalidCredentials);
+        };
+
+        if !session.is_active {
+            tracing::warn!("Session {} has been revoked", claims.sid);
+            return Err(AuthError::InvalidCredentials);
         }
 
-        // Update session last active
-        let _ = self.session_service.touch_session(&claims.sid).await;
+        // A refresh token that was already rotated away is being replayed
+        let blacklisted = match self.session_blacklist {
+            Some(ref blacklist) => blacklist
+                .is_blacklisted(&claims.jti)
+                .await
+                .map_err(|_| AuthError::InvalidCredentials)?,
+            None => false,
+        };
+        let superseded = session
+            .refresh_jti
+            .as_deref()
+            .is_some_and(|current| current != claims.jti);
+
+        if blacklisted || superseded {
+            return Err(self.refresh_token_reused(&session, &claims.jti).await);
+        }
 
         let user = self
             .user_repo
@@ -493,22 +834,48 @@ impl AuthService {
             .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?
             .ok_or(AuthError::InvalidCredentials)?;
 
-        // Blacklist old token
-        if let Some(ref blacklist) = self.session_blacklist {
-            let _ = blacklist.blacklist_session(&claims.jti, claims.exp).await;
+        if let Some(reason) = self.session_policy_violation(&session, claims.s_iat, &user) {
+            tracing::info!("Session {} ended: {}", claims.sid, reason);
+            if let Err(e) = self
+                .session_service
+                .revoke_session(session.id, reason)
+                .await
+            {
+                tracing::error!("Failed to revoke session: {:?}", e);
+            }
+            return Err(AuthError::SessionExpired);
         }
 
         // Generate new tokens with same session
         let roles = vec![user.role()];
         let tokens = crate::feature::auth::utils::jwt::create_token_pair_with_session(
+            &self.jwt_keys,
             user.id,
             &user.email,
             &roles,
             &claims.sid,
             claims.s_iat,
+            claims.auth_time,
         )
         .map_err(|_| AuthError::HashError)?;
 
+        // Compare-and-swap: of two concurrent refreshes with the same token,
+        // only one can win — the other is treated as reuse
+        let rotated = self
+            .session_service
+            .rotate_tokens(&claims.sid, &claims.jti, &session_token_ids(&tokens))
+            .await
+            .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?;
+
+        if !rotated {
+            return Err(self.refresh_token_reused(&session, &claims.jti).await);
+        }
+
+        // Blacklist old token
+        if let Some(ref blacklist) = self.session_blacklist {
+            let _ = blacklist.blacklist_session(&claims.jti, claims.exp).await;
+        }
+
         let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
 
         Ok((
@@ -520,31 +887,409 @@ impl AuthService {
         ))
     }
 
-    /// Logout
+    /// Start a session in which `admin` acts as `user`. Only an access token
+    /// is issued; the session is tagged with the admin and the start is
+    /// recorded as a security event on the user's account.
+    pub async fn impersonate(
+        &self,
+        admin: &AuthUser,
+        user: &User,
+        device_info: &DeviceInfo,
+    ) -> Result<TokenResponse, AuthError> {
+        let ttl = self.config.session.impersonation_ttl_secs;
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let (access_token, claims) = create_impersonation_token(
+            &self.jwt_keys,
+            user.id,
+            &[user.role()],
+            admin.user_id,
+            &session_id,
+            ttl,
+        )
+        .map_err(|_| AuthError::HashError)?;
+
+        let expires_at = chrono::DateTime::from_timestamp(claims.exp, 0)
+            .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::seconds(ttl));
+        let metadata = SessionMetadata {
+            impersonated_by: Some(admin.user_id),
+            ..Default::default()
+        };
+        // No refresh token exists for this session, so nothing can rotate it
+        let tokens = SessionTokenIds {
+            refresh_jti: String::new(),
+            access_jti: claims.jti,
+            access_expires_at: expires_at,
+        };
+        self.session_service
+            .create_session(
+                user.id,
+                &session_id,
+                device_info,
+                expires_at,
+                &metadata,
+                &tokens,
+            )
+            .await
+            .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?;
+
+        self.security_events
+            .emit(
+                Some(user.id),
+                SecurityEventType::ImpersonationStarted,
+                Some(&session_id),
+                serde_json::json!({
+                    "admin_id": admin.user_id,
+                    "admin_session_id": admin.session_id,
+                    "ip_address": device_info.ip_address,
+                    "expires_at": expires_at,
+                }),
+            )
+            .await;
+
+        Ok(TokenResponse {
+            access_token,
+            expires_in: ttl,
+        })
+    }
+
+    /// Why the session policy ends this session, if it does: too long since
+    /// sign-in (the admin limit applies to current admins), or idle too long
+    fn session_policy_violation(
+        &self,
+        session: &UserSession,
+        session_iat: i64,
+        user: &User,
+    ) -> Option<&'static str> {
+        let policy = &self.config.session;
+        let now = chrono::Utc::now();
+
+        let lifetime = policy.absolute_lifetime_for(user.role() == Role::Admin);
+        if now.timestamp() > session_iat + lifetime || now > session.expires_at {
+            return Some("absolute_timeout");
+        }
+
+        let idle = (now - session.last_active_at).num_seconds();
+        if policy.idle_timeout_secs > 0 && idle > policy.idle_timeout_secs {
+            return Some("idle_timeout");
+        }
+
+        None
+    }
+
+    /// Step-up re-authentication inside the current session. On success the
+    /// session's tokens are rotated and the new ones carry a fresh `auth_time`.
+    pub async fn reauthenticate(
+        &self,
+        auth_user: &AuthUser,
+        proof: ReauthProof<'_>,
+    ) -> Result<(TokenResponse, Cookie<'static>), AuthError> {
+        let user = self
+            .user_repo
+            .find_by_id(self.db.pool(), auth_user.user_id)
+            .await?
+            .filter(|u| u.is_active)
+            .ok_or(AuthError::InvalidCredentials)?;
+
+        match proof {
+            ReauthProof::Password(password) => {
+                self.verify_password_for_reauth(&user, password).await?
+            }
+            ReauthProof::MfaCode(code) => self
+                .throttled_mfa(&user.email, self.mfa_service.verify(user.id, code))
+                .await
+                .map_err(|e| match e {
+                    AuthError::Mfa(MfaError::InvalidCode | MfaError::NotEnabled) => {
+                        AuthError::InvalidCredentials
+                    }
+                    e => e,
+                })?,
+        }
+
+        let session = self
+            .session_service
+            .get_session(&auth_user.session_id)
+            .await
+            .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?
+            .filter(|s| s.is_active)
+            .ok_or(AuthError::InvalidCredentials)?;
+
+        let roles = vec![user.role()];
+        let tokens = crate::feature::auth::utils::jwt::create_token_pair_with_session(
+            &self.jwt_keys,
+            user.id,
+            &user.email,
+            &roles,
+            &session.session_id,
+            auth_user.session_iat,
+            chrono::Utc::now().timestamp(),
+        )
+        .map_err(|_| AuthError::HashError)?;
+
+        let current_refresh_jti = session.refresh_jti.clone().unwrap_or_default();
+        let rotated = self
+            .session_service
+            .rotate_tokens(
+                &session.session_id,
+                &current_refresh_jti,
+                &session_token_ids(&tokens),
+            )
+            .await
+            .map_err(|_| AuthError::Database(sqlx::Error::RowNotFound))?;
+
+        if !rotated {
+            return Err(AuthError::InvalidCredentials);
+        }
+
+        if let Some(ref blacklist) = self.session_blacklist
+            && let Some(jti) = &session.refresh_jti
+        {
+            let _ = blacklist
+                .blacklist_session(jti, session.expires_at.timestamp())
+                .await;
+        }
+
+        let refresh_cookie = create_refresh_cookie(&tokens.refresh_token, &self.config);
+
+        Ok((
+            TokenResponse {
+                access_token: tokens.access_token,
+                expires_in: tokens.expires_in,
+            },
+            refresh_cookie,
+        ))
+    }
+
+    /// Password check for step-up, counted by the same throttle as login
+    async fn verify_password_for_reauth(
+        &self,
+        user: &User,
+        password: &str,
+    ) -> Result<(), AuthError> {
+        match self.login_throttle.check(&user.email).await {
+            ThrottleStatus::Allowed => {}
+            ThrottleStatus::Delayed { retry_after_secs } => {
+                return Err(AuthError::TooManyAttempts { retry_after_secs });
+            }
+            ThrottleStatus::Locked { retry_after_secs } => {
+                return Err(AuthError::AccountLocked { retry_after_secs });
+            }
+        }
+
+        let auth_method = self
+            .auth_method_service
+            .find_by_user_and_provider(user.id, AuthProvider::Password)
+            .await?
+            .ok_or(AuthError::InvalidCredentials)?;
+
+        if !self
+            .auth_method_service
+            .verify_password(&auth_method, password)
+            .map_err(|_| AuthError::HashError)?
+        {
+            if let ThrottleStatus::Locked { retry_after_secs } =
+                self.login_throttle.record_failure(&user.email).await
+            {
+                return Err(AuthError::AccountLocked { retry_after_secs });
+            }
+            return Err(AuthError::InvalidCredentials);
+        }
+
+        self.login_throttle.record_success(&user.email).await;
+        Ok(())
+    }
+
+    /// Run a second-factor check under the login throttle: refused while the
+    /// account is delayed or locked, and wrong codes count as failures
+    async fn throttled_mfa<T>(
+        &self,
+        email: &str,
+        attempt: impl Future<Output = Result<T, MfaError>>,
+    ) -> Result<T, AuthError> {
+        match self.login_throttle.check(email).await {
+            ThrottleStatus::Allowed => {}
+            ThrottleStatus::Delayed { retry_after_secs } => {
+                return Err(AuthError::TooManyAttempts { retry_after_secs });
+            }
+            ThrottleStatus::Locked { retry_after_secs } => {
+                return Err(AuthError::AccountLocked { retry_after_secs });
+            }
+        }
+
+        match attempt.await {
+            Ok(value) => {
+                self.login_throttle.record_success(email).await;
+                Ok(value)
+            }
+            Err(MfaError::InvalidCode) => {
+                if let ThrottleStatus::Locked { retry_after_secs } =
+                    self.login_throttle.record_failure(email).await
+                {
+                    return Err(AuthError::AccountLocked { retry_after_secs });
+                }
+                Err(MfaError::InvalidCode.into())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// A rotated refresh token was presented again: either the legitimate
+    /// client or an attacker holds a stolen copy. Kill the whole session,
+    /// blacklist its live access token and record a security event.
+    async fn refresh_token_reused(&self, session: &UserSession, presented_jti: &str) -> AuthError {
+        tracing::warn!("Token reuse detected for session: {}", session.session_id);
+
+        if let Err(e) = self
+            .session_service
+            .revoke_session(session.id, "token_reuse_detected")
+            .await
+        {
+            tracing::error!("Failed to revoke session after token reuse: {:?}", e);
+        }
+
+        // Re-read so a token issued by a concurrent rotation is blacklisted too
+        let current = self
+            .session_service
+            .get_session(&session.session_id)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| session.clone());
+
+        if let Some(ref blacklist) = self.session_blacklist
+            && let (Some(jti), Some(exp)) = (&current.access_jti, current.access_expires_at)
+            && let Err(e) = blacklist.blacklist_session(jti, exp.timestamp()).await
+        {
+            tracing::error!("Failed to blacklist access token after token reuse: {}", e);
+        }
+
+        self.security_events
+            .emit(
+                Some(session.user_id),
+                SecurityEventType::TokenReuseDetected,
+                Some(&session.session_id),
+                serde_json::json!({
+                    "presented_jti": presented_jti,
+                    "ip_address": session.ip_address,
+                    "device_name": session.device_name,
+                }),
+            )
+            .await;
+
+        AuthError::TokenReuseDetected
+    }
+
+    /// Blacklist the presented tokens and revoke the refresh token's session
     pub async fn logout(
         &self,
         refresh_token: Option<&str>,
         access_token: Option<&str>,
     ) -> Cookie<'static> {
-        // Blacklist tokens if provided and revoke session in DB
-        if let (Some(token), Some(blacklist)) = (refresh_token, self.session_blacklist.as_ref())
-            && let Ok(claims) = validate_refresh_token(token)
+        for token in [refresh_token, access_token].into_iter().flatten() {
+            if let Some(claims) = self.decode_token(token, None) {
+                self.revoke_claims(&claims, "user_logout").await;
+            }
+        }
+
+        create_cleared_cookie(&self.config)
+    }
+
+    /// RFC 7009 revocation of an access or refresh token. Unknown and invalid
+    /// tokens are ignored, as the RFC asks.
+    pub async fn revoke_token(&self, token: &str, type_hint: Option<&str>) {
+        if let Some(claims) = self.decode_token(token, type_hint) {
+            self.revoke_claims(&claims, "token_revoked").await;
+        }
+    }
+
+    /// RFC 7662 introspection: a token is active when its signature and
+    /// expiry check out, it is not blacklisted and its session is still live.
+    /// A refresh token must also be the session's current one. Service tokens
+    /// (client credentials) have no session and only need to be unexpired.
+    pub async fn introspect(&self, token: &str, type_hint: Option<&str>) -> TokenIntrospection {
+        let Some(claims) = self.decode_token(token, type_hint) else {
+            return TokenIntrospection::default();
+        };
+
+        if let Some(ref blacklist) = self.session_blacklist
+            && blacklist.is_blacklisted(&claims.jti).await.unwrap_or(true)
         {
-            let _ = blacklist.blacklist_session(&claims.jti, claims.exp).await;
-            // Revoke session in database
-            let _ = self
-                .session_service
-                .revoke_by_session_id(&claims.sid, "user_logout")
-                .await;
+            return TokenIntrospection::default();
+        }
+
+        if claims.service_key_id().is_some() {
+            return TokenIntrospection {
+                active: true,
+                sub: Some(claims.sub),
+                roles: Some(claims.roles),
+                token_type: Some("access_token"),
+                exp: Some(claims.exp),
+                iat: Some(claims.iat),
+                client_id: claims.client_id,
+                scope: claims.scope,
+                ..Default::default()
+            };
         }
 
-        if let (Some(token), Some(blacklist)) = (access_token, self.session_blacklist.as_ref())
-            && let Ok(claims) = crate::feature::auth::utils::validate_access_token(token)
+        let session = match self.session_service.get_session(&claims.sid).await {
+            Ok(Some(session)) if session.is_live() => session,
+            Ok(_) => return TokenIntrospection::default(),
+            Err(e) => {
+                tracing::error!("Failed to load session for introspection: {:?}", e);
+                return TokenIntrospection::default();
+            }
+        };
+
+        let token_type = match claims.token_type {
+            TokenType::Refresh if session.refresh_jti.as_deref() != Some(&claims.jti) => {
+                return TokenIntrospection::default();
+            }
+            TokenType::Refresh => "refresh_token",
+            _ => "access_token",
+        };
+
+        TokenIntrospection {
+            active: true,
+            sub: Some(claims.sub),
+            sid: Some(claims.sid),
+            roles: Some(claims.roles),
+            token_type: Some(token_type),
+            exp: Some(claims.exp),
+            iat: Some(claims.iat),
+            act: claims.act,
+            client_id: claims.client_id,
+            scope: claims.scope,
+        }
+    }
+
+    /// Validate `token` as an access or refresh token, trying the hinted
+    /// type first
+    fn decode_token(&self, token: &str, type_hint: Option<&str>) -> Option<Claims> {
+        let access = || validate_access_token(&self.jwt_keys, token).ok();
+        let refresh = || validate_refresh_token(&self.jwt_keys, token).ok();
+
+        if type_hint == Some("refresh_token") {
+            refresh().or_else(access)
+        } else {
+            access().or_else(refresh)
+        }
+    }
+
+    /// Blacklist a token; revoking a refresh token also ends its session
+    async fn revoke_claims(&self, claims: &Claims, reason: &str) {
+        if let Some(ref blacklist) = self.session_blacklist
+            && let Err(e) = blacklist.blacklist_session(&claims.jti, claims.exp).await
         {
-            let _ = blacklist.blacklist_session(&claims.jti, claims.exp).await;
+            tracing::error!("Failed to blacklist token: {}", e);
         }
 
-        create_cleared_cookie(&self.config)
+        if claims.token_type == TokenType::Refresh
+            && let Err(e) = self
+                .session_service
+                .revoke_by_session_id(&claims.sid, reason)
+                .await
+        {
+            tracing::error!("Failed to revoke session: {:?}", e);
+        }
     }
 
     /// Get user with profile
@@ -566,4 +1311,29 @@ impl AuthService {
     pub fn session_service(&self) -> &SessionService {
         &self.session_service
     }
+
+    /// Get MFA service
+    pub fn mfa_service(&self) -> &MfaService {
+        &self.mfa_service
+    }
+
+    /// Get login throttle
+    pub fn login_throttle(&self) -> &LoginThrottle {
+        &self.login_throttle
+    }
+
+    /// Get security event service
+    pub fn security_events(&self) -> &SecurityEventService {
+        &self.security_events
+    }
+}
+
+/// Token identifiers recorded on the session row
+fn session_token_ids(tokens: &TokenPair) -> SessionTokenIds {
+    SessionTokenIds {
+        refresh_jti: tokens.refresh_jti.clone(),
+        access_jti: tokens.access_jti.clone(),
+        access_expires_at: chrono::DateTime::from_timestamp(tokens.access_exp, 0)
+            .unwrap_or_else(chrono::Utc::now),
+    }
 }
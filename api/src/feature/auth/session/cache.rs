@@ -0,0 +1,136 @@
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use uuid::Uuid;
+
+/// Entries kept before stale ones are swept on insert
+const MAX_ENTRIES: usize = 10_000;
+
+struct Entry {
+    /// Row id and owner, for invalidation on revoke (`None` if no such session)
+    owner: Option<(Uuid, Uuid)>,
+    active: bool,
+    checked_at: Instant,
+    touched_at: Option<Instant>,
+}
+
+/// In-process cache of session activity lookups, keyed by the JWT `sid`.
+///
+/// Revocations made through this instance are applied immediately; ones
+/// made elsewhere become visible once the entry expires.
+pub struct SessionActivityCache {
+    entries: DashMap<String, Entry>,
+    ttl: Duration,
+    touch_interval: Duration,
+}
+
+impl SessionActivityCache {
+    pub fn new(ttl: Duration, touch_interval: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl,
+            touch_interval,
+        }
+    }
+
+    /// Cached activity for `sid`, if still fresh
+    pub fn get(&self, sid: &str) -> Option<bool> {
+        self.entries
+            .get(sid)
+            .filter(|e| e.checked_at.elapsed() < self.ttl)
+            .map(|e| e.active)
+    }
+
+    /// Record a fresh lookup, keeping the last touch time
+    pub fn insert(&self, sid: &str, owner: Option<(Uuid, Uuid)>, active: bool) {
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries
+                .retain(|_, e| e.checked_at.elapsed() < self.ttl);
+        }
+
+        let mut entry = self.entries.entry(sid.to_string()).or_insert(Entry {
+            owner,
+            active,
+            checked_at: Instant::now(),
+            touched_at: None,
+        });
+        entry.owner = owner;
+        entry.active = active;
+        entry.checked_at = Instant::now();
+    }
+
+    /// Whether `last_active_at` is due an update; claims the slot if so
+    pub fn claim_touch(&self, sid: &str) -> bool {
+        let Some(mut entry) = self.entries.get_mut(sid) else {
+            return false;
+        };
+        if entry
+            .touched_at
+            .is_some_and(|t| t.elapsed() < self.touch_interval)
+        {
+            return false;
+        }
+        entry.touched_at = Some(Instant::now());
+        true
+    }
+
+    /// Drop the entry for a session row
+    pub fn forget_session(&self, id: Uuid) {
+        self.entries
+            .retain(|_, e| e.owner.is_none_or(|(session_id, _)| session_id != id));
+    }
+
+    /// Drop every entry belonging to a user
+    pub fn forget_user(&self, user_id: Uuid) {
+        self.entries
+            .retain(|_, e| e.owner.is_none_or(|(_, owner)| owner != user_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> SessionActivityCache {
+        SessionActivityCache::new(Duration::from_secs(30), Duration::from_secs(60))
+    }
+
+    #[test]
+    fn test_entries_expire() {
+        let cache = SessionActivityCache::new(Duration::ZERO, Duration::from_secs(60));
+        cache.insert("sid", None, true);
+        assert_eq!(cache.get("sid"), None);
+    }
+
+    #[test]
+    fn test_touch_is_throttled() {
+        let cache = cache();
+        assert!(!cache.claim_touch("sid"));
+
+        cache.insert("sid", None, true);
+        assert!(cache.claim_touch("sid"));
+        assert!(!cache.claim_touch("sid"));
+
+        // A refreshed lookup does not reset the touch clock
+        cache.insert("sid", None, true);
+        assert!(!cache.claim_touch("sid"));
+    }
+
+    #[test]
+    fn test_forget_by_session_and_user() {
+        let cache = cache();
+        let (user, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        cache.insert("a", Some((a, user)), true);
+        cache.insert("b", Some((b, user)), true);
+        cache.insert("c", Some((c, other)), true);
+
+        cache.forget_session(a);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b"), Some(true));
+
+        cache.forget_user(user);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(true));
+    }
+}
@@ -1,8 +1,17 @@
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    str::FromStr,
+};
+
+use axum::http::HeaderMap;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::user_agent::UserAgent;
+use crate::feature::auth::utils::sha256_hex;
+
 /// User session entity for device tracking
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct UserSession {
@@ -12,15 +21,59 @@ pub struct UserSession {
     pub session_id: String,
     pub device_name: Option<String>,
     pub device_type: Option<String>,
+    pub browser: Option<String>,
+    pub browser_version: Option<String>,
+    pub os: Option<String>,
+    pub os_version: Option<String>,
+    #[serde(skip)]
+    pub device_fingerprint: Option<String>,
     pub ip_address: String,
     pub user_agent: Option<String>,
     pub location: Option<String>,
+    pub ip_country_code: Option<String>,
     pub created_at: DateTime<Utc>,
     pub last_active_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
     pub is_active: bool,
     pub revoked_at: Option<DateTime<Utc>>,
     pub revoked_reason: Option<String>,
+    pub metadata: Option<sqlx::types::Json<SessionMetadata>>,
+    #[serde(skip)]
+    pub refresh_jti: Option<String>,
+    #[serde(skip)]
+    pub access_jti: Option<String>,
+    #[serde(skip)]
+    pub access_expires_at: Option<DateTime<Utc>>,
+}
+
+impl UserSession {
+    /// Not revoked and not past its expiry
+    pub fn is_live(&self) -> bool {
+        self.is_active && self.expires_at >= Utc::now()
+    }
+}
+
+/// Identifiers of the token pair currently issued for a session
+#[derive(Debug, Clone)]
+pub struct SessionTokenIds {
+    pub refresh_jti: String,
+    pub access_jti: String,
+    pub access_expires_at: DateTime<Utc>,
+}
+
+/// Extra attributes stored in `user_sessions.metadata` (JSONB)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionMetadata {
+    /// Session was created after a successful second-factor check
+    #[serde(default)]
+    pub mfa_used: bool,
+    /// First sign-in from this device fingerprint on an account that had
+    /// signed in elsewhere before
+    #[serde(default)]
+    pub new_device: bool,
+    /// Admin who started this session to act as the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonated_by: Option<Uuid>,
 }
 
 /// Device information extracted from request
@@ -30,64 +83,72 @@ pub struct DeviceInfo {
     pub device_type: String,
     pub user_agent: String,
     pub ip_address: String,
+    #[serde(default)]
+    pub browser: Option<String>,
+    #[serde(default)]
+    pub browser_version: Option<String>,
+    #[serde(default)]
+    pub os: Option<String>,
+    #[serde(default)]
+    pub os_version: Option<String>,
+    /// SHA-256 of the parsed browser, OS, device type and preferred languages.
+    /// Stable across browser updates, unlike the raw user agent.
+    #[serde(default)]
+    pub fingerprint: String,
 }
 
 impl DeviceInfo {
+    /// Build device info from request headers (User-Agent, X-Forwarded-For / X-Real-IP)
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let user_agent = headers
+            .get("user-agent")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("Unknown");
+
+        // First (client) hop of X-Forwarded-For; anything unparsable is ignored
+        // since the address is stored as INET
+        let ip_address = header_ip(headers, "x-forwarded-for")
+            .or_else(|| header_ip(headers, "x-real-ip"))
+            .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+            .to_string();
+
+        let accept_language = headers
+            .get("accept-language")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+
+        let mut info = Self::from_user_agent(user_agent, &ip_address);
+        info.fingerprint = Self::fingerprint(&info.name, &info.device_type, accept_language);
+        info
+    }
+
     /// Parse user agent to get device name
     pub fn from_user_agent(user_agent: &str, ip: &str) -> Self {
-        let (name, device_type) = Self::parse_user_agent(user_agent);
+        let parsed = UserAgent::parse(user_agent);
+        let name = parsed.device_name();
+        let device_type = parsed.device_class.as_str().to_string();
+        let fingerprint = Self::fingerprint(&name, &device_type, "");
 
         Self {
             name,
             device_type,
             user_agent: user_agent.to_string(),
             ip_address: ip.to_string(),
+            browser: parsed.browser,
+            browser_version: parsed.browser_version,
+            os: parsed.os,
+            os_version: parsed.os_version,
+            fingerprint,
         }
     }
 
-    fn parse_user_agent(ua: &str) -> (String, String) {
-        let ua_lower = ua.to_lowercase();
-
-        // Detect device type
-        let device_type = if ua_lower.contains("mobile") {
-            "mobile"
-        } else if ua_lower.contains("tablet") || ua_lower.contains("ipad") {
-            "tablet"
-        } else {
-            "desktop"
-        }
-        .to_string();
-
-        // Detect browser
-        let browser = if ua_lower.contains("chrome") {
-            "Chrome"
-        } else if ua_lower.contains("firefox") {
-            "Firefox"
-        } else if ua_lower.contains("safari") && !ua_lower.contains("chrome") {
-            "Safari"
-        } else if ua_lower.contains("edge") {
-            "Edge"
-        } else {
-            "Unknown"
-        };
-
-        // Detect OS
-        let os = if ua_lower.contains("windows") {
-            "Windows"
-        } else if ua_lower.contains("macintosh") || ua_lower.contains("mac os") {
-            "macOS"
-        } else if ua_lower.contains("linux") {
-            "Linux"
-        } else if ua_lower.contains("android") {
-            "Android"
-        } else if ua_lower.contains("iphone") || ua_lower.contains("ipad") {
-            "iOS"
-        } else {
-            "Unknown"
-        };
-
-        let name = format!("{} on {}", browser, os);
-        (name, device_type)
+    fn fingerprint(name: &str, device_type: &str, accept_language: &str) -> String {
+        sha256_hex(&format!(
+            "{}|{}|{}",
+            device_type,
+            name,
+            accept_language.trim().to_lowercase()
+        ))
     }
 }
 
@@ -95,11 +156,80 @@ impl DeviceInfo {
 #[derive(Debug, Clone, Serialize)]
 pub struct SessionResponse {
     pub id: Uuid,
-    pub device_name: Option<String>,
+    #[serde(rename = "device")]
+    pub device_name: String,
     pub device_type: Option<String>,
+    pub browser: Option<String>,
+    pub browser_version: Option<String>,
+    pub os: Option<String>,
+    pub os_version: Option<String>,
+    #[serde(rename = "ip")]
     pub ip_address: String,
-    pub location: Option<String>,
+    pub location: String,
+    pub country_code: Option<String>,
     pub created_at: DateTime<Utc>,
     pub last_active_at: DateTime<Utc>,
     pub is_current: bool,
+    pub mfa_used: bool,
+    pub new_device: bool,
+    pub impersonated: bool,
+}
+
+impl SessionResponse {
+    pub fn from_session(session: UserSession, current_session_id: &str) -> Self {
+        let metadata = session.metadata.map(|m| m.0).unwrap_or_default();
+        Self {
+            is_current: session.session_id == current_session_id,
+            id: session.id,
+            device_name: session
+                .device_name
+                .unwrap_or_else(|| "Unknown Device".to_string()),
+            device_type: session.device_type,
+            browser: session.browser,
+            browser_version: session.browser_version,
+            os: session.os,
+            os_version: session.os_version,
+            ip_address: session.ip_address,
+            location: session
+                .location
+                .unwrap_or_else(|| "Unknown Location".to_string()),
+            country_code: session.ip_country_code,
+            created_at: session.created_at,
+            last_active_at: session.last_active_at,
+            mfa_used: metadata.mfa_used,
+            new_device: metadata.new_device,
+            impersonated: metadata.impersonated_by.is_some(),
+        }
+    }
+}
+
+/// First comma-separated entry of an IP header, if it is a valid address
+fn header_ip(headers: &HeaderMap, name: &str) -> Option<IpAddr> {
+    let value = headers.get(name)?.to_str().ok()?;
+    IpAddr::from_str(value.split(',').next()?.trim()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_from_forwarding_headers() {
+        let ip = |pairs: &[(&'static str, &'static str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.insert(*name, value.parse().unwrap());
+            }
+            DeviceInfo::from_headers(&headers).ip_address
+        };
+
+        assert_eq!(ip(&[("x-forwarded-for", "1.2.3.4, 10.0.0.1")]), "1.2.3.4");
+        assert_eq!(ip(&[("x-forwarded-for", " 2001:db8::1 ")]), "2001:db8::1");
+        assert_eq!(
+            ip(&[("x-forwarded-for", "junk"), ("x-real-ip", "5.6.7.8")]),
+            "5.6.7.8"
+        );
+        assert_eq!(ip(&[("x-forwarded-for", "junk")]), "0.0.0.0");
+        assert_eq!(ip(&[]), "0.0.0.0");
+    }
 }
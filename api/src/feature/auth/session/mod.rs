@@ -1,7 +1,10 @@
+pub mod cache;
 pub mod entity;
 pub mod repository;
 pub mod service;
+pub mod user_agent;
 
-pub use entity::{DeviceInfo, UserSession};
+pub use entity::{DeviceInfo, SessionMetadata, SessionResponse, SessionTokenIds, UserSession};
 pub use repository::{SessionRepository, SessionRepositoryError, SessionRepositoryImpl};
 pub use service::SessionService;
+pub use user_agent::{DeviceClass, UserAgent};
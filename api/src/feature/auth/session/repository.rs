@@ -4,7 +4,9 @@ use sqlx::PgPool;
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::entity::{DeviceInfo, UserSession};
+use crate::infrastructure::geoip::GeoLocation;
+
+use super::entity::{DeviceInfo, SessionMetadata, SessionTokenIds, UserSession};
 
 #[derive(Debug, Error)]
 pub enum SessionRepositoryError {
@@ -19,15 +21,30 @@ pub enum SessionRepositoryError {
 #[async_trait]
 pub trait SessionRepository: Send + Sync {
     /// Create a new session
+    #[allow(clippy::too_many_arguments)]
     async fn create(
         &self,
         pool: &PgPool,
         user_id: Uuid,
         session_id: &str,
         device_info: &DeviceInfo,
+        location: Option<&GeoLocation>,
         expires_at: DateTime<Utc>,
+        metadata: &SessionMetadata,
+        tokens: &SessionTokenIds,
     ) -> Result<UserSession, SessionRepositoryError>;
 
+    /// Record a refresh rotation. Only succeeds while `presented_refresh_jti`
+    /// is still the session's current refresh token (or none was recorded),
+    /// so a rotated token can never be rotated twice.
+    async fn rotate_tokens(
+        &self,
+        pool: &PgPool,
+        session_id: &str,
+        presented_refresh_jti: &str,
+        tokens: &SessionTokenIds,
+    ) -> Result<bool, SessionRepositoryError>;
+
     /// Find session by session_id
     async fn find_by_session_id(
         &self,
@@ -42,6 +59,30 @@ pub trait SessionRepository: Send + Sync {
         id: Uuid,
     ) -> Result<Option<UserSession>, SessionRepositoryError>;
 
+    /// Find a session by the hash of its revoke link token
+    async fn find_by_revoke_token_hash(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+    ) -> Result<Option<UserSession>, SessionRepositoryError>;
+
+    /// Store the hash of the token that revokes the session from an alert link
+    async fn set_revoke_token_hash(
+        &self,
+        pool: &PgPool,
+        id: Uuid,
+        token_hash: &str,
+    ) -> Result<(), SessionRepositoryError>;
+
+    /// Whether the user has sessions with a recorded fingerprint, and whether
+    /// any of them used `fingerprint`
+    async fn fingerprint_history(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        fingerprint: &str,
+    ) -> Result<(bool, bool), SessionRepositoryError>;
+
     /// List all active sessions for a user
     async fn list_active_by_user(
         &self,
@@ -114,15 +155,21 @@ impl SessionRepository for SessionRepositoryImpl {
         user_id: Uuid,
         session_id: &str,
         device_info: &DeviceInfo,
+        location: Option<&GeoLocation>,
         expires_at: DateTime<Utc>,
+        metadata: &SessionMetadata,
+        tokens: &SessionTokenIds,
     ) -> Result<UserSession, SessionRepositoryError> {
         let session = sqlx::query_as::<_, UserSession>(
             r#"
             INSERT INTO user_sessions (
                 user_id, session_id, device_name, device_type, 
-                ip_address, user_agent, location, expires_at
+                ip_address, user_agent, location, ip_country_code, expires_at, metadata,
+                refresh_jti, access_jti, access_expires_at, device_fingerprint,
+                browser, browser_version, os, os_version
             )
-            VALUES ($1, $2, $3, $4, $5::inet, $6, NULL, $7)
+            VALUES ($1, $2, $3, $4, $5::inet, $6, $7, $8, $9, $10, $11, $12, $13, $14,
+                    $15, $16, $17, $18)
             RETURNING *
             "#,
         )
@@ -132,13 +179,52 @@ impl SessionRepository for SessionRepositoryImpl {
         .bind(&device_info.device_type)
         .bind(&device_info.ip_address)
         .bind(&device_info.user_agent)
+        .bind(location.and_then(GeoLocation::label))
+        .bind(location.and_then(|l| l.country_code.as_deref()))
         .bind(expires_at)
+        .bind(sqlx::types::Json(metadata))
+        .bind(&tokens.refresh_jti)
+        .bind(&tokens.access_jti)
+        .bind(tokens.access_expires_at)
+        .bind(Some(device_info.fingerprint.as_str()).filter(|f| !f.is_empty()))
+        .bind(&device_info.browser)
+        .bind(&device_info.browser_version)
+        .bind(&device_info.os)
+        .bind(&device_info.os_version)
         .fetch_one(pool)
         .await?;
 
         Ok(session)
     }
 
+    async fn rotate_tokens(
+        &self,
+        pool: &PgPool,
+        session_id: &str,
+        presented_refresh_jti: &str,
+        tokens: &SessionTokenIds,
+    ) -> Result<bool, SessionRepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_sessions
+            SET refresh_jti = $3, access_jti = $4, access_expires_at = $5,
+                last_active_at = NOW()
+            WHERE session_id = $1
+              AND is_active = TRUE
+              AND (refresh_jti IS NULL OR refresh_jti = $2)
+            "#,
+        )
+        .bind(session_id)
+        .bind(presented_refresh_jti)
+        .bind(&tokens.refresh_jti)
+        .bind(&tokens.access_jti)
+        .bind(tokens.access_expires_at)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     async fn find_by_session_id(
         &self,
         pool: &PgPool,
@@ -168,6 +254,57 @@ impl SessionRepository for SessionRepositoryImpl {
         Ok(session)
     }
 
+    async fn find_by_revoke_token_hash(
+        &self,
+        pool: &PgPool,
+        token_hash: &str,
+    ) -> Result<Option<UserSession>, SessionRepositoryError> {
+        let session = sqlx::query_as::<_, UserSession>(
+            r#"SELECT * FROM user_sessions WHERE revoke_token_hash = $1"#,
+        )
+        .bind(token_hash)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    async fn set_revoke_token_hash(
+        &self,
+        pool: &PgPool,
+        id: Uuid,
+        token_hash: &str,
+    ) -> Result<(), SessionRepositoryError> {
+        sqlx::query(r#"UPDATE user_sessions SET revoke_token_hash = $2 WHERE id = $1"#)
+            .bind(id)
+            .bind(token_hash)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn fingerprint_history(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        fingerprint: &str,
+    ) -> Result<(bool, bool), SessionRepositoryError> {
+        let history = sqlx::query_as::<_, (bool, bool)>(
+            r#"
+            SELECT COUNT(*) > 0, COALESCE(BOOL_OR(device_fingerprint = $2), FALSE)
+            FROM user_sessions
+            WHERE user_id = $1 AND device_fingerprint IS NOT NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(fingerprint)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(history)
+    }
+
     async fn list_active_by_user(
         &self,
         pool: &PgPool,
@@ -1,35 +1,88 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::{
-    feature::auth::session::{DeviceInfo, SessionRepository, SessionRepositoryError, UserSession},
-    infrastructure::persistence::Database,
+    feature::auth::{
+        session::{
+            DeviceInfo, SessionMetadata, SessionRepository, SessionRepositoryError,
+            SessionTokenIds, UserSession, cache::SessionActivityCache,
+        },
+        utils::{random_token, sha256_hex},
+    },
+    infrastructure::{config::Config, geoip::GeoIp, persistence::Database},
 };
 
+/// Random bytes per revoke link token (256 bits)
+const REVOKE_TOKEN_BYTES: usize = 32;
+
 /// Session service for managing user sessions
 #[derive(Clone)]
 pub struct SessionService {
     db: Database,
     repo: Arc<dyn SessionRepository>,
+    config: Arc<Config>,
+    activity: Arc<SessionActivityCache>,
+    geoip: GeoIp,
 }
 
 impl SessionService {
-    pub fn new(db: Database, repo: Arc<dyn SessionRepository>) -> Self {
-        Self { db, repo }
+    pub fn new(
+        db: Database,
+        repo: Arc<dyn SessionRepository>,
+        config: Arc<Config>,
+        geoip: GeoIp,
+    ) -> Self {
+        let activity = Arc::new(SessionActivityCache::new(
+            Duration::from_secs(config.session.cache_ttl_secs),
+            Duration::from_secs(config.session.touch_interval_secs),
+        ));
+        Self {
+            db,
+            repo,
+            config,
+            activity,
+            geoip,
+        }
     }
 
-    /// Create a new session
+    /// Create a new session, located by its IP when a GeoIP database is loaded
     pub async fn create_session(
         &self,
         user_id: Uuid,
         session_id: &str,
         device_info: &DeviceInfo,
         expires_at: DateTime<Utc>,
+        metadata: &SessionMetadata,
+        tokens: &SessionTokenIds,
     ) -> Result<UserSession, SessionRepositoryError> {
+        let location = self.geoip.lookup(&device_info.ip_address);
+        self.repo
+            .create(
+                self.db.pool(),
+                user_id,
+                session_id,
+                device_info,
+                location.as_ref(),
+                expires_at,
+                metadata,
+                tokens,
+            )
+            .await
+    }
+
+    /// Swap in a freshly issued token pair. Returns `false` if the presented
+    /// refresh token is no longer the session's current one (reuse) or the
+    /// session is not active.
+    pub async fn rotate_tokens(
+        &self,
+        session_id: &str,
+        presented_refresh_jti: &str,
+        tokens: &SessionTokenIds,
+    ) -> Result<bool, SessionRepositoryError> {
         self.repo
-            .create(self.db.pool(), user_id, session_id, device_info, expires_at)
+            .rotate_tokens(self.db.pool(), session_id, presented_refresh_jti, tokens)
             .await
     }
 
@@ -43,6 +96,53 @@ impl SessionService {
             .await
     }
 
+    /// Whether `fingerprint` is new for a user who has signed in from other
+    /// fingerprinted devices before. A first sign-in is not a new device.
+    pub async fn is_new_device(
+        &self,
+        user_id: Uuid,
+        fingerprint: &str,
+    ) -> Result<bool, SessionRepositoryError> {
+        if fingerprint.is_empty() {
+            return Ok(false);
+        }
+        let (has_history, seen) = self
+            .repo
+            .fingerprint_history(self.db.pool(), user_id, fingerprint)
+            .await?;
+        Ok(has_history && !seen)
+    }
+
+    /// Issue the token for a link that revokes the session without signing
+    /// in. Returns the raw token — only its hash is stored.
+    pub async fn issue_revoke_token(&self, id: Uuid) -> Result<String, SessionRepositoryError> {
+        let token = random_token(REVOKE_TOKEN_BYTES);
+        self.repo
+            .set_revoke_token_hash(self.db.pool(), id, &sha256_hex(&token))
+            .await?;
+        Ok(token)
+    }
+
+    /// Revoke the session a revoke link was issued for. Returns the session
+    /// if it was still active.
+    pub async fn revoke_by_token(
+        &self,
+        token: &str,
+    ) -> Result<Option<UserSession>, SessionRepositoryError> {
+        let Some(session) = self
+            .repo
+            .find_by_revoke_token_hash(self.db.pool(), &sha256_hex(token))
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let revoked = self
+            .revoke_session(session.id, "revoked_from_alert")
+            .await?;
+        Ok(revoked.then_some(session))
+    }
+
     /// List all active sessions for a user
     pub async fn list_sessions(
         &self,
@@ -64,7 +164,9 @@ impl SessionService {
         session_id: Uuid,
         reason: &str,
     ) -> Result<bool, SessionRepositoryError> {
-        self.repo.revoke(self.db.pool(), session_id, reason).await
+        let revoked = self.repo.revoke(self.db.pool(), session_id, reason).await?;
+        self.activity.forget_session(session_id);
+        Ok(revoked)
     }
 
     /// Revoke session by session_id string (JWT sid claim)
@@ -79,7 +181,7 @@ impl SessionService {
             .find_by_session_id(self.db.pool(), session_id)
             .await?
         {
-            self.repo.revoke(self.db.pool(), session.id, reason).await
+            self.revoke_session(session.id, reason).await
         } else {
             Ok(false) // Session not found
         }
@@ -91,9 +193,12 @@ impl SessionService {
         user_id: Uuid,
         current_session_id: &str,
     ) -> Result<u64, SessionRepositoryError> {
-        self.repo
+        let revoked = self
+            .repo
             .revoke_all_except(self.db.pool(), user_id, current_session_id)
-            .await
+            .await?;
+        self.activity.forget_user(user_id);
+        Ok(revoked)
     }
 
     /// Revoke all sessions (e.g., on password change)
@@ -102,27 +207,56 @@ impl SessionService {
         user_id: Uuid,
         reason: &str,
     ) -> Result<u64, SessionRepositoryError> {
-        self.repo.revoke_all(self.db.pool(), user_id, reason).await
+        let revoked = self
+            .repo
+            .revoke_all(self.db.pool(), user_id, reason)
+            .await?;
+        self.activity.forget_user(user_id);
+        Ok(revoked)
     }
 
     /// Check if session is valid and active
     pub async fn is_session_valid(&self, session_id: &str) -> Result<bool, SessionRepositoryError> {
-        match self
+        Ok(self
             .repo
             .find_by_session_id(self.db.pool(), session_id)
             .await?
-        {
-            Some(session) => {
-                if !session.is_active {
-                    return Ok(false);
-                }
-                if session.expires_at < Utc::now() {
-                    return Ok(false);
-                }
-                Ok(true)
+            .is_some_and(|session| session.is_live()))
+    }
+
+    /// Confirm the session behind an access token is still active, using the
+    /// in-process cache, and bump `last_active_at` at most once per touch
+    /// interval. Always `true` when access-token validation is disabled.
+    pub async fn check_access(&self, session_id: &str) -> Result<bool, SessionRepositoryError> {
+        if !self.config.session.validate_access_tokens {
+            return Ok(true);
+        }
+
+        let active = match self.activity.get(session_id) {
+            Some(active) => active,
+            None => {
+                let session = self
+                    .repo
+                    .find_by_session_id(self.db.pool(), session_id)
+                    .await?;
+                let active = session.as_ref().is_some_and(UserSession::is_live);
+                self.activity
+                    .insert(session_id, session.map(|s| (s.id, s.user_id)), active);
+                active
             }
-            None => Ok(false),
+        };
+
+        if active && self.activity.claim_touch(session_id) {
+            let service = self.clone();
+            let session_id = session_id.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = service.touch_session(&session_id).await {
+                    tracing::warn!("Failed to touch session {session_id}: {e}");
+                }
+            });
         }
+
+        Ok(active)
     }
 
     /// Get active session count for user
@@ -0,0 +1,414 @@
+//! User-Agent parsing for session device names.
+//!
+//! Token order matters: Edge and Opera also send `Chrome/` and `Safari/`,
+//! Chrome sends `Safari/`, and Android sends `Linux`, so the more specific
+//! tokens are checked first.
+//!
+//! Our mobile app identifies itself as
+//! `QuaxApp/<app version> (<OS> <OS version>; <device model>)`, e.g.
+//! `QuaxApp/2.3.0 (iOS 17.5; iPhone15,2)`.
+
+use serde::Serialize;
+
+/// Product token of our own mobile app
+const APP_PRODUCT: &str = "QuaxApp";
+
+/// Browsers as `(UA token, display name)`, most specific first
+const BROWSERS: &[(&str, &str)] = &[
+    ("Edg/", "Edge"),
+    ("EdgA/", "Edge"),
+    ("EdgiOS/", "Edge"),
+    ("Edge/", "Edge"),
+    ("OPR/", "Opera"),
+    ("OPiOS/", "Opera"),
+    ("SamsungBrowser/", "Samsung Internet"),
+    ("YaBrowser/", "Yandex Browser"),
+    ("Vivaldi/", "Vivaldi"),
+    ("FxiOS/", "Firefox"),
+    ("Firefox/", "Firefox"),
+    ("CriOS/", "Chrome"),
+    ("Chromium/", "Chromium"),
+    ("Chrome/", "Chrome"),
+];
+
+/// Command-line clients and HTTP libraries, matched as the leading product
+/// with or without a version (`curl/8.7.1`, `curl`)
+const CLI_CLIENTS: &[(&str, &str)] = &[
+    ("curl", "curl"),
+    ("Wget", "Wget"),
+    ("HTTPie", "HTTPie"),
+    ("python-requests", "Python Requests"),
+    ("Python-urllib", "Python urllib"),
+    ("python-httpx", "HTTPX"),
+    ("Go-http-client", "Go HTTP client"),
+    ("PostmanRuntime", "Postman"),
+    ("insomnia", "Insomnia"),
+    ("axios", "axios"),
+    ("node-fetch", "node-fetch"),
+    ("okhttp", "OkHttp"),
+    ("libwww-perl", "libwww-perl"),
+    ("Java", "Java"),
+];
+
+/// Lowercase markers of crawlers and link-preview fetchers, matched against
+/// product names (`Googlebot/2.1`) only: "CUBOT" phones and robot vacuums
+/// mention "bot" elsewhere in the header
+const BOT_MARKERS: &[&str] = &[
+    "bot",
+    "crawler",
+    "spider",
+    "facebookexternalhit",
+    "headlesschrome",
+];
+
+/// Lowercase signatures of crawlers that send no product token of their own
+const BOT_SIGNATURES: &[&str] = &["yahoo! slurp", "chrome-lighthouse"];
+
+/// What kind of client sent the request (stored as `user_sessions.device_type`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceClass {
+    Desktop,
+    Mobile,
+    Tablet,
+    SmartTv,
+    Bot,
+    Cli,
+    /// Our own mobile app
+    App,
+    Other,
+}
+
+impl DeviceClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeviceClass::Desktop => "desktop",
+            DeviceClass::Mobile => "mobile",
+            DeviceClass::Tablet => "tablet",
+            DeviceClass::SmartTv => "smart_tv",
+            DeviceClass::Bot => "bot",
+            DeviceClass::Cli => "cli",
+            DeviceClass::App => "app",
+            DeviceClass::Other => "other",
+        }
+    }
+}
+
+/// Structured view of a User-Agent header
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UserAgent {
+    pub browser: Option<String>,
+    pub browser_version: Option<String>,
+    pub os: Option<String>,
+    pub os_version: Option<String>,
+    pub device_class: DeviceClass,
+}
+
+impl UserAgent {
+    pub fn parse(ua: &str) -> Self {
+        let ua = ua.trim();
+
+        if let Some(app) = Self::parse_app(ua) {
+            return app;
+        }
+
+        let product = ua.split(['/', ' ']).next().unwrap_or_default();
+        if let Some((_, name)) = CLI_CLIENTS
+            .iter()
+            .find(|(token, _)| token.eq_ignore_ascii_case(product))
+        {
+            return Self {
+                browser: Some(name.to_string()),
+                browser_version: version_after(ua, &format!("{product}/")),
+                os: None,
+                os_version: None,
+                device_class: DeviceClass::Cli,
+            };
+        }
+
+        let (os, os_version) = parse_os(ua);
+        let (browser, browser_version) = match parse_browser(ua) {
+            Some((name, version)) => (Some(name), version),
+            None => (None, None),
+        };
+
+        let lower = ua.to_lowercase();
+        let bot = bot_product(ua);
+        let device_class = if bot.is_some() || BOT_SIGNATURES.iter().any(|s| lower.contains(s)) {
+            DeviceClass::Bot
+        } else if [
+            "smart-tv", "smarttv", "appletv", "crkey", "googletv", "hbbtv",
+        ]
+        .iter()
+        .any(|m| lower.contains(m))
+        {
+            DeviceClass::SmartTv
+        } else if lower.contains("ipad")
+            || lower.contains("tablet")
+            || (os.as_deref() == Some("Android") && !lower.contains("mobile"))
+        {
+            DeviceClass::Tablet
+        } else if lower.contains("mobi") || lower.contains("iphone") || lower.contains("ipod") {
+            DeviceClass::Mobile
+        } else if matches!(
+            os.as_deref(),
+            Some("Windows" | "macOS" | "Linux" | "ChromeOS")
+        ) {
+            DeviceClass::Desktop
+        } else {
+            DeviceClass::Other
+        };
+
+        // Bots are named by their own product token, e.g. "Googlebot/2.1"
+        let (browser, browser_version) = match bot {
+            Some((name, version)) => (Some(name), version),
+            None => (browser, browser_version),
+        };
+
+        Self {
+            browser,
+            browser_version,
+            os,
+            os_version,
+            device_class,
+        }
+    }
+
+    /// Version-free label such as "Edge on Windows", stable across updates
+    pub fn device_name(&self) -> String {
+        let browser = self.browser.as_deref().unwrap_or("Unknown");
+        match &self.os {
+            Some(os) => format!("{browser} on {os}"),
+            None if self.device_class == DeviceClass::Cli
+                || self.device_class == DeviceClass::Bot =>
+            {
+                browser.to_string()
+            }
+            None => format!("{browser} on Unknown"),
+        }
+    }
+
+    fn parse_app(ua: &str) -> Option<Self> {
+        let rest = ua.strip_prefix(APP_PRODUCT)?.strip_prefix('/')?;
+        let (version, details) = match rest.split_once(' ') {
+            Some((version, details)) => (version, details.trim()),
+            None => (rest, ""),
+        };
+
+        // "(iOS 17.5; iPhone15,2)" → OS "iOS", version "17.5"
+        let platform = details
+            .strip_prefix('(')
+            .and_then(|d| d.split(';').next())
+            .map(str::trim)
+            .filter(|p| !p.is_empty());
+        let (os, os_version) = match platform.map(|p| p.rsplit_once(' ').unwrap_or((p, ""))) {
+            Some((os, version)) => (
+                Some(os.to_string()),
+                Some(version.trim_end_matches(')').to_string()).filter(|v| !v.is_empty()),
+            ),
+            None => (None, None),
+        };
+
+        Some(Self {
+            browser: Some("Quax App".to_string()),
+            browser_version: Some(version.to_string()).filter(|v| !v.is_empty()),
+            os,
+            os_version,
+            device_class: DeviceClass::App,
+        })
+    }
+}
+
+/// The version following `token` (e.g. "126.0.0.0" after "Chrome/")
+fn version_after(ua: &str, token: &str) -> Option<String> {
+    let start = ua.find(token)? + token.len();
+    let version: String = ua[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '_')
+        .collect();
+    (!version.is_empty()).then_some(version)
+}
+
+fn parse_browser(ua: &str) -> Option<(String, Option<String>)> {
+    if let Some((token, name)) = BROWSERS.iter().find(|(token, _)| ua.contains(token)) {
+        return Some((name.to_string(), version_after(ua, token)));
+    }
+    if ua.contains("Safari/") && ua.contains("Version/") {
+        return Some(("Safari".to_string(), version_after(ua, "Version/")));
+    }
+    if ua.contains("Trident/") || ua.contains("MSIE ") {
+        let version = version_after(ua, "MSIE ").or_else(|| version_after(ua, "rv:"));
+        return Some(("Internet Explorer".to_string(), version));
+    }
+    None
+}
+
+fn parse_os(ua: &str) -> (Option<String>, Option<String>) {
+    let os = |name: &str, version: Option<String>| (Some(name.to_string()), version);
+
+    if let Some(nt) = version_after(ua, "Windows NT ") {
+        let version = match nt.as_str() {
+            // Windows 11 still reports NT 10.0
+            "10.0" => "10",
+            "6.3" => "8.1",
+            "6.2" => "8",
+            "6.1" => "7",
+            other => other,
+        };
+        return os("Windows", Some(version.to_string()));
+    }
+    if ua.contains("Windows") {
+        return os("Windows", None);
+    }
+    // Before "Linux", which Android UAs contain too
+    if ua.contains("Android") {
+        return os("Android", version_after(ua, "Android "));
+    }
+    // iPhone: "iPhone OS 17_5", iPad: "CPU OS 17_5"
+    if ua.contains("iPhone") || ua.contains("iPad") || ua.contains("iPod") {
+        let version = version_after(ua, " OS ").map(|v| v.replace('_', "."));
+        return os("iOS", version);
+    }
+    if ua.contains("Mac OS X") {
+        let version = version_after(ua, "Mac OS X ").map(|v| v.replace('_', "."));
+        return os("macOS", version);
+    }
+    if ua.contains("CrOS") {
+        return os("ChromeOS", None);
+    }
+    if ua.contains("Linux") || ua.contains("X11") {
+        return os("Linux", None);
+    }
+    (None, None)
+}
+
+/// "Googlebot/2.1" → ("Googlebot", "2.1")
+fn bot_product(ua: &str) -> Option<(String, Option<String>)> {
+    ua.split(|c: char| c.is_whitespace() || c == ';' || c == '(' || c == ')')
+        .filter_map(|token| token.split_once('/').map(|(name, _)| (token, name)))
+        .find(|(_, name)| {
+            let lower = name.to_lowercase();
+            BOT_MARKERS.iter().any(|m| lower.contains(m))
+        })
+        .map(|(token, name)| (name.to_string(), version_after(token, &format!("{name}/"))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_ua(
+        ua: &str,
+        browser: (&str, &str),
+        os: (Option<&str>, Option<&str>),
+        class: DeviceClass,
+    ) {
+        let parsed = UserAgent::parse(ua);
+        assert_eq!(parsed.browser.as_deref(), Some(browser.0), "{ua}");
+        assert_eq!(parsed.browser_version.as_deref(), Some(browser.1), "{ua}");
+        assert_eq!(parsed.os.as_deref(), os.0, "{ua}");
+        assert_eq!(parsed.os_version.as_deref(), os.1, "{ua}");
+        assert_eq!(parsed.device_class, class, "{ua}");
+    }
+
+    #[test]
+    fn test_chromium_based_browsers() {
+        assert_ua(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+             Chrome/126.0.0.0 Safari/537.36 Edg/126.0.2592.87",
+            ("Edge", "126.0.2592.87"),
+            (Some("Windows"), Some("10")),
+            DeviceClass::Desktop,
+        );
+        assert_ua(
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) \
+             Chrome/126.0.0.0 Safari/537.36 OPR/112.0.0.0",
+            ("Opera", "112.0.0.0"),
+            (Some("macOS"), Some("10.15.7")),
+            DeviceClass::Desktop,
+        );
+        assert_ua(
+            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) \
+             Chrome/126.0.0.0 Safari/537.36",
+            ("Chrome", "126.0.0.0"),
+            (Some("Linux"), None),
+            DeviceClass::Desktop,
+        );
+    }
+
+    #[test]
+    fn test_mobile_and_tablet() {
+        assert_ua(
+            "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) \
+             Chrome/126.0.6478.71 Mobile Safari/537.36",
+            ("Chrome", "126.0.6478.71"),
+            (Some("Android"), Some("14")),
+            DeviceClass::Mobile,
+        );
+        assert_ua(
+            "Mozilla/5.0 (Linux; Android 13; SM-X710) AppleWebKit/537.36 (KHTML, like Gecko) \
+             SamsungBrowser/25.0 Chrome/121.0.0.0 Safari/537.36",
+            ("Samsung Internet", "25.0"),
+            (Some("Android"), Some("13")),
+            DeviceClass::Tablet,
+        );
+        assert_ua(
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 \
+             (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1",
+            ("Safari", "17.5"),
+            (Some("iOS"), Some("17.5")),
+            DeviceClass::Mobile,
+        );
+        assert_ua(
+            "Mozilla/5.0 (iPad; CPU OS 16_6 like Mac OS X) AppleWebKit/605.1.15 \
+             (KHTML, like Gecko) CriOS/126.0.6478.54 Mobile/15E148 Safari/604.1",
+            ("Chrome", "126.0.6478.54"),
+            (Some("iOS"), Some("16.6")),
+            DeviceClass::Tablet,
+        );
+    }
+
+    #[test]
+    fn test_bots_cli_and_app() {
+        assert_ua(
+            "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+            ("Googlebot", "2.1"),
+            (None, None),
+            DeviceClass::Bot,
+        );
+        assert_ua(
+            "Mozilla/5.0 (Linux; Android 11; CUBOT X50) AppleWebKit/537.36 (KHTML, like Gecko) \
+             Chrome/114.0.5735.196 Mobile Safari/537.36",
+            ("Chrome", "114.0.5735.196"),
+            (Some("Android"), Some("11")),
+            DeviceClass::Mobile,
+        );
+        assert_ua(
+            "curl/8.7.1",
+            ("curl", "8.7.1"),
+            (None, None),
+            DeviceClass::Cli,
+        );
+        assert_ua(
+            "QuaxApp/2.3.0 (iOS 17.5; iPhone15,2)",
+            ("Quax App", "2.3.0"),
+            (Some("iOS"), Some("17.5")),
+            DeviceClass::App,
+        );
+    }
+
+    #[test]
+    fn test_device_name_and_unknown() {
+        let firefox = UserAgent::parse(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:128.0) Gecko/20100101 Firefox/128.0",
+        );
+        assert_eq!(firefox.device_name(), "Firefox on Windows");
+        assert_eq!(UserAgent::parse("curl/8.7.1").device_name(), "curl");
+        assert_eq!(UserAgent::parse("curl").device_class, DeviceClass::Cli);
+
+        let unknown = UserAgent::parse("Unknown");
+        assert_eq!(unknown.browser, None);
+        assert_eq!(unknown.device_class, DeviceClass::Other);
+        assert_eq!(unknown.device_name(), "Unknown on Unknown");
+    }
+}
@@ -0,0 +1,5 @@
+pub mod service;
+pub mod store;
+
+pub use service::{LoginThrottle, ThrottleStatus};
+pub use store::{AttemptState, LoginAttemptStore, PgLoginAttemptStore, RedisLoginAttemptStore};
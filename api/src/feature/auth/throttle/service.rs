@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+
+use crate::infrastructure::config::{Config, LoginThrottleConfig};
+
+use super::store::LoginAttemptStore;
+
+/// Whether a login attempt for an account may proceed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleStatus {
+    Allowed,
+    /// Too many recent failures — wait before trying again
+    Delayed {
+        retry_after_secs: u64,
+    },
+    /// Temporarily locked out
+    Locked {
+        retry_after_secs: u64,
+    },
+}
+
+/// Per-account failed login counters with progressive delays and lockout.
+///
+/// Accounts are keyed by normalized email, so addresses without an account
+/// are throttled exactly like real ones. Store errors fail open: a broken
+/// Redis must not lock everyone out.
+#[derive(Clone)]
+pub struct LoginThrottle {
+    store: Arc<dyn LoginAttemptStore>,
+    config: Arc<Config>,
+}
+
+impl LoginThrottle {
+    pub fn new(store: Arc<dyn LoginAttemptStore>, config: Arc<Config>) -> Self {
+        Self { store, config }
+    }
+
+    fn account_key(email: &str) -> String {
+        email.trim().to_lowercase()
+    }
+
+    fn settings(&self) -> &LoginThrottleConfig {
+        &self.config.login_throttle
+    }
+
+    /// Decide whether a login attempt for `email` may be evaluated at all
+    pub async fn check(&self, email: &str) -> ThrottleStatus {
+        let state = match self.store.get(&Self::account_key(email)).await {
+            Ok(state) => state,
+            Err(e) => {
+                tracing::error!("Login throttle lookup failed: {e}");
+                return ThrottleStatus::Allowed;
+            }
+        };
+
+        let now = Utc::now();
+
+        if let Some(until) = state.locked_until
+            && until > now
+        {
+            return ThrottleStatus::Locked {
+                retry_after_secs: (until - now).num_seconds().max(1) as u64,
+            };
+        }
+
+        let Some(last) = state.last_failed_at else {
+            return ThrottleStatus::Allowed;
+        };
+
+        // Failures outside the window no longer count
+        if last + Duration::seconds(self.settings().window_secs as i64) < now {
+            return ThrottleStatus::Allowed;
+        }
+
+        match delay_for(state.failures, self.settings()) {
+            Some(delay) if last + Duration::seconds(delay as i64) > now => {
+                ThrottleStatus::Delayed {
+                    retry_after_secs: (last + Duration::seconds(delay as i64) - now)
+                        .num_seconds()
+                        .max(1) as u64,
+                }
+            }
+            _ => ThrottleStatus::Allowed,
+        }
+    }
+
+    /// Count a failed attempt. Returns `Locked` when this failure reached the
+    /// lockout threshold.
+    pub async fn record_failure(&self, email: &str) -> ThrottleStatus {
+        let key = Self::account_key(email);
+        let settings = self.settings();
+
+        let failures = match self.store.record_failure(&key, settings.window_secs).await {
+            Ok(failures) => failures,
+            Err(e) => {
+                tracing::error!("Failed to record login failure: {e}");
+                return ThrottleStatus::Allowed;
+            }
+        };
+
+        if settings.lockout_threshold == 0 || failures < settings.lockout_threshold {
+            return ThrottleStatus::Allowed;
+        }
+
+        let until = Utc::now() + Duration::seconds(settings.lockout_secs as i64);
+        if let Err(e) = self.store.lock(&key, until).await {
+            tracing::error!("Failed to lock account: {e}");
+            return ThrottleStatus::Allowed;
+        }
+
+        tracing::warn!(account = %key, "Account locked after {failures} failed logins");
+        ThrottleStatus::Locked {
+            retry_after_secs: settings.lockout_secs,
+        }
+    }
+
+    /// Clear counters after a successful login
+    pub async fn record_success(&self, email: &str) {
+        if let Err(e) = self.store.reset(&Self::account_key(email)).await {
+            tracing::error!("Failed to reset login failures: {e}");
+        }
+    }
+
+    /// Lift a lockout and clear counters (admin action)
+    pub async fn unlock(&self, email: &str) -> eyre::Result<()> {
+        self.store.reset(&Self::account_key(email)).await
+    }
+}
+
+/// Seconds an account must wait after `failures` consecutive failures
+fn delay_for(failures: u32, settings: &LoginThrottleConfig) -> Option<u64> {
+    if failures < settings.delay_after.max(1) {
+        return None;
+    }
+    let exponent = (failures - settings.delay_after.max(1)).min(32);
+    Some(
+        settings
+            .delay_base_secs
+            .saturating_mul(1u64 << exponent)
+            .min(settings.delay_max_secs),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> LoginThrottleConfig {
+        LoginThrottleConfig {
+            delay_after: 3,
+            delay_base_secs: 1,
+            delay_max_secs: 60,
+            lockout_threshold: 10,
+            lockout_secs: 900,
+            window_secs: 900,
+        }
+    }
+
+    #[test]
+    fn test_no_delay_below_threshold() {
+        assert_eq!(delay_for(0, &settings()), None);
+        assert_eq!(delay_for(2, &settings()), None);
+    }
+
+    #[test]
+    fn test_delay_doubles_and_caps() {
+        assert_eq!(delay_for(3, &settings()), Some(1));
+        assert_eq!(delay_for(4, &settings()), Some(2));
+        assert_eq!(delay_for(5, &settings()), Some(4));
+        assert_eq!(delay_for(9, &settings()), Some(60));
+        assert_eq!(delay_for(u32::MAX, &settings()), Some(60));
+    }
+
+    #[test]
+    fn test_account_key_is_normalized() {
+        assert_eq!(
+            LoginThrottle::account_key("  Alice@Example.COM "),
+            "alice@example.com"
+        );
+    }
+}
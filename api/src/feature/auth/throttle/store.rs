@@ -0,0 +1,222 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::infrastructure::persistence::{Database, RedisPool};
+
+/// Failed-login bookkeeping for one account
+#[derive(Debug, Clone, Default)]
+pub struct AttemptState {
+    pub failures: u32,
+    pub last_failed_at: Option<DateTime<Utc>>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+/// Storage for per-account failed login counters
+#[async_trait]
+pub trait LoginAttemptStore: Send + Sync {
+    /// Current counters for `key`
+    async fn get(&self, key: &str) -> eyre::Result<AttemptState>;
+
+    /// Count one more failure. Failures older than `window_secs` are
+    /// forgotten first. Returns the new count.
+    async fn record_failure(&self, key: &str, window_secs: u64) -> eyre::Result<u32>;
+
+    /// Lock `key` until `until` and clear its failure count
+    async fn lock(&self, key: &str, until: DateTime<Utc>) -> eyre::Result<()>;
+
+    /// Forget all failures and any lock for `key`
+    async fn reset(&self, key: &str) -> eyre::Result<()>;
+}
+
+/// Postgres-backed store (`login_attempts` table), used when Redis is not configured
+#[derive(Clone)]
+pub struct PgLoginAttemptStore {
+    db: Database,
+}
+
+impl PgLoginAttemptStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl LoginAttemptStore for PgLoginAttemptStore {
+    async fn get(&self, key: &str) -> eyre::Result<AttemptState> {
+        let row: Option<(i32, Option<DateTime<Utc>>, Option<DateTime<Utc>>)> = sqlx::query_as(
+            r#"
+            SELECT failed_count, last_failed_at, locked_until
+            FROM login_attempts
+            WHERE account_key = $1
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        Ok(row
+            .map(|(failures, last_failed_at, locked_until)| AttemptState {
+                failures: failures.max(0) as u32,
+                last_failed_at,
+                locked_until,
+            })
+            .unwrap_or_default())
+    }
+
+    async fn record_failure(&self, key: &str, window_secs: u64) -> eyre::Result<u32> {
+        let failures: i32 = sqlx::query_scalar(
+            r#"
+            INSERT INTO login_attempts (account_key, failed_count, last_failed_at)
+            VALUES ($1, 1, NOW())
+            ON CONFLICT (account_key) DO UPDATE SET
+                failed_count = CASE
+                    WHEN login_attempts.last_failed_at IS NULL
+                      OR login_attempts.last_failed_at < NOW() - make_interval(secs => $2)
+                    THEN 1
+                    ELSE login_attempts.failed_count + 1
+                END,
+                last_failed_at = NOW(),
+                updated_at = NOW()
+            RETURNING failed_count
+            "#,
+        )
+        .bind(key)
+        .bind(window_secs as f64)
+        .fetch_one(self.db.pool())
+        .await?;
+
+        Ok(failures.max(0) as u32)
+    }
+
+    async fn lock(&self, key: &str, until: DateTime<Utc>) -> eyre::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO login_attempts (account_key, locked_until)
+            VALUES ($1, $2)
+            ON CONFLICT (account_key) DO UPDATE SET
+                failed_count = 0,
+                locked_until = EXCLUDED.locked_until,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(key)
+        .bind(until)
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn reset(&self, key: &str) -> eyre::Result<()> {
+        sqlx::query("DELETE FROM login_attempts WHERE account_key = $1")
+            .bind(key)
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Redis-backed store. Counters expire on their own after the window.
+#[derive(Clone)]
+pub struct RedisLoginAttemptStore {
+    pool: RedisPool,
+}
+
+impl RedisLoginAttemptStore {
+    pub fn new(pool: RedisPool) -> Self {
+        Self { pool }
+    }
+
+    fn attempts_key(key: &str) -> String {
+        format!("login:attempts:{key}")
+    }
+
+    fn lock_key(key: &str) -> String {
+        format!("login:lock:{key}")
+    }
+}
+
+#[async_trait]
+impl LoginAttemptStore for RedisLoginAttemptStore {
+    async fn get(&self, key: &str) -> eyre::Result<AttemptState> {
+        let mut conn = self.pool.get().await?;
+
+        let (failures, last_failed_at): (Option<u32>, Option<i64>) = redis::cmd("HMGET")
+            .arg(Self::attempts_key(key))
+            .arg("failures")
+            .arg("last_failed_at")
+            .query_async(&mut *conn)
+            .await?;
+
+        let locked_until: Option<i64> = redis::cmd("GET")
+            .arg(Self::lock_key(key))
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(AttemptState {
+            failures: failures.unwrap_or(0),
+            last_failed_at: last_failed_at.and_then(|ts| DateTime::from_timestamp(ts, 0)),
+            locked_until: locked_until.and_then(|ts| DateTime::from_timestamp(ts, 0)),
+        })
+    }
+
+    async fn record_failure(&self, key: &str, window_secs: u64) -> eyre::Result<u32> {
+        let mut conn = self.pool.get().await?;
+        let attempts_key = Self::attempts_key(key);
+
+        let (failures,): (u32,) = redis::pipe()
+            .atomic()
+            .cmd("HINCRBY")
+            .arg(&attempts_key)
+            .arg("failures")
+            .arg(1)
+            .cmd("HSET")
+            .arg(&attempts_key)
+            .arg("last_failed_at")
+            .arg(Utc::now().timestamp())
+            .ignore()
+            .cmd("EXPIRE")
+            .arg(&attempts_key)
+            .arg(window_secs.max(1))
+            .ignore()
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(failures)
+    }
+
+    async fn lock(&self, key: &str, until: DateTime<Utc>) -> eyre::Result<()> {
+        let ttl = (until - Utc::now()).num_seconds();
+        if ttl <= 0 {
+            return Ok(());
+        }
+
+        let mut conn = self.pool.get().await?;
+        redis::pipe()
+            .atomic()
+            .cmd("DEL")
+            .arg(Self::attempts_key(key))
+            .ignore()
+            .cmd("SETEX")
+            .arg(Self::lock_key(key))
+            .arg(ttl as u64)
+            .arg(until.timestamp())
+            .ignore()
+            .query_async::<()>(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn reset(&self, key: &str) -> eyre::Result<()> {
+        let mut conn = self.pool.get().await?;
+        redis::cmd("DEL")
+            .arg(Self::attempts_key(key))
+            .arg(Self::lock_key(key))
+            .query_async::<()>(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+}
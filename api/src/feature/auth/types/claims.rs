@@ -1,29 +1,57 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
+/// Role name, as stored in `users.role` and the `roles` table. Custom roles
+/// are created by admins; what each role may do is resolved from
+/// `role_permissions` (see `RbacService`).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
 pub enum Role {
-    Admin,
+    Admin, // Every permission, implicitly
+    Moderator,
     User,
+    Custom(String),
 }
 
-impl fmt::Display for Role {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Role {
+    pub fn as_str(&self) -> &str {
         match self {
-            Role::Admin => write!(f, "admin"),
-            Role::User => write!(f, "user"),
+            Role::Admin => "admin",
+            Role::Moderator => "moderator",
+            Role::User => "user",
+            Role::Custom(name) => name,
         }
     }
 }
 
-impl TryFrom<&str> for Role {
-    type Error = ();
-    fn try_from(s: &str) -> Result<Self, Self::Error> {
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for Role {
+    fn from(s: &str) -> Self {
         match s {
-            "admin" => Ok(Role::Admin),
-            "user" => Ok(Role::User),
-            _ => Err(()),
+            "admin" => Role::Admin,
+            "moderator" => Role::Moderator,
+            "user" => Role::User,
+            _ => Role::Custom(s.to_string()),
+        }
+    }
+}
+
+impl From<String> for Role {
+    fn from(s: String) -> Self {
+        Role::from(s.as_str())
+    }
+}
+
+impl From<Role> for String {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Custom(name) => name,
+            role => role.as_str().to_string(),
         }
     }
 }
@@ -33,6 +61,7 @@ impl TryFrom<&str> for Role {
 pub enum TokenType {
     Access,
     Refresh,
+    MfaChallenge,
 }
 
 impl fmt::Display for TokenType {
@@ -40,10 +69,20 @@ impl fmt::Display for TokenType {
         match self {
             TokenType::Access => write!(f, "access"),
             TokenType::Refresh => write!(f, "refresh"),
+            TokenType::MfaChallenge => write!(f, "mfa_challenge"),
         }
     }
 }
 
+/// `act` claim (RFC 8693): the party acting on behalf of the subject
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Actor {
+    pub sub: String, // Admin user ID
+}
+
+/// `sub` prefix of tokens issued to API keys (client credentials grant)
+pub const SERVICE_SUBJECT_PREFIX: &str = "service:";
+
 /// JWT Claims with session tracking
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Claims {
@@ -55,6 +94,23 @@ pub struct Claims {
     pub token_type: TokenType, // access or refresh
     pub sid: String,           // Session ID (shared across tokens in same session)
     pub s_iat: i64,            // Session issued at (for absolute timeout)
+    #[serde(default)]
+    pub auth_time: i64, // Last sign-in or step-up re-authentication
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Actor>, // Set when an admin impersonates the subject
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>, // OIDC client or API key the token was issued to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>, // Space-separated scopes granted to that client
+}
+
+impl Claims {
+    /// API key behind a client-credentials token (`sub` = "service:<key id>")
+    pub fn service_key_id(&self) -> Option<uuid::Uuid> {
+        self.sub
+            .strip_prefix(SERVICE_SUBJECT_PREFIX)
+            .and_then(|id| uuid::Uuid::parse_str(id).ok())
+    }
 }
 
 /// Authenticated user extracted from JWT
@@ -63,5 +119,35 @@ pub struct AuthUser {
     pub user_id: uuid::Uuid,
     pub email: String,
     pub roles: Vec<Role>,
-    pub session_id: String, // Added for session management
+    pub session_id: String,               // Added for session management
+    pub session_iat: i64,                 // When the user signed in (survives token refresh)
+    pub auth_time: i64, // Last sign-in or re-authentication (survives token refresh)
+    pub impersonator: Option<uuid::Uuid>, // Admin acting as this user (`act` claim)
+}
+
+impl AuthUser {
+    /// Whether the user proved their identity within the last `max_age_secs`,
+    /// for sensitive changes that need a recent re-authentication
+    pub fn authenticated_within(&self, max_age_secs: i64) -> bool {
+        chrono::Utc::now().timestamp() - self.auth_time <= max_age_secs
+    }
+
+    /// Whether an admin is acting as this user
+    pub fn is_impersonated(&self) -> bool {
+        self.impersonator.is_some()
+    }
+}
+
+/// Machine client authenticated with an API key, either directly
+/// (`api_key_middleware`) or through a client-credentials access token
+#[derive(Debug, Clone)]
+pub struct ServicePrincipal {
+    pub key_id: uuid::Uuid,
+    pub scopes: Vec<String>,
+}
+
+impl ServicePrincipal {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
 }
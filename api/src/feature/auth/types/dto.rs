@@ -24,7 +24,8 @@ pub struct RegisterRequest {
     #[validate(length(min = 3, message = "Name must be at least 3 characters"))]
     pub name: Option<String>,
 
-    #[validate(length(min = 6, message = "Password must be at least 6 characters"))]
+    /// Checked against the password policy on registration
+    #[validate(length(min = 1, message = "Password is required"))]
     pub password: String,
 }
 
@@ -43,6 +44,7 @@ pub struct LoginRequest {
 pub struct UserResponse {
     pub id: uuid::Uuid,
     pub email: String,
+    pub email_verified: bool,
     pub username: Option<String>,
     pub name: String,
     pub avatar_url: Option<String>,
@@ -63,27 +65,105 @@ pub struct AuthResponse {
     pub token: TokenResponse,
 }
 
+/// Login response — tokens, or an MFA challenge to complete via `/auth/mfa/verify`
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Authenticated(AuthResponse),
+    MfaRequired(crate::feature::auth::mfa::MfaChallengeResponse),
+}
+
+/// Register response — tokens, or the new account when the email address must
+/// be verified before the first login
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum RegisterResponse {
+    Authenticated(AuthResponse),
+    VerificationRequired {
+        user: UserResponse,
+        verification_required: bool,
+    },
+}
+
 /// Request body for change password
 #[derive(Debug, Deserialize, Validate)]
 pub struct ChangePasswordRequest {
     #[validate(length(min = 1, message = "Current password is required"))]
     pub current_password: String,
 
-    #[validate(length(min = 8, message = "New password must be at least 8 characters"))]
+    /// Checked against the password policy by the handler
+    #[validate(length(min = 1, message = "New password is required"))]
+    pub new_password: String,
+
+    /// Sign out every other device
+    #[serde(default)]
+    pub revoke_other_sessions: bool,
+}
+
+/// Request body for set password (accounts without one, e.g. OAuth sign-ups)
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetPasswordRequest {
+    /// Checked against the password policy by the handler
+    #[validate(length(min = 1, message = "Password is required"))]
     pub new_password: String,
+
+    /// Sign out every other device
+    #[serde(default)]
+    pub revoke_other_sessions: bool,
+}
+
+/// Request body for step-up re-authentication: the password or an MFA code
+#[derive(Debug, Deserialize)]
+pub struct ReauthenticateRequest {
+    pub password: Option<String>,
+    /// TOTP or recovery code
+    pub code: Option<String>,
+}
+
+/// Request body for POST /auth/sessions/revoke (link from a new sign-in alert)
+#[derive(Debug, Deserialize, Validate)]
+pub struct RevokeSessionLinkRequest {
+    #[validate(length(min = 1, max = 128, message = "Token is required"))]
+    pub token: String,
 }
 
-/// Hash password using Argon2
-pub fn hash_password(
-    password: &str,
-) -> Result<String, crate::feature::auth::repository::AuthError> {
-    use argon2::{
-        Argon2,
-        password_hash::{PasswordHasher, SaltString, rand_core::OsRng},
-    };
-    let salt = SaltString::generate(&mut OsRng);
-    Argon2::default()
-        .hash_password(password.as_bytes(), &salt)
-        .map(|h| h.to_string())
-        .map_err(|_| crate::feature::auth::repository::AuthError::HashError)
+/// Form body of POST /oauth/introspect (RFC 7662) and /oauth/revoke (RFC 7009)
+#[derive(Debug, Deserialize)]
+pub struct TokenRequest {
+    pub token: String,
+    /// `access_token` or `refresh_token`; only decides which type is tried first
+    pub token_type_hint: Option<String>,
+}
+
+/// RFC 7662 introspection response. Inactive tokens only carry `active: false`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TokenIntrospection {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<super::Role>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    /// Admin acting as the subject (impersonation)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub act: Option<super::Actor>,
+    /// OIDC client the token was issued to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+/// Response to change/set password
+#[derive(Debug, Clone, Serialize)]
+pub struct PasswordUpdateResponse {
+    /// Number of other sessions that were signed out
+    pub revoked_sessions: u64,
 }
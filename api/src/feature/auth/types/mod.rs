@@ -1,8 +1,10 @@
 pub mod claims;
 pub mod dto;
 
-pub use claims::{AuthUser, Claims, Role, TokenType};
+pub use claims::{Actor, AuthUser, Claims, Role, ServicePrincipal, TokenType};
 pub use dto::{
-    AuthResponse, ChangePasswordRequest, LoginCredentials, LoginRequest, RegisterRequest,
-    TokenResponse, UserResponse, hash_password,
+    AuthResponse, ChangePasswordRequest, LoginCredentials, LoginRequest, LoginResponse,
+    PasswordUpdateResponse, ReauthenticateRequest, RegisterRequest, RegisterResponse,
+    RevokeSessionLinkRequest, SetPasswordRequest, TokenIntrospection, TokenRequest, TokenResponse,
+    UserResponse,
 };
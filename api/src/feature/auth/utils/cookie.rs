@@ -21,11 +21,7 @@ pub const REFRESH_COOKIE_PATH: &str = "/api/v1/auth";
 ///   - None: cross-domain with HTTPS (requires Secure=true)
 /// - path: limited to auth endpoints only
 pub fn create_refresh_cookie(token: &str, config: &Config) -> Cookie<'static> {
-    let expiry_secs = std::env::var("JWT_REFRESH_EXPIRY_SECS")
-        .ok()
-        .and_then(|v| v.parse::<i64>().ok())
-        .unwrap_or(604_800); // default 7 days
-
+    let expiry_secs = config.jwt.refresh_expiry_secs;
     let cookie_config = &config.cookie;
 
     Cookie::build((REFRESH_TOKEN_COOKIE, token.to_string()))
@@ -1,36 +1,10 @@
-use std::env;
-
 use chrono::{Duration, Utc};
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use jsonwebtoken::{Header, Validation, decode, decode_header, encode};
 use uuid::Uuid;
 
-use crate::feature::auth::types::claims::{Claims, Role, TokenType};
-
-fn access_secret() -> Vec<u8> {
-    env::var("JWT_ACCESS_SECRET")
-        .expect("JWT_ACCESS_SECRET must be set")
-        .into_bytes()
-}
-
-fn refresh_secret() -> Vec<u8> {
-    env::var("JWT_REFRESH_SECRET")
-        .expect("JWT_REFRESH_SECRET must be set")
-        .into_bytes()
-}
+use crate::feature::auth::types::claims::{Actor, Claims, Role, SERVICE_SUBJECT_PREFIX, TokenType};
 
-fn access_expiry_secs() -> i64 {
-    env::var("JWT_ACCESS_EXPIRY_SECS")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(3600) // default 1 hour
-}
-
-fn refresh_expiry_secs() -> i64 {
-    env::var("JWT_REFRESH_EXPIRY_SECS")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(604800) // default 7 days
-}
+use super::keys::JwtKeys;
 
 /// JWT Error types
 #[derive(Debug, thiserror::Error)]
@@ -43,8 +17,6 @@ pub enum JwtError {
     WrongType,
     #[error("Token creation failed")]
     CreationFailed,
-    #[error("Session expired - absolute timeout reached")]
-    SessionExpired,
 }
 
 impl From<jsonwebtoken::errors::Error> for JwtError {
@@ -64,25 +36,37 @@ pub struct TokenPair {
     pub expires_in: i64, // Access token expiry in seconds
     pub session_id: String,
     pub session_iat: i64,
+    pub auth_time: i64,
+    pub access_jti: String,
+    pub access_exp: i64,
+    pub refresh_jti: String,
 }
 
-/// Create token pair with existing session (for refresh)
+/// Create token pair with existing session (for refresh and re-authentication)
 pub fn create_token_pair_with_session(
+    keys: &JwtKeys,
     user_id: Uuid,
     _email: &str,
     roles: &[Role],
     session_id: &str,
     session_iat: i64,
+    auth_time: i64,
 ) -> Result<TokenPair, JwtError> {
-    let access_token = create_access_token_with_session(user_id, roles, session_id, session_iat)?;
-    let refresh_token = create_refresh_token_with_session(user_id, session_id, session_iat)?;
+    let (access_token, access_claims) =
+        create_access_token_with_session(keys, user_id, roles, session_id, session_iat, auth_time)?;
+    let (refresh_token, refresh_claims) =
+        create_refresh_token_with_session(keys, user_id, session_id, session_iat, auth_time)?;
 
     Ok(TokenPair {
         access_token,
         refresh_token,
-        expires_in: access_expiry_secs(),
+        expires_in: keys.access_expiry_secs,
         session_id: session_id.to_string(),
         session_iat,
+        auth_time,
+        access_jti: access_claims.jti,
+        access_exp: access_claims.exp,
+        refresh_jti: refresh_claims.jti,
     })
 }
 
@@ -93,14 +77,19 @@ pub fn create_token_pair_with_session(
 /// * `roles` - User roles
 /// * `session_id` - Session ID (shared with refresh token)
 /// * `session_iat` - Session issued at (for absolute timeout)
+/// * `auth_time` - Last sign-in or re-authentication (for step-up checks)
+///
+/// Returns the encoded token together with its claims.
 fn create_access_token_with_session(
+    keys: &JwtKeys,
     user_id: Uuid,
     roles: &[Role],
     session_id: &str,
     session_iat: i64,
-) -> Result<String, JwtError> {
+    auth_time: i64,
+) -> Result<(String, Claims), JwtError> {
     let now = Utc::now();
-    let expiry = access_expiry_secs();
+    let expiry = keys.access_expiry_secs;
     let exp = now + Duration::seconds(expiry);
 
     let claims = Claims {
@@ -112,14 +101,16 @@ fn create_access_token_with_session(
         token_type: TokenType::Access,
         sid: session_id.to_string(),
         s_iat: session_iat,
+        auth_time,
+        act: None,
+        client_id: None,
+        scope: None,
     };
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(&access_secret()),
-    )
-    .map_err(|_| JwtError::CreationFailed)
+    let (header, key) = keys.access_signer();
+    let token = encode(&header, &claims, key).map_err(|_| JwtError::CreationFailed)?;
+
+    Ok((token, claims))
 }
 
 /// Create refresh token (long-lived)
@@ -128,13 +119,18 @@ fn create_access_token_with_session(
 /// * `user_id` - User UUID
 /// * `session_id` - Session ID (shared with access token)
 /// * `session_iat` - Session issued at (for absolute timeout)
+/// * `auth_time` - Last sign-in or re-authentication, carried over on refresh
+///
+/// Returns the encoded token together with its claims.
 fn create_refresh_token_with_session(
+    keys: &JwtKeys,
     user_id: Uuid,
     session_id: &str,
     session_iat: i64,
-) -> Result<String, JwtError> {
+    auth_time: i64,
+) -> Result<(String, Claims), JwtError> {
     let now = Utc::now();
-    let expiry = refresh_expiry_secs();
+    let expiry = keys.refresh_expiry_secs;
     let exp = now + Duration::seconds(expiry);
 
     let claims = Claims {
@@ -146,46 +142,152 @@ fn create_refresh_token_with_session(
         token_type: TokenType::Refresh,
         sid: session_id.to_string(),
         s_iat: session_iat,
+        auth_time,
+        act: None,
+        client_id: None,
+        scope: None,
     };
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(&refresh_secret()),
-    )
-    .map_err(|_| JwtError::CreationFailed)
+    let token = encode(&Header::default(), &claims, keys.refresh_keys().0)
+        .map_err(|_| JwtError::CreationFailed)?;
+
+    Ok((token, claims))
 }
 
 /// Create both tokens with session tracking
 pub fn create_token_pair(
+    keys: &JwtKeys,
     user_id: Uuid,
-    _email: &str,
+    email: &str,
     roles: &[Role],
 ) -> Result<TokenPair, JwtError> {
     let session_id = Uuid::new_v4().to_string();
     let session_iat = Utc::now().timestamp();
 
-    let access_token = create_access_token_with_session(user_id, roles, &session_id, session_iat)?;
-    let refresh_token = create_refresh_token_with_session(user_id, &session_id, session_iat)?;
-
-    Ok(TokenPair {
-        access_token,
-        refresh_token,
-        expires_in: access_expiry_secs(),
-        session_id,
+    create_token_pair_with_session(
+        keys,
+        user_id,
+        email,
+        roles,
+        &session_id,
         session_iat,
-    })
+        session_iat,
+    )
+}
+
+/// Create an access token that lets `admin_id` act as `user_id`, carrying the
+/// admin in the `act` claim. There is no matching refresh token, and its
+/// `auth_time` of 0 never satisfies a re-authentication check.
+pub fn create_impersonation_token(
+    keys: &JwtKeys,
+    user_id: Uuid,
+    roles: &[Role],
+    admin_id: Uuid,
+    session_id: &str,
+    ttl_secs: i64,
+) -> Result<(String, Claims), JwtError> {
+    let now = Utc::now();
+
+    let claims = Claims {
+        sub: user_id.to_string(),
+        jti: Uuid::new_v4().to_string(),
+        exp: (now + Duration::seconds(ttl_secs)).timestamp(),
+        iat: now.timestamp(),
+        roles: roles.to_vec(),
+        token_type: TokenType::Access,
+        sid: session_id.to_string(),
+        s_iat: now.timestamp(),
+        auth_time: 0,
+        act: Some(Actor {
+            sub: admin_id.to_string(),
+        }),
+        client_id: None,
+        scope: None,
+    };
+
+    let (header, key) = keys.access_signer();
+    let token = encode(&header, &claims, key).map_err(|_| JwtError::CreationFailed)?;
+
+    Ok((token, claims))
+}
+
+/// Create an access token for an OIDC client, tied to the user's quax
+/// session. The `client_id` claim keeps it out of the quax API itself; it
+/// is only good for `/oauth/userinfo` and the client's own resource servers.
+#[allow(clippy::too_many_arguments)]
+pub fn create_client_access_token(
+    keys: &JwtKeys,
+    user_id: Uuid,
+    roles: &[Role],
+    client_id: &str,
+    scope: &str,
+    session_id: &str,
+    session_iat: i64,
+    auth_time: i64,
+) -> Result<(String, Claims), JwtError> {
+    let now = Utc::now();
+
+    let claims = Claims {
+        sub: user_id.to_string(),
+        jti: Uuid::new_v4().to_string(),
+        exp: (now + Duration::seconds(keys.access_expiry_secs)).timestamp(),
+        iat: now.timestamp(),
+        roles: roles.to_vec(),
+        token_type: TokenType::Access,
+        sid: session_id.to_string(),
+        s_iat: session_iat,
+        auth_time,
+        act: None,
+        client_id: Some(client_id.to_string()),
+        scope: Some(scope.to_string()),
+    };
+
+    let (header, key) = keys.access_signer();
+    let token = encode(&header, &claims, key).map_err(|_| JwtError::CreationFailed)?;
+
+    Ok((token, claims))
+}
+
+/// Create an access token for an API key (client credentials grant). The
+/// subject is a service principal, not a user, and there is no session:
+/// `sid` is empty and the token simply runs out after `ttl_secs`.
+pub fn create_service_access_token(
+    keys: &JwtKeys,
+    key_id: Uuid,
+    scope: &str,
+    ttl_secs: i64,
+) -> Result<(String, Claims), JwtError> {
+    let now = Utc::now();
+
+    let claims = Claims {
+        sub: format!("{SERVICE_SUBJECT_PREFIX}{key_id}"),
+        jti: Uuid::new_v4().to_string(),
+        exp: (now + Duration::seconds(ttl_secs)).timestamp(),
+        iat: now.timestamp(),
+        roles: Vec::new(),
+        token_type: TokenType::Access,
+        sid: String::new(),
+        s_iat: now.timestamp(),
+        auth_time: now.timestamp(),
+        act: None,
+        client_id: Some(key_id.to_string()),
+        scope: Some(scope.to_string()),
+    };
+
+    let (header, key) = keys.access_signer();
+    let token = encode(&header, &claims, key).map_err(|_| JwtError::CreationFailed)?;
+
+    Ok((token, claims))
 }
 
-/// Validate access token
-pub fn validate_access_token(token: &str) -> Result<Claims, JwtError> {
-    let validation = Validation::default();
+/// Validate access token, picking the verification key by its `kid` header
+pub fn validate_access_token(keys: &JwtKeys, token: &str) -> Result<Claims, JwtError> {
+    let header = decode_header(token)?;
+    let (algorithm, key) = keys
+        .access_verifier(header.kid.as_deref())
+        .ok_or(JwtError::Invalid)?;
 
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(&access_secret()),
-        &validation,
-    )?;
+    let token_data = decode::<Claims>(token, key, &Validation::new(algorithm))?;
 
     if token_data.claims.token_type != TokenType::Access {
         return Err(JwtError::WrongType);
@@ -194,26 +296,53 @@ pub fn validate_access_token(token: &str) -> Result<Claims, JwtError> {
     Ok(token_data.claims)
 }
 
-/// Validate refresh token with absolute session timeout check
-pub fn validate_refresh_token(token: &str) -> Result<Claims, JwtError> {
-    let validation = Validation::default();
-
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(&refresh_secret()),
-        &validation,
-    )?;
+/// Validate refresh token. Session lifetime limits are enforced by
+/// `AuthService::refresh_token` against the session policy.
+pub fn validate_refresh_token(keys: &JwtKeys, token: &str) -> Result<Claims, JwtError> {
+    let token_data = decode::<Claims>(token, keys.refresh_keys().1, &Validation::default())?;
 
     if token_data.claims.token_type != TokenType::Refresh {
         return Err(JwtError::WrongType);
     }
 
-    // Check absolute session timeout (7 days from session start)
-    let max_session_duration = refresh_expiry_secs();
-    let now = Utc::now().timestamp();
+    Ok(token_data.claims)
+}
+
+/// Create a short-lived MFA challenge token, issued by login in place of a
+/// token pair. It only proves the password step succeeded and cannot be used
+/// as an access token.
+pub fn create_mfa_challenge_token(
+    keys: &JwtKeys,
+    user_id: Uuid,
+    ttl_secs: i64,
+) -> Result<String, JwtError> {
+    let now = Utc::now();
+
+    let claims = Claims {
+        sub: user_id.to_string(),
+        jti: Uuid::new_v4().to_string(),
+        exp: (now + Duration::seconds(ttl_secs)).timestamp(),
+        iat: now.timestamp(),
+        roles: vec![],
+        token_type: TokenType::MfaChallenge,
+        sid: String::new(), // No session until the second factor is verified
+        s_iat: now.timestamp(),
+        auth_time: 0,
+        act: None,
+        client_id: None,
+        scope: None,
+    };
+
+    encode(&Header::default(), &claims, keys.challenge_keys().0)
+        .map_err(|_| JwtError::CreationFailed)
+}
 
-    if now > token_data.claims.s_iat + max_session_duration {
-        return Err(JwtError::SessionExpired);
+/// Validate MFA challenge token
+pub fn validate_mfa_challenge_token(keys: &JwtKeys, token: &str) -> Result<Claims, JwtError> {
+    let token_data = decode::<Claims>(token, keys.challenge_keys().1, &Validation::default())?;
+
+    if token_data.claims.token_type != TokenType::MfaChallenge {
+        return Err(JwtError::WrongType);
     }
 
     Ok(token_data.claims)
@@ -0,0 +1,300 @@
+use std::{collections::HashMap, fs};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use eyre::{Result, WrapErr, bail, eyre};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+};
+use ring::signature::{Ed25519KeyPair, KeyPair, RsaKeyPair, RsaPublicKeyComponents};
+
+use crate::infrastructure::config::JwtConfig;
+
+/// Key that signs new access tokens
+struct SigningKey {
+    kid: String,
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+}
+
+/// Public half of a keyring entry
+struct VerificationKey {
+    algorithm: Algorithm,
+    decoding: DecodingKey,
+}
+
+/// JWT keys, loaded once at startup.
+///
+/// With `JWT_KEYS_DIR` set, access tokens are signed (RS256 or EdDSA) by the
+/// active key and carry its `kid`; every key in the directory still verifies,
+/// so keys can be rotated without invalidating live tokens. Without it, access
+/// tokens fall back to HS256 with `JWT_ACCESS_SECRET`.
+///
+/// Refresh and MFA challenge tokens are only ever read by this service and
+/// stay HS256 with their own secrets.
+pub struct JwtKeys {
+    signing: Option<SigningKey>,
+    verification: HashMap<String, VerificationKey>,
+    jwks: JwkSet,
+    access_encoding: EncodingKey,
+    access_decoding: DecodingKey,
+    refresh_encoding: EncodingKey,
+    refresh_decoding: DecodingKey,
+    pub(crate) access_expiry_secs: i64,
+    pub(crate) refresh_expiry_secs: i64,
+}
+
+impl JwtKeys {
+    pub fn from_config(config: &JwtConfig) -> Result<Self> {
+        let mut keys = Self {
+            signing: None,
+            verification: HashMap::new(),
+            jwks: JwkSet { keys: Vec::new() },
+            access_encoding: EncodingKey::from_secret(config.access_secret.as_bytes()),
+            access_decoding: DecodingKey::from_secret(config.access_secret.as_bytes()),
+            refresh_encoding: EncodingKey::from_secret(config.refresh_secret.as_bytes()),
+            refresh_decoding: DecodingKey::from_secret(config.refresh_secret.as_bytes()),
+            access_expiry_secs: config.access_expiry_secs,
+            refresh_expiry_secs: config.refresh_expiry_secs,
+        };
+
+        let Some(dir) = config.keys_dir.as_deref() else {
+            return Ok(keys);
+        };
+
+        let active_kid = config
+            .active_kid
+            .as_deref()
+            .ok_or_else(|| eyre!("JWT_ACTIVE_KID must be set when JWT_KEYS_DIR is used"))?;
+
+        let mut entries: Vec<_> = fs::read_dir(dir)
+            .wrap_err_with(|| format!("Failed to read JWT_KEYS_DIR '{dir}'"))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "pem"))
+            .collect();
+        entries.sort();
+
+        for path in entries {
+            let kid = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .ok_or_else(|| eyre!("Invalid key file name: {}", path.display()))?
+                .to_string();
+            let pem = fs::read(&path)
+                .wrap_err_with(|| format!("Failed to read key file {}", path.display()))?;
+            keys.add_key(&kid, &pem, kid == active_kid)
+                .wrap_err_with(|| format!("Invalid JWT key {}", path.display()))?;
+        }
+
+        if keys.signing.is_none() {
+            bail!("JWT_ACTIVE_KID '{active_kid}' not found in {dir}");
+        }
+
+        Ok(keys)
+    }
+
+    /// Load a private key in PEM form and register it under `kid`
+    fn add_key(&mut self, kid: &str, pem_bytes: &[u8], active: bool) -> Result<()> {
+        let pem = pem::parse(pem_bytes)?;
+
+        let (algorithm, params, encoding) = match pem.tag() {
+            "PRIVATE KEY" => {
+                if let Ok(pair) = Ed25519KeyPair::from_pkcs8_maybe_unchecked(pem.contents()) {
+                    (
+                        Algorithm::EdDSA,
+                        ed25519_params(pair.public_key().as_ref()),
+                        EncodingKey::from_ed_pem(pem_bytes)?,
+                    )
+                } else {
+                    let pair = RsaKeyPair::from_pkcs8(pem.contents())
+                        .map_err(|e| eyre!("Unsupported PKCS#8 key: {e}"))?;
+                    (
+                        Algorithm::RS256,
+                        rsa_params(&pair),
+                        EncodingKey::from_rsa_pem(pem_bytes)?,
+                    )
+                }
+            }
+            "RSA PRIVATE KEY" => {
+                let pair = RsaKeyPair::from_der(pem.contents())
+                    .map_err(|e| eyre!("Invalid RSA key: {e}"))?;
+                (
+                    Algorithm::RS256,
+                    rsa_params(&pair),
+                    EncodingKey::from_rsa_pem(pem_bytes)?,
+                )
+            }
+            tag => bail!("Unsupported PEM block '{tag}', expected a private key"),
+        };
+
+        let jwk = Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(match algorithm {
+                    Algorithm::EdDSA => KeyAlgorithm::EdDSA,
+                    _ => KeyAlgorithm::RS256,
+                }),
+                key_id: Some(kid.to_string()),
+                ..Default::default()
+            },
+            algorithm: params,
+        };
+
+        self.verification.insert(
+            kid.to_string(),
+            VerificationKey {
+                algorithm,
+                decoding: DecodingKey::from_jwk(&jwk)?,
+            },
+        );
+        self.jwks.keys.push(jwk);
+
+        if active {
+            self.signing = Some(SigningKey {
+                kid: kid.to_string(),
+                algorithm,
+                encoding,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Public keys for `/.well-known/jwks.json` (empty in HS256 mode)
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+
+    /// Whether tokens are signed with a key from `JWT_KEYS_DIR`, so others can
+    /// verify them through the JWKS
+    pub(crate) fn has_public_keys(&self) -> bool {
+        self.signing.is_some()
+    }
+
+    /// Header and key for a new access token
+    pub(crate) fn access_signer(&self) -> (jsonwebtoken::Header, &EncodingKey) {
+        match &self.signing {
+            Some(key) => {
+                let mut header = jsonwebtoken::Header::new(key.algorithm);
+                header.kid = Some(key.kid.clone());
+                (header, &key.encoding)
+            }
+            None => (jsonwebtoken::Header::default(), &self.access_encoding),
+        }
+    }
+
+    /// Algorithm and key that verify an access token with the given `kid`
+    pub(crate) fn access_verifier(&self, kid: Option<&str>) -> Option<(Algorithm, &DecodingKey)> {
+        if self.signing.is_none() {
+            // HS256 mode: tokens carry no kid
+            return kid
+                .is_none()
+                .then_some((Algorithm::HS256, &self.access_decoding));
+        }
+
+        let key = self.verification.get(kid?)?;
+        Some((key.algorithm, &key.decoding))
+    }
+
+    /// HS256 key pair for MFA challenge tokens
+    pub(crate) fn challenge_keys(&self) -> (&EncodingKey, &DecodingKey) {
+        (&self.access_encoding, &self.access_decoding)
+    }
+
+    /// HS256 key pair for refresh tokens
+    pub(crate) fn refresh_keys(&self) -> (&EncodingKey, &DecodingKey) {
+        (&self.refresh_encoding, &self.refresh_decoding)
+    }
+}
+
+fn ed25519_params(public_key: &[u8]) -> AlgorithmParameters {
+    AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+        key_type: OctetKeyPairType::OctetKeyPair,
+        curve: EllipticCurve::Ed25519,
+        x: URL_SAFE_NO_PAD.encode(public_key),
+    })
+}
+
+fn rsa_params(pair: &RsaKeyPair) -> AlgorithmParameters {
+    let components: RsaPublicKeyComponents<Vec<u8>> = pair.public().into();
+    AlgorithmParameters::RSA(RSAKeyParameters {
+        key_type: RSAKeyType::RSA,
+        n: URL_SAFE_NO_PAD.encode(components.n),
+        e: URL_SAFE_NO_PAD.encode(components.e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use std::path::Path;
+
+    fn ed25519_pem() -> String {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        pem::encode(&pem::Pem::new("PRIVATE KEY", pkcs8.as_ref().to_vec()))
+    }
+
+    fn config(keys_dir: Option<&Path>, active_kid: Option<&str>) -> JwtConfig {
+        JwtConfig {
+            access_secret: "access-secret".into(),
+            refresh_secret: "refresh-secret".into(),
+            access_expiry_secs: 3600,
+            refresh_expiry_secs: 604800,
+            keys_dir: keys_dir.map(|p| p.to_string_lossy().into_owned()),
+            active_kid: active_kid.map(Into::into),
+        }
+    }
+
+    fn key_dir(kids: &[&str]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("quax-jwt-keys-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        for kid in kids {
+            fs::write(dir.join(format!("{kid}.pem")), ed25519_pem()).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_hs256_fallback_has_no_jwks() {
+        let keys = JwtKeys::from_config(&config(None, None)).unwrap();
+        assert!(keys.jwks().keys.is_empty());
+        assert!(!keys.has_public_keys());
+        assert!(keys.access_signer().0.kid.is_none());
+        assert!(keys.access_verifier(None).is_some());
+        assert!(keys.access_verifier(Some("other")).is_none());
+    }
+
+    #[test]
+    fn test_keyring_publishes_every_key() {
+        let dir = key_dir(&["2025-01", "2025-02"]);
+        let keys = JwtKeys::from_config(&config(Some(&dir), Some("2025-02"))).unwrap();
+
+        let (header, _) = keys.access_signer();
+        assert_eq!(header.alg, Algorithm::EdDSA);
+        assert_eq!(header.kid.as_deref(), Some("2025-02"));
+
+        let kids: Vec<_> = keys
+            .jwks()
+            .keys
+            .iter()
+            .filter_map(|k| k.common.key_id.as_deref())
+            .collect();
+        assert_eq!(kids, ["2025-01", "2025-02"]);
+        assert!(keys.access_verifier(Some("2025-01")).is_some());
+        assert!(keys.access_verifier(None).is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_active_kid_is_rejected() {
+        let dir = key_dir(&["2025-01"]);
+        assert!(JwtKeys::from_config(&config(Some(&dir), Some("nope"))).is_err());
+        assert!(JwtKeys::from_config(&config(Some(&dir), None)).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,8 +1,15 @@
 pub mod cookie;
 pub mod jwt;
+pub mod keys;
+pub mod password;
+pub mod token;
 
 pub use cookie::{REFRESH_TOKEN_COOKIE, create_cleared_cookie, create_refresh_cookie};
 pub use jwt::{
-    JwtError, TokenPair, create_token_pair, extract_user_id, validate_access_token,
-    validate_refresh_token,
+    JwtError, TokenPair, create_client_access_token, create_impersonation_token,
+    create_mfa_challenge_token, create_service_access_token, create_token_pair, extract_user_id,
+    validate_access_token, validate_mfa_challenge_token, validate_refresh_token,
 };
+pub use keys::JwtKeys;
+pub use password::PasswordHasher;
+pub use token::{random_token, sha256_hex};
@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use argon2::{
+    Algorithm, Argon2, KeyId, Params, ParamsBuilder, Version,
+    password_hash::{
+        self, PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString, rand_core::OsRng,
+    },
+};
+use sha2::{Digest, Sha256};
+
+use crate::infrastructure::config::PasswordHashConfig;
+
+/// Bytes of the pepper fingerprint stored as the `keyid` of peppered hashes
+const PEPPER_ID_LEN: usize = 4;
+
+struct Pepper {
+    secret: Vec<u8>,
+    id: KeyId,
+}
+
+/// Argon2id hashing for stored passwords, loaded once at startup.
+///
+/// Peppered hashes carry a short fingerprint of the pepper as their `keyid`,
+/// so hashes made before a pepper was configured still verify (without it)
+/// and are reported by [`needs_rehash`](Self::needs_rehash), like hashes made
+/// with older cost parameters.
+#[derive(Clone)]
+pub struct PasswordHasher {
+    params: Params,
+    pepper: Option<Arc<Pepper>>,
+}
+
+impl PasswordHasher {
+    pub fn from_config(config: &PasswordHashConfig) -> Result<Self, argon2::Error> {
+        let pepper = match &config.pepper {
+            Some(secret) => {
+                let digest = Sha256::digest(secret.as_bytes());
+                Some(Arc::new(Pepper {
+                    secret: secret.as_bytes().to_vec(),
+                    id: KeyId::new(&digest[..PEPPER_ID_LEN])?,
+                }))
+            }
+            None => None,
+        };
+
+        let mut builder = ParamsBuilder::new();
+        builder
+            .m_cost(config.memory_kib)
+            .t_cost(config.iterations)
+            .p_cost(config.parallelism);
+        if let Some(pepper) = &pepper {
+            builder.keyid(pepper.id);
+        }
+
+        let hasher = Self {
+            params: builder.build()?,
+            pepper,
+        };
+        // Fail at startup rather than on the first login
+        hasher.argon2(true)?;
+        Ok(hasher)
+    }
+
+    fn argon2(&self, peppered: bool) -> Result<Argon2<'_>, argon2::Error> {
+        match &self.pepper {
+            Some(pepper) if peppered => Argon2::new_with_secret(
+                &pepper.secret,
+                Algorithm::Argon2id,
+                Version::V0x13,
+                self.params.clone(),
+            ),
+            _ => Ok(Argon2::new(
+                Algorithm::Argon2id,
+                Version::V0x13,
+                self.params.clone(),
+            )),
+        }
+    }
+
+    /// Hash a password with the current parameters and pepper
+    pub fn hash(&self, password: &str) -> Result<String, password_hash::Error> {
+        let salt = SaltString::generate(&mut OsRng);
+        Ok(self
+            .argon2(true)?
+            .hash_password(password.as_bytes(), &salt)?
+            .to_string())
+    }
+
+    /// Check a password against a stored hash, whatever parameters it was
+    /// made with. Hashes peppered with a different pepper never match.
+    pub fn verify(&self, password: &str, hash: &str) -> Result<bool, password_hash::Error> {
+        let parsed = PasswordHash::new(hash)?;
+        let keyid = Params::try_from(&parsed)?.keyid().to_vec();
+
+        let peppered = match &self.pepper {
+            _ if keyid.is_empty() => false,
+            Some(pepper) if pepper.id.as_bytes() == keyid.as_slice() => true,
+            _ => {
+                tracing::warn!("Password hash was made with an unknown pepper");
+                return Ok(false);
+            }
+        };
+
+        match self
+            .argon2(peppered)?
+            .verify_password(password.as_bytes(), &parsed)
+        {
+            Ok(()) => Ok(true),
+            Err(password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Whether a stored hash was made with other parameters or without the
+    /// current pepper, and should be replaced after the next successful
+    /// verification
+    pub fn needs_rehash(&self, hash: &str) -> bool {
+        let Ok(parsed) = PasswordHash::new(hash) else {
+            return false;
+        };
+        let Ok(params) = Params::try_from(&parsed) else {
+            return false;
+        };
+
+        parsed.algorithm != Algorithm::Argon2id.ident()
+            || parsed.version != Some(Version::V0x13.into())
+            || params.m_cost() != self.params.m_cost()
+            || params.t_cost() != self.params.t_cost()
+            || params.p_cost() != self.params.p_cost()
+            || params.keyid() != self.params.keyid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hasher(memory_kib: u32, pepper: Option<&str>) -> PasswordHasher {
+        PasswordHasher::from_config(&PasswordHashConfig {
+            memory_kib,
+            iterations: 1,
+            parallelism: 1,
+            pepper: pepper.map(str::to_string),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_hash_and_verify() {
+        let hasher = hasher(64, None);
+        let hash = hasher.hash("hunter22").unwrap();
+
+        assert!(hasher.verify("hunter22", &hash).unwrap());
+        assert!(!hasher.verify("hunter23", &hash).unwrap());
+        assert!(!hasher.needs_rehash(&hash));
+    }
+
+    #[test]
+    fn test_weaker_parameters_need_rehash() {
+        let old = hasher(64, None).hash("hunter22").unwrap();
+        let current = hasher(128, None);
+
+        assert!(current.verify("hunter22", &old).unwrap());
+        assert!(current.needs_rehash(&old));
+        assert!(!current.needs_rehash(&current.hash("hunter22").unwrap()));
+    }
+
+    #[test]
+    fn test_pepper() {
+        let plain = hasher(64, None).hash("hunter22").unwrap();
+        let peppered = hasher(64, Some("pepper"));
+        let hash = peppered.hash("hunter22").unwrap();
+
+        // Hashes from before the pepper still verify and get upgraded
+        assert!(peppered.verify("hunter22", &plain).unwrap());
+        assert!(peppered.needs_rehash(&plain));
+        assert!(!peppered.needs_rehash(&hash));
+
+        // The pepper is required for peppered hashes
+        assert!(peppered.verify("hunter22", &hash).unwrap());
+        assert!(!hasher(64, None).verify("hunter22", &hash).unwrap());
+        assert!(!hasher(64, Some("other")).verify("hunter22", &hash).unwrap());
+    }
+}
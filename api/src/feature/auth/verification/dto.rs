@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use validator::Validate;
+
+/// Request body for POST /auth/verify-email
+#[derive(Debug, Deserialize, Validate)]
+pub struct VerifyEmailRequest {
+    #[validate(length(min = 1, max = 128, message = "Token is required"))]
+    pub token: String,
+}
+
+/// Request body for POST /auth/resend-verification
+#[derive(Debug, Deserialize, Validate)]
+pub struct ResendVerificationRequest {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+}
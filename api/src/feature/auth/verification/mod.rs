@@ -0,0 +1,5 @@
+pub mod dto;
+pub mod service;
+
+pub use dto::{ResendVerificationRequest, VerifyEmailRequest};
+pub use service::{EmailVerificationService, VerificationError};
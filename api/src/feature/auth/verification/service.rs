@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    feature::{
+        auth::action_token::{ActionTokenPurpose, ActionTokenService},
+        user::repository::UserRepository,
+    },
+    infrastructure::{
+        config::{Config, EmailVerificationRequirement},
+        mail::{Email, MailError, Mailer},
+        persistence::Database,
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerificationError {
+    #[error("Invalid or expired verification token")]
+    InvalidToken,
+
+    #[error("Mail error: {0}")]
+    Mail(#[from] MailError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Sends verification links and marks addresses as verified
+#[derive(Clone)]
+pub struct EmailVerificationService {
+    db: Database,
+    user_repo: Arc<dyn UserRepository>,
+    tokens: ActionTokenService,
+    mailer: Arc<dyn Mailer>,
+    config: Arc<Config>,
+}
+
+impl EmailVerificationService {
+    pub fn new(
+        db: Database,
+        user_repo: Arc<dyn UserRepository>,
+        tokens: ActionTokenService,
+        mailer: Arc<dyn Mailer>,
+        config: Arc<Config>,
+    ) -> Self {
+        Self {
+            db,
+            user_repo,
+            tokens,
+            mailer,
+            config,
+        }
+    }
+
+    /// Current enforcement mode
+    pub fn requirement(&self) -> EmailVerificationRequirement {
+        self.config.email_verification.required
+    }
+
+    /// Issue a new verification token for `email` and send the link to it
+    pub async fn send_verification(
+        &self,
+        user_id: Uuid,
+        email: &str,
+    ) -> Result<(), VerificationError> {
+        let token = self
+            .tokens
+            .issue(
+                user_id,
+                ActionTokenPurpose::EmailVerification,
+                email,
+                self.config.email_verification.token_ttl_secs,
+                None,
+            )
+            .await?;
+
+        let link = format!(
+            "{}/verify-email?token={}",
+            self.config.mail.frontend_url.trim_end_matches('/'),
+            token
+        );
+        let hours = self.config.email_verification.token_ttl_secs / 3600;
+
+        self.mailer
+            .send(&Email {
+                to: email.to_string(),
+                subject: "Verify your email address".to_string(),
+                text_body: format!(
+                    "Confirm your email address by opening the link below:\n\n{link}\n\n\
+                     The link expires in {hours} hour(s). If you did not create an account, \
+                     you can ignore this message."
+                ),
+                html_body: Some(format!(
+                    "<p>Confirm your email address by clicking the link below:</p>\
+                     <p><a href=\"{link}\">Verify email</a></p>\
+                     <p>The link expires in {hours} hour(s). If you did not create an account, \
+                     you can ignore this message.</p>"
+                )),
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Resend the verification email. Always succeeds for unknown or already
+    /// verified addresses so the endpoint cannot be used to probe accounts;
+    /// requests inside the cooldown window are silently dropped.
+    pub async fn resend(&self, email: &str) -> Result<(), VerificationError> {
+        let Some(user) = self.user_repo.find_by_email(self.db.pool(), email).await? else {
+            return Ok(());
+        };
+
+        if user.email_verified || !user.is_active {
+            return Ok(());
+        }
+
+        let cooldown = Duration::seconds(self.config.email_verification.resend_cooldown_secs);
+        if let Some(last) = self
+            .tokens
+            .last_issued_at(user.id, ActionTokenPurpose::EmailVerification)
+            .await?
+            && last + cooldown > Utc::now()
+        {
+            return Ok(());
+        }
+
+        self.send_verification(user.id, &user.email).await
+    }
+
+    /// Redeem a verification token. The token is only valid for the address
+    /// it was sent to; if the user changed email since, it is rejected.
+    pub async fn verify(&self, token: &str) -> Result<Uuid, VerificationError> {
+        let record = self
+            .tokens
+            .redeem(token, ActionTokenPurpose::EmailVerification, None)
+            .await?
+            .ok_or(VerificationError::InvalidToken)?;
+
+        let user = self
+            .user_repo
+            .find_by_id(self.db.pool(), record.user_id)
+            .await?
+            .ok_or(VerificationError::InvalidToken)?;
+
+        if !user.email.eq_ignore_ascii_case(&record.email) {
+            return Err(VerificationError::InvalidToken);
+        }
+
+        if !user.email_verified {
+            self.user_repo
+                .set_email_verified(self.db.pool(), user.id, true)
+                .await?;
+        }
+
+        Ok(user.id)
+    }
+
+    /// Whether the user's address is verified
+    pub async fn is_verified(&self, user_id: Uuid) -> Result<bool, VerificationError> {
+        Ok(self
+            .user_repo
+            .find_by_id(self.db.pool(), user_id)
+            .await?
+            .is_some_and(|u| u.email_verified))
+    }
+}
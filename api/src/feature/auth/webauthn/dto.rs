@@ -0,0 +1,180 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{entity::WebAuthnCredential, protocol};
+
+// Ceremony options use the WebAuthn JSON encoding (camelCase, base64url
+// buffers) so they can be passed straight to `PublicKeyCredential.parse*OptionsFromJSON`.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelyingParty {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserEntity {
+    /// base64url user handle (the user's UUID bytes)
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialParameters {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub alg: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialDescriptor {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub id: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transports: Vec<String>,
+}
+
+impl From<&WebAuthnCredential> for CredentialDescriptor {
+    fn from(credential: &WebAuthnCredential) -> Self {
+        Self {
+            kind: "public-key",
+            id: protocol::encode(&credential.credential_id),
+            transports: credential.transports.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorSelection {
+    pub resident_key: &'static str,
+    pub user_verification: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreationOptions {
+    pub rp: RelyingParty,
+    pub user: UserEntity,
+    pub challenge: String,
+    pub pub_key_cred_params: Vec<CredentialParameters>,
+    /// Milliseconds
+    pub timeout: u64,
+    pub exclude_credentials: Vec<CredentialDescriptor>,
+    pub authenticator_selection: AuthenticatorSelection,
+    pub attestation: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestOptions {
+    pub challenge: String,
+    /// Milliseconds
+    pub timeout: u64,
+    pub rp_id: String,
+    pub allow_credentials: Vec<CredentialDescriptor>,
+    pub user_verification: &'static str,
+}
+
+/// Response for POST /auth/webauthn/register/begin
+#[derive(Debug, Clone, Serialize)]
+pub struct PasskeyRegistrationOptions {
+    pub ceremony_id: Uuid,
+    pub options: CreationOptions,
+}
+
+/// Response for POST /auth/webauthn/login/begin
+#[derive(Debug, Clone, Serialize)]
+pub struct PasskeyLoginOptions {
+    pub ceremony_id: Uuid,
+    pub options: RequestOptions,
+}
+
+/// `PublicKeyCredential.toJSON()` of a `navigator.credentials.create()` result
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationCredential {
+    pub id: String,
+    pub response: AttestationResponse,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub attestation_object: String,
+    #[serde(default)]
+    pub transports: Vec<String>,
+}
+
+/// `PublicKeyCredential.toJSON()` of a `navigator.credentials.get()` result
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionCredential {
+    pub id: String,
+    pub response: AssertionResponse,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    pub user_handle: Option<String>,
+}
+
+/// Request body for POST /auth/webauthn/register/finish
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct PasskeyRegisterFinishRequest {
+    pub ceremony_id: Uuid,
+
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: Option<String>,
+
+    pub credential: RegistrationCredential,
+}
+
+/// Request body for POST /auth/webauthn/login/begin
+#[derive(Debug, Clone, Default, Deserialize, Validate)]
+pub struct PasskeyLoginBeginRequest {
+    /// Limits the ceremony to this account's passkeys; omit for discoverable login
+    #[validate(email(message = "Invalid email format"))]
+    pub email: Option<String>,
+}
+
+/// Request body for POST /auth/webauthn/login/finish
+#[derive(Debug, Clone, Deserialize)]
+pub struct PasskeyLoginFinishRequest {
+    pub ceremony_id: Uuid,
+    pub credential: AssertionCredential,
+}
+
+/// A registered passkey as shown to its owner
+#[derive(Debug, Clone, Serialize)]
+pub struct PasskeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub transports: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<WebAuthnCredential> for PasskeyResponse {
+    fn from(credential: WebAuthnCredential) -> Self {
+        Self {
+            id: credential.id,
+            name: credential.name,
+            transports: credential.transports,
+            created_at: credential.created_at,
+            last_used_at: credential.last_used_at,
+        }
+    }
+}
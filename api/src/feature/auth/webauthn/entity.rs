@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "VARCHAR", rename_all = "lowercase")]
+pub enum CeremonyPurpose {
+    Registration,
+    Authentication,
+}
+
+/// Pending registration or login, created by `begin` and consumed by `finish`
+#[derive(Debug, Clone, FromRow)]
+pub struct WebAuthnCeremony {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub purpose: CeremonyPurpose,
+    pub challenge: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Registered passkey, backed by a `passkey` row in `auth_methods`
+#[derive(Debug, Clone, FromRow)]
+pub struct WebAuthnCredential {
+    pub id: Uuid,
+    pub auth_method_id: Uuid,
+    pub user_id: Uuid,
+    pub credential_id: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub algorithm: i32,
+    pub sign_count: i64,
+    pub transports: Vec<String>,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Data needed to store a newly registered passkey
+#[derive(Debug, Clone)]
+pub struct NewWebAuthnCredential {
+    pub user_id: Uuid,
+    pub credential_id: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub algorithm: i32,
+    pub sign_count: i64,
+    pub transports: Vec<String>,
+    pub name: String,
+}
@@ -0,0 +1,13 @@
+pub mod dto;
+pub mod entity;
+pub mod protocol;
+pub mod repository;
+pub mod service;
+
+pub use dto::{
+    PasskeyLoginBeginRequest, PasskeyLoginFinishRequest, PasskeyLoginOptions,
+    PasskeyRegisterFinishRequest, PasskeyRegistrationOptions, PasskeyResponse,
+};
+pub use entity::WebAuthnCredential;
+pub use repository::{WebAuthnRepository, WebAuthnRepositoryImpl};
+pub use service::{WebAuthnError, WebAuthnService};
@@ -0,0 +1,396 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use ciborium::Value;
+use rand::RngCore;
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// COSE algorithm identifiers (RFC 9053)
+pub const ES256: i64 = -7;
+pub const EDDSA: i64 = -8;
+pub const RS256: i64 = -257;
+
+/// Algorithms offered at registration, in order of preference
+pub const SUPPORTED_ALGORITHMS: [i64; 3] = [ES256, EDDSA, RS256];
+
+// Authenticator data flags (WebAuthn §6.1)
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_DATA: u8 = 0x40;
+
+/// rpIdHash (32) + flags (1) + signCount (4)
+const AUTH_DATA_MIN_LEN: usize = 37;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ProtocolError {
+    #[error("Malformed {0}")]
+    Malformed(&'static str),
+
+    #[error("{0} mismatch")]
+    Mismatch(&'static str),
+
+    #[error("User presence not confirmed")]
+    UserNotPresent,
+
+    #[error("User verification required")]
+    UserNotVerified,
+
+    #[error("Unsupported public key algorithm")]
+    UnsupportedAlgorithm,
+
+    #[error("Invalid signature")]
+    InvalidSignature,
+}
+
+/// 32 random bytes for a ceremony challenge
+pub fn generate_challenge() -> Vec<u8> {
+    let mut bytes = vec![0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Decode a base64url field (browsers omit padding, some libraries keep it)
+pub fn decode(field: &'static str, value: &str) -> Result<Vec<u8>, ProtocolError> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| ProtocolError::Malformed(field))
+}
+
+pub fn encode(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[derive(Deserialize)]
+struct CollectedClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+    #[serde(default, rename = "crossOrigin")]
+    cross_origin: bool,
+}
+
+/// Check `clientDataJSON` against the ceremony (§7.1 steps 7-9) and return
+/// its SHA-256, which the authenticator signs over
+pub fn verify_client_data(
+    raw: &[u8],
+    kind: &str,
+    challenge: &[u8],
+    origins: &[String],
+) -> Result<[u8; 32], ProtocolError> {
+    let data: CollectedClientData =
+        serde_json::from_slice(raw).map_err(|_| ProtocolError::Malformed("clientDataJSON"))?;
+
+    if data.kind != kind {
+        return Err(ProtocolError::Mismatch("Ceremony type"));
+    }
+
+    let presented = decode("challenge", &data.challenge)?;
+    if !constant_time_eq::constant_time_eq(&presented, challenge) {
+        return Err(ProtocolError::Mismatch("Challenge"));
+    }
+
+    if data.cross_origin || !origins.contains(&data.origin) {
+        return Err(ProtocolError::Mismatch("Origin"));
+    }
+
+    Ok(Sha256::digest(raw).into())
+}
+
+/// Credential created during registration
+#[derive(Debug, Clone)]
+pub struct AttestedCredential {
+    pub credential_id: Vec<u8>,
+    /// COSE_Key exactly as sent by the authenticator
+    pub public_key: Vec<u8>,
+    pub algorithm: i64,
+}
+
+/// Parsed `authenticatorData` (§6.1)
+#[derive(Debug, Clone)]
+pub struct AuthenticatorData {
+    pub user_verified: bool,
+    pub sign_count: u32,
+    pub attested: Option<AttestedCredential>,
+}
+
+impl AuthenticatorData {
+    /// Parse and check the RP ID hash and user flags
+    pub fn parse(raw: &[u8], rp_id: &str, require_uv: bool) -> Result<Self, ProtocolError> {
+        if raw.len() < AUTH_DATA_MIN_LEN {
+            return Err(ProtocolError::Malformed("authenticatorData"));
+        }
+
+        let rp_id_hash: [u8; 32] = Sha256::digest(rp_id.as_bytes()).into();
+        if raw[..32] != rp_id_hash {
+            return Err(ProtocolError::Mismatch("RP ID"));
+        }
+
+        let flags = raw[32];
+        if flags & FLAG_USER_PRESENT == 0 {
+            return Err(ProtocolError::UserNotPresent);
+        }
+        let user_verified = flags & FLAG_USER_VERIFIED != 0;
+        if require_uv && !user_verified {
+            return Err(ProtocolError::UserNotVerified);
+        }
+
+        let sign_count = u32::from_be_bytes([raw[33], raw[34], raw[35], raw[36]]);
+
+        let attested = if flags & FLAG_ATTESTED_DATA != 0 {
+            Some(parse_attested_credential(&raw[AUTH_DATA_MIN_LEN..])?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            user_verified,
+            sign_count,
+            attested,
+        })
+    }
+}
+
+/// aaguid (16) | credentialIdLength (2) | credentialId | COSE_Key | extensions
+fn parse_attested_credential(data: &[u8]) -> Result<AttestedCredential, ProtocolError> {
+    const MALFORMED: ProtocolError = ProtocolError::Malformed("attested credential data");
+
+    let len_bytes = data.get(16..18).ok_or(MALFORMED)?;
+    let id_len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
+    let credential_id = data.get(18..18 + id_len).ok_or(MALFORMED)?;
+
+    // The key is followed by optional extensions, so only its own bytes are kept
+    let key_start = &data[18 + id_len..];
+    let mut rest = key_start;
+    let value: Value = ciborium::from_reader(&mut rest).map_err(|_| MALFORMED)?;
+    let public_key = &key_start[..key_start.len() - rest.len()];
+
+    Ok(AttestedCredential {
+        credential_id: credential_id.to_vec(),
+        public_key: public_key.to_vec(),
+        algorithm: CoseKey::from_value(&value)?.algorithm,
+    })
+}
+
+/// `authData` of an attestation object. The attestation statement is not
+/// checked: registration asks for `none`, so keys are trusted on first use.
+pub fn attestation_auth_data(raw: &[u8]) -> Result<Vec<u8>, ProtocolError> {
+    const MALFORMED: ProtocolError = ProtocolError::Malformed("attestationObject");
+
+    let value: Value = ciborium::from_reader(raw).map_err(|_| MALFORMED)?;
+    value
+        .as_map()
+        .and_then(|map| {
+            map.iter()
+                .find(|(k, _)| k.as_text() == Some("authData"))
+                .and_then(|(_, v)| v.as_bytes())
+        })
+        .cloned()
+        .ok_or(MALFORMED)
+}
+
+/// Message signed by an assertion: `authenticatorData || SHA-256(clientDataJSON)`
+pub fn signed_data(auth_data: &[u8], client_data_hash: &[u8; 32]) -> Vec<u8> {
+    [auth_data, client_data_hash].concat()
+}
+
+#[derive(Debug, Clone)]
+enum PublicKey {
+    /// Uncompressed P-256 point
+    Ec2(Vec<u8>),
+    Ed25519(Vec<u8>),
+    Rsa {
+        n: Vec<u8>,
+        e: Vec<u8>,
+    },
+}
+
+/// Credential public key in COSE_Key form (RFC 9052 §7)
+#[derive(Debug, Clone)]
+pub struct CoseKey {
+    pub algorithm: i64,
+    key: PublicKey,
+}
+
+impl CoseKey {
+    pub fn parse(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let value: Value =
+            ciborium::from_reader(raw).map_err(|_| ProtocolError::Malformed("COSE key"))?;
+        Self::from_value(&value)
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ProtocolError> {
+        const MALFORMED: ProtocolError = ProtocolError::Malformed("COSE key");
+
+        let map = value.as_map().ok_or(MALFORMED)?;
+        let param = |label: i64| {
+            map.iter()
+                .find(|(k, _)| {
+                    k.as_integer()
+                        .and_then(|i| i64::try_from(i).ok())
+                        .is_some_and(|k| k == label)
+                })
+                .map(|(_, v)| v)
+        };
+        let int = |label: i64| {
+            param(label)
+                .and_then(|v| v.as_integer())
+                .and_then(|i| i64::try_from(i).ok())
+                .ok_or(MALFORMED)
+        };
+        let bytes = |label: i64| {
+            param(label)
+                .and_then(|v| v.as_bytes())
+                .cloned()
+                .ok_or(MALFORMED)
+        };
+
+        let algorithm = int(3)?;
+        let key = match (int(1)?, algorithm) {
+            // EC2, curve P-256
+            (2, ES256) if int(-1)? == 1 => {
+                let (x, y) = (bytes(-2)?, bytes(-3)?);
+                if x.len() != 32 || y.len() != 32 {
+                    return Err(MALFORMED);
+                }
+                PublicKey::Ec2([&[0x04], x.as_slice(), y.as_slice()].concat())
+            }
+            // OKP, curve Ed25519
+            (1, EDDSA) if int(-1)? == 6 => PublicKey::Ed25519(bytes(-2)?),
+            (3, RS256) => PublicKey::Rsa {
+                n: bytes(-1)?,
+                e: bytes(-2)?,
+            },
+            _ => return Err(ProtocolError::UnsupportedAlgorithm),
+        };
+
+        Ok(Self { algorithm, key })
+    }
+
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), ProtocolError> {
+        match &self.key {
+            PublicKey::Ec2(point) => {
+                UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, point)
+                    .verify(message, signature)
+            }
+            PublicKey::Ed25519(x) => {
+                UnparsedPublicKey::new(&signature::ED25519, x).verify(message, signature)
+            }
+            PublicKey::Rsa { n, e } => RsaPublicKeyComponents { n, e }.verify(
+                &signature::RSA_PKCS1_2048_8192_SHA256,
+                message,
+                signature,
+            ),
+        }
+        .map_err(|_| ProtocolError::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::{
+        rand::SystemRandom,
+        signature::{ECDSA_P256_SHA256_ASN1_SIGNING, EcdsaKeyPair, KeyPair},
+    };
+
+    const ORIGIN: &str = "http://localhost:5173";
+
+    fn p256_key() -> EcdsaKeyPair {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng).unwrap()
+    }
+
+    fn cose_p256(pair: &EcdsaKeyPair) -> Vec<u8> {
+        let point = pair.public_key().as_ref();
+        let key = Value::Map(vec![
+            (1.into(), 2.into()),
+            (3.into(), ES256.into()),
+            ((-1).into(), 1.into()),
+            ((-2).into(), Value::Bytes(point[1..33].to_vec())),
+            ((-3).into(), Value::Bytes(point[33..].to_vec())),
+        ]);
+        let mut out = Vec::new();
+        ciborium::into_writer(&key, &mut out).unwrap();
+        out
+    }
+
+    fn client_data(kind: &str, challenge: &[u8], origin: &str) -> Vec<u8> {
+        serde_json::json!({ "type": kind, "challenge": encode(challenge), "origin": origin })
+            .to_string()
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_client_data_checks() {
+        let origins = vec![ORIGIN.to_string()];
+        let challenge = generate_challenge();
+
+        let ok = client_data("webauthn.get", &challenge, ORIGIN);
+        let hash = verify_client_data(&ok, "webauthn.get", &challenge, &origins).unwrap();
+        assert_eq!(hash.as_slice(), Sha256::digest(&ok).as_slice());
+
+        let wrong_type = client_data("webauthn.create", &challenge, ORIGIN);
+        let wrong_origin = client_data("webauthn.get", &challenge, "https://evil.example");
+        let wrong_challenge = client_data("webauthn.get", &generate_challenge(), ORIGIN);
+        for (raw, expected) in [
+            (wrong_type, ProtocolError::Mismatch("Ceremony type")),
+            (wrong_origin, ProtocolError::Mismatch("Origin")),
+            (wrong_challenge, ProtocolError::Mismatch("Challenge")),
+        ] {
+            assert_eq!(
+                verify_client_data(&raw, "webauthn.get", &challenge, &origins),
+                Err(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_attested_credential_is_split_from_extensions() {
+        let pair = p256_key();
+        let cose = cose_p256(&pair);
+
+        let mut auth_data = Sha256::digest(b"localhost").to_vec();
+        auth_data.push(FLAG_USER_PRESENT | FLAG_USER_VERIFIED | FLAG_ATTESTED_DATA | 0x80);
+        auth_data.extend_from_slice(&7u32.to_be_bytes());
+        auth_data.extend_from_slice(&[0u8; 16]);
+        auth_data.extend_from_slice(&4u16.to_be_bytes());
+        auth_data.extend_from_slice(b"cred");
+        auth_data.extend_from_slice(&cose);
+        // Extensions map: {"credProtect": 2}
+        ciborium::into_writer(
+            &Value::Map(vec![(Value::Text("credProtect".into()), 2.into())]),
+            &mut auth_data,
+        )
+        .unwrap();
+
+        let parsed = AuthenticatorData::parse(&auth_data, "localhost", true).unwrap();
+        assert!(parsed.user_verified);
+        assert_eq!(parsed.sign_count, 7);
+        let attested = parsed.attested.unwrap();
+        assert_eq!(attested.credential_id, b"cred");
+        assert_eq!(attested.public_key, cose);
+        assert_eq!(attested.algorithm, ES256);
+
+        assert_eq!(
+            AuthenticatorData::parse(&auth_data, "example.com", true).unwrap_err(),
+            ProtocolError::Mismatch("RP ID")
+        );
+    }
+
+    #[test]
+    fn test_p256_signature_verification() {
+        let pair = p256_key();
+        let key = CoseKey::parse(&cose_p256(&pair)).unwrap();
+
+        let message = b"authenticator data and client hash";
+        let signature = pair.sign(&SystemRandom::new(), message).unwrap();
+
+        assert!(key.verify(message, signature.as_ref()).is_ok());
+        assert_eq!(
+            key.verify(b"something else", signature.as_ref()),
+            Err(ProtocolError::InvalidSignature)
+        );
+    }
+}
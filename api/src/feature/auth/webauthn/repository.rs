@@ -0,0 +1,208 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::entity::{CeremonyPurpose, NewWebAuthnCredential, WebAuthnCeremony, WebAuthnCredential};
+
+#[async_trait]
+pub trait WebAuthnRepository: Send + Sync {
+    /// Store a pending ceremony and return its id
+    async fn create_ceremony(
+        &self,
+        pool: &PgPool,
+        user_id: Option<Uuid>,
+        purpose: CeremonyPurpose,
+        challenge: &[u8],
+        expires_at: DateTime<Utc>,
+    ) -> Result<Uuid, sqlx::Error>;
+
+    /// Atomically fetch and delete a pending ceremony (single use).
+    /// Expired rows are never returned.
+    async fn consume_ceremony(
+        &self,
+        pool: &PgPool,
+        id: Uuid,
+        purpose: CeremonyPurpose,
+    ) -> Result<Option<WebAuthnCeremony>, sqlx::Error>;
+
+    /// Remove expired ceremonies
+    async fn cleanup_expired(&self, pool: &PgPool) -> Result<u64, sqlx::Error>;
+
+    /// Insert the passkey together with its `auth_methods` row
+    async fn create_credential(
+        &self,
+        pool: &PgPool,
+        credential: &NewWebAuthnCredential,
+    ) -> Result<WebAuthnCredential, sqlx::Error>;
+
+    async fn find_by_credential_id(
+        &self,
+        pool: &PgPool,
+        credential_id: &[u8],
+    ) -> Result<Option<WebAuthnCredential>, sqlx::Error>;
+
+    async fn list_by_user(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<WebAuthnCredential>, sqlx::Error>;
+
+    /// Record a successful assertion
+    async fn record_use(&self, pool: &PgPool, id: Uuid, sign_count: i64)
+    -> Result<(), sqlx::Error>;
+
+    /// Delete a user's passkey (the credential goes with its auth method)
+    async fn delete(&self, pool: &PgPool, user_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WebAuthnRepositoryImpl;
+
+impl WebAuthnRepositoryImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl WebAuthnRepository for WebAuthnRepositoryImpl {
+    async fn create_ceremony(
+        &self,
+        pool: &PgPool,
+        user_id: Option<Uuid>,
+        purpose: CeremonyPurpose,
+        challenge: &[u8],
+        expires_at: DateTime<Utc>,
+    ) -> Result<Uuid, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO webauthn_ceremonies (user_id, purpose, challenge, expires_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(purpose)
+        .bind(challenge)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await
+    }
+
+    async fn consume_ceremony(
+        &self,
+        pool: &PgPool,
+        id: Uuid,
+        purpose: CeremonyPurpose,
+    ) -> Result<Option<WebAuthnCeremony>, sqlx::Error> {
+        sqlx::query_as::<_, WebAuthnCeremony>(
+            r#"
+            DELETE FROM webauthn_ceremonies
+            WHERE id = $1 AND purpose = $2 AND expires_at > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(purpose)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn cleanup_expired(&self, pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM webauthn_ceremonies WHERE expires_at <= NOW()")
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn create_credential(
+        &self,
+        pool: &PgPool,
+        credential: &NewWebAuthnCredential,
+    ) -> Result<WebAuthnCredential, sqlx::Error> {
+        sqlx::query_as::<_, WebAuthnCredential>(
+            r#"
+            WITH method AS (
+                INSERT INTO auth_methods (user_id, provider, provider_id, is_primary, is_verified)
+                VALUES ($1, 'passkey', $2, FALSE, TRUE)
+                RETURNING id
+            )
+            INSERT INTO webauthn_credentials
+                (auth_method_id, user_id, credential_id, public_key, algorithm,
+                 sign_count, transports, name)
+            SELECT id, $1, $3, $4, $5, $6, $7, $8 FROM method
+            RETURNING *
+            "#,
+        )
+        .bind(credential.user_id)
+        .bind(super::protocol::encode(&credential.credential_id))
+        .bind(&credential.credential_id)
+        .bind(&credential.public_key)
+        .bind(credential.algorithm)
+        .bind(credential.sign_count)
+        .bind(&credential.transports)
+        .bind(&credential.name)
+        .fetch_one(pool)
+        .await
+    }
+
+    async fn find_by_credential_id(
+        &self,
+        pool: &PgPool,
+        credential_id: &[u8],
+    ) -> Result<Option<WebAuthnCredential>, sqlx::Error> {
+        sqlx::query_as::<_, WebAuthnCredential>(
+            "SELECT * FROM webauthn_credentials WHERE credential_id = $1",
+        )
+        .bind(credential_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    async fn list_by_user(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<WebAuthnCredential>, sqlx::Error> {
+        sqlx::query_as::<_, WebAuthnCredential>(
+            "SELECT * FROM webauthn_credentials WHERE user_id = $1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    async fn record_use(
+        &self,
+        pool: &PgPool,
+        id: Uuid,
+        sign_count: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE webauthn_credentials SET sign_count = $2, last_used_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(sign_count)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, pool: &PgPool, user_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM auth_methods
+            WHERE id = (
+                SELECT auth_method_id FROM webauthn_credentials
+                WHERE id = $1 AND user_id = $2
+            )
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,391 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    feature::{
+        auth::{
+            repository::AuthError,
+            service::{AuthService, LoginOutcome},
+            session::DeviceInfo,
+        },
+        user::repository::UserRepository,
+    },
+    infrastructure::{config::Config, persistence::Database},
+};
+
+use super::{
+    dto::{
+        AuthenticatorSelection, CreationOptions, CredentialDescriptor, CredentialParameters,
+        PasskeyLoginFinishRequest, PasskeyLoginOptions, PasskeyRegisterFinishRequest,
+        PasskeyRegistrationOptions, PasskeyResponse, RelyingParty, RequestOptions, UserEntity,
+    },
+    entity::{CeremonyPurpose, NewWebAuthnCredential, WebAuthnCeremony},
+    protocol::{self, AuthenticatorData, CoseKey, ProtocolError},
+    repository::WebAuthnRepository,
+};
+
+const DEFAULT_PASSKEY_NAME: &str = "Passkey";
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebAuthnError {
+    #[error("Unknown or expired ceremony")]
+    InvalidCeremony,
+
+    #[error("Passkey response rejected: {0}")]
+    Rejected(#[from] ProtocolError),
+
+    #[error("Unknown passkey")]
+    UnknownCredential,
+
+    #[error("Signature counter did not increase - possible cloned authenticator")]
+    CounterRegression,
+
+    #[error("Passkey already registered")]
+    CredentialExists,
+
+    #[error("Passkey not found")]
+    NotFound,
+
+    #[error("Cannot remove the last sign-in method")]
+    LastLoginMethod,
+
+    #[error("Login failed: {0}")]
+    Auth(#[from] AuthError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Passkey registration and login ceremonies, handing off to
+/// `AuthService::passkey_login` once an assertion checks out
+#[derive(Clone)]
+pub struct WebAuthnService {
+    db: Database,
+    repo: Arc<dyn WebAuthnRepository>,
+    user_repo: Arc<dyn UserRepository>,
+    config: Arc<Config>,
+    auth_service: Arc<AuthService>,
+}
+
+impl WebAuthnService {
+    pub fn new(
+        db: Database,
+        repo: Arc<dyn WebAuthnRepository>,
+        user_repo: Arc<dyn UserRepository>,
+        config: Arc<Config>,
+        auth_service: Arc<AuthService>,
+    ) -> Self {
+        Self {
+            db,
+            repo,
+            user_repo,
+            config,
+            auth_service,
+        }
+    }
+
+    fn user_verification(&self) -> &'static str {
+        if self.config.webauthn.require_user_verification {
+            "required"
+        } else {
+            "preferred"
+        }
+    }
+
+    fn timeout_ms(&self) -> u64 {
+        self.config.webauthn.challenge_ttl_secs.max(0) as u64 * 1000
+    }
+
+    /// Persist a fresh challenge for `purpose`
+    async fn start_ceremony(
+        &self,
+        user_id: Option<Uuid>,
+        purpose: CeremonyPurpose,
+    ) -> Result<(Uuid, Vec<u8>), WebAuthnError> {
+        // Opportunistic cleanup of abandoned ceremonies
+        let _ = self.repo.cleanup_expired(self.db.pool()).await;
+
+        let challenge = protocol::generate_challenge();
+        let expires_at = Utc::now() + Duration::seconds(self.config.webauthn.challenge_ttl_secs);
+        let id = self
+            .repo
+            .create_ceremony(self.db.pool(), user_id, purpose, &challenge, expires_at)
+            .await?;
+
+        Ok((id, challenge))
+    }
+
+    async fn consume_ceremony(
+        &self,
+        id: Uuid,
+        purpose: CeremonyPurpose,
+    ) -> Result<WebAuthnCeremony, WebAuthnError> {
+        self.repo
+            .consume_ceremony(self.db.pool(), id, purpose)
+            .await?
+            .ok_or(WebAuthnError::InvalidCeremony)
+    }
+
+    /// Options for `navigator.credentials.create()`
+    pub async fn begin_registration(
+        &self,
+        user_id: Uuid,
+    ) -> Result<PasskeyRegistrationOptions, WebAuthnError> {
+        let user = self
+            .user_repo
+            .find_by_id(self.db.pool(), user_id)
+            .await?
+            .ok_or(AuthError::UserNotFound)?;
+
+        let existing = self.repo.list_by_user(self.db.pool(), user.id).await?;
+        let (ceremony_id, challenge) = self
+            .start_ceremony(Some(user.id), CeremonyPurpose::Registration)
+            .await?;
+
+        let webauthn = &self.config.webauthn;
+        let options = CreationOptions {
+            rp: RelyingParty {
+                id: webauthn.rp_id.clone(),
+                name: webauthn.rp_name.clone(),
+            },
+            user: UserEntity {
+                id: protocol::encode(user.id.as_bytes()),
+                display_name: user.username.clone().unwrap_or_else(|| user.email.clone()),
+                name: user.email,
+            },
+            challenge: protocol::encode(&challenge),
+            pub_key_cred_params: protocol::SUPPORTED_ALGORITHMS
+                .iter()
+                .map(|&alg| CredentialParameters {
+                    kind: "public-key",
+                    alg,
+                })
+                .collect(),
+            timeout: self.timeout_ms(),
+            exclude_credentials: existing.iter().map(CredentialDescriptor::from).collect(),
+            authenticator_selection: AuthenticatorSelection {
+                resident_key: "preferred",
+                user_verification: self.user_verification(),
+            },
+            attestation: "none",
+        };
+
+        Ok(PasskeyRegistrationOptions {
+            ceremony_id,
+            options,
+        })
+    }
+
+    /// Verify the new credential and store it as one of the user's auth methods
+    pub async fn finish_registration(
+        &self,
+        user_id: Uuid,
+        req: PasskeyRegisterFinishRequest,
+    ) -> Result<PasskeyResponse, WebAuthnError> {
+        let ceremony = self
+            .consume_ceremony(req.ceremony_id, CeremonyPurpose::Registration)
+            .await?;
+        if ceremony.user_id != Some(user_id) {
+            return Err(WebAuthnError::InvalidCeremony);
+        }
+
+        let webauthn = &self.config.webauthn;
+        let response = &req.credential.response;
+
+        let client_data = protocol::decode("clientDataJSON", &response.client_data_json)?;
+        protocol::verify_client_data(
+            &client_data,
+            "webauthn.create",
+            &ceremony.challenge,
+            &webauthn.origins,
+        )?;
+
+        let attestation = protocol::decode("attestationObject", &response.attestation_object)?;
+        let auth_data = AuthenticatorData::parse(
+            &protocol::attestation_auth_data(&attestation)?,
+            &webauthn.rp_id,
+            webauthn.require_user_verification,
+        )?;
+        let attested = auth_data
+            .attested
+            .ok_or(ProtocolError::Malformed("attested credential data"))?;
+
+        if protocol::decode("credential id", &req.credential.id)? != attested.credential_id {
+            return Err(ProtocolError::Mismatch("Credential ID").into());
+        }
+
+        let credential = self
+            .repo
+            .create_credential(
+                self.db.pool(),
+                &NewWebAuthnCredential {
+                    user_id,
+                    credential_id: attested.credential_id,
+                    public_key: attested.public_key,
+                    algorithm: attested.algorithm as i32,
+                    sign_count: i64::from(auth_data.sign_count),
+                    transports: response.transports.clone(),
+                    name: req.name.unwrap_or_else(|| DEFAULT_PASSKEY_NAME.to_string()),
+                },
+            )
+            .await
+            .map_err(|e| match e.as_database_error() {
+                Some(db) if db.is_unique_violation() => WebAuthnError::CredentialExists,
+                _ => WebAuthnError::Database(e),
+            })?;
+
+        Ok(credential.into())
+    }
+
+    /// Options for `navigator.credentials.get()`. With an email the ceremony
+    /// is limited to that account's passkeys; unknown addresses get the same
+    /// response as discoverable login so accounts cannot be probed.
+    pub async fn begin_login(
+        &self,
+        email: Option<&str>,
+    ) -> Result<PasskeyLoginOptions, WebAuthnError> {
+        let user = match email {
+            Some(email) => self
+                .user_repo
+                .find_by_email(self.db.pool(), email)
+                .await?
+                .filter(|u| u.is_active),
+            None => None,
+        };
+
+        let allow_credentials = match &user {
+            Some(user) => self
+                .repo
+                .list_by_user(self.db.pool(), user.id)
+                .await?
+                .iter()
+                .map(CredentialDescriptor::from)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let (ceremony_id, challenge) = self
+            .start_ceremony(user.map(|u| u.id), CeremonyPurpose::Authentication)
+            .await?;
+
+        Ok(PasskeyLoginOptions {
+            ceremony_id,
+            options: RequestOptions {
+                challenge: protocol::encode(&challenge),
+                timeout: self.timeout_ms(),
+                rp_id: self.config.webauthn.rp_id.clone(),
+                allow_credentials,
+                user_verification: self.user_verification(),
+            },
+        })
+    }
+
+    /// Verify an assertion and log its owner in
+    pub async fn finish_login(
+        &self,
+        req: PasskeyLoginFinishRequest,
+        device_info: Option<&DeviceInfo>,
+    ) -> Result<LoginOutcome, WebAuthnError> {
+        let ceremony = self
+            .consume_ceremony(req.ceremony_id, CeremonyPurpose::Authentication)
+            .await?;
+
+        let credential_id = protocol::decode("credential id", &req.credential.id)?;
+        let credential = self
+            .repo
+            .find_by_credential_id(self.db.pool(), &credential_id)
+            .await?
+            .filter(|c| ceremony.user_id.is_none_or(|id| id == c.user_id))
+            .ok_or(WebAuthnError::UnknownCredential)?;
+
+        let webauthn = &self.config.webauthn;
+        let response = &req.credential.response;
+
+        if let Some(handle) = response.user_handle.as_deref().filter(|h| !h.is_empty())
+            && protocol::decode("userHandle", handle)? != credential.user_id.as_bytes()
+        {
+            return Err(ProtocolError::Mismatch("User handle").into());
+        }
+
+        let client_data = protocol::decode("clientDataJSON", &response.client_data_json)?;
+        let client_data_hash = protocol::verify_client_data(
+            &client_data,
+            "webauthn.get",
+            &ceremony.challenge,
+            &webauthn.origins,
+        )?;
+
+        let raw_auth_data = protocol::decode("authenticatorData", &response.authenticator_data)?;
+        let auth_data = AuthenticatorData::parse(
+            &raw_auth_data,
+            &webauthn.rp_id,
+            webauthn.require_user_verification,
+        )?;
+
+        CoseKey::parse(&credential.public_key)?.verify(
+            &protocol::signed_data(&raw_auth_data, &client_data_hash),
+            &protocol::decode("signature", &response.signature)?,
+        )?;
+
+        // Authenticators that count must always move forward (§6.1.1)
+        let sign_count = i64::from(auth_data.sign_count);
+        if (sign_count != 0 || credential.sign_count != 0) && sign_count <= credential.sign_count {
+            tracing::warn!(
+                "Passkey {} counter went from {} to {} - possible cloned authenticator",
+                credential.id,
+                credential.sign_count,
+                sign_count
+            );
+            return Err(WebAuthnError::CounterRegression);
+        }
+
+        self.repo
+            .record_use(self.db.pool(), credential.id, sign_count)
+            .await?;
+        let _ = self
+            .auth_service
+            .auth_method_service()
+            .touch(credential.auth_method_id)
+            .await;
+
+        let outcome = self
+            .auth_service
+            .passkey_login(credential.user_id, auth_data.user_verified, device_info)
+            .await?;
+
+        Ok(outcome)
+    }
+
+    pub async fn list_passkeys(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<PasskeyResponse>, WebAuthnError> {
+        let credentials = self.repo.list_by_user(self.db.pool(), user_id).await?;
+        Ok(credentials.into_iter().map(Into::into).collect())
+    }
+
+    /// Remove a passkey, as long as the user keeps another way to sign in
+    pub async fn delete_passkey(&self, user_id: Uuid, id: Uuid) -> Result<(), WebAuthnError> {
+        let methods = self
+            .auth_service
+            .auth_method_service()
+            .list_by_user(user_id)
+            .await?;
+
+        let credentials = self.repo.list_by_user(self.db.pool(), user_id).await?;
+        if !credentials.iter().any(|c| c.id == id) {
+            return Err(WebAuthnError::NotFound);
+        }
+        if methods.len() <= 1 {
+            return Err(WebAuthnError::LastLoginMethod);
+        }
+
+        if !self.repo.delete(self.db.pool(), user_id, id).await? {
+            return Err(WebAuthnError::NotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -20,7 +20,7 @@ pub struct User {
 
 impl User {
     pub fn role(&self) -> Role {
-        Role::try_from(self.role.as_str()).unwrap_or(Role::User)
+        Role::from(self.role.as_str())
     }
 }
 
@@ -92,6 +92,6 @@ pub struct UserWithProfile {
 
 impl UserWithProfile {
     pub fn role(&self) -> Role {
-        Role::try_from(self.role.as_str()).unwrap_or(Role::User)
+        Role::from(self.role.as_str())
     }
 }
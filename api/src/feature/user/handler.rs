@@ -6,7 +6,10 @@ use crate::{
         auth::AuthUser,
         user::dto::{UpdateProfileRequest, UserProfileResponse},
     },
-    infrastructure::web::response::{ApiError, ApiResult, ApiSuccess, codes::generic},
+    infrastructure::web::{
+        middleware::{ensure_not_impersonated, ensure_recent_auth},
+        response::{ApiError, ApiResult, ApiSuccess, codes::generic},
+    },
     state::AppState,
 };
 
@@ -52,6 +55,21 @@ pub async fn update_me(
             .with_message(format!("Validation error: {}", e)));
     }
 
+    // Changing the email (the sign-in identity) needs a recent authentication
+    let mut email_changed = false;
+    if let Some(email) = req.email.as_deref() {
+        let current = state
+            .user_repo
+            .find_by_id(state.db.pool(), auth_user.user_id)
+            .await
+            .map_err(|e| ApiError::default().log_only(e))?;
+        if current.is_some_and(|u| !u.email.eq_ignore_ascii_case(email)) {
+            ensure_not_impersonated(&auth_user)?;
+            ensure_recent_auth(&auth_user, &state.config)?;
+            email_changed = true;
+        }
+    }
+
     // Update user (email, username)
     let user = state
         .user_repo
@@ -70,6 +88,16 @@ pub async fn update_me(
                 .with_message("User not found")
         })?;
 
+    // The new address is unverified until its owner confirms it
+    if email_changed
+        && let Err(e) = state
+            .email_verification_service
+            .send_verification(user.id, &user.email)
+            .await
+    {
+        tracing::error!("Failed to send verification email: {}", e);
+    }
+
     // Update profile (name) if provided
     if req.name.is_some() {
         let _ = state
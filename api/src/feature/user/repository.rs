@@ -47,7 +47,7 @@ pub trait UserRepository: Send + Sync {
     /// List all users
     async fn list(&self, pool: &PgPool, limit: i64, offset: i64) -> Result<Vec<User>, sqlx::Error>;
 
-    /// Update user identity
+    /// Update user identity. A changed email is no longer verified.
     async fn update(
         &self,
         pool: &PgPool,
@@ -187,7 +187,11 @@ impl UserRepository for UserRepositoryImpl {
         let mut next_param = 2; // $1 is id
 
         if email.is_some() {
-            query.push_str(&format!(", email = ${}", next_param));
+            // The right-hand side sees the old row, so this compares old and new
+            query.push_str(&format!(
+                ", email = ${p}, email_verified = email_verified AND LOWER(email) = LOWER(${p})",
+                p = next_param
+            ));
             next_param += 1;
         }
         if username.is_some() {
@@ -3,15 +3,23 @@ use axum::{
     routing::{delete, get, patch, post},
 };
 
-use crate::{infrastructure::web::middleware::auth_middleware, state::AppState};
+use crate::{
+    infrastructure::web::middleware::{auth_middleware, require_verified_email},
+    state::AppState,
+};
 
 use super::{avatar, handler};
 
 pub fn user_routes() -> Router<AppState> {
+    // Unverified users can still see and correct their address
+    let verified = Router::new()
+        .route("/avatar", post(avatar::upload_avatar))
+        .route("/avatar", delete(avatar::delete_avatar))
+        .layer(middleware::from_fn(require_verified_email));
+
     Router::new()
         .route("/me", get(handler::get_me))
         .route("/me", patch(handler::update_me))
-        .route("/avatar", post(avatar::upload_avatar))
-        .route("/avatar", delete(avatar::delete_avatar))
+        .merge(verified)
         .layer(middleware::from_fn(auth_middleware))
 }
@@ -7,6 +7,14 @@ fn require_env(key: &str) -> Result<String> {
     env::var(key).wrap_err_with(|| format!("Missing required environment variable: {key}"))
 }
 
+/// Parse an optional env var, falling back to `default` when unset or invalid
+fn parse_env<T: std::str::FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
 fn get_rust_env() -> Result<String> {
     let rust_env = require_env("RUST_ENV")?;
     if cfg!(debug_assertions) && rust_env == "production" {
@@ -158,6 +166,637 @@ impl UploadConfig {
     }
 }
 
+/// Endpoints and credentials for a single OAuth2 provider.
+///
+/// A provider is only enabled when both `OAUTH_{NAME}_CLIENT_ID` and
+/// `OAUTH_{NAME}_CLIENT_SECRET` are set. Endpoint URLs default to the
+/// provider's public endpoints and can be overridden (e.g. to point
+/// integration tests at a local mock IdP).
+#[derive(Debug, Clone)]
+pub struct OAuthProviderConfig {
+    /// Provider name as stored in `auth_methods.provider` (e.g. "google")
+    pub name: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub authorize_url: String,
+    pub token_url: String,
+    pub userinfo_url: String,
+    pub scopes: Vec<String>,
+}
+
+/// Public endpoints of a supported OAuth2 provider
+struct ProviderDefaults {
+    name: &'static str,
+    authorize_url: &'static str,
+    token_url: &'static str,
+    userinfo_url: &'static str,
+    scopes: &'static str,
+}
+
+const PROVIDER_DEFAULTS: &[ProviderDefaults] = &[
+    ProviderDefaults {
+        name: "google",
+        authorize_url: "https://accounts.google.com/o/oauth2/v2/auth",
+        token_url: "https://oauth2.googleapis.com/token",
+        userinfo_url: "https://openidconnect.googleapis.com/v1/userinfo",
+        scopes: "openid email profile",
+    },
+    ProviderDefaults {
+        name: "github",
+        authorize_url: "https://github.com/login/oauth/authorize",
+        token_url: "https://github.com/login/oauth/access_token",
+        userinfo_url: "https://api.github.com/user",
+        scopes: "read:user user:email",
+    },
+    ProviderDefaults {
+        name: "discord",
+        authorize_url: "https://discord.com/oauth2/authorize",
+        token_url: "https://discord.com/api/oauth2/token",
+        userinfo_url: "https://discord.com/api/users/@me",
+        scopes: "identify email",
+    },
+    ProviderDefaults {
+        name: "twitter",
+        authorize_url: "https://twitter.com/i/oauth2/authorize",
+        token_url: "https://api.twitter.com/2/oauth2/token",
+        userinfo_url: "https://api.twitter.com/2/users/me?user.fields=confirmed_email",
+        scopes: "users.read tweet.read users.email",
+    },
+];
+
+impl OAuthProviderConfig {
+    fn from_env(defaults: &ProviderDefaults) -> Option<Self> {
+        let prefix = format!("OAUTH_{}", defaults.name.to_uppercase());
+        let client_id = env::var(format!("{prefix}_CLIENT_ID")).ok()?;
+        let client_secret = env::var(format!("{prefix}_CLIENT_SECRET")).ok()?;
+
+        let var_or = |suffix: &str, default: &str| {
+            env::var(format!("{prefix}_{suffix}")).unwrap_or_else(|_| default.to_string())
+        };
+
+        Some(Self {
+            name: defaults.name.to_string(),
+            client_id,
+            client_secret,
+            authorize_url: var_or("AUTHORIZE_URL", defaults.authorize_url),
+            token_url: var_or("TOKEN_URL", defaults.token_url),
+            userinfo_url: var_or("USERINFO_URL", defaults.userinfo_url),
+            scopes: var_or("SCOPES", defaults.scopes)
+                .split_whitespace()
+                .map(|s| s.to_string())
+                .collect(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OAuthConfig {
+    /// Public base URL of this API, used to build provider redirect URIs
+    /// (env: OAUTH_CALLBACK_BASE_URL, default: "http://localhost:8080").
+    pub callback_base_url: String,
+    /// Frontend URL the callback redirects to once login completes or fails
+    /// (env: OAUTH_SUCCESS_REDIRECT, default: "http://localhost:5173/auth/callback").
+    pub success_redirect: String,
+    /// How long a pending authorization (state + PKCE verifier) stays valid
+    /// (env: OAUTH_STATE_TTL_SECS, default: 600).
+    pub state_ttl_secs: i64,
+    /// Enabled providers (only those with client credentials configured)
+    pub providers: Vec<OAuthProviderConfig>,
+}
+
+impl OAuthConfig {
+    fn from_env() -> Self {
+        let callback_base_url = env::var("OAUTH_CALLBACK_BASE_URL")
+            .unwrap_or_else(|_| "http://localhost:8080".to_string());
+
+        let success_redirect = env::var("OAUTH_SUCCESS_REDIRECT")
+            .unwrap_or_else(|_| "http://localhost:5173/auth/callback".to_string());
+
+        let state_ttl_secs = parse_env("OAUTH_STATE_TTL_SECS", 600);
+
+        let providers = PROVIDER_DEFAULTS
+            .iter()
+            .filter_map(OAuthProviderConfig::from_env)
+            .collect();
+
+        Self {
+            callback_base_url,
+            success_redirect,
+            state_ttl_secs,
+            providers,
+        }
+    }
+
+    /// Look up an enabled provider by name
+    pub fn provider(&self, name: &str) -> Option<&OAuthProviderConfig> {
+        self.providers.iter().find(|p| p.name == name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JwtConfig {
+    /// HS256 secret for access tokens when no signing keys are configured,
+    /// and for MFA challenge tokens (env: JWT_ACCESS_SECRET, required).
+    pub access_secret: String,
+    /// HS256 secret for refresh tokens (env: JWT_REFRESH_SECRET, required).
+    pub refresh_secret: String,
+    /// Access token lifetime (env: JWT_ACCESS_EXPIRY_SECS, default: 3600).
+    pub access_expiry_secs: i64,
+    /// Refresh token lifetime (env: JWT_REFRESH_EXPIRY_SECS, default: 604800).
+    pub refresh_expiry_secs: i64,
+    /// Directory of `<kid>.pem` private keys (RSA or Ed25519, PKCS#8 or PKCS#1).
+    /// Every key verifies and is published in the JWKS (env: JWT_KEYS_DIR).
+    pub keys_dir: Option<String>,
+    /// Key that signs new access tokens (env: JWT_ACTIVE_KID, required with JWT_KEYS_DIR).
+    pub active_kid: Option<String>,
+}
+
+impl JwtConfig {
+    fn from_env() -> Result<Self> {
+        Ok(Self {
+            access_secret: require_env("JWT_ACCESS_SECRET")?,
+            refresh_secret: require_env("JWT_REFRESH_SECRET")?,
+            access_expiry_secs: parse_env("JWT_ACCESS_EXPIRY_SECS", 3600),
+            refresh_expiry_secs: parse_env("JWT_REFRESH_EXPIRY_SECS", 604800),
+            keys_dir: env::var("JWT_KEYS_DIR").ok().filter(|v| !v.is_empty()),
+            active_kid: env::var("JWT_ACTIVE_KID").ok().filter(|v| !v.is_empty()),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    /// Reject access tokens whose session was revoked, instead of trusting the
+    /// JWT until it expires (env: SESSION_VALIDATE_ACCESS_TOKENS, default: true).
+    pub validate_access_tokens: bool,
+    /// How long a session lookup is cached in-process
+    /// (env: SESSION_CACHE_TTL_SECS, default: 30).
+    pub cache_ttl_secs: u64,
+    /// Minimum gap between `last_active_at` updates for one session
+    /// (env: SESSION_TOUCH_INTERVAL_SECS, default: 60).
+    pub touch_interval_secs: u64,
+    /// How recently the user must have signed in or re-authenticated for
+    /// sensitive operations (env: SESSION_REAUTH_MAX_AGE_SECS, default: 300).
+    pub reauth_max_age_secs: i64,
+    /// Sessions unused for this long are revoked on their next refresh; 0
+    /// disables the check (env: SESSION_IDLE_TIMEOUT_SECS, default: 259200).
+    pub idle_timeout_secs: i64,
+    /// Longest a session lives from sign-in, however active
+    /// (env: SESSION_ABSOLUTE_LIFETIME_SECS, default: JWT_REFRESH_EXPIRY_SECS).
+    pub absolute_lifetime_secs: i64,
+    /// Shorter absolute lifetime for admin sessions
+    /// (env: SESSION_ADMIN_ABSOLUTE_LIFETIME_SECS, default: none).
+    pub admin_absolute_lifetime_secs: Option<i64>,
+    /// Lifetime of admin impersonation tokens, which cannot be refreshed
+    /// (env: SESSION_IMPERSONATION_TTL_SECS, default: 900).
+    pub impersonation_ttl_secs: i64,
+}
+
+impl SessionConfig {
+    fn from_env() -> Self {
+        Self {
+            validate_access_tokens: parse_env("SESSION_VALIDATE_ACCESS_TOKENS", true),
+            cache_ttl_secs: parse_env("SESSION_CACHE_TTL_SECS", 30),
+            touch_interval_secs: parse_env("SESSION_TOUCH_INTERVAL_SECS", 60),
+            reauth_max_age_secs: parse_env("SESSION_REAUTH_MAX_AGE_SECS", 300),
+            idle_timeout_secs: parse_env("SESSION_IDLE_TIMEOUT_SECS", 259200),
+            absolute_lifetime_secs: parse_env(
+                "SESSION_ABSOLUTE_LIFETIME_SECS",
+                parse_env("JWT_REFRESH_EXPIRY_SECS", 604800),
+            ),
+            admin_absolute_lifetime_secs: env::var("SESSION_ADMIN_ABSOLUTE_LIFETIME_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            impersonation_ttl_secs: parse_env("SESSION_IMPERSONATION_TTL_SECS", 900),
+        }
+    }
+
+    /// Absolute session lifetime, taking the admin limit into account
+    pub fn absolute_lifetime_for(&self, is_admin: bool) -> i64 {
+        match self.admin_absolute_lifetime_secs {
+            Some(admin) if is_admin => admin.min(self.absolute_lifetime_secs),
+            _ => self.absolute_lifetime_secs,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MfaConfig {
+    /// Issuer shown in authenticator apps (env: MFA_ISSUER, default: "Quax").
+    pub issuer: String,
+    /// Lifetime of the challenge token returned by login when MFA is enabled
+    /// (env: MFA_CHALLENGE_TTL_SECS, default: 300).
+    pub challenge_ttl_secs: i64,
+}
+
+impl MfaConfig {
+    fn from_env() -> Self {
+        let issuer = env::var("MFA_ISSUER").unwrap_or_else(|_| "Quax".to_string());
+
+        Self {
+            issuer,
+            challenge_ttl_secs: parse_env("MFA_CHALLENGE_TTL_SECS", 300),
+        }
+    }
+}
+
+/// TLS mode for the SMTP connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Plain connection upgraded with STARTTLS (port 587)
+    StartTls,
+    /// Implicit TLS (port 465)
+    Tls,
+    /// No encryption — local relays / mail catchers only
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub tls: SmtpTls,
+}
+
+#[derive(Debug, Clone)]
+pub enum MailTransport {
+    /// Write messages as JSON files into a directory (development / tests)
+    File { outbox_dir: String },
+    /// Deliver through an SMTP relay
+    Smtp(SmtpConfig),
+}
+
+/// Outgoing email configuration
+///
+/// - MAIL_TRANSPORT: "file" (default) or "smtp"
+/// - MAIL_OUTBOX_DIR: directory for the file transport (default: "./outbox")
+/// - SMTP_HOST / SMTP_PORT / SMTP_USERNAME / SMTP_PASSWORD / SMTP_TLS ("starttls" | "tls" | "none")
+#[derive(Debug, Clone)]
+pub struct MailConfig {
+    /// Sender address (env: MAIL_FROM, default: "Quax <no-reply@quax.dev>").
+    pub from: String,
+    /// Base URL of the frontend, used to build links in emails
+    /// (env: FRONTEND_URL, default: "http://localhost:5173").
+    pub frontend_url: String,
+    pub transport: MailTransport,
+}
+
+impl MailConfig {
+    fn from_env() -> Result<Self> {
+        let from = env::var("MAIL_FROM").unwrap_or_else(|_| "Quax <no-reply@quax.dev>".to_string());
+
+        let frontend_url =
+            env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:5173".to_string());
+
+        let transport = match env::var("MAIL_TRANSPORT")
+            .unwrap_or_else(|_| "file".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "smtp" => {
+                let tls = match env::var("SMTP_TLS")
+                    .unwrap_or_else(|_| "starttls".to_string())
+                    .to_lowercase()
+                    .as_str()
+                {
+                    "tls" => SmtpTls::Tls,
+                    "none" => SmtpTls::None,
+                    _ => SmtpTls::StartTls,
+                };
+                let default_port = if tls == SmtpTls::Tls { 465 } else { 587 };
+
+                MailTransport::Smtp(SmtpConfig {
+                    host: require_env("SMTP_HOST")?,
+                    port: parse_env("SMTP_PORT", default_port),
+                    username: env::var("SMTP_USERNAME").ok(),
+                    password: env::var("SMTP_PASSWORD").ok(),
+                    tls,
+                })
+            }
+            _ => MailTransport::File {
+                outbox_dir: env::var("MAIL_OUTBOX_DIR").unwrap_or_else(|_| "./outbox".to_string()),
+            },
+        };
+
+        Ok(Self {
+            from,
+            frontend_url,
+            transport,
+        })
+    }
+}
+
+/// What an unverified email address is allowed to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailVerificationRequirement {
+    /// Verification is optional
+    Off,
+    /// Password login is refused until the address is verified
+    Login,
+    /// Login works, but routes guarded by `require_verified_email` return 403
+    Routes,
+}
+
+#[derive(Debug, Clone)]
+pub struct EmailVerificationConfig {
+    /// Enforcement mode (env: EMAIL_VERIFICATION_REQUIRED = "off" | "login" | "routes", default: "off").
+    pub required: EmailVerificationRequirement,
+    /// Lifetime of a verification link (env: EMAIL_VERIFICATION_TTL_SECS, default: 86400).
+    pub token_ttl_secs: i64,
+    /// Minimum delay between two verification emails for one account
+    /// (env: EMAIL_VERIFICATION_RESEND_SECS, default: 60).
+    pub resend_cooldown_secs: i64,
+}
+
+impl EmailVerificationConfig {
+    fn from_env() -> Self {
+        let required = match env::var("EMAIL_VERIFICATION_REQUIRED")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "login" => EmailVerificationRequirement::Login,
+            "routes" => EmailVerificationRequirement::Routes,
+            _ => EmailVerificationRequirement::Off,
+        };
+
+        Self {
+            required,
+            token_ttl_secs: parse_env("EMAIL_VERIFICATION_TTL_SECS", 86400),
+            resend_cooldown_secs: parse_env("EMAIL_VERIFICATION_RESEND_SECS", 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PasswordResetConfig {
+    /// Lifetime of a reset link (env: PASSWORD_RESET_TTL_SECS, default: 900).
+    pub token_ttl_secs: i64,
+    /// Minimum delay between two reset emails for one account
+    /// (env: PASSWORD_RESET_RESEND_SECS, default: 60).
+    pub resend_cooldown_secs: i64,
+}
+
+impl PasswordResetConfig {
+    fn from_env() -> Self {
+        Self {
+            token_ttl_secs: parse_env("PASSWORD_RESET_TTL_SECS", 900),
+            resend_cooldown_secs: parse_env("PASSWORD_RESET_RESEND_SECS", 60),
+        }
+    }
+}
+
+/// Rules every new password must satisfy
+#[derive(Debug, Clone)]
+pub struct PasswordPolicyConfig {
+    /// Minimum length in characters (env: PASSWORD_MIN_LENGTH, default: 8).
+    pub min_length: usize,
+    /// Maximum length in characters, bounding hashing cost
+    /// (env: PASSWORD_MAX_LENGTH, default: 128).
+    pub max_length: usize,
+    /// Require a lowercase letter (env: PASSWORD_REQUIRE_LOWERCASE, default: false).
+    pub require_lowercase: bool,
+    /// Require an uppercase letter (env: PASSWORD_REQUIRE_UPPERCASE, default: false).
+    pub require_uppercase: bool,
+    /// Require a digit (env: PASSWORD_REQUIRE_DIGIT, default: false).
+    pub require_digit: bool,
+    /// Require a character that is neither a letter nor a digit
+    /// (env: PASSWORD_REQUIRE_SYMBOL, default: false).
+    pub require_symbol: bool,
+    /// Reject passwords containing the email's local part or the username
+    /// (env: PASSWORD_FORBID_PERSONAL_INFO, default: true).
+    pub forbid_personal_info: bool,
+    /// Number of previous passwords that cannot be reused, 0 disables the check
+    /// (env: PASSWORD_HISTORY_SIZE, default: 5).
+    pub history_size: i64,
+    /// Offline list of breached passwords: a file of SHA-1 hashes sorted by
+    /// hash, or a directory of SHA-1 prefix files (env: PASSWORD_BREACHED_LIST, default: unset).
+    pub breached_list: Option<String>,
+}
+
+impl PasswordPolicyConfig {
+    fn from_env() -> Self {
+        Self {
+            min_length: parse_env("PASSWORD_MIN_LENGTH", 8),
+            max_length: parse_env("PASSWORD_MAX_LENGTH", 128),
+            require_lowercase: parse_env("PASSWORD_REQUIRE_LOWERCASE", false),
+            require_uppercase: parse_env("PASSWORD_REQUIRE_UPPERCASE", false),
+            require_digit: parse_env("PASSWORD_REQUIRE_DIGIT", false),
+            require_symbol: parse_env("PASSWORD_REQUIRE_SYMBOL", false),
+            forbid_personal_info: parse_env("PASSWORD_FORBID_PERSONAL_INFO", true),
+            history_size: parse_env("PASSWORD_HISTORY_SIZE", 5),
+            breached_list: env::var("PASSWORD_BREACHED_LIST")
+                .ok()
+                .filter(|v| !v.is_empty()),
+        }
+    }
+}
+
+/// Argon2id cost and optional pepper for stored passwords
+///
+/// Hashes made with other parameters keep verifying and are upgraded on the
+/// next successful login.
+#[derive(Debug, Clone)]
+pub struct PasswordHashConfig {
+    /// Memory cost in KiB (env: ARGON2_MEMORY_KIB, default: 19456).
+    pub memory_kib: u32,
+    /// Number of passes (env: ARGON2_ITERATIONS, default: 2).
+    pub iterations: u32,
+    /// Degree of parallelism (env: ARGON2_PARALLELISM, default: 1).
+    pub parallelism: u32,
+    /// Server-side secret mixed into every hash and never stored with it
+    /// (env: PASSWORD_PEPPER, default: unset).
+    pub pepper: Option<String>,
+}
+
+impl PasswordHashConfig {
+    fn from_env() -> Self {
+        Self {
+            memory_kib: parse_env("ARGON2_MEMORY_KIB", 19 * 1024),
+            iterations: parse_env("ARGON2_ITERATIONS", 2),
+            parallelism: parse_env("ARGON2_PARALLELISM", 1),
+            pepper: env::var("PASSWORD_PEPPER").ok().filter(|v| !v.is_empty()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MagicLinkConfig {
+    /// Lifetime of a sign-in link (env: MAGIC_LINK_TTL_SECS, default: 600).
+    pub token_ttl_secs: i64,
+    /// Minimum delay between two sign-in emails for one account
+    /// (env: MAGIC_LINK_RESEND_SECS, default: 60).
+    pub resend_cooldown_secs: i64,
+}
+
+impl MagicLinkConfig {
+    fn from_env() -> Self {
+        Self {
+            token_ttl_secs: parse_env("MAGIC_LINK_TTL_SECS", 600),
+            resend_cooldown_secs: parse_env("MAGIC_LINK_RESEND_SECS", 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GeoIpConfig {
+    /// MaxMind DB file (GeoLite2 / GeoIP2 City or Country) used to locate
+    /// session IPs. Lookups are skipped when unset (env: GEOIP_DATABASE, default: unset).
+    pub database_path: Option<String>,
+}
+
+impl GeoIpConfig {
+    fn from_env() -> Self {
+        Self {
+            database_path: env::var("GEOIP_DATABASE").ok().filter(|v| !v.is_empty()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginAlertChannel {
+    /// New devices are flagged on the session, nobody is notified
+    Off,
+    /// Write the alert to the `security` log target (development)
+    Log,
+    /// Email the account owner
+    Email,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoginAlertConfig {
+    /// Where "new sign-in" alerts go
+    /// (env: LOGIN_ALERTS = "email" | "log" | "off", default: "email").
+    pub channel: LoginAlertChannel,
+}
+
+impl LoginAlertConfig {
+    fn from_env() -> Self {
+        let channel = match env::var("LOGIN_ALERTS")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "off" => LoginAlertChannel::Off,
+            "log" => LoginAlertChannel::Log,
+            _ => LoginAlertChannel::Email,
+        };
+
+        Self { channel }
+    }
+}
+
+/// Per-account failed login tracking
+///
+/// After `delay_after` consecutive failures every further attempt must wait
+/// `delay_base_secs * 2^(failures - delay_after)` seconds (capped at
+/// `delay_max_secs`); at `lockout_threshold` failures the account is locked.
+#[derive(Debug, Clone)]
+pub struct LoginThrottleConfig {
+    /// Failures before delays kick in (env: LOGIN_DELAY_AFTER, default: 3).
+    pub delay_after: u32,
+    /// First delay (env: LOGIN_DELAY_BASE_SECS, default: 1).
+    pub delay_base_secs: u64,
+    /// Upper bound for a single delay (env: LOGIN_DELAY_MAX_SECS, default: 60).
+    pub delay_max_secs: u64,
+    /// Failures that trigger a lockout, 0 disables it (env: LOGIN_LOCKOUT_THRESHOLD, default: 10).
+    pub lockout_threshold: u32,
+    /// Lockout duration (env: LOGIN_LOCKOUT_SECS, default: 900).
+    pub lockout_secs: u64,
+    /// Failures older than this are forgotten (env: LOGIN_ATTEMPT_WINDOW_SECS, default: 900).
+    pub window_secs: u64,
+}
+
+impl LoginThrottleConfig {
+    fn from_env() -> Self {
+        Self {
+            delay_after: parse_env("LOGIN_DELAY_AFTER", 3),
+            delay_base_secs: parse_env("LOGIN_DELAY_BASE_SECS", 1),
+            delay_max_secs: parse_env("LOGIN_DELAY_MAX_SECS", 60),
+            lockout_threshold: parse_env("LOGIN_LOCKOUT_THRESHOLD", 10),
+            lockout_secs: parse_env("LOGIN_LOCKOUT_SECS", 900),
+            window_secs: parse_env("LOGIN_ATTEMPT_WINDOW_SECS", 900),
+        }
+    }
+}
+
+/// Relying party settings for passkeys
+#[derive(Debug, Clone)]
+pub struct WebAuthnConfig {
+    /// Domain the passkeys are scoped to (env: WEBAUTHN_RP_ID, default: "localhost").
+    pub rp_id: String,
+    /// Name shown by the authenticator (env: WEBAUTHN_RP_NAME, default: "Quax").
+    pub rp_name: String,
+    /// Origins allowed to run ceremonies, comma separated
+    /// (env: WEBAUTHN_ORIGINS, default: FRONTEND_URL).
+    pub origins: Vec<String>,
+    /// Lifetime of a registration or login challenge
+    /// (env: WEBAUTHN_CHALLENGE_TTL_SECS, default: 300).
+    pub challenge_ttl_secs: i64,
+    /// Require the authenticator to verify the user (PIN, biometrics); a
+    /// verified passkey login then counts as multi-factor
+    /// (env: WEBAUTHN_REQUIRE_USER_VERIFICATION, default: true).
+    pub require_user_verification: bool,
+}
+
+impl WebAuthnConfig {
+    fn from_env(frontend_url: &str) -> Self {
+        let origins = env::var("WEBAUTHN_ORIGINS")
+            .unwrap_or_else(|_| frontend_url.to_string())
+            .split(',')
+            .map(|s| s.trim().trim_end_matches('/').to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        Self {
+            rp_id: env::var("WEBAUTHN_RP_ID").unwrap_or_else(|_| "localhost".to_string()),
+            rp_name: env::var("WEBAUTHN_RP_NAME").unwrap_or_else(|_| "Quax".to_string()),
+            origins,
+            challenge_ttl_secs: parse_env("WEBAUTHN_CHALLENGE_TTL_SECS", 300),
+            require_user_verification: parse_env("WEBAUTHN_REQUIRE_USER_VERIFICATION", true),
+        }
+    }
+}
+
+/// OpenID Connect provider for internal apps
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    /// `iss` of ID tokens and base of the discovery document; the public URL
+    /// of this API (env: OIDC_ISSUER, default: OAUTH_CALLBACK_BASE_URL).
+    pub issuer: String,
+    /// Frontend page that signs the user in and asks for consent, advertised
+    /// as the authorization endpoint
+    /// (env: OIDC_AUTHORIZE_URL, default: "<FRONTEND_URL>/oauth/authorize").
+    pub authorize_url: String,
+    /// Lifetime of an authorization code (env: OIDC_CODE_TTL_SECS, default: 60).
+    pub code_ttl_secs: i64,
+    /// Lifetime of a client refresh token; it also dies with the quax session
+    /// (env: OIDC_REFRESH_TOKEN_TTL_SECS, default: 2592000).
+    pub refresh_token_ttl_secs: i64,
+    /// Lifetime of an access token issued to an API key through the client
+    /// credentials grant (env: OIDC_SERVICE_TOKEN_TTL_SECS, default: 300).
+    pub service_token_ttl_secs: i64,
+}
+
+impl OidcConfig {
+    fn from_env(callback_base_url: &str, frontend_url: &str) -> Self {
+        Self {
+            issuer: env::var("OIDC_ISSUER")
+                .unwrap_or_else(|_| callback_base_url.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            authorize_url: env::var("OIDC_AUTHORIZE_URL").unwrap_or_else(|_| {
+                format!("{}/oauth/authorize", frontend_url.trim_end_matches('/'))
+            }),
+            code_ttl_secs: parse_env("OIDC_CODE_TTL_SECS", 60),
+            refresh_token_ttl_secs: parse_env("OIDC_REFRESH_TOKEN_TTL_SECS", 2592000),
+            service_token_ttl_secs: parse_env("OIDC_SERVICE_TOKEN_TTL_SECS", 300),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub rust_env: String,
@@ -166,7 +805,22 @@ pub struct Config {
     pub database: DatabaseConfig,
     pub redis_url: Option<String>,
     pub cookie: CookieConfig,
+    pub jwt: JwtConfig,
+    pub session: SessionConfig,
     pub upload: UploadConfig,
+    pub oauth: OAuthConfig,
+    pub mfa: MfaConfig,
+    pub mail: MailConfig,
+    pub email_verification: EmailVerificationConfig,
+    pub password_reset: PasswordResetConfig,
+    pub password_policy: PasswordPolicyConfig,
+    pub password_hash: PasswordHashConfig,
+    pub magic_link: MagicLinkConfig,
+    pub login_throttle: LoginThrottleConfig,
+    pub login_alerts: LoginAlertConfig,
+    pub geoip: GeoIpConfig,
+    pub webauthn: WebAuthnConfig,
+    pub oidc: OidcConfig,
 }
 
 impl Config {
@@ -174,13 +828,14 @@ impl Config {
         let rust_env = get_rust_env()?;
         let is_production = rust_env == "production";
 
-        // Validate required JWT secrets at startup (fail fast)
-        require_env("JWT_ACCESS_SECRET")?;
-        require_env("JWT_REFRESH_SECRET")?;
-
         // Redis is optional - if not configured, caching features will be disabled
         let redis_url = env::var("REDIS_URL").ok();
 
+        let mail = MailConfig::from_env()?;
+        let webauthn = WebAuthnConfig::from_env(&mail.frontend_url);
+        let oauth = OAuthConfig::from_env();
+        let oidc = OidcConfig::from_env(&oauth.callback_base_url, &mail.frontend_url);
+
         Ok(Self {
             rust_env,
             is_production,
@@ -188,7 +843,22 @@ impl Config {
             database: DatabaseConfig::from_env()?,
             redis_url,
             cookie: CookieConfig::from_env(is_production),
+            jwt: JwtConfig::from_env()?,
+            session: SessionConfig::from_env(),
             upload: UploadConfig::from_env(),
+            oauth,
+            mfa: MfaConfig::from_env(),
+            mail,
+            email_verification: EmailVerificationConfig::from_env(),
+            password_reset: PasswordResetConfig::from_env(),
+            password_policy: PasswordPolicyConfig::from_env(),
+            password_hash: PasswordHashConfig::from_env(),
+            magic_link: MagicLinkConfig::from_env(),
+            login_throttle: LoginThrottleConfig::from_env(),
+            login_alerts: LoginAlertConfig::from_env(),
+            geoip: GeoIpConfig::from_env(),
+            webauthn,
+            oidc,
         })
     }
 }
@@ -0,0 +1,128 @@
+use std::{net::IpAddr, sync::Arc};
+
+use maxminddb::{MaxMindDBError, Reader, geoip2};
+use serde::Serialize;
+
+use crate::infrastructure::config::GeoIpConfig;
+
+/// Language used for place names
+const NAME_LANGUAGE: &str = "en";
+
+/// Where an IP address is, as far as the GeoIP database knows
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GeoLocation {
+    pub city: Option<String>,
+    pub country: Option<String>,
+    /// ISO 3166-1 alpha-2
+    pub country_code: Option<String>,
+}
+
+impl GeoLocation {
+    /// Human-readable place, e.g. "Jakarta, Indonesia"
+    pub fn label(&self) -> Option<String> {
+        match (&self.city, &self.country) {
+            (Some(city), Some(country)) => Some(format!("{city}, {country}")),
+            (Some(place), None) | (None, Some(place)) => Some(place.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Offline IP geolocation against a MaxMind DB file (GeoLite2 / GeoIP2 City
+/// or Country), loaded once at startup. Without a database every lookup
+/// returns `None`.
+#[derive(Clone, Default)]
+pub struct GeoIp {
+    reader: Option<Arc<Reader<Vec<u8>>>>,
+}
+
+impl GeoIp {
+    /// Lookups always return `None`
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Loads the database, if one is configured
+    pub fn from_config(config: &GeoIpConfig) -> Result<Self, MaxMindDBError> {
+        let Some(path) = config.database_path.as_deref() else {
+            return Ok(Self::disabled());
+        };
+
+        let reader = Reader::open_readfile(path)?;
+        tracing::info!(
+            "GeoIP database loaded: {} (built {})",
+            reader.metadata.database_type,
+            reader.metadata.build_epoch
+        );
+        Ok(Self {
+            reader: Some(Arc::new(reader)),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.reader.is_some()
+    }
+
+    /// Locate an address as stored on sessions. For a forwarded chain
+    /// ("client, proxy1, ...") the client address is used, and a Postgres
+    /// `inet` suffix ("/32") is ignored. Private, unknown and malformed
+    /// addresses yield `None`.
+    pub fn lookup(&self, ip: &str) -> Option<GeoLocation> {
+        let reader = self.reader.as_ref()?;
+        let client = ip.split(',').next()?.trim();
+        let ip: IpAddr = client.split('/').next()?.parse().ok()?;
+
+        let city = match reader.lookup::<geoip2::City>(ip) {
+            Ok(city) => city,
+            Err(MaxMindDBError::AddressNotFoundError(_)) => return None,
+            Err(e) => {
+                tracing::warn!("GeoIP lookup failed for {}: {}", ip, e);
+                return None;
+            }
+        };
+
+        let name = |names: Option<std::collections::BTreeMap<&str, &str>>| {
+            names.and_then(|n| n.get(NAME_LANGUAGE).map(|s| s.to_string()))
+        };
+        let location = GeoLocation {
+            city: city.city.and_then(|c| name(c.names)),
+            country_code: city
+                .country
+                .as_ref()
+                .and_then(|c| c.iso_code)
+                .map(str::to_string),
+            country: city.country.and_then(|c| name(c.names)),
+        };
+
+        (location != GeoLocation::default()).then_some(location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_lookup() {
+        let geoip = GeoIp::from_config(&GeoIpConfig {
+            database_path: None,
+        })
+        .unwrap();
+        assert!(!geoip.is_enabled());
+        assert_eq!(geoip.lookup("203.0.113.7"), None);
+    }
+
+    #[test]
+    fn test_label() {
+        let mut location = GeoLocation {
+            city: Some("Jakarta".into()),
+            country: Some("Indonesia".into()),
+            country_code: Some("ID".into()),
+        };
+        assert_eq!(location.label().as_deref(), Some("Jakarta, Indonesia"));
+        location.city = None;
+        assert_eq!(location.label().as_deref(), Some("Indonesia"));
+        location.country = None;
+        assert_eq!(location.label(), None);
+    }
+}
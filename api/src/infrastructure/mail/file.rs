@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+use std::path::PathBuf;
+use tokio::fs;
+
+use super::provider::{Email, MailError, Mailer};
+
+/// Outbox backend: writes every message as a JSON file instead of sending it.
+///
+/// Used in development and tests — files land in `{outbox_dir}/{timestamp}-{uuid}.json`
+/// and can be inspected by hand or read back by integration tests.
+#[derive(Debug, Clone)]
+pub struct FileMailer {
+    outbox_dir: PathBuf,
+    from: String,
+}
+
+impl FileMailer {
+    pub fn new(outbox_dir: impl Into<PathBuf>, from: impl Into<String>) -> Self {
+        Self {
+            outbox_dir: outbox_dir.into(),
+            from: from.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        fs::create_dir_all(&self.outbox_dir).await?;
+
+        let file_name = format!(
+            "{}-{}.json",
+            chrono::Utc::now().format("%Y%m%dT%H%M%S%.6f"),
+            uuid::Uuid::new_v4().simple()
+        );
+        let path = self.outbox_dir.join(file_name);
+
+        let message = serde_json::json!({
+            "from": self.from,
+            "to": email.to,
+            "subject": email.subject,
+            "text_body": email.text_body,
+            "html_body": email.html_body,
+        });
+        let contents =
+            serde_json::to_vec_pretty(&message).map_err(|e| MailError::Transport(e.to_string()))?;
+        fs::write(&path, contents).await?;
+
+        tracing::info!("📧 Email to {} written to {}", email.to, path.display());
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+pub mod file;
+pub mod provider;
+pub mod smtp;
+
+use std::sync::Arc;
+
+pub use file::FileMailer;
+pub use provider::{Email, MailError, Mailer};
+pub use smtp::SmtpMailer;
+
+use crate::infrastructure::config::{MailConfig, MailTransport};
+
+/// Build the configured mail backend
+pub fn build_mailer(config: &MailConfig) -> Result<Arc<dyn Mailer>, MailError> {
+    Ok(match &config.transport {
+        MailTransport::File { outbox_dir } => {
+            Arc::new(FileMailer::new(outbox_dir, &config.from)) as Arc<dyn Mailer>
+        }
+        MailTransport::Smtp(smtp) => Arc::new(SmtpMailer::new(smtp, &config.from)?),
+    })
+}
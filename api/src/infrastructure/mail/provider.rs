@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use serde::Serialize;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MailError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid address: {0}")]
+    Address(String),
+
+    #[error("Transport error: {0}")]
+    Transport(String),
+}
+
+/// An outgoing message. The sender address comes from the mailer's configuration.
+#[derive(Debug, Clone, Serialize)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub text_body: String,
+    pub html_body: Option<String>,
+}
+
+/// Abstraction over any email delivery backend.
+///
+/// Implementations decide how the message leaves the process
+/// (SMTP relay, local outbox directory, third-party API, etc.).
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    /// Deliver `email`. Returns once the backend has accepted the message.
+    async fn send(&self, email: &Email) -> Result<(), MailError>;
+}
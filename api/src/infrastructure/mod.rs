@@ -1,6 +1,8 @@
 pub mod config;
 pub mod env;
+pub mod geoip;
 pub mod logging;
+pub mod mail;
 pub mod persistence;
 pub mod server;
 pub mod storage;
@@ -1,28 +1,118 @@
-use std::env;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use axum::{extract::Request, http::StatusCode, middleware::Next, response::Response};
+use axum::{
+    Extension,
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dashmap::DashMap;
+use uuid::Uuid;
 
-const API_KEY_HEADER: &str = "x-api-key";
+use crate::feature::{
+    admin::api_key::{repository::ApiKeyError, service::ApiKeyService},
+    auth::ServicePrincipal,
+};
 
-/// Middleware: validate X-Api-Key header against API_KEY env var
-pub async fn api_key_middleware(req: Request, next: Next) -> Result<Response, StatusCode> {
-    let expected = env::var("API_KEY").unwrap_or_default();
+pub(super) const API_KEY_HEADER: &str = "x-api-key";
 
-    if expected.is_empty() {
-        // API_KEY not configured — deny all to prevent accidental open access
-        tracing::warn!("API_KEY env var not set, denying request");
-        return Err(StatusCode::UNAUTHORIZED);
+const WINDOW: Duration = Duration::from_secs(60);
+
+struct Window {
+    count: u32,
+    reset_at: Instant,
+}
+
+/// Per-key request counter enforcing each key's `rate_limit_rpm`
+#[derive(Clone, Default)]
+pub struct ApiKeyRateLimiter {
+    map: Arc<DashMap<Uuid, Window>>,
+}
+
+impl ApiKeyRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
     }
 
+    fn is_allowed(&self, key_id: Uuid, rpm: u32) -> bool {
+        let now = Instant::now();
+        let mut entry = self.map.entry(key_id).or_insert_with(|| Window {
+            count: 0,
+            reset_at: now + WINDOW,
+        });
+
+        if now >= entry.reset_at {
+            entry.count = 1;
+            entry.reset_at = now + WINDOW;
+            true
+        } else if entry.count < rpm {
+            entry.count += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn cleanup(&self) {
+        let now = Instant::now();
+        self.map.retain(|_, v| v.reset_at > now);
+    }
+}
+
+/// Middleware: authenticate the `X-Api-Key` header against the `api_keys`
+/// table and inject a `ServicePrincipal` with the key's scopes. Combine with
+/// `require_scope` for per-route scopes.
+/// Returns 401 for missing, unknown, revoked or expired keys and 429 once the
+/// key exceeds its `rate_limit_rpm`.
+pub async fn api_key_middleware(
+    Extension(api_keys): Extension<ApiKeyService>,
+    Extension(limiter): Extension<ApiKeyRateLimiter>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, Response> {
     let provided = req
         .headers()
         .get(API_KEY_HEADER)
         .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| StatusCode::UNAUTHORIZED.into_response())?;
 
-    if !constant_time_eq::constant_time_eq(expected.as_bytes(), provided.as_bytes()) {
-        return Err(StatusCode::UNAUTHORIZED);
+    let key = api_keys
+        .validate_key(provided)
+        .await
+        .map_err(lookup_error)?;
+
+    if let Some(rpm) = key.rate_limit_rpm
+        && !limiter.is_allowed(key.id, rpm.max(0) as u32)
+    {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            "Rate limit exceeded. Please try again later.",
+        )
+            .into_response());
     }
 
+    // Only admitted requests count as uses
+    api_keys.record_use(key.id).await.map_err(lookup_error)?;
+
+    req.extensions_mut().insert(ServicePrincipal {
+        key_id: key.id,
+        scopes: key.scopes,
+    });
+
     Ok(next.run(req).await)
 }
+
+fn lookup_error(e: ApiKeyError) -> Response {
+    match e {
+        ApiKeyError::Database(e) => {
+            tracing::error!(error = %e, "API key lookup failed");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+        _ => StatusCode::UNAUTHORIZED.into_response(),
+    }
+}
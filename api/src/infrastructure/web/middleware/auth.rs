@@ -1,15 +1,34 @@
-use axum::{Extension, extract::Request, http::StatusCode, middleware::Next, response::Response};
+use axum::{
+    Extension,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
 use std::sync::Arc;
 
 use crate::{
-    feature::auth::{AuthUser, types::Role, utils::validate_access_token},
+    feature::{
+        admin::api_key::{repository::ApiKeyError, service::ApiKeyService},
+        auth::{
+            AuthUser,
+            session::SessionService,
+            types::{Claims, Role, ServicePrincipal},
+            utils::{JwtKeys, validate_access_token},
+        },
+    },
     infrastructure::persistence::redis_trait::SessionBlacklist,
 };
 
+use super::api_key::{API_KEY_HEADER, ApiKeyRateLimiter, api_key_middleware};
+
 /// Require valid JWT. Injects `AuthUser` into request extensions.
-/// Returns 401 if token is missing, invalid, or blacklisted.
+/// Returns 401 if token is missing, invalid, blacklisted, issued to an OIDC
+/// client or API key, or its session was revoked.
 pub async fn auth_middleware(
     Extension(blacklist): Extension<Option<Arc<dyn SessionBlacklist>>>,
+    Extension(jwt_keys): Extension<Arc<JwtKeys>>,
+    Extension(sessions): Extension<SessionService>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
@@ -23,7 +42,12 @@ pub async fn auth_middleware(
         .strip_prefix("Bearer ")
         .ok_or(StatusCode::UNAUTHORIZED)?;
 
-    let claims = validate_access_token(token).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let claims = validate_access_token(&jwt_keys, token).map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    // Tokens issued to OIDC clients and API keys are not good for the quax API itself
+    if claims.client_id.is_some() {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
 
     // Check if session is blacklisted (if Redis is configured)
     if let Some(ref blacklist) = blacklist {
@@ -37,13 +61,38 @@ pub async fn auth_middleware(
         }
     }
 
+    // Session still active? (cached; a no-op when SESSION_VALIDATE_ACCESS_TOKENS=false)
+    let session_active = sessions
+        .check_access(&claims.sid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !session_active {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
     let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let impersonator = impersonator(&claims)?;
+
+    if let Some(admin_id) = impersonator {
+        tracing::info!(
+            target: "security",
+            admin_id = %admin_id,
+            user_id = %user_id,
+            method = %request.method(),
+            path = %request.uri().path(),
+            "Impersonated request"
+        );
+    }
 
     request.extensions_mut().insert(AuthUser {
         user_id,
         email: String::new(), // not stored in claims; fetch from DB if needed
         roles: claims.roles,
         session_id: claims.sid,
+        session_iat: claims.s_iat,
+        auth_time: claims.auth_time,
+        impersonator,
     });
 
     Ok(next.run(request).await)
@@ -53,6 +102,8 @@ pub async fn auth_middleware(
 /// Injects `AuthUser` only when a valid token is present.
 pub async fn optional_auth_middleware(
     Extension(blacklist): Extension<Option<Arc<dyn SessionBlacklist>>>,
+    Extension(jwt_keys): Extension<Arc<JwtKeys>>,
+    Extension(sessions): Extension<SessionService>,
     mut request: Request,
     next: Next,
 ) -> Response {
@@ -61,7 +112,8 @@ pub async fn optional_auth_middleware(
         .get("Authorization")
         .and_then(|v| v.to_str().ok())
         .and_then(|v| v.strip_prefix("Bearer "))
-        && let Ok(claims) = validate_access_token(token)
+        && let Ok(claims) = validate_access_token(&jwt_keys, token)
+        && claims.client_id.is_none()
     {
         // Check blacklist if available
         let is_blacklisted = if let Some(ref blacklist) = blacklist {
@@ -70,12 +122,21 @@ pub async fn optional_auth_middleware(
             false
         };
 
-        if !is_blacklisted && let Ok(user_id) = uuid::Uuid::parse_str(&claims.sub) {
+        let session_active = sessions.check_access(&claims.sid).await.unwrap_or(true);
+
+        if !is_blacklisted
+            && session_active
+            && let Ok(user_id) = uuid::Uuid::parse_str(&claims.sub)
+            && let Ok(impersonator) = impersonator(&claims)
+        {
             request.extensions_mut().insert(AuthUser {
                 user_id,
                 email: String::new(),
                 roles: claims.roles,
                 session_id: claims.sid,
+                session_iat: claims.s_iat,
+                auth_time: claims.auth_time,
+                impersonator,
             });
         }
     }
@@ -83,6 +144,97 @@ pub async fn optional_auth_middleware(
     next.run(request).await
 }
 
+/// Require a client-credentials access token (API key exchanged at
+/// `/oauth/token`). Injects `ServicePrincipal` into request extensions.
+/// Returns 401 if the token is missing, invalid, blacklisted, belongs to a
+/// user, or its API key has since been revoked or expired.
+pub async fn service_auth_middleware(
+    Extension(blacklist): Extension<Option<Arc<dyn SessionBlacklist>>>,
+    Extension(jwt_keys): Extension<Arc<JwtKeys>>,
+    Extension(api_keys): Extension<ApiKeyService>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let token = request
+        .headers()
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let claims = validate_access_token(&jwt_keys, token).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let key_id = claims.service_key_id().ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if let Some(ref blacklist) = blacklist {
+        let is_blacklisted = blacklist
+            .is_blacklisted(&claims.jti)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        if is_blacklisted {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    api_keys.find_usable(key_id).await.map_err(|e| match e {
+        ApiKeyError::Database(e) => {
+            tracing::error!(error = %e, "API key lookup failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        _ => StatusCode::UNAUTHORIZED,
+    })?;
+
+    request.extensions_mut().insert(ServicePrincipal {
+        key_id,
+        scopes: claims
+            .scope
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect(),
+    });
+
+    Ok(next.run(request).await)
+}
+
+/// Accept either an `X-Api-Key` header (`api_key_middleware`) or a
+/// client-credentials access token (`service_auth_middleware`), for machine
+/// routes that serve both kinds of caller.
+pub async fn machine_auth_middleware(
+    Extension(blacklist): Extension<Option<Arc<dyn SessionBlacklist>>>,
+    Extension(jwt_keys): Extension<Arc<JwtKeys>>,
+    Extension(api_keys): Extension<ApiKeyService>,
+    Extension(limiter): Extension<ApiKeyRateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.headers().contains_key(API_KEY_HEADER) {
+        return api_key_middleware(Extension(api_keys), Extension(limiter), request, next)
+            .await
+            .unwrap_or_else(|response| response);
+    }
+
+    service_auth_middleware(
+        Extension(blacklist),
+        Extension(jwt_keys),
+        Extension(api_keys),
+        request,
+        next,
+    )
+    .await
+    .into_response()
+}
+
+/// Admin ID from the `act` claim of an impersonation token
+fn impersonator(claims: &Claims) -> Result<Option<uuid::Uuid>, StatusCode> {
+    claims
+        .act
+        .as_ref()
+        .map(|act| uuid::Uuid::parse_str(&act.sub).map_err(|_| StatusCode::UNAUTHORIZED))
+        .transpose()
+}
+
 /// Require `Role::Admin`. Must run AFTER `auth_middleware`.
 /// Returns 401 if no AuthUser, 403 if not admin.
 pub async fn admin_middleware(request: Request, next: Next) -> Result<Response, StatusCode> {
@@ -97,3 +249,25 @@ pub async fn admin_middleware(request: Request, next: Next) -> Result<Response,
 
     Ok(next.run(request).await)
 }
+
+/// Require a scope on the `ServicePrincipal`. Must run AFTER
+/// `service_auth_middleware`, `api_key_middleware` or
+/// `machine_auth_middleware`; attach with
+/// `middleware::from_fn_with_state("reports:read", require_scope)`.
+/// Returns 401 if no ServicePrincipal, 403 if the scope is missing.
+pub async fn require_scope(
+    State(scope): State<&'static str>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let principal = request
+        .extensions()
+        .get::<ServicePrincipal>()
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if !principal.has_scope(scope) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    Ok(next.run(request).await)
+}
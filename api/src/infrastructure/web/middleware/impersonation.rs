@@ -0,0 +1,38 @@
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    feature::auth::AuthUser,
+    infrastructure::web::response::{ApiError, codes::auth as auth_codes},
+};
+
+/// 403 `AUTH_021` for impersonated tokens. For handlers where only some
+/// requests must come from the account owner (e.g. a profile update that
+/// changes the email).
+pub fn ensure_not_impersonated(auth_user: &AuthUser) -> Result<(), ApiError> {
+    if !auth_user.is_impersonated() {
+        return Ok(());
+    }
+
+    Err(ApiError::default()
+        .with_code(StatusCode::FORBIDDEN)
+        .with_error_code(auth_codes::IMPERSONATION_FORBIDDEN)
+        .with_message("Not available while impersonating a user"))
+}
+
+/// Route layer form of [`ensure_not_impersonated`], for credential, MFA and
+/// API key changes. Must run AFTER `auth_middleware`.
+pub async fn forbid_impersonation(request: Request, next: Next) -> Response {
+    let Some(auth_user) = request.extensions().get::<AuthUser>() else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    match ensure_not_impersonated(auth_user) {
+        Ok(()) => next.run(request).await,
+        Err(e) => e.into_response(),
+    }
+}
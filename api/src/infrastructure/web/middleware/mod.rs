@@ -1,11 +1,22 @@
 pub mod api_key;
 pub mod auth;
 pub mod http_trace;
+pub mod impersonation;
+pub mod permission;
 pub mod rate_limit;
+pub mod reauth;
 pub mod request_id;
+pub mod verified_email;
 
-pub use api_key::api_key_middleware;
-pub use auth::{admin_middleware, auth_middleware, optional_auth_middleware};
+pub use api_key::{ApiKeyRateLimiter, api_key_middleware};
+pub use auth::{
+    admin_middleware, auth_middleware, machine_auth_middleware, optional_auth_middleware,
+    require_scope, service_auth_middleware,
+};
 pub use http_trace::http_trace_middleware;
+pub use impersonation::{ensure_not_impersonated, forbid_impersonation};
+pub use permission::require_permission;
 pub use rate_limit::{RateLimiter, rate_limit_middleware};
+pub use reauth::{ensure_recent_auth, require_recent_auth};
 pub use request_id::{RequestId, request_id_middleware};
+pub use verified_email::require_verified_email;
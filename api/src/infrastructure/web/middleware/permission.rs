@@ -0,0 +1,39 @@
+use axum::{
+    Extension,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::feature::auth::{AuthUser, rbac::RbacService};
+
+/// Require a permission from the user's roles. Must run AFTER
+/// `auth_middleware`; attach with
+/// `middleware::from_fn_with_state(permissions::USERS_WRITE, require_permission)`.
+/// Returns 401 if no AuthUser, 403 if none of the roles grants it.
+pub async fn require_permission(
+    State(permission): State<&'static str>,
+    Extension(rbac): Extension<RbacService>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let auth_user = request
+        .extensions()
+        .get::<AuthUser>()
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let allowed = rbac
+        .has_permission(&auth_user.roles, permission)
+        .await
+        .map_err(|e| {
+            tracing::error!("Permission lookup failed: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    if !allowed {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    Ok(next.run(request).await)
+}
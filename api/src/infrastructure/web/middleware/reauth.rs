@@ -0,0 +1,46 @@
+use axum::{
+    Extension,
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::{
+    feature::auth::AuthUser,
+    infrastructure::{
+        config::Config,
+        web::response::{ApiError, codes::auth as auth_codes},
+    },
+};
+
+/// 403 `AUTH_019` unless the user signed in or re-authenticated within
+/// `SESSION_REAUTH_MAX_AGE_SECS`. For handlers where only some requests are
+/// sensitive (e.g. a profile update that changes the email).
+pub fn ensure_recent_auth(auth_user: &AuthUser, config: &Config) -> Result<(), ApiError> {
+    if auth_user.authenticated_within(config.session.reauth_max_age_secs) {
+        return Ok(());
+    }
+
+    Err(ApiError::default()
+        .with_code(StatusCode::FORBIDDEN)
+        .with_error_code(auth_codes::REAUTH_REQUIRED)
+        .with_message("Please confirm your identity via /auth/reauthenticate to continue"))
+}
+
+/// Route layer form of [`ensure_recent_auth`]. Must run AFTER `auth_middleware`.
+pub async fn require_recent_auth(
+    Extension(config): Extension<Arc<Config>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(auth_user) = request.extensions().get::<AuthUser>() else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    match ensure_recent_auth(auth_user, &config) {
+        Ok(()) => next.run(request).await,
+        Err(e) => e.into_response(),
+    }
+}
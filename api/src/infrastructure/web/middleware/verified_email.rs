@@ -0,0 +1,47 @@
+use axum::{
+    Extension,
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::{
+    feature::auth::{AuthUser, verification::EmailVerificationService},
+    infrastructure::{
+        config::EmailVerificationRequirement,
+        web::response::{ApiError, codes::auth as auth_codes},
+    },
+};
+
+/// Reject users whose email address is not verified when
+/// `EMAIL_VERIFICATION_REQUIRED=routes`. A no-op under any other policy.
+/// Must run AFTER `auth_middleware`.
+pub async fn require_verified_email(
+    Extension(verification): Extension<Arc<EmailVerificationService>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if verification.requirement() != EmailVerificationRequirement::Routes {
+        return next.run(request).await;
+    }
+
+    let Some(auth_user) = request.extensions().get::<AuthUser>() else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    match verification.is_verified(auth_user.user_id).await {
+        Ok(true) => next.run(request).await,
+        Ok(false) => ApiError::default()
+            .with_code(StatusCode::FORBIDDEN)
+            .with_error_code(auth_codes::EMAIL_NOT_VERIFIED)
+            .with_message("Please verify your email address to continue")
+            .into_response(),
+        Err(e) => ApiError::default()
+            .with_code(StatusCode::INTERNAL_SERVER_ERROR)
+            .with_error_code(auth_codes::INTERNAL_ERROR)
+            .log_only(e)
+            .into_response(),
+    }
+}
@@ -21,6 +21,19 @@ pub mod auth {
     pub const API_KEY_INVALID: ErrorCode = ErrorCode("AUTH_007");
     pub const USER_NOT_FOUND: ErrorCode = ErrorCode("AUTH_008");
     pub const INTERNAL_ERROR: ErrorCode = ErrorCode("AUTH_009");
+    pub const MFA_INVALID_CODE: ErrorCode = ErrorCode("AUTH_010");
+    pub const MFA_NOT_ENABLED: ErrorCode = ErrorCode("AUTH_011");
+    pub const MFA_ALREADY_ENABLED: ErrorCode = ErrorCode("AUTH_012");
+    pub const EMAIL_NOT_VERIFIED: ErrorCode = ErrorCode("AUTH_013");
+    pub const ACCOUNT_LOCKED: ErrorCode = ErrorCode("AUTH_014");
+    pub const LOGIN_THROTTLED: ErrorCode = ErrorCode("AUTH_015");
+    pub const PASSKEY_REJECTED: ErrorCode = ErrorCode("AUTH_016");
+    pub const PASSKEY_EXISTS: ErrorCode = ErrorCode("AUTH_017");
+    pub const LAST_LOGIN_METHOD: ErrorCode = ErrorCode("AUTH_018");
+    pub const REAUTH_REQUIRED: ErrorCode = ErrorCode("AUTH_019");
+    pub const PASSWORD_ALREADY_SET: ErrorCode = ErrorCode("AUTH_020");
+    pub const IMPERSONATION_FORBIDDEN: ErrorCode = ErrorCode("AUTH_021");
+    pub const OIDC_CLIENT_INVALID: ErrorCode = ErrorCode("AUTH_022");
 }
 
 /// Validation errors
@@ -29,6 +42,7 @@ pub mod validation {
     pub const INVALID_INPUT: ErrorCode = ErrorCode("VAL_001");
     pub const MISSING_FIELD: ErrorCode = ErrorCode("VAL_002");
     pub const INVALID_FORMAT: ErrorCode = ErrorCode("VAL_003");
+    pub const PASSWORD_POLICY: ErrorCode = ErrorCode("VAL_004");
 }
 
 /// Generic errors
@@ -39,4 +53,5 @@ pub mod generic {
     pub const RATE_LIMITED: ErrorCode = ErrorCode("GEN_003");
     pub const FORBIDDEN: ErrorCode = ErrorCode("GEN_004");
     pub const INVALID_INPUT: ErrorCode = ErrorCode("GEN_005");
+    pub const CONFLICT: ErrorCode = ErrorCode("GEN_006");
 }
@@ -18,6 +18,8 @@ pub struct ApiError {
     pub error_code: Option<String>,
     pub message: String,
     pub details: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<serde_json::Value>,
     timestamp: i64,
 }
 
@@ -31,6 +33,7 @@ impl Default for ApiError {
             error_code: None,
             message: "An internal server error occurred".to_string(),
             details: None,
+            errors: None,
             timestamp: Utc::now().timestamp(),
         }
     }
@@ -64,6 +67,12 @@ impl ApiError {
         self
     }
 
+    /// Attach structured, per-field or per-rule errors the client can act on
+    pub fn with_errors(mut self, errors: impl Serialize) -> Self {
+        self.errors = serde_json::to_value(errors).ok();
+        self
+    }
+
     /// Log error server-side, return generic error to client
     pub fn log_only(self, details: impl Display) -> Self {
         error!(target: "api_error", details = %details, "Error occurred");
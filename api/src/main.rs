@@ -35,7 +35,7 @@ async fn main() -> eyre::Result<()> {
     info!("✅ Application state initialized");
 
     // 6. Bootstrap (create initial admin if needed)
-    bootstrap::bootstrap(&state.db, &config).await?;
+    bootstrap::bootstrap(&state.db, &config, state.jwt_keys.clone()).await?;
 
     // 7. Start server (dual-stack, graceful shutdown)
     server::serve(state).await
@@ -4,7 +4,7 @@ use tower_http::services::ServeDir;
 
 use crate::{
     feature::{admin, auth, health, user},
-    infrastructure::web::middleware::{RateLimiter, rate_limit_middleware},
+    infrastructure::web::middleware::{ApiKeyRateLimiter, RateLimiter, rate_limit_middleware},
     state::AppState,
 };
 
@@ -13,15 +13,19 @@ pub fn app_routes(state: AppState) -> Router {
     let global_limiter = RateLimiter::new(120, Duration::from_secs(60));
     // Auth: 10 req/min per IP (anti brute-force)
     let auth_limiter = RateLimiter::new(10, Duration::from_secs(60));
+    // API keys: each key's own rate_limit_rpm
+    let api_key_limiter = ApiKeyRateLimiter::new();
 
     // Cleanup expired entries every minute
     let g = global_limiter.clone();
     let a = auth_limiter.clone();
+    let k = api_key_limiter.clone();
     tokio::spawn(async move {
         loop {
             tokio::time::sleep(Duration::from_secs(60)).await;
             g.cleanup();
             a.cleanup();
+            k.cleanup();
         }
     });
 
@@ -32,17 +36,37 @@ pub fn app_routes(state: AppState) -> Router {
 
     // Provide session_blacklist to auth middleware
     let blacklist = state.session_blacklist.clone();
+    let email_verification = state.email_verification_service.clone();
+    let jwt_keys = state.jwt_keys.clone();
+    let sessions = state.auth_service.session_service().clone();
+    let rbac = state.rbac_service.clone();
+    let api_keys = state.api_key_service.clone();
+    let config = state.config.clone();
     let api_routes = Router::new()
         .nest("/auth", auth::auth_routes().merge(auth_sensitive))
+        .nest("/oauth", auth::oauth_routes())
         .nest("/users", user::user_routes())
         .nest("/admin", admin::routes::admin_routes())
         .nest("/admin/api-keys", admin::api_key::api_key_routes())
+        .nest("/admin/roles", admin::role::role_routes())
+        .nest(
+            "/admin/oidc-clients",
+            admin::oidc_client::oidc_client_routes(),
+        )
         .layer(Extension(blacklist)) // Inject blacklist for auth middleware
+        .layer(Extension(jwt_keys)) // Verification keys for auth middleware
+        .layer(Extension(sessions)) // Revoked-session check for auth middleware
+        .layer(Extension(rbac)) // Role permissions for require_permission
+        .layer(Extension(api_keys)) // Key lookup for api_key_middleware
+        .layer(Extension(api_key_limiter)) // Per-key limits for api_key_middleware
+        .layer(Extension(email_verification)) // For require_verified_email
+        .layer(Extension(config)) // Re-authentication window for require_recent_auth
         .layer(from_fn(rate_limit_middleware))
         .layer(Extension(global_limiter));
 
     Router::new()
         .nest("/health", health::health_routes())
+        .nest("/.well-known", auth::well_known_routes())
         .nest("/api/v1", api_routes)
         .nest_service("/media", ServeDir::new("uploads"))
         .fallback(handle_404)
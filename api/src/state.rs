@@ -5,13 +5,30 @@ use eyre::WrapErr;
 use crate::{
     feature::{
         admin::{
+            api_key::{repository::ApiKeyRepositoryImpl, service::ApiKeyService},
             stats::{StatsRepository, StatsRepositoryImpl, StatsService},
             user::{AdminUserRepository, AdminUserRepositoryImpl},
         },
         auth::{
+            action_token::{ActionTokenRepositoryImpl, ActionTokenService},
             auth_method::{AuthMethodRepositoryImpl, AuthMethodService},
+            login_alert::build_login_alert_notifier,
+            magic_link::MagicLinkService,
+            mfa::{MfaRepositoryImpl, MfaService},
+            oauth::{OAuthService, OAuthStateRepositoryImpl},
+            oidc::{OidcRepositoryImpl, OidcService},
+            password_policy::{PasswordHistoryRepositoryImpl, PasswordPolicy},
+            password_reset::PasswordResetService,
+            rbac::{RbacRepositoryImpl, RbacService},
+            security_event::{SecurityEventRepositoryImpl, SecurityEventService},
             service::AuthService,
             session::{SessionRepositoryImpl, SessionService},
+            throttle::{
+                LoginAttemptStore, LoginThrottle, PgLoginAttemptStore, RedisLoginAttemptStore,
+            },
+            utils::{JwtKeys, PasswordHasher},
+            verification::EmailVerificationService,
+            webauthn::{WebAuthnRepositoryImpl, WebAuthnService},
         },
         user::{
             UserProfileRepository, UserProfileRepositoryImpl, UserRepository, UserRepositoryImpl,
@@ -19,7 +36,9 @@ use crate::{
     },
     infrastructure::{
         config::Config,
+        geoip::GeoIp,
         logging::ReloadFilterHandle,
+        mail::{Mailer, build_mailer},
         persistence::{
             Database,
             redis::create_redis_pool,
@@ -34,12 +53,23 @@ pub struct AppState {
     pub config: Arc<Config>,
     pub db: Database,
     pub auth_service: Arc<AuthService>,
+    pub oauth_service: Arc<OAuthService>,
+    pub webauthn_service: Arc<WebAuthnService>,
+    pub email_verification_service: Arc<EmailVerificationService>,
+    pub password_reset_service: Arc<PasswordResetService>,
+    pub magic_link_service: Arc<MagicLinkService>,
+    pub oidc_service: Arc<OidcService>,
+    pub rbac_service: RbacService,
+    pub api_key_service: ApiKeyService,
     pub user_repo: Arc<dyn UserRepository>,
     pub user_profile_repo: Arc<dyn UserProfileRepository>,
     pub admin_user_repo: Arc<dyn AdminUserRepository>,
     pub stats_service: Arc<StatsService>,
     pub storage: Arc<dyn StorageProvider>,
+    pub mailer: Arc<dyn Mailer>,
+    pub geoip: GeoIp,
     pub session_blacklist: Option<Arc<dyn SessionBlacklist>>,
+    pub jwt_keys: Arc<JwtKeys>,
     pub log_reload_handle: Arc<ReloadFilterHandle>,
 }
 
@@ -63,6 +93,9 @@ impl AppState {
             .await
             .wrap_err("Failed to connect to database")?;
 
+        let jwt_keys =
+            Arc::new(JwtKeys::from_config(&config.jwt).wrap_err("Failed to load JWT keys")?);
+
         // Repositories
         let user_repo: Arc<dyn UserRepository> = Arc::new(UserRepositoryImpl::new());
         let user_profile_repo: Arc<dyn UserProfileRepository> =
@@ -74,17 +107,40 @@ impl AppState {
         let stats_repository: Arc<dyn StatsRepository> = Arc::new(StatsRepositoryImpl::new());
 
         // Services
-        let auth_method_service = AuthMethodService::new(db.clone(), auth_method_repo);
-        let session_service = SessionService::new(db.clone(), session_repo);
+        let password_hasher = PasswordHasher::from_config(&config.password_hash)
+            .map_err(|e| eyre::eyre!("Invalid password hashing settings: {e}"))?;
+        let password_policy = PasswordPolicy::new(
+            db.clone(),
+            Arc::new(PasswordHistoryRepositoryImpl::new()),
+            password_hasher.clone(),
+            Arc::new(config.clone()),
+        )
+        .wrap_err("Failed to load breached password list")?;
+        let auth_method_service = AuthMethodService::new(
+            db.clone(),
+            auth_method_repo,
+            password_hasher,
+            password_policy,
+        );
+        let geoip = GeoIp::from_config(&config.geoip).wrap_err("Failed to load GeoIP database")?;
+        let session_service = SessionService::new(
+            db.clone(),
+            session_repo,
+            Arc::new(config.clone()),
+            geoip.clone(),
+        );
+        let mfa_service = MfaService::new(
+            db.clone(),
+            Arc::new(MfaRepositoryImpl::new()),
+            Arc::new(config.clone()),
+        );
 
         // Initialize Redis if configured
-        let session_blacklist: Option<Arc<dyn SessionBlacklist>> = if let Some(ref _redis_url) =
-            config.redis_url
-        {
+        let redis_pool = if let Some(ref _redis_url) = config.redis_url {
             match create_redis_pool(&config).await {
                 Ok(pool) => {
                     tracing::info!("✅ Redis session blacklist enabled");
-                    Some(Arc::new(RedisSessionBlacklist::new(pool)) as Arc<dyn SessionBlacklist>)
+                    Some(pool)
                 }
                 Err(e) => {
                     tracing::warn!("⚠️  Redis not available (session blacklist disabled): {e}");
@@ -96,6 +152,18 @@ impl AppState {
             None
         };
 
+        let session_blacklist: Option<Arc<dyn SessionBlacklist>> = redis_pool
+            .clone()
+            .map(|pool| Arc::new(RedisSessionBlacklist::new(pool)) as Arc<dyn SessionBlacklist>);
+
+        // Failed login counters: Redis when available, Postgres otherwise
+        let login_attempt_store: Arc<dyn LoginAttemptStore> = match redis_pool {
+            Some(pool) => Arc::new(RedisLoginAttemptStore::new(pool)),
+            None => Arc::new(PgLoginAttemptStore::new(db.clone())),
+        };
+
+        let mailer = build_mailer(&config.mail).wrap_err("Failed to initialize mailer")?;
+
         let auth_service = Arc::new(AuthService::new(
             db.clone(),
             Arc::clone(&user_repo),
@@ -104,8 +172,71 @@ impl AppState {
             Arc::new(config.clone()),
             session_blacklist.clone(),
             session_service,
+            mfa_service,
+            SecurityEventService::new(
+                db.clone(),
+                Arc::new(SecurityEventRepositoryImpl::new()),
+                geoip.clone(),
+            ),
+            build_login_alert_notifier(&config.login_alerts, Arc::clone(&mailer)),
+            LoginThrottle::new(login_attempt_store, Arc::new(config.clone())),
+            Arc::clone(&jwt_keys),
+        ));
+
+        let oauth_service = Arc::new(OAuthService::new(
+            db.clone(),
+            Arc::new(OAuthStateRepositoryImpl::new()),
+            Arc::new(config.clone()),
+            Arc::clone(&auth_service),
+        ));
+        let webauthn_service = Arc::new(WebAuthnService::new(
+            db.clone(),
+            Arc::new(WebAuthnRepositoryImpl::new()),
+            Arc::clone(&user_repo),
+            Arc::new(config.clone()),
+            Arc::clone(&auth_service),
+        ));
+
+        let email_verification_service = Arc::new(EmailVerificationService::new(
+            db.clone(),
+            Arc::clone(&user_repo),
+            ActionTokenService::new(db.clone(), Arc::new(ActionTokenRepositoryImpl::new())),
+            Arc::clone(&mailer),
+            Arc::new(config.clone()),
+        ));
+        let password_reset_service = Arc::new(PasswordResetService::new(
+            db.clone(),
+            Arc::clone(&user_repo),
+            ActionTokenService::new(db.clone(), Arc::new(ActionTokenRepositoryImpl::new())),
+            auth_service.auth_method_service().clone(),
+            auth_service.session_service().clone(),
+            Arc::clone(&mailer),
+            Arc::new(config.clone()),
+        ));
+        let magic_link_service = Arc::new(MagicLinkService::new(
+            db.clone(),
+            Arc::clone(&user_repo),
+            ActionTokenService::new(db.clone(), Arc::new(ActionTokenRepositoryImpl::new())),
+            Arc::clone(&mailer),
+            Arc::new(config.clone()),
+            Arc::clone(&auth_service),
+        ));
+
+        let api_key_service = ApiKeyService::new(db.clone(), Arc::new(ApiKeyRepositoryImpl::new()));
+
+        let oidc_service = Arc::new(OidcService::new(
+            db.clone(),
+            Arc::new(OidcRepositoryImpl::new()),
+            Arc::clone(&user_repo),
+            api_key_service.clone(),
+            Arc::clone(&auth_service),
+            session_blacklist.clone(),
+            Arc::clone(&jwt_keys),
+            Arc::new(config.clone()),
         ));
 
+        let rbac_service = RbacService::new(db.clone(), Arc::new(RbacRepositoryImpl::new()));
+
         let stats_service = Arc::new(StatsService::new(stats_repository));
 
         let storage: Arc<dyn StorageProvider> = Arc::new(LocalStorage::new(
@@ -117,18 +248,31 @@ impl AppState {
             config: Arc::new(config),
             db,
             auth_service,
+            oauth_service,
+            webauthn_service,
+            email_verification_service,
+            password_reset_service,
+            magic_link_service,
+            oidc_service,
+            rbac_service,
+            api_key_service,
             user_repo,
             user_profile_repo,
             admin_user_repo,
             stats_service,
             storage,
+            mailer,
+            geoip,
             session_blacklist,
+            jwt_keys,
             log_reload_handle: Arc::new(log_reload_handle),
         })
     }
 
     /// Build AppState from an existing Database — used by integration tests
     pub fn new_for_test(config: Config, db: Database) -> Self {
+        let jwt_keys = Arc::new(JwtKeys::from_config(&config.jwt).expect("Invalid JWT keys"));
+
         use crate::infrastructure::storage::LocalStorage;
         use tracing_subscriber::{EnvFilter, Registry, reload};
 
@@ -143,12 +287,39 @@ impl AppState {
         let stats_repository: Arc<dyn StatsRepository> = Arc::new(StatsRepositoryImpl::new());
 
         // Services
-        let auth_method_service = AuthMethodService::new(db.clone(), auth_method_repo);
-        let session_service = SessionService::new(db.clone(), session_repo);
+        let password_hasher = PasswordHasher::from_config(&config.password_hash)
+            .expect("Invalid password hashing settings");
+        let password_policy = PasswordPolicy::new(
+            db.clone(),
+            Arc::new(PasswordHistoryRepositoryImpl::new()),
+            password_hasher.clone(),
+            Arc::new(config.clone()),
+        )
+        .expect("Invalid breached password list");
+        let auth_method_service = AuthMethodService::new(
+            db.clone(),
+            auth_method_repo,
+            password_hasher,
+            password_policy,
+        );
+        let geoip = GeoIp::from_config(&config.geoip).expect("Failed to load GeoIP database");
+        let session_service = SessionService::new(
+            db.clone(),
+            session_repo,
+            Arc::new(config.clone()),
+            geoip.clone(),
+        );
+        let mfa_service = MfaService::new(
+            db.clone(),
+            Arc::new(MfaRepositoryImpl::new()),
+            Arc::new(config.clone()),
+        );
 
         // No Redis in tests
         let session_blacklist: Option<Arc<dyn SessionBlacklist>> = None;
 
+        let mailer = build_mailer(&config.mail).expect("Failed to initialize mailer");
+
         let auth_service = Arc::new(AuthService::new(
             db.clone(),
             Arc::clone(&user_repo),
@@ -157,8 +328,74 @@ impl AppState {
             Arc::new(config.clone()),
             session_blacklist.clone(),
             session_service,
+            mfa_service,
+            SecurityEventService::new(
+                db.clone(),
+                Arc::new(SecurityEventRepositoryImpl::new()),
+                geoip.clone(),
+            ),
+            build_login_alert_notifier(&config.login_alerts, Arc::clone(&mailer)),
+            LoginThrottle::new(
+                Arc::new(PgLoginAttemptStore::new(db.clone())),
+                Arc::new(config.clone()),
+            ),
+            Arc::clone(&jwt_keys),
         ));
 
+        let oauth_service = Arc::new(OAuthService::new(
+            db.clone(),
+            Arc::new(OAuthStateRepositoryImpl::new()),
+            Arc::new(config.clone()),
+            Arc::clone(&auth_service),
+        ));
+        let webauthn_service = Arc::new(WebAuthnService::new(
+            db.clone(),
+            Arc::new(WebAuthnRepositoryImpl::new()),
+            Arc::clone(&user_repo),
+            Arc::new(config.clone()),
+            Arc::clone(&auth_service),
+        ));
+
+        let email_verification_service = Arc::new(EmailVerificationService::new(
+            db.clone(),
+            Arc::clone(&user_repo),
+            ActionTokenService::new(db.clone(), Arc::new(ActionTokenRepositoryImpl::new())),
+            Arc::clone(&mailer),
+            Arc::new(config.clone()),
+        ));
+        let password_reset_service = Arc::new(PasswordResetService::new(
+            db.clone(),
+            Arc::clone(&user_repo),
+            ActionTokenService::new(db.clone(), Arc::new(ActionTokenRepositoryImpl::new())),
+            auth_service.auth_method_service().clone(),
+            auth_service.session_service().clone(),
+            Arc::clone(&mailer),
+            Arc::new(config.clone()),
+        ));
+        let magic_link_service = Arc::new(MagicLinkService::new(
+            db.clone(),
+            Arc::clone(&user_repo),
+            ActionTokenService::new(db.clone(), Arc::new(ActionTokenRepositoryImpl::new())),
+            Arc::clone(&mailer),
+            Arc::new(config.clone()),
+            Arc::clone(&auth_service),
+        ));
+
+        let api_key_service = ApiKeyService::new(db.clone(), Arc::new(ApiKeyRepositoryImpl::new()));
+
+        let oidc_service = Arc::new(OidcService::new(
+            db.clone(),
+            Arc::new(OidcRepositoryImpl::new()),
+            Arc::clone(&user_repo),
+            api_key_service.clone(),
+            Arc::clone(&auth_service),
+            session_blacklist.clone(),
+            Arc::clone(&jwt_keys),
+            Arc::new(config.clone()),
+        ));
+
+        let rbac_service = RbacService::new(db.clone(), Arc::new(RbacRepositoryImpl::new()));
+
         let stats_service = Arc::new(StatsService::new(stats_repository));
 
         // Dummy reload handle — never called in tests
@@ -174,12 +411,23 @@ impl AppState {
             config: Arc::new(config),
             db,
             auth_service,
+            oauth_service,
+            webauthn_service,
+            email_verification_service,
+            password_reset_service,
+            magic_link_service,
+            oidc_service,
+            rbac_service,
+            api_key_service,
             user_repo,
             user_profile_repo,
             admin_user_repo,
             stats_service,
             storage,
+            mailer,
+            geoip,
             session_blacklist: None,
+            jwt_keys,
             log_reload_handle: Arc::new(handle),
         }
     }
@@ -0,0 +1,125 @@
+//! X-Api-Key authentication against the api_keys table
+
+mod common;
+
+use axum::{
+    Extension, Json, Router,
+    body::Body,
+    http::{Request, StatusCode},
+    middleware::{from_fn, from_fn_with_state},
+    routing::get,
+};
+use quax::{
+    feature::{admin::api_key::dto::UpdateApiKey, auth::ServicePrincipal},
+    infrastructure::web::middleware::{ApiKeyRateLimiter, api_key_middleware, require_scope},
+    state::AppState,
+};
+use serde_json::{Value, json};
+
+use common::*;
+
+/// Key-protected routes, the way a machine API would mount them
+fn machine_routes(state: &AppState) -> Router {
+    async fn whoami(Extension(principal): Extension<ServicePrincipal>) -> Json<Value> {
+        Json(json!({ "key_id": principal.key_id, "scopes": principal.scopes }))
+    }
+
+    Router::new()
+        .route(
+            "/reports",
+            get(whoami).route_layer(from_fn_with_state("reports:read", require_scope)),
+        )
+        .route(
+            "/reports/export",
+            get(whoami).route_layer(from_fn_with_state("reports:write", require_scope)),
+        )
+        .route_layer(from_fn(api_key_middleware))
+        .layer(Extension(state.api_key_service.clone()))
+        .layer(Extension(ApiKeyRateLimiter::new()))
+}
+
+async fn call(app: Router, uri: &str, key: Option<&str>) -> (StatusCode, Value) {
+    let mut builder = Request::builder().method("GET").uri(uri);
+    if let Some(key) = key {
+        builder = builder.header("x-api-key", key);
+    }
+    let (status, _, body) = raw_request(app, builder.body(Body::empty()).unwrap()).await;
+    (status, body)
+}
+
+#[tokio::test]
+async fn test_database_keys_carry_their_scopes() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let app = machine_routes(&state);
+    let key = create_api_key(&state, &["reports:read"]).await;
+
+    let (status, body) = call(app.clone(), "/reports", Some(&key)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["scopes"], json!(["reports:read"]));
+    let (status, _) = call(app.clone(), "/reports/export", Some(&key)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    for key in [None, Some(""), Some("ak_unknown")] {
+        let (status, _) = call(app.clone(), "/reports", key).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    // Every authenticated request is counted
+    let id = body["key_id"].as_str().unwrap().parse().unwrap();
+    let stored = state.api_key_service.get_key(id).await.unwrap().unwrap();
+    assert_eq!(stored.use_count, 2);
+    assert!(stored.last_used_at.is_some());
+
+    // Expired and revoked keys stop working immediately
+    sqlx::query("UPDATE api_keys SET expires_at = NOW() - INTERVAL '1 minute' WHERE id = $1")
+        .bind(id)
+        .execute(state.db.pool())
+        .await
+        .unwrap();
+    let (status, _) = call(app.clone(), "/reports", Some(&key)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let other = create_api_key(&state, &["reports:read"]).await;
+    let (status, body) = call(app.clone(), "/reports", Some(&other)).await;
+    assert_eq!(status, StatusCode::OK);
+    let other_id = body["key_id"].as_str().unwrap().parse().unwrap();
+    state.api_key_service.revoke_key(other_id).await.unwrap();
+    let (status, _) = call(app, "/reports", Some(&other)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_rate_limit_is_per_key() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let app = machine_routes(&state);
+    let limited = create_api_key(&state, &["reports:read"]).await;
+    let other = create_api_key(&state, &["reports:read"]).await;
+
+    let (_, body) = call(app.clone(), "/reports", Some(&limited)).await;
+    let id = body["key_id"].as_str().unwrap().parse().unwrap();
+    let payload = UpdateApiKey {
+        name: None,
+        scopes: None,
+        is_active: None,
+        rate_limit_rpm: Some(3),
+    };
+    state
+        .api_key_service
+        .update_key(id, &payload)
+        .await
+        .unwrap();
+
+    for _ in 0..2 {
+        let (status, _) = call(app.clone(), "/reports", Some(&limited)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, _) = call(app.clone(), "/reports", Some(&limited)).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+    // Refused requests are not counted as uses
+    let stored = state.api_key_service.get_key(id).await.unwrap().unwrap();
+    assert_eq!(stored.use_count, 3);
+
+    let (status, _) = call(app, "/reports", Some(&other)).await;
+    assert_eq!(status, StatusCode::OK);
+}
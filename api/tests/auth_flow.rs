@@ -6,6 +6,8 @@ use axum::{
 };
 use serde_json::json;
 
+use quax::{routes::app_routes, state::AppState};
+
 use common::*;
 
 // ─── Register ────────────────────────────────────────────────────────────────
@@ -66,14 +68,14 @@ async fn test_login_success() {
     post_json(
         app.clone(),
         "/api/v1/auth/register",
-        &json!({ "email": "bob@example.com", "name": "Bob", "password": "hunter2" }),
+        &json!({ "email": "bob@example.com", "name": "Bob", "password": "hunter22" }),
     )
     .await;
 
     let (status, body) = post_json(
         app,
         "/api/v1/auth/login",
-        &json!({ "email": "bob@example.com", "password": "hunter2" }),
+        &json!({ "email": "bob@example.com", "password": "hunter22" }),
     )
     .await;
 
@@ -89,7 +91,7 @@ async fn test_login_wrong_password() {
     post_json(
         app.clone(),
         "/api/v1/auth/register",
-        &json!({ "email": "carol@example.com", "name": "Carol", "password": "correct" }),
+        &json!({ "email": "carol@example.com", "name": "Carol", "password": "correct-horse" }),
     )
     .await;
 
@@ -118,6 +120,57 @@ async fn test_login_nonexistent_user() {
     assert_eq!(status, StatusCode::UNAUTHORIZED);
 }
 
+#[tokio::test]
+async fn test_login_upgrades_outdated_password_hash() {
+    let (old, _c) = build_test_state_with(|c| c.password_hash.memory_kib = 8 * 1024).await;
+    let credentials = json!({ "email": "dave@example.com", "password": "password123" });
+    let (status, _) = post_json(
+        app_routes(old.clone()),
+        "/api/v1/auth/register",
+        &json!({ "email": "dave@example.com", "name": "Dave", "password": "password123" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Same database, stronger parameters and a pepper
+    let mut config = (*old.config).clone();
+    config.password_hash.memory_kib = 16 * 1024;
+    config.password_hash.pepper = Some("test-pepper".to_string());
+    let current = AppState::new_for_test(config, old.db.clone());
+
+    let stored_hash = || async {
+        sqlx::query_scalar::<_, String>(
+            "SELECT password_hash FROM auth_methods WHERE provider = 'password'",
+        )
+        .fetch_one(current.db.pool())
+        .await
+        .unwrap()
+    };
+    assert!(stored_hash().await.contains("m=8192,"));
+
+    let (status, _) = post_json(
+        app_routes(current.clone()),
+        "/api/v1/auth/login",
+        &credentials,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let upgraded = stored_hash().await;
+    assert!(upgraded.contains("m=16384,"), "{upgraded}");
+    assert!(upgraded.contains("keyid="), "{upgraded}");
+
+    // The upgraded hash needs the pepper
+    let (status, _) = post_json(
+        app_routes(current.clone()),
+        "/api/v1/auth/login",
+        &credentials,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = post_json(app_routes(old), "/api/v1/auth/login", &credentials).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
 // ─── Protected endpoints ─────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -226,6 +279,285 @@ async fn test_refresh_without_cookie() {
     assert_eq!(status, StatusCode::UNAUTHORIZED);
 }
 
+/// Presenting a rotated refresh token again revokes the whole session
+#[tokio::test]
+async fn test_refresh_token_reuse_revokes_session() {
+    let (app, _c) = build_test_app().await;
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/register")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "email": "reuse@example.com", "name": "Reuse", "password": "pass1234" })
+                .to_string(),
+        ))
+        .unwrap();
+    let (status, headers, body) = raw_request(app.clone(), req).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let access_token = body["data"]["token"]["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let first_cookie = extract_set_cookie(&headers, "refresh_token").unwrap();
+
+    let refresh = |cookie: String| {
+        let app = app.clone();
+        async move {
+            let req = Request::builder()
+                .method("POST")
+                .uri("/api/v1/auth/refresh")
+                .header(header::COOKIE, cookie)
+                .body(Body::empty())
+                .unwrap();
+            raw_request(app, req).await
+        }
+    };
+
+    // Normal rotation
+    let (status, headers, _) = refresh(first_cookie.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    let second_cookie = extract_set_cookie(&headers, "refresh_token").unwrap();
+    assert_ne!(first_cookie, second_cookie);
+
+    // Replay of the rotated token is detected (no Redis needed)
+    let (status, _, body) = refresh(first_cookie).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error_code"], "AUTH_004");
+
+    // The legitimate (latest) token is dead too — the whole session was revoked
+    let (status, _, _) = refresh(second_cookie).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // ...and so is its access token
+    let (status, _) = get_authed(app, "/api/v1/auth/sessions", &access_token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+/// Revoking a session kills its access token right away, without Redis
+#[tokio::test]
+async fn test_revoked_session_rejects_access_token() {
+    let (app, _c) = build_test_app().await;
+
+    let body = json!({ "email": "revoke@example.com", "name": "Revoke", "password": "pass1234" });
+    let (status, _) = post_json(app.clone(), "/api/v1/auth/register", &body).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let login = || {
+        let app = app.clone();
+        let body = body.clone();
+        async move {
+            let (status, resp) = post_json(app, "/api/v1/auth/login", &body).await;
+            assert_eq!(status, StatusCode::OK);
+            resp["data"]["token"]["access_token"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        }
+    };
+    let (laptop, phone, tablet) = (login().await, login().await, login().await);
+
+    // Warms the session cache for the laptop token, too
+    let (status, sessions) = get_authed(app.clone(), "/api/v1/auth/sessions", &laptop).await;
+    assert_eq!(status, StatusCode::OK);
+    let laptop_session = sessions["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["is_current"] == true)
+        .map(|s| s["id"].as_str().unwrap().to_string())
+        .unwrap();
+
+    // The phone revokes the laptop's session
+    let req = Request::builder()
+        .method("DELETE")
+        .uri(format!("/api/v1/auth/sessions/{laptop_session}"))
+        .header(header::AUTHORIZATION, format!("Bearer {phone}"))
+        .body(Body::empty())
+        .unwrap();
+    let (status, _, _) = raw_request(app.clone(), req).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", &laptop).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", &tablet).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Logging out everywhere else leaves only the phone
+    let req = Request::builder()
+        .method("DELETE")
+        .uri("/api/v1/auth/sessions")
+        .header(header::AUTHORIZATION, format!("Bearer {phone}"))
+        .body(Body::empty())
+        .unwrap();
+    let (status, _, _) = raw_request(app.clone(), req).await;
+    assert_eq!(status, StatusCode::OK);
+
+    for token in [&laptop, &tablet] {
+        let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", token).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+    let (status, _) = get_authed(app, "/api/v1/auth/me", &phone).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+// ─── Password ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_change_password_can_revoke_other_sessions() {
+    let (app, _c) = build_test_app().await;
+
+    let body = json!({ "email": "rotate@example.com", "name": "Rotate", "password": "pass1234" });
+    let (status, _) = post_json(app.clone(), "/api/v1/auth/register", &body).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let login = |password: &'static str| {
+        let app = app.clone();
+        async move {
+            let (status, resp) = post_json(
+                app,
+                "/api/v1/auth/login",
+                &json!({ "email": "rotate@example.com", "password": password }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            resp["data"]["token"]["access_token"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        }
+    };
+    let (laptop, phone) = (login("pass1234").await, login("pass1234").await);
+
+    // Without the flag other devices stay signed in
+    let (status, resp) = post_json_authed(
+        app.clone(),
+        "/api/v1/auth/change-password",
+        &laptop,
+        &json!({ "current_password": "pass1234", "new_password": "pass5678" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(resp["data"]["revoked_sessions"], 0);
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", &phone).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, resp) = post_json_authed(
+        app.clone(),
+        "/api/v1/auth/change-password",
+        &laptop,
+        &json!({
+            "current_password": "pass5678",
+            "new_password": "pass9012",
+            "revoke_other_sessions": true,
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(resp["data"]["revoked_sessions"], 2);
+
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", &phone).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", &laptop).await;
+    assert_eq!(status, StatusCode::OK);
+    login("pass9012").await;
+}
+
+#[tokio::test]
+async fn test_set_password_requires_recent_sign_in() {
+    let (app, _c) = build_test_app_with(|c| c.session.reauth_max_age_secs = 0).await;
+
+    let body = json!({ "email": "stale@example.com", "name": "Stale", "password": "pass1234" });
+    let (_, resp) = post_json(app.clone(), "/api/v1/auth/register", &body).await;
+    let token = resp["data"]["token"]["access_token"].as_str().unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    let (status, resp) = post_json_authed(
+        app,
+        "/api/v1/auth/set-password",
+        token,
+        &json!({ "new_password": "another-pass" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(resp["error_code"], "AUTH_019");
+}
+
+/// Email changes need a recent authentication, restored by re-authenticating
+#[tokio::test]
+async fn test_reauthenticate_unlocks_email_change() {
+    let (app, _c) = build_test_app_with(|c| c.session.reauth_max_age_secs = 1).await;
+
+    let body = json!({ "email": "stepup@example.com", "name": "Step Up", "password": "pass1234" });
+    let (_, resp) = post_json(app.clone(), "/api/v1/auth/register", &body).await;
+    let token = resp["data"]["token"]["access_token"].as_str().unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+
+    // Only the email change is gated
+    let (status, resp) = patch_authed(
+        app.clone(),
+        "/api/v1/users/me",
+        token,
+        &json!({ "email": "moved@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(resp["error_code"], "AUTH_019");
+    let (status, _) = patch_authed(
+        app.clone(),
+        "/api/v1/users/me",
+        token,
+        &json!({ "name": "Renamed" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    for (proof, expected) in [
+        (json!({}), StatusCode::BAD_REQUEST),
+        (
+            json!({ "password": "pass1234", "code": "123456" }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            json!({ "password": "wrong-pass" }),
+            StatusCode::UNAUTHORIZED,
+        ),
+    ] {
+        let (status, _) =
+            post_json_authed(app.clone(), "/api/v1/auth/reauthenticate", token, &proof).await;
+        assert_eq!(status, expected);
+    }
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/reauthenticate")
+        .header(header::AUTHORIZATION, format!("Bearer {token}"))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "password": "pass1234" }).to_string()))
+        .unwrap();
+    let (status, headers, resp) = raw_request(app.clone(), req).await;
+    assert_eq!(status, StatusCode::OK);
+    let fresh = resp["data"]["access_token"].as_str().unwrap();
+    let refresh_cookie = extract_set_cookie(&headers, "refresh_token").unwrap();
+
+    let (status, resp) = patch_authed(
+        app.clone(),
+        "/api/v1/users/me",
+        fresh,
+        &json!({ "email": "moved@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(resp["data"]["email"], "moved@example.com");
+
+    // The session carries on with the rotated refresh token
+    let (status, _) =
+        post_json_with_cookie(app, "/api/v1/auth/refresh", &json!({}), &refresh_cookie).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
 // ─── Full auth flow ───────────────────────────────────────────────────────────
 
 /// register → get_me → update_me → refresh → get_me with new token
@@ -0,0 +1,250 @@
+//! OAuth2 client credentials grant: API keys exchanged for service tokens
+
+mod common;
+
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json, Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    middleware::{from_fn, from_fn_with_state},
+    routing::get,
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use quax::{
+    feature::{
+        admin::api_key::{repository::ApiKeyRepositoryImpl, service::ApiKeyService},
+        auth::ServicePrincipal,
+    },
+    infrastructure::web::middleware::{require_scope, service_auth_middleware},
+    routes::app_routes,
+    state::AppState,
+};
+use serde_json::{Value, json};
+
+use common::*;
+
+fn api_keys(state: &AppState) -> ApiKeyService {
+    ApiKeyService::new(state.db.clone(), Arc::new(ApiKeyRepositoryImpl::new()))
+}
+
+/// POST a form to `/api/v1/oauth/{endpoint}`
+async fn post_form(
+    state: &AppState,
+    endpoint: &str,
+    form: &str,
+    basic: Option<(&str, &str)>,
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri(format!("/api/v1/oauth/{endpoint}"))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+    if endpoint == "introspect" {
+        let key = create_api_key(state, &["tokens:introspect"]).await;
+        builder = builder.header("x-api-key", key);
+    }
+    if let Some((id, secret)) = basic {
+        let credentials = STANDARD.encode(format!("{id}:{secret}"));
+        builder = builder.header(header::AUTHORIZATION, format!("Basic {credentials}"));
+    }
+    let req = builder.body(Body::from(form.to_string())).unwrap();
+    let (status, _, body) = raw_request(app_routes(state.clone()), req).await;
+    (status, body)
+}
+
+/// Service-only routes, the way a machine API would mount them
+fn machine_routes(state: &AppState) -> Router {
+    async fn whoami(Extension(principal): Extension<ServicePrincipal>) -> Json<Value> {
+        Json(json!({ "key_id": principal.key_id, "scopes": principal.scopes }))
+    }
+
+    Router::new()
+        .route(
+            "/reports",
+            get(whoami).route_layer(from_fn_with_state("reports:read", require_scope)),
+        )
+        .route(
+            "/reports/export",
+            get(whoami).route_layer(from_fn_with_state("reports:write", require_scope)),
+        )
+        .route_layer(from_fn(service_auth_middleware))
+        .layer(Extension(state.session_blacklist.clone()))
+        .layer(Extension(state.jwt_keys.clone()))
+        .layer(Extension(state.api_key_service.clone()))
+}
+
+/// Exchange a key for a service token carrying all of its scopes
+async fn service_token(state: &AppState, key_id: &str, key: &str) -> String {
+    let (status, body) = post_form(
+        state,
+        "token",
+        "grant_type=client_credentials",
+        Some((key_id, key)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    body["access_token"].as_str().unwrap().to_string()
+}
+
+/// Introspect `token` as a caller holding the service token `bearer`
+async fn introspect_as(state: &AppState, bearer: &str, token: &str) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/oauth/introspect")
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .header(header::AUTHORIZATION, format!("Bearer {bearer}"))
+        .body(Body::from(format!("token={token}")))
+        .unwrap();
+    let (status, _, body) = raw_request(app_routes(state.clone()), req).await;
+    (status, body)
+}
+
+#[tokio::test]
+async fn test_api_key_exchanged_for_scoped_service_token() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let key = api_keys(&state)
+        .generate_key(
+            "reporting",
+            vec!["reports:read".into(), "reports:write".into()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let key_id = key.id.to_string();
+
+    // All of the key's scopes by default, no refresh token
+    let (status, body) = post_form(
+        &state,
+        "token",
+        "grant_type=client_credentials",
+        Some((&key_id, &key.key)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["token_type"], "Bearer");
+    assert_eq!(body["expires_in"], 300);
+    assert_eq!(body["scope"], "reports:read reports:write");
+    assert!(body["refresh_token"].is_null() && body["id_token"].is_null());
+    let full = body["access_token"].as_str().unwrap().to_string();
+
+    // Narrowed scope, key sent as client_secret
+    let form = format!(
+        "grant_type=client_credentials&client_secret={}&scope=reports:read",
+        key.key
+    );
+    let (status, body) = post_form(&state, "token", &form, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["scope"], "reports:read");
+    let read_only = body["access_token"].as_str().unwrap().to_string();
+
+    let form = format!(
+        "grant_type=client_credentials&client_secret={}&scope=admin:full",
+        key.key
+    );
+    let (status, body) = post_form(&state, "token", &form, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_scope");
+
+    // Routes behind service_auth_middleware see the principal and its scopes
+    let machine = machine_routes(&state);
+    let (status, body) = get_authed(machine.clone(), "/reports/export", &full).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["key_id"], key_id);
+    let (status, _) = get_authed(machine.clone(), "/reports", &read_only).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = get_authed(machine.clone(), "/reports/export", &read_only).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // ...while service and user tokens stay on their own side
+    let (status, _) = get_authed(app_routes(state.clone()), "/api/v1/auth/me", &full).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (_, body) = post_json(
+        app_routes(state.clone()),
+        "/api/v1/auth/register",
+        &json!({ "email": "person@example.com", "name": "Someone", "password": "password123" }),
+    )
+    .await;
+    let user_token = body["data"]["token"]["access_token"].as_str().unwrap();
+    let (status, _) = get_authed(machine, "/reports", user_token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Introspection reports the service principal, without a session
+    let (status, body) = post_form(&state, "introspect", &format!("token={full}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["active"], true);
+    assert_eq!(body["sub"], format!("service:{key_id}"));
+    assert_eq!(body["client_id"], key_id);
+    assert_eq!(body["scope"], "reports:read reports:write");
+    assert!(body["sid"].is_null());
+}
+
+#[tokio::test]
+async fn test_invalid_or_revoked_keys_are_rejected() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let service = api_keys(&state);
+    let key = service
+        .generate_key("billing", vec!["billing:read".into()], None, None)
+        .await
+        .unwrap();
+    let other = service
+        .generate_key("other", vec![], None, None)
+        .await
+        .unwrap();
+
+    for (id, secret) in [
+        (key.id.to_string(), "ak_wrong".to_string()),
+        (other.id.to_string(), key.key.clone()),
+    ] {
+        let (status, body) = post_form(
+            &state,
+            "token",
+            "grant_type=client_credentials",
+            Some((&id, &secret)),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "invalid_client");
+    }
+
+    service.revoke_key(key.id).await.unwrap();
+    let form = format!("grant_type=client_credentials&client_secret={}", key.key);
+    let (status, body) = post_form(&state, "token", &form, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "invalid_client");
+}
+
+#[tokio::test]
+async fn test_service_tokens_reach_introspection_until_key_revoked() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let service = api_keys(&state);
+    let introspector = service
+        .generate_key("gateway", vec!["tokens:introspect".into()], None, None)
+        .await
+        .unwrap();
+    let reporter = service
+        .generate_key("reporting", vec!["reports:read".into()], None, None)
+        .await
+        .unwrap();
+    let gateway = service_token(&state, &introspector.id.to_string(), &introspector.key).await;
+    let reports = service_token(&state, &reporter.id.to_string(), &reporter.key).await;
+
+    let (status, body) = introspect_as(&state, &gateway, &reports).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["active"], true);
+    let (status, _) = introspect_as(&state, &reports, &gateway).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Revoking a key ends the tokens it was exchanged for
+    let machine = machine_routes(&state);
+    let (status, _) = get_authed(machine.clone(), "/reports", &reports).await;
+    assert_eq!(status, StatusCode::OK);
+    service.revoke_key(reporter.id).await.unwrap();
+    let (status, _) = get_authed(machine, "/reports", &reports).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    service.revoke_key(introspector.id).await.unwrap();
+    let (status, _) = introspect_as(&state, &gateway, &reports).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
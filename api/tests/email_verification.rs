@@ -0,0 +1,260 @@
+//! Email verification: outbox delivery, verify/resend endpoints, enforcement modes
+
+mod common;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+
+use quax::infrastructure::config::EmailVerificationRequirement;
+
+use common::*;
+
+const EMAIL: &str = "verify@example.com";
+const PASSWORD: &str = "password123";
+
+async fn register(app: Router) -> Value {
+    let (status, body) = post_json(
+        app,
+        "/api/v1/auth/register",
+        &json!({ "email": EMAIL, "name": "Verify User", "password": PASSWORD }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    body
+}
+
+async fn verify(app: Router, token: &str) -> StatusCode {
+    let (status, _) = post_json(app, "/api/v1/auth/verify-email", &json!({ "token": token })).await;
+    status
+}
+
+async fn delete_avatar(app: Router, access: &str) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("DELETE")
+        .uri("/api/v1/users/avatar")
+        .header(header::AUTHORIZATION, format!("Bearer {access}"))
+        .body(Body::empty())
+        .unwrap();
+    let (status, _, body) = raw_request(app, req).await;
+    (status, body)
+}
+
+#[tokio::test]
+async fn test_register_sends_verification_email() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| outbox = Some(use_test_outbox(c))).await;
+    let outbox = outbox.unwrap();
+
+    let body = register(app.clone()).await;
+    assert_eq!(body["data"]["user"]["email_verified"], false);
+    let access = body["data"]["token"]["access_token"].as_str().unwrap();
+
+    let messages = read_outbox(&outbox);
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["to"], EMAIL);
+    let token = extract_token(&messages[0]);
+
+    // Resend inside the cooldown window does not send another message
+    let (status, _) = post_json(
+        app.clone(),
+        "/api/v1/auth/resend-verification",
+        &json!({ "email": EMAIL }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(read_outbox(&outbox).len(), 1);
+
+    assert_eq!(verify(app.clone(), &token).await, StatusCode::OK);
+
+    let (status, me) = get_authed(app.clone(), "/api/v1/auth/me", access).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(me["data"]["email_verified"], true);
+
+    // Single use
+    assert_eq!(verify(app.clone(), &token).await, StatusCode::BAD_REQUEST);
+    assert_eq!(verify(app, "not-a-token").await, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_resend_replaces_previous_token() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| {
+        outbox = Some(use_test_outbox(c));
+        c.email_verification.resend_cooldown_secs = 0;
+    })
+    .await;
+    let outbox = outbox.unwrap();
+
+    register(app.clone()).await;
+
+    // Unknown addresses get the same answer and no mail
+    let (status, unknown) = post_json(
+        app.clone(),
+        "/api/v1/auth/resend-verification",
+        &json!({ "email": "nobody@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(read_outbox(&outbox).len(), 1);
+
+    let (status, known) = post_json(
+        app.clone(),
+        "/api/v1/auth/resend-verification",
+        &json!({ "email": EMAIL }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(unknown["message"], known["message"]);
+
+    let messages = read_outbox(&outbox);
+    assert_eq!(messages.len(), 2);
+    let old_token = extract_token(&messages[0]);
+    let new_token = extract_token(&messages[1]);
+
+    assert_eq!(
+        verify(app.clone(), &old_token).await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(verify(app.clone(), &new_token).await, StatusCode::OK);
+
+    // Already verified: nothing more is sent
+    let (status, _) = post_json(
+        app,
+        "/api/v1/auth/resend-verification",
+        &json!({ "email": EMAIL }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(read_outbox(&outbox).len(), 2);
+}
+
+#[tokio::test]
+async fn test_login_blocked_until_verified() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| {
+        outbox = Some(use_test_outbox(c));
+        c.email_verification.required = EmailVerificationRequirement::Login;
+    })
+    .await;
+    let outbox = outbox.unwrap();
+
+    // No session is started for an unverified account
+    let body = register(app.clone()).await;
+    assert_eq!(body["data"]["verification_required"], true);
+    assert!(body["data"].get("token").is_none());
+
+    let credentials = json!({ "email": EMAIL, "password": PASSWORD });
+    let (status, body) = post_json(app.clone(), "/api/v1/auth/login", &credentials).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error_code"], "AUTH_013");
+
+    // Wrong password still reports invalid credentials, not the verification state
+    let (status, body) = post_json(
+        app.clone(),
+        "/api/v1/auth/login",
+        &json!({ "email": EMAIL, "password": "wrong-password" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error_code"], "AUTH_001");
+
+    let token = extract_token(&read_outbox(&outbox)[0]);
+    assert_eq!(verify(app.clone(), &token).await, StatusCode::OK);
+
+    let (status, body) = post_json(app, "/api/v1/auth/login", &credentials).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]["token"]["access_token"].is_string());
+}
+
+#[tokio::test]
+async fn test_routes_policy_guards_user_routes() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| {
+        outbox = Some(use_test_outbox(c));
+        c.email_verification.required = EmailVerificationRequirement::Routes;
+    })
+    .await;
+    let outbox = outbox.unwrap();
+
+    let body = register(app.clone()).await;
+    let access = body["data"]["token"]["access_token"].as_str().unwrap();
+
+    let (status, body) = delete_avatar(app.clone(), access).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error_code"], "AUTH_013");
+
+    // The profile and auth routes stay reachable so the client can show the
+    // verification state and fix a mistyped address
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", access).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = get_authed(app.clone(), "/api/v1/users/me", access).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = patch_authed(
+        app.clone(),
+        "/api/v1/users/me",
+        access,
+        &json!({ "email": "fixed@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let messages = read_outbox(&outbox);
+    assert_eq!(messages[1]["to"], "fixed@example.com");
+    assert_eq!(
+        verify(app.clone(), &extract_token(&messages[1])).await,
+        StatusCode::OK
+    );
+
+    let (status, _) = delete_avatar(app, access).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_changed_email_must_be_verified_again() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| outbox = Some(use_test_outbox(c))).await;
+    let outbox = outbox.unwrap();
+
+    let body = register(app.clone()).await;
+    let access = body["data"]["token"]["access_token"].as_str().unwrap();
+    let token = extract_token(&read_outbox(&outbox)[0]);
+    assert_eq!(verify(app.clone(), &token).await, StatusCode::OK);
+
+    // Same address in another case keeps the verification
+    let (status, _) = patch_authed(
+        app.clone(),
+        "/api/v1/users/me",
+        access,
+        &json!({ "email": EMAIL.to_uppercase() }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, me) = get_authed(app.clone(), "/api/v1/auth/me", access).await;
+    assert_eq!(me["data"]["email_verified"], true);
+    assert_eq!(read_outbox(&outbox).len(), 1);
+
+    let (status, _) = patch_authed(
+        app.clone(),
+        "/api/v1/users/me",
+        access,
+        &json!({ "email": "moved@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, me) = get_authed(app.clone(), "/api/v1/auth/me", access).await;
+    assert_eq!(me["data"]["email_verified"], false);
+
+    let messages = read_outbox(&outbox);
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1]["to"], "moved@example.com");
+    assert_eq!(
+        verify(app.clone(), &extract_token(&messages[1])).await,
+        StatusCode::OK
+    );
+    let (_, me) = get_authed(app, "/api/v1/auth/me", access).await;
+    assert_eq!(me["data"]["email_verified"], true);
+}
@@ -0,0 +1,109 @@
+//! Offline GeoIP enrichment of sessions and security events
+
+mod common;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use quax::{feature::auth::Role, infrastructure::config::LoginAlertChannel};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use common::*;
+
+const EMAIL: &str = "geo@example.com";
+const PASSWORD: &str = "password123";
+
+/// Register or log in from `ip`; returns the response body
+async fn sign_in(app: Router, uri: &str, ip: &str, user_agent: &str) -> Value {
+    let body = json!({ "email": EMAIL, "name": "Geo", "password": PASSWORD });
+    let req = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-forwarded-for", ip)
+        .header(header::USER_AGENT, user_agent)
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let (status, _, body) = raw_request(app, req).await;
+    assert!(status.is_success(), "{uri} failed: {body}");
+    body
+}
+
+#[tokio::test]
+async fn test_sessions_and_events_are_located() {
+    let db = std::env::temp_dir().join(format!("quax-geoip-{}.mmdb", Uuid::new_v4()));
+    write_test_geoip_db(&db, [203, 0, 113], "Jakarta", "Indonesia", "ID");
+
+    let (app, _c) = build_test_app_with(|c| {
+        c.geoip.database_path = Some(db.to_string_lossy().into_owned());
+        c.login_alerts.channel = LoginAlertChannel::Off;
+        // The minted admin token has no session
+        c.session.validate_access_tokens = false;
+    })
+    .await;
+
+    let body = sign_in(app.clone(), "/api/v1/auth/register", "198.51.100.1", "curl").await;
+    let user_id = body["data"]["user"]["id"].as_str().unwrap().to_string();
+    let body = sign_in(app.clone(), "/api/v1/auth/login", "203.0.113.7", "Firefox").await;
+    let token = body["data"]["token"]["access_token"].as_str().unwrap();
+
+    let (_, sessions) = get_authed(app.clone(), "/api/v1/auth/sessions", token).await;
+    let located: Vec<_> = sessions["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["location"].as_str().unwrap())
+        .collect();
+    assert!(located.contains(&"Jakarta, Indonesia"));
+    // Addresses the database does not know keep the fallback
+    assert!(located.contains(&"Unknown Location"));
+
+    let admin = mint_access_token(Uuid::new_v4(), &[Role::Admin]);
+    let (status, sessions) = get_authed(
+        app.clone(),
+        &format!("/api/v1/admin/users/{user_id}/sessions"),
+        &admin,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let jakarta = sessions["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["ip"].as_str().unwrap().starts_with("203.0.113.7"))
+        .expect("located session");
+    assert_eq!(jakarta["location"], "Jakarta, Indonesia");
+    assert_eq!(jakarta["country_code"], "ID");
+    assert_eq!(jakarta["new_device"], true);
+
+    // The new-device event carries the location too
+    let (status, events) = get_authed(
+        app,
+        &format!("/api/v1/admin/users/{user_id}/security-events"),
+        &admin,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let event = &events["data"][0];
+    assert_eq!(event["event_type"], "new_device_sign_in");
+    assert_eq!(event["details"]["location"], "Jakarta, Indonesia");
+    assert_eq!(event["details"]["country_code"], "ID");
+
+    std::fs::remove_file(db).ok();
+}
+
+#[tokio::test]
+async fn test_sessions_without_geoip_database() {
+    let (app, _c) = build_test_app().await;
+
+    let body = sign_in(app.clone(), "/api/v1/auth/register", "203.0.113.7", "curl").await;
+    let token = body["data"]["token"]["access_token"].as_str().unwrap();
+
+    let (status, sessions) = get_authed(app, "/api/v1/auth/sessions", token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(sessions["data"][0]["location"], "Unknown Location");
+    assert!(sessions["data"][0]["country_code"].is_null());
+}
@@ -0,0 +1,193 @@
+//! Admin impersonation: act-as tokens, refused routes and the audit trail
+
+mod common;
+
+use axum::http::StatusCode;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use quax::{routes::app_routes, state::AppState};
+use serde_json::{Value, json};
+
+use common::*;
+
+const PASSWORD: &str = "password123";
+
+/// Register a user; returns (user id, access token)
+async fn register(state: &AppState, email: &str) -> (String, String) {
+    let body = json!({ "email": email, "name": "Someone", "password": PASSWORD });
+    let (status, body) = post_json(app_routes(state.clone()), "/api/v1/auth/register", &body).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    (
+        body["data"]["user"]["id"].as_str().unwrap().to_string(),
+        body["data"]["token"]["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string(),
+    )
+}
+
+/// An admin with a real session; returns (admin id, access token)
+async fn admin(state: &AppState) -> (String, String) {
+    let (id, _) = register(state, "admin@example.com").await;
+    sqlx::query("UPDATE users SET role = 'admin' WHERE email = 'admin@example.com'")
+        .execute(state.db.pool())
+        .await
+        .unwrap();
+    let body = json!({ "email": "admin@example.com", "password": PASSWORD });
+    let (status, body) = post_json(app_routes(state.clone()), "/api/v1/auth/login", &body).await;
+    assert_eq!(status, StatusCode::OK);
+    let token = body["data"]["token"]["access_token"].as_str().unwrap();
+    (id, token.to_string())
+}
+
+fn claims(token: &str) -> Value {
+    let payload = token.split('.').nth(1).unwrap();
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_admin_acts_as_user() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let app = app_routes(state.clone());
+    let (admin_id, admin_token) = admin(&state).await;
+    let (user_id, _) = register(&state, "customer@example.com").await;
+
+    let (status, body) = post_json_authed(
+        app.clone(),
+        &format!("/api/v1/admin/users/{user_id}/impersonate"),
+        &admin_token,
+        &json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["data"]["user"]["email"], "customer@example.com");
+    let token = body["data"]["access_token"].as_str().unwrap().to_string();
+
+    let claims = claims(&token);
+    assert_eq!(claims["sub"], user_id.as_str());
+    assert_eq!(claims["act"]["sub"], admin_id.as_str());
+
+    // The app as the user sees it
+    let (status, me) = get_authed(app.clone(), "/api/v1/auth/me", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(me["data"]["email"], "customer@example.com");
+    let (_, sessions) = get_authed(app.clone(), "/api/v1/auth/sessions", &token).await;
+    let current = sessions["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["is_current"] == true)
+        .unwrap();
+    assert_eq!(current["impersonated"], true);
+
+    // Owner-only operations are refused
+    let (status, body) = post_json_authed(
+        app.clone(),
+        "/api/v1/auth/change-password",
+        &token,
+        &json!({ "current_password": PASSWORD, "new_password": "new-password-1" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error_code"], "AUTH_021");
+    let (status, _) =
+        post_json_authed(app.clone(), "/api/v1/auth/mfa/enroll", &token, &json!({})).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // The start is audited on the user's account
+    let (_, events) = get_authed(
+        app.clone(),
+        &format!("/api/v1/admin/users/{user_id}/security-events"),
+        &admin_token,
+    )
+    .await;
+    let event = &events["data"][0];
+    assert_eq!(event["event_type"], "impersonation_started");
+    assert_eq!(event["details"]["admin_id"], admin_id.as_str());
+
+    // Logging out ends the impersonation only
+    let (status, _) =
+        post_json_authed(app.clone(), "/api/v1/auth/logout", &token, &json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", &token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = get_authed(app, "/api/v1/auth/me", &admin_token).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_admins_cannot_be_impersonated() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let app = app_routes(state.clone());
+    let (admin_id, admin_token) = admin(&state).await;
+
+    let (status, _) = post_json_authed(
+        app.clone(),
+        &format!("/api/v1/admin/users/{admin_id}/impersonate"),
+        &admin_token,
+        &json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (other_id, _) = register(&state, "second-admin@example.com").await;
+    sqlx::query("UPDATE users SET role = 'admin' WHERE id = $1::uuid")
+        .bind(&other_id)
+        .execute(state.db.pool())
+        .await
+        .unwrap();
+    let (status, body) = post_json_authed(
+        app,
+        &format!("/api/v1/admin/users/{other_id}/impersonate"),
+        &admin_token,
+        &json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["message"], "Cannot impersonate an administrator");
+}
+
+#[tokio::test]
+async fn test_impersonation_cannot_be_chained() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let app = app_routes(state.clone());
+    let (_, admin_token) = admin(&state).await;
+
+    // A custom role that may impersonate, held by the user being impersonated
+    sqlx::query("INSERT INTO roles (name) VALUES ('support')")
+        .execute(state.db.pool())
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO role_permissions (role, permission) VALUES ('support', 'users:impersonate')",
+    )
+    .execute(state.db.pool())
+    .await
+    .unwrap();
+    let (agent_id, _) = register(&state, "agent@example.com").await;
+    sqlx::query("UPDATE users SET role = 'support' WHERE id = $1::uuid")
+        .bind(&agent_id)
+        .execute(state.db.pool())
+        .await
+        .unwrap();
+    let (customer_id, _) = register(&state, "customer@example.com").await;
+
+    let (status, body) = post_json_authed(
+        app.clone(),
+        &format!("/api/v1/admin/users/{agent_id}/impersonate"),
+        &admin_token,
+        &json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let token = body["data"]["access_token"].as_str().unwrap();
+
+    let (status, body) = post_json_authed(
+        app,
+        &format!("/api/v1/admin/users/{customer_id}/impersonate"),
+        token,
+        &json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error_code"], "AUTH_021");
+}
@@ -0,0 +1,116 @@
+//! Asymmetric access tokens, key rotation and the JWKS endpoint
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use axum::http::StatusCode;
+use jsonwebtoken::{DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
+use ring::{rand::SystemRandom, signature::Ed25519KeyPair};
+use serde_json::json;
+use uuid::Uuid;
+
+use common::*;
+use quax::{
+    feature::auth::{
+        Claims, Role,
+        utils::{JwtKeys, create_token_pair},
+    },
+    infrastructure::config::Config,
+};
+
+/// Key directory with a retiring RSA key ("2025-01") and a new Ed25519 key ("2025-02")
+fn key_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("quax-jwks-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+
+    fs::copy(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/jwt-rs256.pem"),
+        dir.join("2025-01.pem"),
+    )
+    .unwrap();
+
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    let pem = pem::encode(&pem::Pem::new("PRIVATE KEY", pkcs8.as_ref().to_vec()));
+    fs::write(dir.join("2025-02.pem"), pem).unwrap();
+
+    dir
+}
+
+fn use_keys(config: &mut Config, dir: &std::path::Path, active_kid: &str) {
+    config.jwt.keys_dir = Some(dir.to_string_lossy().into_owned());
+    config.jwt.active_kid = Some(active_kid.to_string());
+}
+
+#[tokio::test]
+async fn test_jwks_verifies_access_tokens() {
+    let dir = key_dir();
+    let (app, _c) = build_test_app_with(|c| use_keys(c, &dir, "2025-02")).await;
+
+    let (status, body) = post_json(
+        app.clone(),
+        "/api/v1/auth/register",
+        &json!({ "email": "jwks@example.com", "name": "Jwks User", "password": "password123" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let access_token = body["data"]["token"]["access_token"].as_str().unwrap();
+    let user_id = body["data"]["user"]["id"].as_str().unwrap();
+
+    let header = decode_header(access_token).unwrap();
+    assert_eq!(header.alg, jsonwebtoken::Algorithm::EdDSA);
+    assert_eq!(header.kid.as_deref(), Some("2025-02"));
+
+    // Published without the API envelope, one entry per key
+    let (status, _, jwks) = get(app.clone(), "/.well-known/jwks.json", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let jwks: JwkSet = serde_json::from_value(jwks).unwrap();
+    assert_eq!(jwks.keys.len(), 2);
+    assert!(jwks.find("2025-01").is_some());
+
+    // A third party can verify the token from the JWKS alone
+    let jwk = jwks.find("2025-02").unwrap();
+    let claims = decode::<Claims>(
+        access_token,
+        &DecodingKey::from_jwk(jwk).unwrap(),
+        &Validation::new(jsonwebtoken::Algorithm::EdDSA),
+    )
+    .unwrap()
+    .claims;
+    assert_eq!(claims.sub, user_id);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_tokens_from_rotated_key_still_verify() {
+    let dir = key_dir();
+    let (app, _c) = build_test_app_with(|c| {
+        use_keys(c, &dir, "2025-02");
+        // Minted tokens below have no session row
+        c.session.validate_access_tokens = false;
+    })
+    .await;
+
+    // Token signed before rotation, by the previous (RSA) key
+    let mut config = Config::load().unwrap();
+    use_keys(&mut config, &dir, "2025-01");
+    let old_keys = JwtKeys::from_config(&config.jwt).unwrap();
+    let old_token = create_token_pair(&old_keys, Uuid::new_v4(), "", &[Role::Admin])
+        .unwrap()
+        .access_token;
+    assert_eq!(
+        decode_header(&old_token).unwrap().alg,
+        jsonwebtoken::Algorithm::RS256
+    );
+
+    let (status, _) = get_authed(app.clone(), "/api/v1/admin/users", &old_token).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // HS256 tokens are no longer accepted once signing keys are configured
+    let hs_token = mint_access_token(Uuid::new_v4(), &[Role::Admin]);
+    let (status, _) = get_authed(app.clone(), "/api/v1/admin/users", &hs_token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,125 @@
+//! New-device detection and "new sign-in" alerts with a revoke link
+
+mod common;
+
+use std::path::Path;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use quax::infrastructure::config::LoginAlertChannel;
+use serde_json::{Value, json};
+
+use common::*;
+
+const EMAIL: &str = "alerts@example.com";
+const PASSWORD: &str = "password123";
+
+const FIREFOX_LINUX: &str =
+    "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) \
+     AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1";
+
+fn alert_emails(outbox: &Path) -> Vec<Value> {
+    read_outbox(outbox)
+        .into_iter()
+        .filter(|m| m["subject"] == "New sign-in to your account")
+        .collect()
+}
+
+/// Register or log in with a user agent; returns the access token
+async fn sign_in(app: Router, uri: &str, user_agent: &str) -> String {
+    let body = json!({ "email": EMAIL, "name": "Alerts", "password": PASSWORD });
+    let req = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::USER_AGENT, user_agent)
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let (status, _, body) = raw_request(app, req).await;
+    assert!(status.is_success(), "{uri} failed: {body}");
+    body["data"]["token"]["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+fn current_session(sessions: &Value) -> &Value {
+    sessions["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["is_current"] == true)
+        .expect("current session")
+}
+
+#[tokio::test]
+async fn test_new_device_alert_revokes_session() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| outbox = Some(use_test_outbox(c))).await;
+    let outbox = outbox.unwrap();
+
+    // Neither the first session nor a known device raise an alert
+    sign_in(app.clone(), "/api/v1/auth/register", FIREFOX_LINUX).await;
+    let known = sign_in(app.clone(), "/api/v1/auth/login", FIREFOX_LINUX).await;
+    assert!(alert_emails(&outbox).is_empty());
+
+    let token = sign_in(app.clone(), "/api/v1/auth/login", SAFARI_IPHONE).await;
+    let alerts = alert_emails(&outbox);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0]["to"], EMAIL);
+    assert!(
+        alerts[0]["text_body"]
+            .as_str()
+            .unwrap()
+            .contains("Safari on")
+    );
+
+    let (_, sessions) = get_authed(app.clone(), "/api/v1/auth/sessions", &token).await;
+    let current = current_session(&sessions);
+    assert_eq!(current["new_device"], true);
+    assert_eq!(current["device"], "Safari on iOS");
+    assert_eq!(current["os_version"], "17.5");
+    assert_eq!(current["device_type"], "mobile");
+    let (_, sessions) = get_authed(app.clone(), "/api/v1/auth/sessions", &known).await;
+    assert_eq!(current_session(&sessions)["new_device"], false);
+
+    // The link signs out the new device only, and works once
+    let link = json!({ "token": extract_token(&alerts[0]) });
+    let (status, _) = post_json(app.clone(), "/api/v1/auth/sessions/revoke", &link).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", &token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", &known).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = post_json(app.clone(), "/api/v1/auth/sessions/revoke", &link).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error_code"], "AUTH_004");
+
+    // The device stays known after being revoked
+    sign_in(app, "/api/v1/auth/login", SAFARI_IPHONE).await;
+    assert_eq!(alert_emails(&outbox).len(), 1);
+}
+
+#[tokio::test]
+async fn test_new_device_flagged_without_notification() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| {
+        outbox = Some(use_test_outbox(c));
+        c.login_alerts.channel = LoginAlertChannel::Off;
+    })
+    .await;
+    let outbox = outbox.unwrap();
+
+    sign_in(app.clone(), "/api/v1/auth/register", FIREFOX_LINUX).await;
+    let token = sign_in(app.clone(), "/api/v1/auth/login", SAFARI_IPHONE).await;
+
+    let (_, sessions) = get_authed(app, "/api/v1/auth/sessions", &token).await;
+    assert_eq!(current_session(&sessions)["new_device"], true);
+    assert!(alert_emails(&outbox).is_empty());
+}
@@ -0,0 +1,125 @@
+//! Per-account failed login throttling and admin unlock
+
+mod common;
+
+use axum::{Router, http::StatusCode};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use common::*;
+use quax::feature::auth::Role;
+
+const EMAIL: &str = "throttle@example.com";
+const PASSWORD: &str = "password123";
+
+async fn register(app: Router) -> Uuid {
+    let (status, body) = post_json(
+        app,
+        "/api/v1/auth/register",
+        &json!({ "email": EMAIL, "name": "Throttle User", "password": PASSWORD }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    body["data"]["user"]["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+async fn login(app: Router, password: &str) -> (StatusCode, Value) {
+    post_json(
+        app,
+        "/api/v1/auth/login",
+        &json!({ "email": EMAIL, "password": password }),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_lockout_and_admin_unlock() {
+    let (app, _c) = build_test_app_with(|c| {
+        c.login_throttle.delay_after = 100;
+        c.login_throttle.lockout_threshold = 3;
+        // Minted tokens below have no session row
+        c.session.validate_access_tokens = false;
+    })
+    .await;
+    let user_id = register(app.clone()).await;
+
+    for _ in 0..2 {
+        let (status, _) = login(app.clone(), "wrong-password").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    // The failure that reaches the threshold locks the account
+    let (status, body) = login(app.clone(), "wrong-password").await;
+    assert_eq!(status, StatusCode::LOCKED);
+    assert_eq!(body["error_code"], "AUTH_014");
+
+    // Even the right password is refused while locked
+    let (status, body) = login(app.clone(), PASSWORD).await;
+    assert_eq!(status, StatusCode::LOCKED);
+    assert_eq!(body["error_code"], "AUTH_014");
+
+    // Only admins may unlock
+    let user_token = mint_access_token(user_id, &[Role::User]);
+    let uri = format!("/api/v1/admin/users/{user_id}/unlock");
+    let (status, _) = post_json_authed(app.clone(), &uri, &user_token, &json!({})).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let admin_token = mint_access_token(Uuid::new_v4(), &[Role::Admin]);
+    let (status, _) = post_json_authed(app.clone(), &uri, &admin_token, &json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = login(app.clone(), PASSWORD).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_progressive_delay_after_failures() {
+    let (app, _c) = build_test_app_with(|c| {
+        c.login_throttle.delay_after = 2;
+        c.login_throttle.delay_base_secs = 60;
+        c.login_throttle.lockout_threshold = 0;
+    })
+    .await;
+    register(app.clone()).await;
+
+    for _ in 0..2 {
+        let (status, _) = login(app.clone(), "wrong-password").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    // Delayed regardless of the password, and the email's case doesn't matter
+    let (status, body) = post_json(
+        app.clone(),
+        "/api/v1/auth/login",
+        &json!({ "email": EMAIL.to_uppercase(), "password": PASSWORD }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(body["error_code"], "AUTH_015");
+}
+
+#[tokio::test]
+async fn test_successful_login_clears_failures() {
+    let (app, _c) = build_test_app_with(|c| {
+        c.login_throttle.delay_after = 100;
+        c.login_throttle.lockout_threshold = 3;
+    })
+    .await;
+    register(app.clone()).await;
+
+    for _ in 0..2 {
+        login(app.clone(), "wrong-password").await;
+    }
+    let (status, _) = login(app.clone(), PASSWORD).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Counter restarted: two more failures stay below the threshold
+    for _ in 0..2 {
+        let (status, _) = login(app.clone(), "wrong-password").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
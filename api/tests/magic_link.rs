@@ -0,0 +1,135 @@
+//! Passwordless sign-in via emailed, device-bound magic links
+
+mod common;
+
+use std::path::Path;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+
+use common::*;
+
+const EMAIL: &str = "magic@example.com";
+const PASSWORD: &str = "password123";
+
+/// Sign-in link emails, once `total` messages arrived (registration also
+/// sends a verification email)
+async fn link_emails(outbox: &Path, total: usize) -> Vec<Value> {
+    wait_for_outbox(outbox, total)
+        .await
+        .into_iter()
+        .filter(|m| m["subject"] == "Your sign-in link")
+        .collect()
+}
+
+/// Request a link; returns the response message and the device cookie
+async fn request_link(app: Router, email: &str) -> (Value, String) {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/magic-link")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "email": email }).to_string()))
+        .unwrap();
+    let (status, headers, body) = raw_request(app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let cookie = extract_set_cookie(&headers, "magic_link_device").expect("device cookie");
+    (body["message"].clone(), cookie)
+}
+
+async fn verify(
+    app: Router,
+    token: &str,
+    cookie: Option<&str>,
+) -> (StatusCode, axum::http::HeaderMap, Value) {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/magic-link/verify")
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(c) = cookie {
+        builder = builder.header(header::COOKIE, c);
+    }
+    let req = builder
+        .body(Body::from(json!({ "token": token }).to_string()))
+        .unwrap();
+    raw_request(app, req).await
+}
+
+async fn register(app: Router) {
+    let (status, _) = post_json(
+        app,
+        "/api/v1/auth/register",
+        &json!({ "email": EMAIL, "name": "Magic User", "password": PASSWORD }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn test_magic_link_sign_in() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| outbox = Some(use_test_outbox(c))).await;
+    let outbox = outbox.unwrap();
+    register(app.clone()).await;
+
+    // Same answer for unknown and known addresses; only the real one gets mail
+    let (unknown, _) = request_link(app.clone(), "nobody@example.com").await;
+    assert!(link_emails(&outbox, 1).await.is_empty());
+    let (known, cookie) = request_link(app.clone(), EMAIL).await;
+    assert_eq!(unknown, known);
+
+    let messages = link_emails(&outbox, 2).await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["to"], EMAIL);
+    let token = extract_token(&messages[0]);
+
+    let (status, headers, body) = verify(app.clone(), &token, Some(&cookie)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["data"]["user"]["email"], EMAIL);
+    let access_token = body["data"]["token"]["access_token"].as_str().unwrap();
+    let refresh_cookie = extract_set_cookie(&headers, "refresh_token").unwrap();
+
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", access_token).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = post_json_with_cookie(
+        app.clone(),
+        "/api/v1/auth/refresh",
+        &json!({}),
+        &refresh_cookie,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Single use
+    let (status, _, body) = verify(app, &token, Some(&cookie)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error_code"], "AUTH_004");
+}
+
+#[tokio::test]
+async fn test_magic_link_is_bound_to_requesting_device() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| outbox = Some(use_test_outbox(c))).await;
+    let outbox = outbox.unwrap();
+    register(app.clone()).await;
+
+    let (_, cookie) = request_link(app.clone(), EMAIL).await;
+    let token = extract_token(&link_emails(&outbox, 2).await[0]);
+
+    // Opened in another browser, or without the cookie
+    let (status, _, _) = verify(app.clone(), &token, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _, _) = verify(app.clone(), &token, Some("magic_link_device=other")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Failed attempts do not burn the link for the right device
+    let (status, _, _) = verify(app.clone(), &token, Some(&cookie)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Requests inside the cooldown are answered but send nothing
+    request_link(app.clone(), EMAIL).await;
+    assert_eq!(link_emails(&outbox, 2).await.len(), 1);
+}
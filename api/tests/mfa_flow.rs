@@ -0,0 +1,335 @@
+//! TOTP MFA: enrollment, two-step login, recovery codes
+
+mod common;
+
+use axum::{Router, http::StatusCode};
+use serde_json::{Value, json};
+
+use quax::feature::auth::mfa::totp;
+
+use common::*;
+
+const EMAIL: &str = "mfa@example.com";
+const PASSWORD: &str = "password123";
+
+fn now_step() -> u64 {
+    totp::step_at(chrono::Utc::now().timestamp() as u64)
+}
+
+/// Register, enroll and confirm MFA. Returns (access token, secret, recovery codes).
+async fn register_with_mfa(app: Router) -> (String, String, Vec<String>) {
+    let (status, body) = post_json(
+        app.clone(),
+        "/api/v1/auth/register",
+        &json!({ "email": EMAIL, "name": "Mfa User", "password": PASSWORD }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let token = body["data"]["token"]["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let (status, body) =
+        post_json_authed(app.clone(), "/api/v1/auth/mfa/enroll", &token, &json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let secret = body["data"]["secret"].as_str().unwrap().to_string();
+    assert!(
+        body["data"]["provisioning_uri"]
+            .as_str()
+            .unwrap()
+            .starts_with("otpauth://totp/")
+    );
+
+    let code = totp::code_at(&secret, now_step()).unwrap();
+    let (status, body) = post_json_authed(
+        app,
+        "/api/v1/auth/mfa/confirm",
+        &token,
+        &json!({ "code": code }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let recovery_codes: Vec<String> = body["data"]["recovery_codes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c.as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(recovery_codes.len(), 10);
+
+    (token, secret, recovery_codes)
+}
+
+async fn login(app: Router) -> Value {
+    let (status, body) = post_json(
+        app,
+        "/api/v1/auth/login",
+        &json!({ "email": EMAIL, "password": PASSWORD }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    body
+}
+
+#[tokio::test]
+async fn test_login_with_mfa_requires_second_step() {
+    let (app, _c) = build_test_app().await;
+    let (_, secret, _) = register_with_mfa(app.clone()).await;
+
+    // Password alone yields a challenge, not tokens
+    let body = login(app.clone()).await;
+    assert_eq!(body["data"]["mfa_required"], true);
+    assert!(body["data"].get("token").is_none());
+    let mfa_token = body["data"]["mfa_token"].as_str().unwrap();
+
+    // The challenge is not an access token
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", mfa_token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Wrong code
+    let (status, body) = post_json(
+        app.clone(),
+        "/api/v1/auth/mfa/verify",
+        &json!({ "mfa_token": mfa_token, "code": "000000" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error_code"], "AUTH_010");
+
+    // Next step's code (the current one was consumed by enrollment)
+    let code = totp::code_at(&secret, now_step() + 1).unwrap();
+    let (status, body) = post_json(
+        app.clone(),
+        "/api/v1/auth/mfa/verify",
+        &json!({ "mfa_token": mfa_token, "code": code }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let access = body["data"]["token"]["access_token"].as_str().unwrap();
+
+    // Session records that MFA was used
+    let (status, sessions) = get_authed(app.clone(), "/api/v1/auth/sessions", access).await;
+    assert_eq!(status, StatusCode::OK);
+    let current = sessions["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["is_current"] == true)
+        .unwrap();
+    assert_eq!(current["mfa_used"], true);
+
+    // Same code cannot be replayed
+    let (status, _) = post_json(
+        app,
+        "/api/v1/auth/mfa/verify",
+        &json!({ "mfa_token": mfa_token, "code": code }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_recovery_codes_are_single_use() {
+    let (app, _c) = build_test_app().await;
+    let (token, _, recovery_codes) = register_with_mfa(app.clone()).await;
+
+    let body = login(app.clone()).await;
+    let mfa_token = body["data"]["mfa_token"].as_str().unwrap().to_string();
+
+    // Dash and case don't matter
+    let typed = recovery_codes[0].replace('-', "").to_uppercase();
+    let (status, _) = post_json(
+        app.clone(),
+        "/api/v1/auth/mfa/verify",
+        &json!({ "mfa_token": mfa_token, "code": typed }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = post_json(
+        app.clone(),
+        "/api/v1/auth/mfa/verify",
+        &json!({ "mfa_token": mfa_token, "code": recovery_codes[0] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = get_authed(app, "/api/v1/auth/mfa", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["enabled"], true);
+    assert_eq!(body["data"]["recovery_codes_remaining"], 9);
+}
+
+#[tokio::test]
+async fn test_wrong_codes_lock_the_account() {
+    let (app, _c) = build_test_app_with(|c| {
+        c.login_throttle.delay_after = 100;
+        c.login_throttle.lockout_threshold = 3;
+    })
+    .await;
+    let (_, secret, _) = register_with_mfa(app.clone()).await;
+
+    // Fresh challenges don't reset the count: the password alone is not a success
+    let mut mfa_token = String::new();
+    for expected in [
+        StatusCode::UNAUTHORIZED,
+        StatusCode::UNAUTHORIZED,
+        StatusCode::LOCKED,
+    ] {
+        let body = login(app.clone()).await;
+        mfa_token = body["data"]["mfa_token"].as_str().unwrap().to_string();
+        let (status, _) = post_json(
+            app.clone(),
+            "/api/v1/auth/mfa/verify",
+            &json!({ "mfa_token": mfa_token, "code": "000000" }),
+        )
+        .await;
+        assert_eq!(status, expected);
+    }
+
+    // Locked: even the right code is refused, and so is the password
+    let code = totp::code_at(&secret, now_step() + 1).unwrap();
+    let (status, _) = post_json(
+        app.clone(),
+        "/api/v1/auth/mfa/verify",
+        &json!({ "mfa_token": mfa_token, "code": code }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::LOCKED);
+    let (status, body) = post_json(
+        app.clone(),
+        "/api/v1/auth/login",
+        &json!({ "email": EMAIL, "password": PASSWORD }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::LOCKED);
+    assert_eq!(body["error_code"], "AUTH_014");
+}
+
+#[tokio::test]
+async fn test_wrong_reauth_codes_lock_the_account() {
+    let (app, _c) = build_test_app_with(|c| {
+        c.login_throttle.delay_after = 100;
+        c.login_throttle.lockout_threshold = 3;
+    })
+    .await;
+    let (token, secret, _) = register_with_mfa(app.clone()).await;
+
+    for expected in [
+        StatusCode::UNAUTHORIZED,
+        StatusCode::UNAUTHORIZED,
+        StatusCode::LOCKED,
+    ] {
+        let (status, _) = post_json_authed(
+            app.clone(),
+            "/api/v1/auth/reauthenticate",
+            &token,
+            &json!({ "code": "000000" }),
+        )
+        .await;
+        assert_eq!(status, expected);
+    }
+
+    let code = totp::code_at(&secret, now_step() + 1).unwrap();
+    let (status, body) = post_json_authed(
+        app,
+        "/api/v1/auth/reauthenticate",
+        &token,
+        &json!({ "code": code }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::LOCKED);
+    assert_eq!(body["error_code"], "AUTH_014");
+}
+
+#[tokio::test]
+async fn test_enroll_twice_and_disable() {
+    let (app, _c) = build_test_app().await;
+    let (token, _, recovery_codes) = register_with_mfa(app.clone()).await;
+
+    // An active secret is never replaced by a new enrollment
+    let (status, body) =
+        post_json_authed(app.clone(), "/api/v1/auth/mfa/enroll", &token, &json!({})).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error_code"], "AUTH_012");
+
+    let (status, _) = post_json_authed(
+        app.clone(),
+        "/api/v1/auth/mfa/disable",
+        &token,
+        &json!({ "code": "not-a-code" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = post_json_authed(
+        app.clone(),
+        "/api/v1/auth/mfa/disable",
+        &token,
+        &json!({ "code": recovery_codes[1] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Back to single-step login
+    let body = login(app).await;
+    assert!(body["data"]["token"]["access_token"].is_string());
+}
+
+#[tokio::test]
+async fn test_disable_is_throttled_and_needs_recent_auth() {
+    let (app, _c) = build_test_app_with(|c| {
+        c.login_throttle.delay_after = 100;
+        c.login_throttle.lockout_threshold = 3;
+        c.session.reauth_max_age_secs = 2;
+    })
+    .await;
+    let (token, secret, _) = register_with_mfa(app.clone()).await;
+
+    for (uri, expected) in [
+        ("/api/v1/auth/mfa/disable", StatusCode::UNAUTHORIZED),
+        ("/api/v1/auth/mfa/recovery-codes", StatusCode::UNAUTHORIZED),
+        ("/api/v1/auth/mfa/disable", StatusCode::LOCKED),
+    ] {
+        let (status, _) =
+            post_json_authed(app.clone(), uri, &token, &json!({ "code": "000000" })).await;
+        assert_eq!(status, expected);
+    }
+
+    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+    let code = totp::code_at(&secret, now_step() + 1).unwrap();
+    for uri in [
+        "/api/v1/auth/mfa/disable",
+        "/api/v1/auth/mfa/recovery-codes",
+    ] {
+        let (status, body) =
+            post_json_authed(app.clone(), uri, &token, &json!({ "code": code })).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error_code"], "AUTH_019");
+    }
+}
+
+#[tokio::test]
+async fn test_confirm_without_enrollment() {
+    let (app, _c) = build_test_app().await;
+
+    let (_, body) = post_json(
+        app.clone(),
+        "/api/v1/auth/register",
+        &json!({ "email": EMAIL, "name": "Mfa User", "password": PASSWORD }),
+    )
+    .await;
+    let token = body["data"]["token"]["access_token"].as_str().unwrap();
+
+    let (status, body) = post_json_authed(
+        app,
+        "/api/v1/auth/mfa/confirm",
+        token,
+        &json!({ "code": "123456" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error_code"], "AUTH_011");
+}
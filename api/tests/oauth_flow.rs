@@ -0,0 +1,476 @@
+//! OAuth2 authorization-code + PKCE flow against a local mock IdP
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Form, Json, Router,
+    body::Body,
+    extract::State,
+    http::{HeaderMap, Request, StatusCode, header},
+    routing,
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use quax::infrastructure::config::OAuthProviderConfig;
+
+use common::*;
+
+const SUCCESS_REDIRECT: &str = "http://frontend.test/auth/callback";
+
+// ─── Mock IdP ────────────────────────────────────────────────────────────────
+
+struct MockIdp {
+    /// code_challenge the test expects the token request's verifier to match
+    expected_challenge: Option<String>,
+    email: String,
+    email_verified: bool,
+}
+
+type Shared = Arc<Mutex<MockIdp>>;
+
+async fn token(
+    State(idp): State<Shared>,
+    Form(form): Form<std::collections::HashMap<String, String>>,
+) -> (StatusCode, Json<Value>) {
+    let idp = idp.lock().unwrap();
+    let verifier = form.get("code_verifier").cloned().unwrap_or_default();
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+
+    if form.get("code").map(String::as_str) != Some("good-code")
+        || form.get("client_secret").map(String::as_str) != Some("mock-secret")
+        || idp.expected_challenge.as_deref() != Some(challenge.as_str())
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "invalid_grant" })),
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(json!({ "access_token": "mock-access", "token_type": "Bearer", "expires_in": 3600 })),
+    )
+}
+
+async fn userinfo(State(idp): State<Shared>, headers: HeaderMap) -> (StatusCode, Json<Value>) {
+    if headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        != Some("Bearer mock-access")
+    {
+        return (StatusCode::UNAUTHORIZED, Json(json!({})));
+    }
+    let idp = idp.lock().unwrap();
+    (
+        StatusCode::OK,
+        Json(json!({
+            "sub": "google-123",
+            "email": idp.email,
+            "email_verified": idp.email_verified,
+            "name": "Olivia OAuth",
+        })),
+    )
+}
+
+async fn start_mock_idp(email: &str, email_verified: bool) -> (String, Shared) {
+    let idp: Shared = Arc::new(Mutex::new(MockIdp {
+        expected_challenge: None,
+        email: email.to_string(),
+        email_verified,
+    }));
+
+    let app = Router::new()
+        .route("/token", routing::post(token))
+        .route("/userinfo", routing::get(userinfo))
+        .with_state(idp.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    (base, idp)
+}
+
+async fn build_app_with_mock(idp_base: &str) -> (Router, impl Sized) {
+    let idp_base = idp_base.to_string();
+    build_test_app_with(move |config| {
+        config.oauth.success_redirect = SUCCESS_REDIRECT.to_string();
+        config.oauth.providers = vec![OAuthProviderConfig {
+            name: "google".to_string(),
+            client_id: "mock-client".to_string(),
+            client_secret: "mock-secret".to_string(),
+            authorize_url: format!("{idp_base}/authorize"),
+            token_url: format!("{idp_base}/token"),
+            userinfo_url: format!("{idp_base}/userinfo"),
+            scopes: vec!["openid".into(), "email".into()],
+        }];
+    })
+    .await
+}
+
+/// Start the flow; returns (state, code_challenge, state cookie)
+async fn authorize(app: Router) -> (String, String, String) {
+    let (status, headers, _) = get(app, "/api/v1/auth/oauth/google/authorize", None).await;
+    assert_eq!(status, StatusCode::SEE_OTHER);
+
+    let location = headers[header::LOCATION].to_str().unwrap();
+    consent_params(location, &headers)
+}
+
+/// Start a link flow as a signed-in user; same return value as `authorize`
+async fn authorize_link(app: Router, token: &str) -> (String, String, String) {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/oauth/google/link")
+        .header(header::AUTHORIZATION, format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let (status, headers, body) = raw_request(app, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    consent_params(
+        body["data"]["authorization_url"].as_str().unwrap(),
+        &headers,
+    )
+}
+
+fn consent_params(location: &str, headers: &HeaderMap) -> (String, String, String) {
+    let url = reqwest::Url::parse(location).unwrap();
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.to_string())
+            .unwrap()
+    };
+    assert_eq!(param("code_challenge_method"), "S256");
+    assert_eq!(param("client_id"), "mock-client");
+
+    let cookie = extract_set_cookie(headers, "oauth_state").expect("state cookie");
+    (param("state"), param("code_challenge"), cookie)
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_oauth_login_creates_account_and_session() {
+    let (idp_base, idp) = start_mock_idp("olivia@example.com", true).await;
+    let (app, _c) = build_app_with_mock(&idp_base).await;
+
+    let (state, challenge, state_cookie) = authorize(app.clone()).await;
+    idp.lock().unwrap().expected_challenge = Some(challenge);
+
+    let (status, headers, _) = get(
+        app.clone(),
+        &format!("/api/v1/auth/oauth/google/callback?code=good-code&state={state}"),
+        Some(&state_cookie),
+    )
+    .await;
+    assert_eq!(status, StatusCode::SEE_OTHER);
+    assert_eq!(headers[header::LOCATION], SUCCESS_REDIRECT);
+
+    // Refresh cookie works → a session row was recorded
+    let refresh_cookie = extract_set_cookie(&headers, "refresh_token").expect("refresh cookie");
+    let (status, body) = post_json_with_cookie(
+        app.clone(),
+        "/api/v1/auth/refresh",
+        &json!({}),
+        &refresh_cookie,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let token = body["data"]["access_token"].as_str().unwrap();
+    let (status, me) = get_authed(app, "/api/v1/auth/me", token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(me["data"]["email"], "olivia@example.com");
+    assert_eq!(me["data"]["name"], "Olivia OAuth");
+}
+
+#[tokio::test]
+async fn test_oauth_state_is_single_use_and_browser_bound() {
+    let (idp_base, idp) = start_mock_idp("olivia@example.com", true).await;
+    let (app, _c) = build_app_with_mock(&idp_base).await;
+
+    let (state, challenge, state_cookie) = authorize(app.clone()).await;
+    idp.lock().unwrap().expected_challenge = Some(challenge);
+    let callback = format!("/api/v1/auth/oauth/google/callback?code=good-code&state={state}");
+
+    // Missing state cookie → rejected
+    let (_, headers, _) = get(app.clone(), &callback, None).await;
+    assert_eq!(
+        headers[header::LOCATION],
+        format!("{SUCCESS_REDIRECT}?error=invalid_state")
+    );
+
+    // Correct cookie → accepted once
+    let (_, headers, _) = get(app.clone(), &callback, Some(&state_cookie)).await;
+    assert_eq!(headers[header::LOCATION], SUCCESS_REDIRECT);
+
+    // Replay → rejected
+    let (_, headers, _) = get(app, &callback, Some(&state_cookie)).await;
+    assert_eq!(
+        headers[header::LOCATION],
+        format!("{SUCCESS_REDIRECT}?error=invalid_state")
+    );
+}
+
+#[tokio::test]
+async fn test_oauth_rejects_unverified_email() {
+    let (idp_base, idp) = start_mock_idp("victim@example.com", false).await;
+    let (app, _c) = build_app_with_mock(&idp_base).await;
+
+    let (state, challenge, state_cookie) = authorize(app.clone()).await;
+    idp.lock().unwrap().expected_challenge = Some(challenge);
+
+    let (_, headers, _) = get(
+        app,
+        &format!("/api/v1/auth/oauth/google/callback?code=good-code&state={state}"),
+        Some(&state_cookie),
+    )
+    .await;
+    assert_eq!(
+        headers[header::LOCATION],
+        format!("{SUCCESS_REDIRECT}?error=email_not_verified")
+    );
+    assert!(extract_set_cookie(&headers, "refresh_token").is_none());
+}
+
+#[tokio::test]
+async fn test_oauth_does_not_take_over_unverified_account() {
+    // Someone registered the address first and never verified it
+    let (idp_base, idp) = start_mock_idp("olivia@example.com", true).await;
+    let (app, _c) = build_app_with_mock(&idp_base).await;
+    register(app.clone(), "olivia@example.com").await;
+
+    let (state, challenge, state_cookie) = authorize(app.clone()).await;
+    idp.lock().unwrap().expected_challenge = Some(challenge);
+
+    let (_, headers, _) = get(
+        app,
+        &format!("/api/v1/auth/oauth/google/callback?code=good-code&state={state}"),
+        Some(&state_cookie),
+    )
+    .await;
+    assert_eq!(
+        headers[header::LOCATION],
+        format!("{SUCCESS_REDIRECT}?error=account_unverified")
+    );
+    assert!(extract_set_cookie(&headers, "refresh_token").is_none());
+}
+
+#[tokio::test]
+async fn test_oauth_unconfigured_provider_not_found() {
+    let (idp_base, _idp) = start_mock_idp("olivia@example.com", true).await;
+    let (app, _c) = build_app_with_mock(&idp_base).await;
+
+    let (status, _, body) = get(app.clone(), "/api/v1/auth/oauth/github/authorize", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["success"], false);
+
+    let (status, _, _) = get(app, "/api/v1/auth/oauth/password/authorize", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_oauth_account_can_add_password() {
+    let (idp_base, idp) = start_mock_idp("olivia@example.com", true).await;
+    let (app, _c) = build_app_with_mock(&idp_base).await;
+
+    let mut tokens = Vec::new();
+    for _ in 0..2 {
+        let (state, challenge, state_cookie) = authorize(app.clone()).await;
+        idp.lock().unwrap().expected_challenge = Some(challenge);
+        let (_, headers, _) = get(
+            app.clone(),
+            &format!("/api/v1/auth/oauth/google/callback?code=good-code&state={state}"),
+            Some(&state_cookie),
+        )
+        .await;
+        let refresh_cookie = extract_set_cookie(&headers, "refresh_token").unwrap();
+        let (_, body) = post_json_with_cookie(
+            app.clone(),
+            "/api/v1/auth/refresh",
+            &json!({}),
+            &refresh_cookie,
+        )
+        .await;
+        tokens.push(body["data"]["access_token"].as_str().unwrap().to_string());
+    }
+    let (token, other_device) = (&tokens[0], &tokens[1]);
+
+    let set_password = json!({ "new_password": "first-password", "revoke_other_sessions": true });
+    let (status, body) = post_json_authed(
+        app.clone(),
+        "/api/v1/auth/set-password",
+        token,
+        &set_password,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    assert_eq!(body["data"]["revoked_sessions"], 1);
+
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", other_device).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = post_json(
+        app.clone(),
+        "/api/v1/auth/login",
+        &json!({ "email": "olivia@example.com", "password": "first-password" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Only once; afterwards it is change-password
+    let (status, body) =
+        post_json_authed(app, "/api/v1/auth/set-password", token, &set_password).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error_code"], "AUTH_020");
+}
+
+async fn register(app: Router, email: &str) -> String {
+    let (status, body) = post_json(
+        app,
+        "/api/v1/auth/register",
+        &json!({ "email": email, "name": "Linker", "password": "password123" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    body["data"]["token"]["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+async fn delete_method(app: Router, token: &str, id: &Value) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("DELETE")
+        .uri(format!("/api/v1/auth/methods/{}", id.as_str().unwrap()))
+        .header(header::AUTHORIZATION, format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let (status, _, body) = raw_request(app, req).await;
+    (status, body)
+}
+
+#[tokio::test]
+async fn test_link_and_unlink_provider() {
+    // The provider address differs from the account's; linking does not need it to match
+    let (idp_base, idp) = start_mock_idp("olivia@example.com", true).await;
+    let (app, _c) = build_app_with_mock(&idp_base).await;
+    let token = register(app.clone(), "linker@example.com").await;
+
+    let (state, challenge, state_cookie) = authorize_link(app.clone(), &token).await;
+    idp.lock().unwrap().expected_challenge = Some(challenge);
+    let (_, headers, _) = get(
+        app.clone(),
+        &format!("/api/v1/auth/oauth/google/callback?code=good-code&state={state}"),
+        Some(&state_cookie),
+    )
+    .await;
+    assert_eq!(
+        headers[header::LOCATION],
+        format!("{SUCCESS_REDIRECT}?linked=google")
+    );
+    assert!(extract_set_cookie(&headers, "refresh_token").is_none());
+
+    let (status, body) = get_authed(app.clone(), "/api/v1/auth/methods", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    let methods = body["data"].as_array().unwrap().clone();
+    assert_eq!(methods.len(), 2);
+    assert_eq!(methods[0]["provider"], "password");
+    assert_eq!(methods[0]["is_primary"], true);
+    assert_eq!(methods[1]["provider"], "google");
+    assert!(methods[1].get("provider_id").is_none());
+
+    // Signing in with the provider now lands in the linked account
+    let (state, challenge, state_cookie) = authorize(app.clone()).await;
+    idp.lock().unwrap().expected_challenge = Some(challenge);
+    let (_, headers, _) = get(
+        app.clone(),
+        &format!("/api/v1/auth/oauth/google/callback?code=good-code&state={state}"),
+        Some(&state_cookie),
+    )
+    .await;
+    let refresh_cookie = extract_set_cookie(&headers, "refresh_token").expect("refresh cookie");
+    let (_, body) = post_json_with_cookie(
+        app.clone(),
+        "/api/v1/auth/refresh",
+        &json!({}),
+        &refresh_cookie,
+    )
+    .await;
+    let (_, me) = get_authed(
+        app.clone(),
+        "/api/v1/auth/me",
+        body["data"]["access_token"].as_str().unwrap(),
+    )
+    .await;
+    assert_eq!(me["data"]["email"], "linker@example.com");
+
+    // Primary can move to the provider
+    let (status, body) = post_json_authed(
+        app.clone(),
+        &format!(
+            "/api/v1/auth/methods/{}/primary",
+            methods[1]["id"].as_str().unwrap()
+        ),
+        &token,
+        &json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["is_primary"], true);
+
+    // The password can go, the last method cannot
+    let (status, _) = delete_method(app.clone(), &token, &methods[0]["id"]).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = delete_method(app.clone(), &token, &methods[1]["id"]).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error_code"], "AUTH_018");
+
+    let (_, body) = get_authed(app, "/api/v1/auth/methods", &token).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(body["data"][0]["is_primary"], true);
+}
+
+#[tokio::test]
+async fn test_link_rejects_identity_owned_by_another_account() {
+    let (idp_base, idp) = start_mock_idp("olivia@example.com", true).await;
+    let (app, _c) = build_app_with_mock(&idp_base).await;
+
+    // Olivia signs up through the provider first
+    let (state, challenge, state_cookie) = authorize(app.clone()).await;
+    idp.lock().unwrap().expected_challenge = Some(challenge);
+    get(
+        app.clone(),
+        &format!("/api/v1/auth/oauth/google/callback?code=good-code&state={state}"),
+        Some(&state_cookie),
+    )
+    .await;
+
+    let token = register(app.clone(), "mallory@example.com").await;
+    let (state, challenge, state_cookie) = authorize_link(app.clone(), &token).await;
+    idp.lock().unwrap().expected_challenge = Some(challenge);
+    let (_, headers, _) = get(
+        app.clone(),
+        &format!("/api/v1/auth/oauth/google/callback?code=good-code&state={state}"),
+        Some(&state_cookie),
+    )
+    .await;
+    assert_eq!(
+        headers[header::LOCATION],
+        format!("{SUCCESS_REDIRECT}?error=already_linked")
+    );
+
+    // Other users' methods are invisible
+    let (_, body) = get_authed(app.clone(), "/api/v1/auth/methods", &token).await;
+    let methods = body["data"].as_array().unwrap();
+    assert_eq!(methods.len(), 1);
+
+    let (status, _) = delete_method(app, &token, &json!(uuid::Uuid::new_v4().to_string())).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
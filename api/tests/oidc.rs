@@ -0,0 +1,315 @@
+//! OpenID Connect provider: client registration, code + PKCE, refresh, userinfo
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
+use quax::{feature::auth::oauth::pkce, routes::app_routes, state::AppState};
+use serde_json::{Value, json};
+
+use common::*;
+
+const PASSWORD: &str = "password123";
+const REDIRECT_URI: &str = "https://wiki.example.com/callback";
+
+/// Register a user; returns their access token
+async fn register(state: &AppState, email: &str) -> String {
+    let body = json!({ "email": email, "name": "Someone", "password": PASSWORD });
+    let (status, body) = post_json(app_routes(state.clone()), "/api/v1/auth/register", &body).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    body["data"]["token"]["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+/// Register a client as a fresh admin; returns the client's data
+async fn register_client(state: &AppState, client: Value) -> Value {
+    register(state, "admin@example.com").await;
+    sqlx::query("UPDATE users SET role = 'admin' WHERE email = 'admin@example.com'")
+        .execute(state.db.pool())
+        .await
+        .unwrap();
+    let login = json!({ "email": "admin@example.com", "password": PASSWORD });
+    let (_, body) = post_json(app_routes(state.clone()), "/api/v1/auth/login", &login).await;
+    let admin_token = body["data"]["token"]["access_token"].as_str().unwrap();
+
+    let (status, body) = post_json_authed(
+        app_routes(state.clone()),
+        "/api/v1/admin/oidc-clients",
+        admin_token,
+        &client,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    body["data"].clone()
+}
+
+fn authorize_request(client_id: &str, verifier: &str, extra: Value) -> Value {
+    let mut req = json!({
+        "response_type": "code",
+        "client_id": client_id,
+        "redirect_uri": REDIRECT_URI,
+        "scope": "openid email profile",
+        "state": "xyz",
+        "nonce": "n-0S6",
+        "code_challenge": pkce::challenge_for(verifier),
+        "code_challenge_method": "S256",
+    });
+    req.as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+    req
+}
+
+/// Query parameter of the redirect URL the authorize endpoint answered with
+fn redirect_param(body: &Value, name: &str) -> Option<String> {
+    assert_eq!(body["data"]["status"], "redirect", "{body}");
+    let url = reqwest::Url::parse(body["data"]["redirect_to"].as_str().unwrap()).unwrap();
+    assert!(url.as_str().starts_with(REDIRECT_URI));
+    url.query_pairs()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.into_owned())
+}
+
+/// POST a form to the token endpoint, optionally with HTTP Basic credentials
+async fn token(
+    state: &AppState,
+    form: &[(&str, &str)],
+    basic: Option<(&str, &str)>,
+) -> (StatusCode, Value) {
+    let body = form
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join("&");
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/api/v1/oauth/token")
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+    if let Some((id, secret)) = basic {
+        let credentials = STANDARD.encode(format!("{id}:{secret}"));
+        builder = builder.header(header::AUTHORIZATION, format!("Basic {credentials}"));
+    }
+    let req = builder.body(Body::from(body)).unwrap();
+    let (status, _, body) = raw_request(app_routes(state.clone()), req).await;
+    (status, body)
+}
+
+fn claims(token: &str) -> Value {
+    let payload = token.split('.').nth(1).unwrap();
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_code_flow_with_consent_and_refresh() {
+    let (state, _c) = build_test_state_with(use_signing_key).await;
+    let app = app_routes(state.clone());
+    let client = register_client(
+        &state,
+        json!({ "name": "Wiki", "redirect_uris": [REDIRECT_URI] }),
+    )
+    .await;
+    let client_id = client["client_id"].as_str().unwrap();
+    let secret = client["client_secret"].as_str().unwrap();
+    let user_token = register(&state, "reader@example.com").await;
+    let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+    // First visit: the user is asked
+    let req = authorize_request(client_id, verifier, json!({}));
+    let (status, body) =
+        post_json_authed(app.clone(), "/api/v1/oauth/authorize", &user_token, &req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["data"]["status"], "consent_required");
+    assert_eq!(body["data"]["client"]["name"], "Wiki");
+
+    let req = authorize_request(client_id, verifier, json!({ "consent": true }));
+    let (_, body) =
+        post_json_authed(app.clone(), "/api/v1/oauth/authorize", &user_token, &req).await;
+    assert_eq!(redirect_param(&body, "state").as_deref(), Some("xyz"));
+    let code = redirect_param(&body, "code").unwrap();
+
+    let form = [
+        ("grant_type", "authorization_code"),
+        ("code", code.as_str()),
+        ("redirect_uri", REDIRECT_URI),
+        ("code_verifier", verifier),
+    ];
+    let (status, tokens) = token(&state, &form, Some((client_id, "wrong"))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(tokens["error"], "invalid_client");
+
+    let (status, tokens) = token(&state, &form, Some((client_id, secret))).await;
+    assert_eq!(status, StatusCode::OK, "{tokens}");
+    let id_token = claims(tokens["id_token"].as_str().unwrap());
+    assert_eq!(id_token["aud"], client_id);
+    assert_eq!(id_token["nonce"], "n-0S6");
+    assert_eq!(id_token["email"], "reader@example.com");
+    assert_eq!(id_token["sid"], claims(&user_token)["sid"]);
+
+    // Codes are single use
+    let (status, body) = token(&state, &form, Some((client_id, secret))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_grant");
+
+    // The client token reads userinfo, but is no key to the quax API
+    let access_token = tokens["access_token"].as_str().unwrap();
+    let (status, info) = get_authed(app.clone(), "/api/v1/oauth/userinfo", access_token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(info["email"], "reader@example.com");
+    assert_eq!(info["name"], "Someone");
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", access_token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Consent is remembered
+    let req = authorize_request(client_id, verifier, json!({ "prompt": "none" }));
+    let (_, body) =
+        post_json_authed(app.clone(), "/api/v1/oauth/authorize", &user_token, &req).await;
+    assert!(redirect_param(&body, "code").is_some());
+
+    // Refresh tokens rotate; replaying an old one ends the chain
+    let first = tokens["refresh_token"].as_str().unwrap();
+    let refresh = |token: &str| {
+        [
+            ("grant_type", "refresh_token"),
+            ("refresh_token", token),
+            ("client_id", client_id),
+            ("client_secret", secret),
+        ]
+        .map(|(k, v)| (k, v.to_string()))
+    };
+    let form = refresh(first);
+    let form: Vec<_> = form.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    let (status, rotated) = token(&state, &form, None).await;
+    assert_eq!(status, StatusCode::OK, "{rotated}");
+    assert!(rotated["id_token"].is_string());
+
+    let (status, _) = token(&state, &form, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let form = refresh(rotated["refresh_token"].as_str().unwrap());
+    let form: Vec<_> = form.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    let (status, body) = token(&state, &form, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_grant");
+}
+
+#[tokio::test]
+async fn test_quax_sign_out_ends_app_sign_in() {
+    let (state, _c) = build_test_state_with(use_signing_key).await;
+    let app = app_routes(state.clone());
+    let client = register_client(
+        &state,
+        json!({
+            "name": "Dashboard",
+            "redirect_uris": [REDIRECT_URI],
+            "confidential": false,
+            "require_consent": false,
+        }),
+    )
+    .await;
+    assert!(client["client_secret"].is_null());
+    let client_id = client["client_id"].as_str().unwrap();
+    let user_token = register(&state, "viewer@example.com").await;
+    let verifier = "another-verifier-that-is-at-least-43-characters";
+
+    let (status, _, discovery) = get(app.clone(), "/.well-known/openid-configuration", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        discovery["code_challenge_methods_supported"],
+        json!(["S256"])
+    );
+
+    // Without a PKCE challenge the client gets an error, not a code
+    let req = authorize_request(client_id, verifier, json!({ "code_challenge": null }));
+    let (_, body) =
+        post_json_authed(app.clone(), "/api/v1/oauth/authorize", &user_token, &req).await;
+    assert_eq!(
+        redirect_param(&body, "error").as_deref(),
+        Some("invalid_request")
+    );
+
+    // Unregistered redirect URIs are refused outright
+    let req = authorize_request(
+        client_id,
+        verifier,
+        json!({ "redirect_uri": "https://evil.example.com/callback" }),
+    );
+    let (status, body) =
+        post_json_authed(app.clone(), "/api/v1/oauth/authorize", &user_token, &req).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error_code"], "AUTH_022");
+
+    // First-party app: no consent screen, public client, PKCE only
+    let req = authorize_request(client_id, verifier, json!({}));
+    let (_, body) =
+        post_json_authed(app.clone(), "/api/v1/oauth/authorize", &user_token, &req).await;
+    let code = redirect_param(&body, "code").unwrap();
+    let form = [
+        ("grant_type", "authorization_code"),
+        ("code", code.as_str()),
+        ("redirect_uri", REDIRECT_URI),
+        ("code_verifier", verifier),
+        ("client_id", client_id),
+    ];
+    let (status, tokens) = token(&state, &form, None).await;
+    assert_eq!(status, StatusCode::OK, "{tokens}");
+    let access_token = tokens["access_token"].as_str().unwrap();
+    let refresh_token = tokens["refresh_token"].as_str().unwrap();
+
+    // Signing out of quax signs the user out of the app too
+    let (status, _) =
+        post_json_authed(app.clone(), "/api/v1/auth/logout", &user_token, &json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = get_authed(app, "/api/v1/oauth/userinfo", access_token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let form = [
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+        ("client_id", client_id),
+    ];
+    let (status, body) = token(&state, &form, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_grant");
+}
+
+#[tokio::test]
+async fn test_provider_needs_signing_key() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let client = register_client(
+        &state,
+        json!({ "name": "Wiki", "redirect_uris": [REDIRECT_URI] }),
+    )
+    .await;
+    let client_id = client["client_id"].as_str().unwrap();
+    let secret = client["client_secret"].as_str().unwrap();
+
+    // HS256 ID tokens could only be checked with the secret behind quax tokens
+    let (status, _, body) = get(
+        app_routes(state.clone()),
+        "/.well-known/openid-configuration",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["error"], "server_error");
+
+    let form = [
+        ("grant_type", "authorization_code"),
+        ("code", "anything"),
+        ("redirect_uri", REDIRECT_URI),
+        (
+            "code_verifier",
+            "verifier-that-is-at-least-43-characters-long",
+        ),
+    ];
+    let (status, body) = token(&state, &form, Some((client_id, secret))).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["error"], "server_error");
+}
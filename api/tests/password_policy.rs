@@ -0,0 +1,141 @@
+//! Password policy: per-rule errors, personal info, reuse and breached passwords
+
+mod common;
+
+use axum::{Router, http::StatusCode};
+use serde_json::{Value, json};
+
+use common::*;
+
+/// SHA-1 of "letmein-please"
+const BREACHED_SHA1: &str = "B8C7E42D25F47C165216C1B0D35266300D7D219B";
+
+fn rules(body: &Value) -> Vec<&str> {
+    body["errors"]
+        .as_array()
+        .expect("per-rule errors")
+        .iter()
+        .map(|e| e["rule"].as_str().unwrap())
+        .collect()
+}
+
+async fn register(app: Router, email: &str, password: &str) -> (StatusCode, Value) {
+    post_json(
+        app,
+        "/api/v1/auth/register",
+        &json!({ "email": email, "name": "Policy User", "password": password }),
+    )
+    .await
+}
+
+async fn change_password(
+    app: Router,
+    token: &str,
+    current: &str,
+    new: &str,
+) -> (StatusCode, Value) {
+    post_json_authed(
+        app,
+        "/api/v1/auth/change-password",
+        token,
+        &json!({ "current_password": current, "new_password": new }),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_register_reports_every_failed_rule() {
+    let (app, _c) = build_test_app_with(|c| {
+        c.password_policy.require_uppercase = true;
+        c.password_policy.require_digit = true;
+    })
+    .await;
+
+    let (status, body) = register(app.clone(), "jane.doe@example.com", "jane.doe").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error_code"], "VAL_004");
+    assert_eq!(rules(&body), ["uppercase", "digit", "personal_info"]);
+    assert!(body["errors"][0]["message"].is_string());
+
+    // Nothing was created for the rejected attempt
+    let (status, _) = register(app, "jane.doe@example.com", "Correct-Horse-42").await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn test_change_password_rejects_recent_passwords() {
+    let (app, _c) = build_test_app_with(|c| c.password_policy.history_size = 2).await;
+
+    let (_, body) = register(app.clone(), "reuse@example.com", "first-password").await;
+    let token = body["data"]["token"]["access_token"].as_str().unwrap();
+
+    let (status, body) =
+        change_password(app.clone(), token, "first-password", "first-password").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(rules(&body), ["reused"]);
+
+    let (status, _) =
+        change_password(app.clone(), token, "first-password", "second-password").await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) =
+        change_password(app.clone(), token, "second-password", "third-password").await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Only the last two are remembered
+    let (status, _) =
+        change_password(app.clone(), token, "third-password", "second-password").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = change_password(app, token, "third-password", "first-password").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_breached_passwords_are_rejected() {
+    let list = std::env::temp_dir().join(format!("quax-breached-{}.txt", uuid::Uuid::new_v4()));
+    std::fs::write(&list, format!("{BREACHED_SHA1}:42\n")).unwrap();
+
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| {
+        c.password_policy.breached_list = Some(list.to_string_lossy().into_owned());
+        outbox = Some(use_test_outbox(c));
+    })
+    .await;
+    let outbox = outbox.unwrap();
+
+    let (status, body) = register(app.clone(), "breach@example.com", "letmein-please").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(rules(&body), ["breached"]);
+
+    let (status, _) = register(app.clone(), "breach@example.com", "password123").await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // A rejected reset keeps the link usable
+    let (status, _) = post_json(
+        app.clone(),
+        "/api/v1/auth/forgot-password",
+        &json!({ "email": "breach@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let reset = wait_for_outbox(&outbox, 2)
+        .await
+        .into_iter()
+        .find(|m| m["subject"] == "Reset your password")
+        .expect("reset email");
+    let token = extract_token(&reset);
+
+    for (password, expected) in [
+        ("letmein-please", StatusCode::BAD_REQUEST),
+        ("a-fresh-password", StatusCode::OK),
+    ] {
+        let (status, _) = post_json(
+            app.clone(),
+            "/api/v1/auth/reset-password",
+            &json!({ "token": token, "new_password": password }),
+        )
+        .await;
+        assert_eq!(status, expected);
+    }
+
+    std::fs::remove_file(list).ok();
+}
@@ -0,0 +1,174 @@
+//! Forgot / reset password via emailed single-use tokens
+
+mod common;
+
+use std::path::Path;
+
+use axum::{Router, http::StatusCode};
+use serde_json::{Value, json};
+
+use common::*;
+
+const EMAIL: &str = "reset@example.com";
+const PASSWORD: &str = "password123";
+const NEW_PASSWORD: &str = "brand-new-password";
+
+/// Reset emails only, once `total` messages arrived (registration also
+/// sends a verification email)
+async fn reset_emails(outbox: &Path, total: usize) -> Vec<Value> {
+    wait_for_outbox(outbox, total)
+        .await
+        .into_iter()
+        .filter(|m| m["subject"] == "Reset your password")
+        .collect()
+}
+
+async fn forgot(app: Router, email: &str) -> Value {
+    let (status, body) = post_json(
+        app,
+        "/api/v1/auth/forgot-password",
+        &json!({ "email": email }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    body
+}
+
+async fn reset(app: Router, token: &str, password: &str) -> (StatusCode, Value) {
+    post_json(
+        app,
+        "/api/v1/auth/reset-password",
+        &json!({ "token": token, "new_password": password }),
+    )
+    .await
+}
+
+async fn login_status(app: Router, password: &str) -> StatusCode {
+    let (status, _) = post_json(
+        app,
+        "/api/v1/auth/login",
+        &json!({ "email": EMAIL, "password": password }),
+    )
+    .await;
+    status
+}
+
+#[tokio::test]
+async fn test_reset_password_flow() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| outbox = Some(use_test_outbox(c))).await;
+    let outbox = outbox.unwrap();
+
+    let (status, headers, _) = raw_request(
+        app.clone(),
+        axum::http::Request::builder()
+            .method("POST")
+            .uri("/api/v1/auth/register")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(
+                json!({ "email": EMAIL, "name": "Reset User", "password": PASSWORD }).to_string(),
+            ))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let refresh_cookie = extract_set_cookie(&headers, "refresh_token").unwrap();
+
+    // Same answer for unknown and known addresses; only the real one gets mail
+    let unknown = forgot(app.clone(), "nobody@example.com").await;
+    assert!(reset_emails(&outbox, 1).await.is_empty());
+    let known = forgot(app.clone(), EMAIL).await;
+    assert_eq!(unknown["message"], known["message"]);
+
+    let messages = reset_emails(&outbox, 2).await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["to"], EMAIL);
+    let token = extract_token(&messages[0]);
+
+    let (status, _) = reset(app.clone(), &token, "short").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = reset(app.clone(), &token, NEW_PASSWORD).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Existing sessions are revoked
+    let (status, _) = post_json_with_cookie(
+        app.clone(),
+        "/api/v1/auth/refresh",
+        &json!({}),
+        &refresh_cookie,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Token is single use
+    let (status, body) = reset(app.clone(), &token, "another-password").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error_code"], "AUTH_004");
+
+    assert_eq!(
+        login_status(app.clone(), PASSWORD).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(login_status(app, NEW_PASSWORD).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_new_reset_request_replaces_old_token() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| {
+        outbox = Some(use_test_outbox(c));
+        c.password_reset.resend_cooldown_secs = 0;
+    })
+    .await;
+    let outbox = outbox.unwrap();
+
+    let (status, _) = post_json(
+        app.clone(),
+        "/api/v1/auth/register",
+        &json!({ "email": EMAIL, "name": "Reset User", "password": PASSWORD }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    forgot(app.clone(), EMAIL).await;
+    forgot(app.clone(), EMAIL).await;
+
+    let messages = reset_emails(&outbox, 3).await;
+    assert_eq!(messages.len(), 2);
+    let old_token = extract_token(&messages[0]);
+    let new_token = extract_token(&messages[1]);
+
+    let (status, _) = reset(app.clone(), &old_token, NEW_PASSWORD).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = reset(app.clone(), &new_token, NEW_PASSWORD).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // A verification token cannot be used as a reset token
+    let verification = read_outbox(&outbox)
+        .into_iter()
+        .find(|m| m["subject"] == "Verify your email address")
+        .unwrap();
+    let (status, _) = reset(app, &extract_token(&verification), NEW_PASSWORD).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_forgot_password_cooldown() {
+    let mut outbox = None;
+    let (app, _c) = build_test_app_with(|c| outbox = Some(use_test_outbox(c))).await;
+    let outbox = outbox.unwrap();
+
+    let (status, _) = post_json(
+        app.clone(),
+        "/api/v1/auth/register",
+        &json!({ "email": EMAIL, "name": "Reset User", "password": PASSWORD }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    forgot(app.clone(), EMAIL).await;
+    forgot(app, EMAIL).await;
+    assert_eq!(reset_emails(&outbox, 2).await.len(), 1);
+}
@@ -0,0 +1,267 @@
+//! Roles and permissions: custom roles, require_permission, no escalation
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use quax::{routes::app_routes, state::AppState};
+use serde_json::{Value, json};
+
+use common::*;
+
+const PASSWORD: &str = "password123";
+
+/// Register and sign in with `role`; returns (user id, access token)
+async fn user_with_role(state: &AppState, email: &str, role: &str) -> (String, String) {
+    let body = json!({ "email": email, "name": "Someone", "password": PASSWORD });
+    let (status, body) = post_json(app_routes(state.clone()), "/api/v1/auth/register", &body).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    sqlx::query("UPDATE users SET role = $1 WHERE email = $2")
+        .bind(role)
+        .bind(email)
+        .execute(state.db.pool())
+        .await
+        .unwrap();
+    login(state, email).await
+}
+
+async fn login(state: &AppState, email: &str) -> (String, String) {
+    let body = json!({ "email": email, "password": PASSWORD });
+    let (status, body) = post_json(app_routes(state.clone()), "/api/v1/auth/login", &body).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    (
+        body["data"]["user"]["id"].as_str().unwrap().to_string(),
+        body["data"]["token"]["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string(),
+    )
+}
+
+async fn request(
+    state: &AppState,
+    method: &str,
+    uri: &str,
+    token: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {token}"))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body.map_or(Body::empty(), |b| Body::from(b.to_string())))
+        .unwrap();
+    let (status, _, body) = raw_request(app_routes(state.clone()), req).await;
+    (status, body)
+}
+
+async fn assign(state: &AppState, token: &str, user_id: &str, role: &str) -> StatusCode {
+    let uri = format!("/api/v1/admin/users/{user_id}/role");
+    request(state, "POST", &uri, token, Some(json!({ "role": role })))
+        .await
+        .0
+}
+
+#[tokio::test]
+async fn test_custom_role_grants_its_permissions() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let (_, admin) = user_with_role(&state, "admin@example.com", "admin").await;
+
+    let support = json!({
+        "name": "support",
+        "description": "Helpdesk",
+        "permissions": ["users:read", "stats:read"],
+    });
+    let (status, body) = request(
+        &state,
+        "POST",
+        "/api/v1/admin/roles",
+        &admin,
+        Some(support.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    assert_eq!(
+        body["data"]["permissions"],
+        json!(["stats:read", "users:read"])
+    );
+
+    let (status, _) = request(&state, "POST", "/api/v1/admin/roles", &admin, Some(support)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let bad = json!({ "name": "Help Desk", "permissions": [] });
+    let (status, _) = request(&state, "POST", "/api/v1/admin/roles", &admin, Some(bad)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let bad = json!({ "name": "helpdesk", "permissions": ["users:destroy"] });
+    let (status, body) = request(&state, "POST", "/api/v1/admin/roles", &admin, Some(bad)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error_code"], "VAL_001");
+
+    // Assigned roles take effect with the next token; the old ones are revoked
+    let (agent_id, old_token) = user_with_role(&state, "agent@example.com", "user").await;
+    assert_eq!(
+        assign(&state, &admin, &agent_id, "support").await,
+        StatusCode::OK
+    );
+    let (status, _) = request(&state, "GET", "/api/v1/users/me", &old_token, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        assign(&state, &admin, &agent_id, "nope").await,
+        StatusCode::BAD_REQUEST
+    );
+    let (_, agent) = login(&state, "agent@example.com").await;
+
+    let (status, _) = request(&state, "GET", "/api/v1/admin/users", &agent, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = request(&state, "GET", "/api/v1/admin/stats", &agent, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = request(&state, "GET", "/api/v1/admin/api-keys", &agent, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let uri = format!("/api/v1/admin/users/{agent_id}/unlock");
+    let (status, _) = request(&state, "POST", &uri, &agent, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Permission changes apply to tokens already out there
+    let patch = json!({ "permissions": ["users:read"] });
+    let (status, _) = request(
+        &state,
+        "PATCH",
+        "/api/v1/admin/roles/support",
+        &admin,
+        Some(patch),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = request(&state, "GET", "/api/v1/admin/stats", &agent, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Built-in roles stay; roles in use cannot go
+    let (status, _) = request(
+        &state,
+        "DELETE",
+        "/api/v1/admin/roles/moderator",
+        &admin,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let patch = json!({ "permissions": [] });
+    let (status, _) = request(
+        &state,
+        "PATCH",
+        "/api/v1/admin/roles/admin",
+        &admin,
+        Some(patch),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = request(
+        &state,
+        "DELETE",
+        "/api/v1/admin/roles/support",
+        &admin,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    assert_eq!(
+        assign(&state, &admin, &agent_id, "user").await,
+        StatusCode::OK
+    );
+    let (status, _) = request(
+        &state,
+        "DELETE",
+        "/api/v1/admin/roles/support",
+        &admin,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = request(&state, "GET", "/api/v1/admin/roles", &admin, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let names: Vec<&str> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["admin", "moderator", "user"]);
+}
+
+#[tokio::test]
+async fn test_roles_cannot_hand_out_more_than_they_hold() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let (_, admin) = user_with_role(&state, "admin@example.com", "admin").await;
+    let manager_role = json!({
+        "name": "manager",
+        "permissions": ["users:read", "users:write", "roles:read", "roles:write"],
+    });
+    let (status, _) = request(
+        &state,
+        "POST",
+        "/api/v1/admin/roles",
+        &admin,
+        Some(manager_role),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (_, manager) = user_with_role(&state, "manager@example.com", "manager").await;
+    let (member_id, member) = user_with_role(&state, "member@example.com", "user").await;
+
+    let (status, _) = request(&state, "GET", "/api/v1/admin/roles", &member, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Roles made of the manager's own permissions are fine...
+    let role = json!({ "name": "reviewer", "permissions": ["users:read"] });
+    let (status, _) = request(&state, "POST", "/api/v1/admin/roles", &manager, Some(role)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(
+        assign(&state, &manager, &member_id, "reviewer").await,
+        StatusCode::OK
+    );
+
+    // ...anything beyond them is not
+    let role = json!({ "name": "keymaster", "permissions": ["api_keys:write"] });
+    let (status, _) = request(&state, "POST", "/api/v1/admin/roles", &manager, Some(role)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let patch = json!({ "permissions": ["users:read", "users:write", "roles:read", "roles:write", "logs:write"] });
+    let (status, _) = request(
+        &state,
+        "PATCH",
+        "/api/v1/admin/roles/manager",
+        &manager,
+        Some(patch),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(
+        assign(&state, &manager, &member_id, "admin").await,
+        StatusCode::FORBIDDEN
+    );
+    // moderator carries stats:read
+    assert_eq!(
+        assign(&state, &manager, &member_id, "moderator").await,
+        StatusCode::FORBIDDEN
+    );
+
+    let (admin_id, _) = login(&state, "admin@example.com").await;
+    assert_eq!(
+        assign(&state, &manager, &admin_id, "user").await,
+        StatusCode::FORBIDDEN
+    );
+
+    // Lockouts too: only of users the manager could have assigned
+    for (user_id, expected) in [
+        (&admin_id, StatusCode::FORBIDDEN),
+        (&member_id, StatusCode::OK),
+    ] {
+        let uri = format!("/api/v1/admin/users/{user_id}/unlock");
+        let (status, _) = request(&state, "POST", &uri, &manager, None).await;
+        assert_eq!(status, expected);
+    }
+}
@@ -0,0 +1,114 @@
+//! Idle timeout and per-role absolute session lifetime
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use quax::{routes::app_routes, state::AppState};
+use serde_json::json;
+
+use common::*;
+
+const EMAIL: &str = "policy@example.com";
+const PASSWORD: &str = "password123";
+
+/// POST `uri` and return the status with the new refresh cookie, if any
+async fn post(
+    state: &AppState,
+    uri: &str,
+    body: serde_json::Value,
+    cookie: Option<&str>,
+) -> (StatusCode, Option<String>) {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(cookie) = cookie {
+        builder = builder.header(header::COOKIE, cookie);
+    }
+    let req = builder.body(Body::from(body.to_string())).unwrap();
+    let (status, headers, _) = raw_request(app_routes(state.clone()), req).await;
+    (status, extract_set_cookie(&headers, "refresh_token"))
+}
+
+async fn register(state: &AppState) -> String {
+    let body = json!({ "email": EMAIL, "name": "Policy", "password": PASSWORD });
+    let (status, cookie) = post(state, "/api/v1/auth/register", body, None).await;
+    assert_eq!(status, StatusCode::CREATED);
+    cookie.expect("refresh cookie")
+}
+
+async fn refresh(state: &AppState, cookie: &str) -> (StatusCode, Option<String>) {
+    post(state, "/api/v1/auth/refresh", json!({}), Some(cookie)).await
+}
+
+async fn revoked_reasons(state: &AppState) -> Vec<String> {
+    sqlx::query_scalar("SELECT revoked_reason FROM user_sessions WHERE revoked_reason IS NOT NULL")
+        .fetch_all(state.db.pool())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_idle_session_is_revoked_on_refresh() {
+    let (state, _c) = build_test_state_with(|c| c.session.idle_timeout_secs = 3600).await;
+
+    let cookie = register(&state).await;
+    let (status, cookie) = refresh(&state, &cookie).await;
+    assert_eq!(status, StatusCode::OK);
+    let cookie = cookie.unwrap();
+
+    sqlx::query("UPDATE user_sessions SET last_active_at = NOW() - INTERVAL '2 hours'")
+        .execute(state.db.pool())
+        .await
+        .unwrap();
+
+    // The cookie is the current one, but the session sat idle too long
+    let (status, _) = refresh(&state, &cookie).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(revoked_reasons(&state).await, ["idle_timeout"]);
+}
+
+#[tokio::test]
+async fn test_admin_sessions_have_shorter_lifetime() {
+    let (state, _c) = build_test_state_with(|c| {
+        c.session.absolute_lifetime_secs = 7 * 86400;
+        c.session.admin_absolute_lifetime_secs = Some(3600);
+    })
+    .await;
+    let pool = state.db.pool();
+
+    register(&state).await;
+    sqlx::query("UPDATE users SET role = 'admin'")
+        .execute(pool)
+        .await
+        .unwrap();
+    let login = json!({ "email": EMAIL, "password": PASSWORD });
+    let (status, cookie) = post(&state, "/api/v1/auth/login", login, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let cookie = cookie.unwrap();
+
+    let lifetimes: Vec<f64> = sqlx::query_scalar(
+        "SELECT EXTRACT(EPOCH FROM expires_at - created_at)::float8 \
+         FROM user_sessions ORDER BY created_at",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap();
+    assert!((lifetimes[0] - 7.0 * 86400.0).abs() < 5.0, "{lifetimes:?}");
+    assert!((lifetimes[1] - 3600.0).abs() < 5.0, "{lifetimes:?}");
+
+    // Past its end the admin session cannot be refreshed
+    sqlx::query(
+        "UPDATE user_sessions SET expires_at = NOW() - INTERVAL '1 minute' \
+         WHERE expires_at < created_at + INTERVAL '2 hours'",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    let (status, _) = refresh(&state, &cookie).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(revoked_reasons(&state).await, ["absolute_timeout"]);
+}
@@ -0,0 +1,120 @@
+//! RFC 7662 introspection and RFC 7009 revocation
+
+mod common;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use quax::routes::app_routes;
+use serde_json::{Value, json};
+
+use common::*;
+
+/// POST a form to `/api/v1/oauth/{endpoint}`
+async fn post_form(
+    app: Router,
+    endpoint: &str,
+    form: &str,
+    api_key: Option<&str>,
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri(format!("/api/v1/oauth/{endpoint}"))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+    if let Some(key) = api_key {
+        builder = builder.header("x-api-key", key);
+    }
+    let (status, _, body) =
+        raw_request(app, builder.body(Body::from(form.to_string())).unwrap()).await;
+    (status, body)
+}
+
+async fn introspect(app: Router, api_key: &str, form: &str) -> Value {
+    let (status, body) = post_form(app, "introspect", form, Some(api_key)).await;
+    assert_eq!(status, StatusCode::OK);
+    body
+}
+
+/// Register; returns (access token, refresh token)
+async fn register(app: Router) -> (String, String) {
+    let body = json!({ "email": "introspect@example.com", "name": "Introspect", "password": "password123" });
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/register")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let (status, headers, body) = raw_request(app, req).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let cookie = extract_set_cookie(&headers, "refresh_token").unwrap();
+    (
+        body["data"]["token"]["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string(),
+        cookie.trim_start_matches("refresh_token=").to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_introspect_access_and_refresh_tokens() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let app = app_routes(state.clone());
+    let key = create_api_key(&state, &["tokens:introspect"]).await;
+    let (access, refresh) = register(app.clone()).await;
+
+    // Other services authenticate with an API key
+    let form = format!("token={access}");
+    let (status, _) = post_form(app.clone(), "introspect", &form, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = post_form(app.clone(), "introspect", &form, Some("wrong")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let unscoped = create_api_key(&state, &["reports:read"]).await;
+    let (status, _) = post_form(app.clone(), "introspect", &form, Some(&unscoped)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let body = introspect(app.clone(), &key, &form).await;
+    assert_eq!(body["active"], true);
+    assert_eq!(body["token_type"], "access_token");
+    assert_eq!(body["roles"], json!(["user"]));
+    assert!(body["sub"].is_string() && body["sid"].is_string() && body["exp"].is_i64());
+
+    let body = introspect(
+        app.clone(),
+        &key,
+        &format!("token={refresh}&token_type_hint=refresh_token"),
+    )
+    .await;
+    assert_eq!(body["active"], true);
+    assert_eq!(body["token_type"], "refresh_token");
+
+    assert_eq!(
+        introspect(app, &key, "token=garbage").await,
+        json!({ "active": false })
+    );
+}
+
+#[tokio::test]
+async fn test_revoked_refresh_token_ends_session() {
+    let (state, _c) = build_test_state_with(|_| {}).await;
+    let app = app_routes(state.clone());
+    let key = create_api_key(&state, &["tokens:introspect"]).await;
+    let (access, refresh) = register(app.clone()).await;
+
+    let (status, _) = post_form(app.clone(), "revoke", &format!("token={refresh}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Signatures still check out, but the session is gone
+    for token in [&access, &refresh] {
+        let body = introspect(app.clone(), &key, &format!("token={token}")).await;
+        assert_eq!(body, json!({ "active": false }));
+    }
+    let (status, _) = get_authed(app.clone(), "/api/v1/auth/me", &access).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Unknown tokens are accepted too
+    let (status, _) = post_form(app, "revoke", "token=garbage", None).await;
+    assert_eq!(status, StatusCode::OK);
+}
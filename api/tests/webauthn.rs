@@ -0,0 +1,350 @@
+//! Passkeys: registration next to a password, login, and rejected assertions,
+//! driven by a software authenticator
+
+mod common;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use ciborium::Value as Cbor;
+use ring::{
+    rand::{SecureRandom, SystemRandom},
+    signature::{ECDSA_P256_SHA256_ASN1_SIGNING, EcdsaKeyPair, KeyPair},
+};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use quax::infrastructure::config::Config;
+
+use common::*;
+
+const EMAIL: &str = "passkey@example.com";
+const PASSWORD: &str = "password123";
+const RP_ID: &str = "localhost";
+const ORIGIN: &str = "http://localhost:5173";
+
+// Authenticator data flags: user present, user verified, attested credential data
+const UP: u8 = 0x01;
+const UV: u8 = 0x04;
+const AT: u8 = 0x40;
+
+fn b64(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn unb64(value: &Value) -> Vec<u8> {
+    URL_SAFE_NO_PAD.decode(value.as_str().unwrap()).unwrap()
+}
+
+/// A platform authenticator holding one ES256 credential that always
+/// verifies the user
+struct SoftAuthenticator {
+    key: EcdsaKeyPair,
+    credential_id: Vec<u8>,
+    user_handle: Vec<u8>,
+    sign_count: u32,
+    origin: String,
+}
+
+impl SoftAuthenticator {
+    fn new() -> Self {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+            .unwrap();
+        let mut credential_id = vec![0u8; 16];
+        rng.fill(&mut credential_id).unwrap();
+
+        Self {
+            key,
+            credential_id,
+            user_handle: Vec::new(),
+            sign_count: 0,
+            origin: ORIGIN.to_string(),
+        }
+    }
+
+    fn cose_key(&self) -> Vec<u8> {
+        let point = self.key.public_key().as_ref();
+        let key = Cbor::Map(vec![
+            (1.into(), 2.into()),
+            (3.into(), (-7).into()),
+            ((-1).into(), 1.into()),
+            ((-2).into(), Cbor::Bytes(point[1..33].to_vec())),
+            ((-3).into(), Cbor::Bytes(point[33..].to_vec())),
+        ]);
+        let mut out = Vec::new();
+        ciborium::into_writer(&key, &mut out).unwrap();
+        out
+    }
+
+    fn auth_data(&mut self, flags: u8, attested: &[u8]) -> Vec<u8> {
+        self.sign_count += 1;
+        let mut data = Sha256::digest(RP_ID).to_vec();
+        data.push(flags);
+        data.extend_from_slice(&self.sign_count.to_be_bytes());
+        data.extend_from_slice(attested);
+        data
+    }
+
+    fn client_data(&self, kind: &str, options: &Value) -> Vec<u8> {
+        json!({
+            "type": kind,
+            "challenge": options["challenge"],
+            "origin": self.origin,
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    /// `navigator.credentials.create()`
+    fn create(&mut self, options: &Value) -> Value {
+        self.user_handle = unb64(&options["user"]["id"]);
+
+        let mut attested = vec![0u8; 16]; // AAGUID
+        attested.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+        attested.extend_from_slice(&self.credential_id);
+        attested.extend_from_slice(&self.cose_key());
+        let auth_data = self.auth_data(UP | UV | AT, &attested);
+
+        let mut attestation_object = Vec::new();
+        ciborium::into_writer(
+            &Cbor::Map(vec![
+                (Cbor::Text("fmt".into()), Cbor::Text("none".into())),
+                (Cbor::Text("attStmt".into()), Cbor::Map(vec![])),
+                (Cbor::Text("authData".into()), Cbor::Bytes(auth_data)),
+            ]),
+            &mut attestation_object,
+        )
+        .unwrap();
+
+        json!({
+            "id": b64(&self.credential_id),
+            "rawId": b64(&self.credential_id),
+            "type": "public-key",
+            "response": {
+                "clientDataJSON": b64(&self.client_data("webauthn.create", options)),
+                "attestationObject": b64(&attestation_object),
+                "transports": ["internal"],
+            },
+        })
+    }
+
+    /// `navigator.credentials.get()`
+    fn get(&mut self, options: &Value) -> Value {
+        let auth_data = self.auth_data(UP | UV, &[]);
+        let client_data = self.client_data("webauthn.get", options);
+
+        let mut message = auth_data.clone();
+        message.extend_from_slice(&Sha256::digest(&client_data));
+        let signature = self.key.sign(&SystemRandom::new(), &message).unwrap();
+
+        json!({
+            "id": b64(&self.credential_id),
+            "rawId": b64(&self.credential_id),
+            "type": "public-key",
+            "response": {
+                "clientDataJSON": b64(&client_data),
+                "authenticatorData": b64(&auth_data),
+                "signature": b64(signature.as_ref()),
+                "userHandle": b64(&self.user_handle),
+            },
+        })
+    }
+}
+
+fn relying_party(c: &mut Config) {
+    c.webauthn.rp_id = RP_ID.to_string();
+    c.webauthn.origins = vec![ORIGIN.to_string()];
+}
+
+/// Register a password account and return its access token
+async fn register(app: Router) -> String {
+    let (status, body) = post_json(
+        app,
+        "/api/v1/auth/register",
+        &json!({ "email": EMAIL, "name": "Passkey User", "password": PASSWORD }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    body["data"]["token"]["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+async fn add_passkey(app: Router, token: &str, authenticator: &mut SoftAuthenticator, name: &str) {
+    let (status, body) = post_json_authed(
+        app.clone(),
+        "/api/v1/auth/webauthn/register/begin",
+        token,
+        &json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let options = &body["data"]["options"];
+    assert_eq!(options["rp"]["id"], RP_ID);
+
+    let (status, body) = post_json_authed(
+        app,
+        "/api/v1/auth/webauthn/register/finish",
+        token,
+        &json!({
+            "ceremony_id": body["data"]["ceremony_id"],
+            "name": name,
+            "credential": authenticator.create(options),
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    assert_eq!(body["data"]["name"], name);
+}
+
+/// Run a login ceremony; returns the finish status and body
+async fn passkey_login(
+    app: Router,
+    email: Option<&str>,
+    authenticator: &mut SoftAuthenticator,
+) -> (StatusCode, Value) {
+    let (status, body) = post_json(
+        app.clone(),
+        "/api/v1/auth/webauthn/login/begin",
+        &json!({ "email": email }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    post_json(
+        app,
+        "/api/v1/auth/webauthn/login/finish",
+        &json!({
+            "ceremony_id": body["data"]["ceremony_id"],
+            "credential": authenticator.get(&body["data"]["options"]),
+        }),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_passkeys_alongside_password() {
+    let (app, _c) = build_test_app_with(relying_party).await;
+    let token = register(app.clone()).await;
+
+    let mut laptop = SoftAuthenticator::new();
+    let mut phone = SoftAuthenticator::new();
+    add_passkey(app.clone(), &token, &mut laptop, "Laptop").await;
+    add_passkey(app.clone(), &token, &mut phone, "Phone").await;
+
+    // The same authenticator cannot be registered twice
+    let (_, body) = post_json_authed(
+        app.clone(),
+        "/api/v1/auth/webauthn/register/begin",
+        &token,
+        &json!({}),
+    )
+    .await;
+    assert_eq!(
+        body["data"]["options"]["excludeCredentials"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+    let (status, _) = post_json_authed(
+        app.clone(),
+        "/api/v1/auth/webauthn/register/finish",
+        &token,
+        &json!({
+            "ceremony_id": body["data"]["ceremony_id"],
+            "credential": laptop.create(&body["data"]["options"]),
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, body) = get_authed(app.clone(), "/api/v1/auth/webauthn/credentials", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    let passkeys = body["data"].as_array().unwrap();
+    assert_eq!(passkeys.len(), 2);
+
+    // Passkey login, with and without naming the account
+    let (status, body) = passkey_login(app.clone(), Some(EMAIL), &mut phone).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let passkey_token = body["data"]["token"]["access_token"].as_str().unwrap();
+    let (status, body) = get_authed(app.clone(), "/api/v1/auth/me", passkey_token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["email"], EMAIL);
+
+    let (status, _) = passkey_login(app.clone(), None, &mut laptop).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // The password keeps working
+    let (status, _) = post_json(
+        app.clone(),
+        "/api/v1/auth/login",
+        &json!({ "email": EMAIL, "password": PASSWORD }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Removing a passkey leaves the others
+    let req = Request::builder()
+        .method("DELETE")
+        .uri(format!(
+            "/api/v1/auth/webauthn/credentials/{}",
+            passkeys[0]["id"].as_str().unwrap()
+        ))
+        .header(header::AUTHORIZATION, format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let (status, _, _) = raw_request(app.clone(), req).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = get_authed(app.clone(), "/api/v1/auth/webauthn/credentials", &token).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(body["data"][0]["name"], "Phone");
+}
+
+#[tokio::test]
+async fn test_passkey_login_rejects_bad_assertions() {
+    let (app, _c) = build_test_app_with(relying_party).await;
+    let token = register(app.clone()).await;
+
+    let mut authenticator = SoftAuthenticator::new();
+    add_passkey(app.clone(), &token, &mut authenticator, "Key").await;
+
+    // Signed for another site
+    authenticator.origin = "https://phishing.example".to_string();
+    let (status, body) = passkey_login(app.clone(), Some(EMAIL), &mut authenticator).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error_code"], "AUTH_016");
+    authenticator.origin = ORIGIN.to_string();
+
+    // A ceremony can only be finished once
+    let (_, begin) = post_json(
+        app.clone(),
+        "/api/v1/auth/webauthn/login/begin",
+        &json!({ "email": EMAIL }),
+    )
+    .await;
+    let finish = json!({
+        "ceremony_id": begin["data"]["ceremony_id"],
+        "credential": authenticator.get(&begin["data"]["options"]),
+    });
+    let (status, _) = post_json(app.clone(), "/api/v1/auth/webauthn/login/finish", &finish).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = post_json(app.clone(), "/api/v1/auth/webauthn/login/finish", &finish).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // A cloned authenticator replays an old counter value
+    authenticator.sign_count -= 1;
+    let (status, _) = passkey_login(app.clone(), Some(EMAIL), &mut authenticator).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Unknown credentials are rejected
+    let (status, _) = passkey_login(app, None, &mut SoftAuthenticator::new()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}